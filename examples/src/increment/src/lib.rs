@@ -12,7 +12,7 @@ pub struct Example {
 }
 
 const ROTATION_INCREMENT_DEGREES: f64 = 90.0;
-const ROTATION_EASING_DURATION_FRAMES: u64 = 120;
+const ROTATION_EASING_DURATION: TransitionDuration = TransitionDuration::Frames(120);
 
 impl Example {
     pub fn handle_pre_render(&mut self, _ctx: &NodeContext) {
@@ -24,6 +24,10 @@ impl Example {
         let old_num_clicks = self.num_clicks.get();
         let new_val = old_num_clicks + 1;
         self.num_clicks.set(new_val);
-        self.current_rotation.ease_to(new_val as f64 * ROTATION_INCREMENT_DEGREES, ROTATION_EASING_DURATION_FRAMES, EasingCurve::OutQuad);
+        self.current_rotation.ease_to(
+            new_val as f64 * ROTATION_INCREMENT_DEGREES,
+            ROTATION_EASING_DURATION,
+            EasingCurve::OutQuad,
+        );
     }
 }
@@ -37,19 +37,42 @@ impl Ball {
             let delay_frames: u64 =
                 ((1.0 - seq_progress_0_1) * (random::<f64>() * crate::LOOP_FRAMES)) as u64;
 
-            self.diameter
-                .ease_to(0.into(), delay_frames, EasingCurve::Linear);
-            self.diameter
-                .ease_to_later(d_lower, 20, EasingCurve::OutQuad);
-            self.diameter
-                .ease_to_later(d_upper, 40, EasingCurve::OutQuad);
-            self.diameter
-                .ease_to_later(d_steady, 40, EasingCurve::InQuad);
-
-            self.hue
-                .ease_to(h_lower, delay_frames, EasingCurve::OutQuad);
-            self.hue.ease_to_later(h_upper, 40, EasingCurve::OutQuad);
-            self.hue.ease_to_later(h_steady, 40, EasingCurve::InQuad);
+            self.diameter.ease_to(
+                0.into(),
+                TransitionDuration::Frames(delay_frames),
+                EasingCurve::Linear,
+            );
+            self.diameter.ease_to_later(
+                d_lower,
+                TransitionDuration::Frames(20),
+                EasingCurve::OutQuad,
+            );
+            self.diameter.ease_to_later(
+                d_upper,
+                TransitionDuration::Frames(40),
+                EasingCurve::OutQuad,
+            );
+            self.diameter.ease_to_later(
+                d_steady,
+                TransitionDuration::Frames(40),
+                EasingCurve::InQuad,
+            );
+
+            self.hue.ease_to(
+                h_lower,
+                TransitionDuration::Frames(delay_frames),
+                EasingCurve::OutQuad,
+            );
+            self.hue.ease_to_later(
+                h_upper,
+                TransitionDuration::Frames(40),
+                EasingCurve::OutQuad,
+            );
+            self.hue.ease_to_later(
+                h_steady,
+                TransitionDuration::Frames(40),
+                EasingCurve::InQuad,
+            );
         }
     }
 
@@ -17,6 +17,7 @@ use pax_runtime::api::SelectStart;
 use pax_runtime::api::TextboxChange;
 use pax_runtime::api::OS;
 use pax_runtime::DefinitionToInstanceTraverser;
+use pax_runtime::{recognize_gestures, PointerPhase};
 use web_time::Instant;
 use_RefCell!();
 
@@ -32,8 +33,9 @@ pub use {console_error_panic_hook, console_log};
 use pax_message::NativeInterrupt;
 use pax_runtime::api::{
     Clap, Click, ContextMenu, DoubleClick, Drop, KeyDown, KeyPress, KeyUp, KeyboardEventArgs,
-    ModifierKey, MouseButton, MouseDown, MouseEventArgs, MouseMove, MouseUp, Touch, TouchEnd,
-    TouchMove, TouchStart, Wheel,
+    ModifierKey, MouseButton, MouseDown, MouseEventArgs, MouseMove, MouseUp, PointerCancel,
+    PointerDown, PointerEventArgs, PointerMove, PointerUp, Touch, TouchEnd, TouchMove, TouchStart,
+    Wheel,
 };
 use serde_json;
 
@@ -520,11 +522,20 @@ impl PaxChassisWeb {
                             .collect(),
                     },
                 };
-                topmost_node.dispatch_mouse_down(
+                engine
+                    .runtime_context
+                    .set_pressed_element(Some(&topmost_node));
+                let prevent_default = topmost_node.dispatch_mouse_down(
                     Event::new(args_mouse_down),
                     &globals,
                     &engine.runtime_context,
-                )
+                );
+                if !prevent_default {
+                    engine
+                        .runtime_context
+                        .focus_closest_focusable(&topmost_node);
+                }
+                prevent_default
             }
             NativeInterrupt::MouseUp(args) => {
                 let topmost_node = engine
@@ -542,6 +553,7 @@ impl PaxChassisWeb {
                             .collect(),
                     },
                 };
+                engine.runtime_context.set_pressed_element(None);
                 topmost_node.dispatch_mouse_up(
                     Event::new(args_mouse_up),
                     &globals,
@@ -570,6 +582,98 @@ impl PaxChassisWeb {
                     &engine.runtime_context,
                 )
             }
+            NativeInterrupt::PointerDown(args) => {
+                let target = engine
+                    .runtime_context
+                    .get_pointer_target(args.pointer_id, Point2::new(args.x, args.y));
+                let pointer = PointerEventArgs::from(args);
+                let args_pointer_down = PointerDown {
+                    pointer: pointer.clone(),
+                };
+                let prevent_default = target.dispatch_pointer_down(
+                    Event::new(args_pointer_down),
+                    &globals,
+                    &engine.runtime_context,
+                );
+                prevent_default
+                    | recognize_gestures(
+                        &engine.runtime_context,
+                        &target,
+                        PointerPhase::Down,
+                        &pointer,
+                    )
+            }
+            NativeInterrupt::PointerMove(args) => {
+                let target = engine
+                    .runtime_context
+                    .get_pointer_target(args.pointer_id, Point2::new(args.x, args.y));
+                let pointer = PointerEventArgs::from(args);
+                let args_pointer_move = PointerMove {
+                    pointer: pointer.clone(),
+                };
+                let prevent_default = target.dispatch_pointer_move(
+                    Event::new(args_pointer_move),
+                    &globals,
+                    &engine.runtime_context,
+                );
+                prevent_default
+                    | recognize_gestures(
+                        &engine.runtime_context,
+                        &target,
+                        PointerPhase::Move,
+                        &pointer,
+                    )
+            }
+            NativeInterrupt::PointerUp(args) => {
+                let target = engine
+                    .runtime_context
+                    .get_pointer_target(args.pointer_id, Point2::new(args.x, args.y));
+                let pointer = PointerEventArgs::from(args);
+                let args_pointer_up = PointerUp {
+                    pointer: pointer.clone(),
+                };
+                let prevent_default = target.dispatch_pointer_up(
+                    Event::new(args_pointer_up),
+                    &globals,
+                    &engine.runtime_context,
+                );
+                let prevent_default = prevent_default
+                    | recognize_gestures(
+                        &engine.runtime_context,
+                        &target,
+                        PointerPhase::Up,
+                        &pointer,
+                    );
+                engine
+                    .runtime_context
+                    .release_pointer_capture(args.pointer_id);
+                prevent_default
+            }
+            NativeInterrupt::PointerCancel(args) => {
+                let target = engine
+                    .runtime_context
+                    .get_pointer_target(args.pointer_id, Point2::new(args.x, args.y));
+                let pointer = PointerEventArgs::from(args);
+                let args_pointer_cancel = PointerCancel {
+                    pointer: pointer.clone(),
+                };
+                let prevent_default = target.dispatch_pointer_cancel(
+                    Event::new(args_pointer_cancel),
+                    &globals,
+                    &engine.runtime_context,
+                );
+                let prevent_default = prevent_default
+                    | recognize_gestures(
+                        &engine.runtime_context,
+                        &target,
+                        PointerPhase::Cancel,
+                        &pointer,
+                    );
+                engine
+                    .runtime_context
+                    .release_pointer_capture(args.pointer_id);
+                prevent_default
+            }
             NativeInterrupt::Screenshot(args) => {
                 let data = Uint8Array::new(additional_payload).to_vec();
                 if let ImageLoadInterruptArgs::Data(args) = args {
@@ -631,6 +735,19 @@ impl PaxChassisWeb {
                             .get(&uni.get_containing_component_type_id())
                             .unwrap();
                         let containing_template = containing_component.template.as_ref().unwrap();
+                        // `else if` / `else` branches are owned by the `if` heading their chain,
+                        // and `match` arms by their `match`
+                        let mut node_id = containing_template
+                            .get_conditional_chain_head(&uni.get_template_node_id());
+                        if containing_template.get_node(&node_id).is_some_and(|n| {
+                            n.type_id.get_pax_type() == &pax_manifest::PaxType::MatchArm
+                        }) {
+                            node_id = containing_template.get_parent(&node_id).unwrap_or(node_id);
+                        }
+                        let uni = pax_manifest::UniqueTemplateNodeIdentifier::build(
+                            uni.get_containing_component_type_id(),
+                            node_id,
+                        );
                         let tnd = containing_template
                             .get_node(&uni.get_template_node_id())
                             .unwrap();
@@ -651,7 +768,8 @@ impl PaxChassisWeb {
                         let instance_node = match pax_type {
                             pax_manifest::PaxType::If
                             | pax_manifest::PaxType::Slot
-                            | pax_manifest::PaxType::Repeat => self
+                            | pax_manifest::PaxType::Repeat
+                            | pax_manifest::PaxType::Match => self
                                 .userland_definition_to_instance_traverser
                                 .build_control_flow(
                                     &uni.get_containing_component_type_id(),
@@ -6,8 +6,23 @@ use web_sys::{HtmlCanvasElement, Window};
 
 #[cfg(not(feature = "gpu"))]
 pub fn get_render_context(window: Window) -> impl RenderContext {
-    use pax_runtime::piet_render_context::PietRenderer;
+    use pax_runtime::api::{BlendMode, Compositing};
+    use pax_runtime::piet_render_context::{blur_rgba, PietRenderer};
+    use piet::kurbo::{Affine, Rect};
     use piet_web::WebRenderContext;
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+    use wasm_bindgen::Clamped;
+    use web_sys::{CanvasRenderingContext2d, ImageData};
+
+    // what each layer is currently drawing to: the layer canvas, followed by the
+    // offscreen canvases of the groups being drawn
+    let targets: Rc<RefCell<HashMap<usize, Vec<CanvasRenderingContext2d>>>> = Default::default();
+    // offscreen canvases by layer and group depth, reused by the next group drawn there
+    let pool: RefCell<HashMap<(usize, usize), HtmlCanvasElement>> = Default::default();
+    let group_window = window.clone();
+    let group_targets = Rc::clone(&targets);
+    let reader_window = window.clone();
+    let reader_targets = Rc::clone(&targets);
     PietRenderer::new(move |layer| {
         let dpr = window.device_pixel_ratio();
         let document = window.document().unwrap();
@@ -29,6 +44,7 @@ pub fn get_render_context(window: Window) -> impl RenderContext {
         canvas.set_width(width as u32);
         canvas.set_height(height as u32);
         let _ = context.scale(dpr, dpr);
+        targets.borrow_mut().insert(layer, vec![context.clone()]);
 
         (
             WebRenderContext::new(context.clone(), window.clone()),
@@ -54,6 +70,124 @@ pub fn get_render_context(window: Window) -> impl RenderContext {
             }),
         )
     })
+    .with_group_factory(move |layer| {
+        let window = group_window.clone();
+        let targets = Rc::clone(&group_targets);
+        let mut layer_targets = targets.borrow_mut();
+        let layer_targets = layer_targets.entry(layer).or_default();
+        let (width, height) = layer_targets
+            .first()
+            .and_then(|context| context.canvas())
+            .map(|canvas| (canvas.width(), canvas.height()))
+            .unwrap_or_default();
+        let canvas = pool
+            .borrow_mut()
+            .entry((layer, layer_targets.len()))
+            .or_insert_with(|| {
+                window
+                    .document()
+                    .unwrap()
+                    .create_element("canvas")
+                    .unwrap()
+                    .dyn_into::<HtmlCanvasElement>()
+                    .unwrap()
+            })
+            .clone();
+        if canvas.width() != width || canvas.height() != height {
+            canvas.set_width(width);
+            canvas.set_height(height);
+        }
+        let context = canvas
+            .get_context("2d")
+            .unwrap()
+            .unwrap()
+            .dyn_into::<CanvasRenderingContext2d>()
+            .unwrap();
+        // a reused canvas still holds what the last group drew to it
+        let dpr = window.device_pixel_ratio();
+        let _ = context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        context.clear_rect(0.0, 0.0, width as f64, height as f64);
+        let _ = context.set_transform(dpr, 0.0, 0.0, dpr, 0.0, 0.0);
+        layer_targets.push(context.clone());
+
+        let composite_targets = Rc::clone(&group_targets);
+        (
+            WebRenderContext::new(context, window.clone()),
+            // composite fn
+            Box::new(move |compositing: &Compositing, mode: BlendMode, bounds| {
+                let mut targets = composite_targets.borrow_mut();
+                let Some(layer_targets) = targets.get_mut(&layer) else {
+                    return;
+                };
+                let Some(offscreen) = layer_targets.pop() else {
+                    return;
+                };
+                let (Some(parent), Some(bounds)) = (layer_targets.last(), bounds) else {
+                    return;
+                };
+                // only the part of the canvas that was drawn to needs to be blurred and
+                // copied over
+                let dpr = window.device_pixel_ratio();
+                let area = Affine::scale(dpr)
+                    .transform_rect_bbox(bounds)
+                    .expand()
+                    .intersect(Rect::new(0.0, 0.0, width as f64, height as f64));
+                if area.is_zero_area() {
+                    return;
+                }
+                if compositing.blur > 0.0 {
+                    if let Ok(image_data) =
+                        offscreen.get_image_data(area.x0, area.y0, area.width(), area.height())
+                    {
+                        let (w, h) = (image_data.width(), image_data.height());
+                        let mut pixels = image_data.data().0;
+                        blur_rgba(&mut pixels, w as usize, h as usize, compositing.blur * dpr);
+                        if let Ok(blurred) =
+                            ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), w, h)
+                        {
+                            let _ = offscreen.put_image_data(&blurred, area.x0, area.y0);
+                        }
+                    }
+                }
+                // draw pixel for pixel, keeping the clip of the parent
+                parent.save();
+                let _ = parent.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+                parent.set_global_alpha(compositing.opacity);
+                // canvas calls the normal blend mode source-over
+                if mode != BlendMode::Normal {
+                    let _ = parent.set_global_composite_operation(mode.css_name());
+                }
+                let _ = parent
+                    .draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                        &canvas,
+                        area.x0,
+                        area.y0,
+                        area.width(),
+                        area.height(),
+                        area.x0,
+                        area.y0,
+                        area.width(),
+                        area.height(),
+                    );
+                parent.restore();
+            }),
+        )
+    })
+    .with_pixel_reader(move |layer, rect| {
+        let targets = reader_targets.borrow();
+        let target = targets.get(&layer)?.last()?;
+        let area = Affine::scale(reader_window.device_pixel_ratio())
+            .transform_rect_bbox(rect)
+            .expand();
+        let image_data = target
+            .get_image_data(area.x0, area.y0, area.width(), area.height())
+            .ok()?;
+        Some((
+            image_data.data().0,
+            image_data.width() as usize,
+            image_data.height() as usize,
+        ))
+    })
 }
 
 #[cfg(feature = "gpu")]
@@ -6,6 +6,7 @@ export class ButtonUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public transform?: number[];
     public content?: string;
     public color?: ColorGroup; 
@@ -26,6 +27,7 @@ export class ButtonUpdatePatch {
         this.content = jsonMessage["content"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.color = jsonMessage["color"];
         this.hoverColor = jsonMessage["hover_color"];
@@ -44,6 +46,7 @@ export class ButtonUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.objectManager.returnToPool(TEXT_STYLE, this.style);
         this.style = undefined;
@@ -5,6 +5,7 @@ export class CheckboxUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public transform?: number[];
     public checked?: boolean;
     public borderRadius?: number;
@@ -23,6 +24,7 @@ export class CheckboxUpdatePatch {
         this.id = jsonMessage["id"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.checked = jsonMessage["checked"];
         this.borderRadius = jsonMessage["border_radius"];
@@ -36,6 +38,7 @@ export class CheckboxUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.checked = undefined;
     }
@@ -6,6 +6,7 @@ export class DropdownUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public stroke_width?: number;
     public stroke_color?: ColorGroup;
     public background?: ColorGroup; 
@@ -24,6 +25,7 @@ export class DropdownUpdatePatch {
         this.id = jsonMessage["id"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.options = jsonMessage["options"];
         this.stroke_color = jsonMessage["stroke_color"];
@@ -43,6 +45,7 @@ export class DropdownUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.options = [];
         this.selected_id = 0;
@@ -4,6 +4,7 @@ export class NativeImageUpdatePatch {
     public fit?: string;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public transform?: number[];
 
     fromPatch(jsonMessage: any) {
@@ -12,6 +13,7 @@ export class NativeImageUpdatePatch {
         this.fit = jsonMessage["fit"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
     }
 
@@ -21,6 +23,7 @@ export class NativeImageUpdatePatch {
         this.fit = '';
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
     }
 }
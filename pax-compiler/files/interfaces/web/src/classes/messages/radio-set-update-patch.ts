@@ -6,6 +6,7 @@ export class RadioSetUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public background?: ColorGroup; 
     public transform?: number[];
     public selected_id?: number;
@@ -24,6 +25,7 @@ export class RadioSetUpdatePatch {
         this.id = jsonMessage["id"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.options = jsonMessage["options"];
         this.background = jsonMessage["background"];
@@ -43,6 +45,7 @@ export class RadioSetUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.options = [];
         this.selected_id = 0;
@@ -5,6 +5,7 @@ export class SliderUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public accent?: ColorGroup;
     public transform?: number[];
     public value?: number;
@@ -23,6 +24,7 @@ export class SliderUpdatePatch {
         this.id = jsonMessage["id"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.accent = jsonMessage["accent"];
         this.value = jsonMessage["value"];
@@ -37,6 +39,7 @@ export class SliderUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.value = 0;
         this.step = 0;
         this.min = 0;
@@ -7,6 +7,7 @@ export class TextUpdatePatch {
     public content?: string;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public transform?: number[];
     public style?: TextStyle;
     public style_link?: TextStyle;
@@ -25,6 +26,7 @@ export class TextUpdatePatch {
         this.content = jsonMessage["content"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.depth = jsonMessage["depth"];
         this.editable = jsonMessage["editable"];
@@ -49,6 +51,7 @@ export class TextUpdatePatch {
         this.content = '';
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.objectManager.returnToPool(TEXT_STYLE, this.style);
         this.style = undefined;
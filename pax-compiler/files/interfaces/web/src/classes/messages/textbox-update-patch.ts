@@ -6,6 +6,7 @@ export class TextboxUpdatePatch {
     public id?: number;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public stroke_width?: number;
     public stroke_color?: ColorGroup;
     public background?: ColorGroup; 
@@ -27,6 +28,7 @@ export class TextboxUpdatePatch {
         this.id = jsonMessage["id"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
         this.text = jsonMessage["text"];
         this.stroke_color = jsonMessage["stroke_color"];
@@ -49,6 +51,7 @@ export class TextboxUpdatePatch {
         this.id = undefined;
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
         this.text = "";
     }
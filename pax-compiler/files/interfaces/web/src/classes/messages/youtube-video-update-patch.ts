@@ -3,6 +3,7 @@ export class YoutubeVideoUpdatePatch {
     public url?: string;
     public size_x?: number;
     public size_y?: number;
+    public opacity?: number;
     public transform?: number[];
 
     fromPatch(jsonMessage: any) {
@@ -10,6 +11,7 @@ export class YoutubeVideoUpdatePatch {
         this.url = jsonMessage["url"];
         this.size_x = jsonMessage["size_x"];
         this.size_y = jsonMessage["size_y"];
+        this.opacity = jsonMessage["opacity"];
         this.transform = jsonMessage["transform"];
     }
 
@@ -18,6 +20,7 @@ export class YoutubeVideoUpdatePatch {
         this.url = '';
         this.size_x = 0;
         this.size_y = 0;
+        this.opacity = undefined;
         this.transform = [];
     }
 }
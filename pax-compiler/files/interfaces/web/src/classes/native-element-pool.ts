@@ -791,6 +791,10 @@ export class NativeElementPool {
             leaf!.style.transform = packAffineCoeffsIntoMatrix3DString(patch.transform);
         }
 
+        if (patch.opacity != null) {
+            leaf!.style.opacity = patch.opacity.toString();
+        }
+
         if (patch.editable != null) {
             if (patch.editable == true) {
                 const selection = window.getSelection();
@@ -1184,4 +1188,7 @@ function updateCommonProps(leaf: HTMLElement, patch: any) {
     if (patch.transform != null) {
         leaf!.style.transform = packAffineCoeffsIntoMatrix3DString(patch.transform);
     }
+    if (patch.opacity != null) {
+        leaf!.style.opacity = patch.opacity.toString();
+    }
 }
@@ -18,6 +18,14 @@ function getMouseButton(event: MouseEvent) {
     }
 }
 
+function getPointerType(event: PointerEvent) {
+    switch (event.pointerType) {
+        case 'touch': return 'Touch';
+        case 'pen': return 'Pen';
+        default: return 'Mouse';
+    }
+}
+
 
 export function setupEventListeners(chassis: PaxChassisWeb) {
 
@@ -167,6 +175,28 @@ export function setupEventListeners(chassis: PaxChassisWeb) {
             evt.preventDefault();
         }
     }, true);
+    function pointerListener(interrupt: string) {
+        return (evt: PointerEvent) => {
+            let event = {
+                [interrupt]: {
+                    "pointer_id": evt.pointerId,
+                    "pointer_type": getPointerType(evt),
+                    "x": evt.clientX,
+                    "y": evt.clientY,
+                    "button": getMouseButton(evt),
+                    "modifiers": convertModifiers(evt)
+                }
+            };
+            let res = chassis.interrupt(JSON.stringify(event), []);
+            if (res.prevent_default) {
+                evt.preventDefault();
+            }
+        };
+    }
+    window.addEventListener('pointerdown', pointerListener("PointerDown"), true);
+    window.addEventListener('pointermove', pointerListener("PointerMove"), true);
+    window.addEventListener('pointerup', pointerListener("PointerUp"), true);
+    window.addEventListener('pointercancel', pointerListener("PointerCancel"), true);
     window.addEventListener('touchstart', (evt) => {
         let event = {
             "TouchStart": {
@@ -95,6 +95,7 @@ public class TextElement {
     public var textStyle: TextStyle
     public var depth: UInt?
     public var style_link: TextStyle?
+    public var opacity: Float = 1.0
     
     public init(id_chain: [UInt64], clipping_ids: [[UInt64]], content: String, transform: [Float], size_x: Float, size_y: Float, textStyle: TextStyle, depth: UInt?, style_link: TextStyle?) {
         self.id_chain = id_chain
@@ -131,6 +132,9 @@ public class TextElement {
         if let depth = patch.depth {
             self.depth = depth
         }
+        if let opacity = patch.opacity {
+            self.opacity = opacity
+        }
         
         // Apply new TextStyle
         if let styleBuffer = patch.style {
@@ -277,6 +281,7 @@ public class TextUpdatePatch {
     public var size_x: Float?
     public var size_y: Float?
     public var depth: UInt?
+    public var opacity: Float?
     public var style: TextStyleMessage?
     public var style_link: TextStyleMessage?
 
@@ -291,6 +296,7 @@ public class TextUpdatePatch {
         self.size_x = fb["size_x"]?.asFloat
         self.size_y = fb["size_y"]?.asFloat
         self.depth = fb["depth"]?.asUInt
+        self.opacity = fb["opacity"]?.asFloat
         
         if let styleBuffer = fb["style"], !styleBuffer.isNull {
             self.style = TextStyleMessage(styleBuffer)
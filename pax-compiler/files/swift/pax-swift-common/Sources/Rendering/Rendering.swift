@@ -67,6 +67,7 @@ public struct NativeRenderingLayer: View {
                         .frame(width: CGFloat(textElement.size_x), height: CGFloat(textElement.size_y), alignment: textElement.textStyle.alignment)
                         .position(x: CGFloat(textElement.size_x / 2.0), y: CGFloat(textElement.size_y / 2.0))
                         .transformEffect(transform)
+                        .opacity(Double(textElement.opacity))
                         .textSelection(.enabled)
 
 //
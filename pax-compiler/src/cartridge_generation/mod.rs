@@ -5,7 +5,10 @@
 
 use std::fs;
 
-use pax_manifest::{cartridge_generation::CommonProperty, PaxManifest};
+use pax_manifest::{
+    cartridge_generation::{expressions, CommonProperty},
+    PaxManifest,
+};
 
 use std::path::PathBuf;
 
@@ -21,7 +24,17 @@ pub fn generate_cartridge_partial_rs(
     merged_manifest: &PaxManifest,
     userland_manifest: &PaxManifest,
     designer_manifest: Option<PaxManifest>,
+    is_release: bool,
 ) -> PathBuf {
+    // release builds run compiled expressions, found by the ids assigned to the
+    // expressions of the manifest embedded in the cartridge
+    let mut userland_manifest = userland_manifest.clone();
+    let compiled_expressions = if is_release {
+        expressions::compile_expressions(&mut userland_manifest)
+    } else {
+        vec![]
+    };
+
     //press template into String
     let generated_lib_rs = templating::press_template_codegen_cartridge_snippet(
         templating::TemplateArgsCodegenCartridgeSnippet {
@@ -32,7 +45,8 @@ pub fn generate_cartridge_partial_rs(
             common_properties: CommonProperty::get_as_common_property(),
             type_table: userland_manifest.type_table.clone(),
             is_designtime: cfg!(feature = "designtime"),
-            userland_manifest_json: serde_json::to_string(userland_manifest).unwrap(),
+            compiled_expressions,
+            userland_manifest_json: serde_json::to_string(&userland_manifest).unwrap(),
             designer_manifest_json: if let Some(designer_manifest) = designer_manifest {
                 serde_json::to_string(&designer_manifest).unwrap()
             } else {
@@ -7,7 +7,9 @@ use std::collections::HashMap;
 use tera::{Context, Tera};
 
 use pax_manifest::{
-    cartridge_generation::{CommonProperty, ComponentInfo},
+    cartridge_generation::{
+        expressions::CompiledExpressionDefinition, CommonProperty, ComponentInfo,
+    },
     TypeTable,
 };
 
@@ -37,6 +39,9 @@ pub struct TemplateArgsCodegenCartridgeSnippet {
     // Whether this is a designtime cartridge
     pub is_designtime: bool,
 
+    // Expressions compiled to Rust indexed by id, registered by non-designtime cartridges; empty for debug builds
+    pub compiled_expressions: Vec<Option<CompiledExpressionDefinition>>,
+
     // JSON string representation of the manifest, used at least for designtime builds
     pub userland_manifest_json: String,
 
@@ -0,0 +1 @@
+pub mod source_map;
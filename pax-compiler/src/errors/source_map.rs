@@ -0,0 +1,70 @@
+//! Maps locations recorded in the manifest back to the source files they were parsed from,
+//! so that errors found after parsing can point at the offending line of a `.pax` file.
+
+use std::collections::HashMap;
+use std::fs;
+
+use pax_manifest::type_checking::ExpressionError;
+use pax_manifest::LocationInfo;
+
+#[derive(Default)]
+pub struct SourceMap {
+    /// Contents of each source file read so far, `None` if it couldn't be read
+    sources: HashMap<String, Option<String>>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Formats `error` like rustc does: `path:line:col: message`, followed by the
+    /// source line with a caret under the reported column
+    pub fn format_error(&mut self, error: &ExpressionError) -> String {
+        let component = error
+            .component
+            .get_pascal_identifier()
+            .unwrap_or_else(|| error.component.get_unique_identifier());
+        let (Some(path), Some(location)) = (&error.file_path, &error.location) else {
+            return format!("error in {}: {}", component, error.message);
+        };
+        let (line, col) = location.start_line_col;
+
+        // inlined templates are parsed from a token stream, so their locations are
+        // positions within that template rather than within the .rs file
+        if path.ends_with(".rs") {
+            return format!(
+                "{}: in the inlined template of {}, at {}:{}: {}",
+                path,
+                component,
+                line + 1,
+                col + 1,
+                error.message
+            );
+        }
+
+        let mut ret = format!("{}:{}:{}: {}", path, line + 1, col + 1, error.message);
+        if let Some(source_line) = self.get_line(path, location) {
+            let gutter = (line + 1).to_string();
+            ret += &format!(
+                "\n{} |\n{} | {}\n{} | {}^",
+                " ".repeat(gutter.len()),
+                gutter,
+                source_line,
+                " ".repeat(gutter.len()),
+                " ".repeat(col)
+            );
+        }
+        ret
+    }
+
+    fn get_line(&mut self, path: &str, location: &LocationInfo) -> Option<String> {
+        self.sources
+            .entry(path.to_string())
+            .or_insert_with(|| fs::read_to_string(path).ok())
+            .as_ref()?
+            .lines()
+            .nth(location.start_line_col.0)
+            .map(|line| line.to_string())
+    }
+}
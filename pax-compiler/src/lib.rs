@@ -15,6 +15,7 @@ extern crate serde;
 extern crate core;
 mod building;
 mod cartridge_generation;
+mod errors;
 pub mod helpers;
 
 pub mod design_server;
@@ -24,6 +25,8 @@ use color_eyre::eyre::Report;
 use eyre::eyre;
 use fs_extra::dir::{self, CopyOptions};
 use helpers::{copy_dir_recursively, wait_with_output, ERR_SPAWN};
+use pax_manifest::pax_runtime_api::Functions;
+use pax_manifest::type_checking::check_manifest;
 use pax_manifest::{
     ComponentDefinition, ComponentTemplate, PaxManifest, TemplateNodeDefinition, TypeId,
 };
@@ -37,6 +40,7 @@ use std::os::unix::process::CommandExt;
 use crate::building::build_project_with_cartridge;
 
 use crate::cartridge_generation::generate_cartridge_partial_rs;
+use crate::errors::source_map::SourceMap;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
@@ -133,6 +137,20 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
     // Simple starting convention: first manifest is userland, second manifest is designer; other schemas are undefined
     let mut userland_manifest = manifests.remove(0);
 
+    println!("{} 🔍 Checking expressions", *PAX_BADGE);
+    Functions::register_all_functions();
+    let expression_errors = check_manifest(&userland_manifest);
+    if !expression_errors.is_empty() {
+        let mut source_map = SourceMap::new();
+        for error in &expression_errors {
+            eprintln!("error: {}\n", source_map.format_error(error));
+        }
+        return Err(eyre!(
+            "Found {} error(s) in expressions",
+            expression_errors.len()
+        ));
+    }
+
     let mut merged_manifest = userland_manifest.clone();
 
     //Hack: add a wrapper component so UniqueTemplateNodeIdentifier is a suitable uniqueid, even for root nodes
@@ -177,8 +195,8 @@ pub fn perform_build(ctx: &RunContext) -> eyre::Result<(PaxManifest, Option<Path
         &merged_manifest,
         &userland_manifest,
         designer_manifest,
+        ctx.is_release,
     );
-    // source_map.extract_ranges_from_generated_code(cartridge_path.to_str().unwrap());
 
     //7. Build full project from source
     println!("{} 🧱 Building project with `cargo`", *PAX_BADGE);
@@ -1,8 +1,10 @@
 #![allow(unused_imports)]
 
 use ::core::f64;
+use std::cell::RefCell;
 
 use anyhow::anyhow;
+use pax_engine::api::properties::PropertyGraph;
 use pax_engine::{api::*, *};
 use pax_std::*;
 
@@ -11,6 +13,16 @@ pub use card::*;
 
 use crate::model;
 
+/// Typing this into the console reports the properties recomputed the most in a frame
+const HOTTEST_PROPERTIES_COMMAND: &str = "/properties";
+const HOTTEST_PROPERTIES_COUNT: usize = 10;
+
+thread_local! {
+    /// The property graph captured when the hottest properties were requested, and the frame
+    /// it was captured on. It's compared against the graph on the next frame.
+    static PROPERTY_BASELINE: RefCell<Option<(u64, PropertyGraph)>> = const { RefCell::new(None) };
+}
+
 #[pax]
 #[engine_import_path("pax_engine")]
 #[file("console/mod.pax")]
@@ -140,6 +152,13 @@ impl Console {
         });
         self.messages.set(messages);
         self.textbox.set("".to_string());
+        if request.trim() == HOTTEST_PROPERTIES_COMMAND {
+            let frame = ctx.frames_elapsed.get();
+            PROPERTY_BASELINE.with_borrow_mut(|baseline| {
+                *baseline = Some((frame, PropertyGraph::capture()));
+            });
+            return;
+        }
         let new_request_id = self.request_id.get() + 1;
         self.request_id.set(new_request_id);
         let mut dt = borrow_mut!(ctx.designtime);
@@ -149,13 +168,42 @@ impl Console {
         };
     }
 
-    pub fn update(&mut self, _ctx: &NodeContext) {
+    pub fn update(&mut self, ctx: &NodeContext) {
         if let Some(e) = self.enqueue_scroll_set.get() {
-            if e.frame == _ctx.frames_elapsed.get() {
+            if e.frame == ctx.frames_elapsed.get() {
                 self.scroll_y.set(e.scroll_y);
                 self.enqueue_scroll_set.set(None);
             }
         }
         self.external_message_listener.get();
+
+        let frame = ctx.frames_elapsed.get();
+        let baseline = PROPERTY_BASELINE.with_borrow_mut(|baseline| {
+            if baseline
+                .as_ref()
+                .is_some_and(|(captured, _)| *captured < frame)
+            {
+                baseline.take()
+            } else {
+                None
+            }
+        });
+        if let Some((_, baseline)) = baseline {
+            let graph = PropertyGraph::capture();
+            let mut text = "Properties recomputed the most last frame:\n".to_string();
+            for (node, recomputes) in graph.hottest_since(&baseline, HOTTEST_PROPERTIES_COUNT) {
+                let name = node.name.as_deref().unwrap_or("<unnamed>");
+                text += &format!(
+                    "\n- `{}` ({}): {} recomputes",
+                    name, node.type_name, recomputes
+                );
+            }
+            let mut messages = self.messages.get();
+            messages.push(Message {
+                message_type: MessageType::Diff,
+                text,
+            });
+            self.messages.set(messages);
+        }
     }
 }
@@ -241,6 +241,8 @@ pub fn toggle_value(value: ValueDefinition) -> anyhow::Result<ValueDefinition> {
         ValueDefinition::LiteralValue(value) => ValueDefinition::Expression(ExpressionInfo {
             expression: PaxExpression::Primary(Box::new(PaxPrimary::Literal(value))),
             dependencies: vec![],
+            location: None,
+            id: None,
         }),
         ValueDefinition::Block(_) => bail!("can't toggle block"),
         ValueDefinition::Expression(expr) => {
@@ -276,8 +278,10 @@ fn try_extract_pax_value(expr: PaxExpression) -> anyhow::Result<PaxValue> {
                     .collect::<anyhow::Result<_>>()?,
             ),
             PaxPrimary::FunctionOrEnum(_, _, _) => bail!("can't toggle function/enum"),
-            PaxPrimary::Range(_, _) => bail!("can't toggle range"),
+            PaxPrimary::Range { .. } => bail!("can't toggle range"),
             PaxPrimary::Tuple(_) => bail!("can't toggle tuple"),
+            PaxPrimary::Lambda(..) => bail!("can't toggle lambda"),
+            PaxPrimary::TemplateString(_) => bail!("can't toggle template string"),
             PaxPrimary::List(list) => PaxValue::Vec(
                 list.into_iter()
                     .map(|v| try_extract_pax_value(v))
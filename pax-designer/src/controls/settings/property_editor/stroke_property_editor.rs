@@ -99,14 +99,17 @@ impl StrokePropertyEditor {
         let external = self.external.clone();
         let data = self.data.clone();
         let ctxc = ctx.clone();
+        let old_stroke = self.stroke.clone();
         self.property_listener.replace_with(Property::computed(
             move || {
                 let color = color.get();
                 let stroke_width = stroke_width.get();
                 if !external.get() {
+                    // keeps the rest of the style, such as dashes, as is
                     let stroke = Stroke {
                         color: Property::new(color),
                         width: Property::new(Size::Pixels(stroke_width.into())),
+                        ..old_stroke.get()
                     };
                     if let Err(e) = data.get().set_value_typed(&ctxc, stroke) {
                         log::warn!("failed to set stroke: {e}");
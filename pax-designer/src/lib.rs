@@ -74,7 +74,7 @@ pub struct PaxDesigner {
 
 const OPEN_CONSOLE_HEIGHT: f64 = 425.0;
 const CLOSED_CONSOLE_HEIGHT: f64 = 55.0;
-const CONSOLE_TRANSITION_DURATION: u64 = 40;
+const CONSOLE_TRANSITION_DURATION: TransitionDuration = TransitionDuration::Frames(40);
 
 impl PaxDesigner {
     pub fn on_mount(&mut self, ctx: &NodeContext) {
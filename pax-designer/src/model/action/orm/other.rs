@@ -57,10 +57,11 @@ impl Action for SwapFillStrokeAction {
                     return Err(anyhow!("stroke or fill property type was unexpected"));
                 };
 
+                let old_stroke_paint = stroke.paint();
                 let new_stroke = Stroke {
                     color: Property::new(match &fill {
                         Fill::Solid(color) => color.clone(),
-                        // TODO when stroke supports gradient
+                        // what native elements draw instead of the gradient
                         Fill::LinearGradient(l) => l
                             .stops
                             .first()
@@ -72,10 +73,14 @@ impl Action for SwapFillStrokeAction {
                             .map(|gs| gs.color.clone())
                             .unwrap_or_default(),
                     }),
+                    fill: Property::new(match &fill {
+                        Fill::Solid(_) => None,
+                        gradient => Some(gradient.clone()),
+                    }),
                     ..stroke
                 };
                 node.set_property_from_typed("stroke", Some(new_stroke))?;
-                node.set_property_from_typed("fill", Some(stroke.color.get()))?;
+                node.set_property_from_typed("fill", Some(old_stroke_paint))?;
                 node.save()
                     .map_err(|e| anyhow!("failed to swap fill/stroke: {e}"))?;
             }
@@ -88,6 +88,7 @@ impl PaintbrushTool {
                         Some(Stroke {
                             color: Property::new(settings.stroke_color.clone()),
                             width: Property::new(Size::Pixels(settings.stroke_width.into())),
+                            ..Default::default()
                         }),
                     )?;
                     builder.set_property_from_typed("fill", Some(settings.fill_color.clone()))?;
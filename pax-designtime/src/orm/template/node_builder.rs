@@ -175,6 +175,8 @@ impl<'a> NodeBuilder<'a> {
                 ValueDefinition::LiteralValue(value) => ExpressionInfo {
                     expression: PaxExpression::Primary(Box::new(PaxPrimary::Literal(value))),
                     dependencies: vec![],
+                    location: None,
+                    id: None,
                 },
                 ValueDefinition::Expression(expression) => expression,
                 ValueDefinition::Identifier(identifier) => ExpressionInfo {
@@ -183,6 +185,8 @@ impl<'a> NodeBuilder<'a> {
                         vec![],
                     ))),
                     dependencies: vec![],
+                    location: None,
+                    id: None,
                 },
                 _ => return Err(anyhow!("a control flow source needs to be an expression")),
             };
@@ -227,6 +231,8 @@ impl<'a> NodeBuilder<'a> {
                 ValueDefinition::LiteralValue(value) => ExpressionInfo {
                     expression: PaxExpression::Primary(Box::new(PaxPrimary::Literal(value))),
                     dependencies: vec![],
+                    location: None,
+                    id: None,
                 },
                 ValueDefinition::Expression(expression) => expression,
                 ValueDefinition::Identifier(identifier) => ExpressionInfo {
@@ -235,6 +241,8 @@ impl<'a> NodeBuilder<'a> {
                         vec![],
                     ))),
                     dependencies: vec![],
+                    location: None,
+                    id: None,
                 },
                 _ => return Err(anyhow!("a control flow source needs to be an expression")),
             };
@@ -365,6 +373,10 @@ impl<'a> NodeBuilder<'a> {
                             .control_flow_updates
                             .slot_index_expression
                             .flatten(),
+                        repeat_key_expression: None,
+                        match_expression: None,
+                        match_arm_pattern: None,
+                        is_else_branch: false,
                     };
 
                     NodeType::ControlFlow(Box::new(control_flow_settings_defintion))
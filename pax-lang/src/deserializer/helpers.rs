@@ -1,4 +1,4 @@
-use pax_runtime_api::constants::INTEGER;
+use pax_runtime_api::constants::{INTEGER, MILLIS};
 use pest::iterators::{Pair, Pairs};
 use serde::{
     de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor},
@@ -353,11 +353,26 @@ impl<'de> MapAccess<'de> for PaxObject<'de> {
 pub struct PaxNumeric<'de> {
     ast: Pair<'de, Rule>,
     wrap_in_enum: bool,
+    /// Factor a duration is multiplied by to get milliseconds
+    scale: Option<f64>,
 }
 
 impl<'de> PaxNumeric<'de> {
     pub fn new(ast: Pair<'de, Rule>, wrap_in_enum: bool) -> Self {
-        PaxNumeric { ast, wrap_in_enum }
+        PaxNumeric {
+            ast,
+            wrap_in_enum,
+            scale: None,
+        }
+    }
+
+    /// A `TransitionDuration::Millis` of the number multiplied by `scale`, e.g. 1000 for `0.3s`
+    pub fn millis(ast: Pair<'de, Rule>, scale: f64) -> Self {
+        PaxNumeric {
+            ast,
+            wrap_in_enum: true,
+            scale: Some(scale),
+        }
     }
 }
 
@@ -371,6 +386,7 @@ impl<'de> EnumAccess<'de> for PaxNumeric<'de> {
         V: de::DeserializeSeed<'de>,
     {
         let variant_name = match self.ast.as_rule() {
+            _ if self.scale.is_some() => MILLIS,
             Rule::literal_number_float => "F64",
             Rule::literal_number_integer => "I64",
             _ => return Err(Error::Message("Unsupported numeric type".to_string())),
@@ -434,6 +450,9 @@ impl<'de> de::Deserializer<'de> for PaxNumeric<'de> {
             return visitor.visit_enum(self);
         }
 
+        if let Some(scale) = self.scale {
+            return visitor.visit_f64(self.ast.as_str().trim().parse::<f64>().unwrap() * scale);
+        }
         match self.ast.as_rule() {
             Rule::literal_number_float => {
                 visitor.visit_f64(self.ast.as_str().trim().parse::<f64>().unwrap())
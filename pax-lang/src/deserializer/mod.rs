@@ -14,7 +14,7 @@ use self::helpers::{ColorChannelInteger, PaxEnum, PaxObject, PaxSeq};
 pub use error::{Error, Result};
 
 use pax_runtime_api::constants::{
-    COLOR, DEGREES, NUMERIC, PERCENT, PIXELS, RADIANS, ROTATION, SIZE,
+    COLOR, DEGREES, DURATION, NUMERIC, PERCENT, PIXELS, RADIANS, ROTATION, SIZE,
 };
 
 const STRING: &str = "String";
@@ -85,6 +85,9 @@ impl<'de> PaxDeserializer<'de> {
                     "px" => visitor.visit_enum(PaxEnum::new_pax_value(SIZE, Some(self.ast))),
                     "rad" => visitor.visit_enum(PaxEnum::new_pax_value(ROTATION, Some(self.ast))),
                     "deg" => visitor.visit_enum(PaxEnum::new_pax_value(ROTATION, Some(self.ast))),
+                    "ms" | "s" => {
+                        visitor.visit_enum(PaxEnum::new_pax_value(DURATION, Some(self.ast)))
+                    }
                     _ => {
                         unreachable!("Unsupported unit: {}", unit)
                     }
@@ -184,6 +187,15 @@ impl<'de> PaxDeserializer<'de> {
                     "px" => visitor.visit_enum(PaxEnum::new(PIXELS, number)),
                     "rad" => visitor.visit_enum(PaxEnum::new(RADIANS, number)),
                     "deg" => visitor.visit_enum(PaxEnum::new(DEGREES, number)),
+                    // durations are kept in milliseconds
+                    "ms" => {
+                        let number = number.unwrap().into_inner().next().unwrap();
+                        visitor.visit_enum(PaxNumeric::millis(number, 1.0))
+                    }
+                    "s" => {
+                        let number = number.unwrap().into_inner().next().unwrap();
+                        visitor.visit_enum(PaxNumeric::millis(number, 1000.0))
+                    }
                     _ => Err(Error::Message(format!("Unsupported unit: {}", unit))),
                 }
             }
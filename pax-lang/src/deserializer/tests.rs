@@ -3,6 +3,7 @@
 use pax_runtime_api::{
     pax_value::{CoercionRules, ToFromPaxAny},
     Color, ColorChannel, ImplToFromPaxAny, Numeric, PaxValue, Percent, Rotation, Size,
+    TransitionDuration,
 };
 use serde::Deserialize;
 
@@ -48,6 +49,14 @@ fn test_radians() {
     assert_eq!(expected, v);
 }
 
+#[test]
+fn test_durations() {
+    let expected = PaxValue::Duration(TransitionDuration::Millis(300.0));
+    assert_eq!(expected, from_pax("300ms").unwrap());
+    let expected = PaxValue::Duration(TransitionDuration::Millis(1500.0));
+    assert_eq!(expected, from_pax("1.5s").unwrap());
+}
+
 #[test]
 fn test_string() {
     let string_pax = "\"hello\"".to_string();
@@ -28,14 +28,16 @@ pub const INFIX_OPERATORS: [Rule; 16] = [
 ];
 
 #[cfg(feature = "parser")]
-pub const _PRIMARY_OPERANDS: [Rule; 9] = [
+pub const _PRIMARY_OPERANDS: [Rule; 11] = [
     Rule::expression_grouped,
+    Rule::xo_lambda,
     Rule::xo_enum_or_function_call,
     Rule::xo_color_space_func,
     Rule::xo_object,
     Rule::xo_range,
     Rule::xo_tuple,
     Rule::xo_list,
+    Rule::xo_template_string,
     Rule::xo_literal,
     Rule::xo_symbol,
 ];
@@ -91,6 +93,8 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         Rule::settings_block_declaration => vec![Box::new(SettingsBlockDeclarationDefaultRule)],
         Rule::settings_event_binding => vec![Box::new(SettingsEventBindingDefaultRule)],
         Rule::selector_block => vec![Box::new(SelectorBlockDefaultRule)],
+        Rule::selector => vec![Box::new(SelectorDefaultRule)],
+        Rule::complex_selector => vec![Box::new(ComplexSelectorDefaultRule)],
         Rule::literal_object | Rule::xo_object => vec![Box::new(ObjectDefaultRule)],
         Rule::settings_key_value_pair => vec![Box::new(SettingsKeyValuePairDefaultRule)],
         Rule::literal_function => vec![Box::new(LiteralFunctionDefaultRule)],
@@ -124,8 +128,15 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
             vec![Box::new(XoObjectSettingsKeyValuePairDefaultRule)]
         }
         Rule::statement_for => vec![Box::new(StatementForDefaultRule)],
+        Rule::statement_for_key => vec![Box::new(StatementForKeyDefaultRule)],
         Rule::statement_if => vec![Box::new(StatementIfDefaultRule)],
+        Rule::statement_else_if => vec![Box::new(StatementElseIfDefaultRule)],
+        Rule::statement_else => vec![Box::new(StatementElseDefaultRule)],
         Rule::statement_slot => vec![Box::new(StatementSlotDefaultRule)],
+        Rule::statement_match => vec![Box::new(StatementMatchDefaultRule)],
+        Rule::statement_match_arm => vec![Box::new(StatementMatchArmDefaultRule)],
+        Rule::statement_match_pattern => vec![Box::new(StatementMatchPatternDefaultRule)],
+        Rule::statement_match_guard => vec![Box::new(StatementMatchGuardDefaultRule)],
         Rule::any_template_value | Rule::node_inner_content | Rule::settings_value => {
             vec![Box::new(WrapExpressionRule), Box::new(ForwardRule)]
         }
@@ -134,7 +145,10 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::literal_value
         | Rule::statement_control_flow => vec![Box::new(ForwardRule)],
 
-        Rule::selector
+        Rule::compound_selector
+        | Rule::selector_qualifier
+        | Rule::pseudo_class
+        | Rule::event_phase
         | Rule::settings_key
         | Rule::literal_number_with_unit
         | Rule::literal_number
@@ -154,6 +168,8 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::pascal_identifier
         | Rule::statement_for_predicate_declaration
         | Rule::statement_for_source
+        | Rule::statement_match_wildcard
+        | Rule::statement_match_variant
         | Rule::comment
         | Rule::xo_neg
         | Rule::xo_bool_not
@@ -177,7 +193,13 @@ fn get_formatting_rules(pest_rule: Rule) -> Vec<Box<dyn FormattingRule>> {
         | Rule::literal_color_const
         | Rule::literal_some
         | Rule::literal_none
-        | Rule::xo_range_exclusive => vec![Box::new(PrintRule)],
+        | Rule::xo_range_exclusive
+        | Rule::xo_range_inclusive
+        | Rule::xo_range_step
+        | Rule::xo_lambda
+        | Rule::xo_template_string
+        | Rule::xo_template_text
+        | Rule::xo_template_expression => vec![Box::new(PrintRule)],
 
         Rule::expression_wrapped
         | Rule::xo_primary
@@ -450,6 +472,39 @@ impl FormattingRule for SelectorBlockDefaultRule {
     }
 }
 
+#[derive(Clone)]
+struct SelectorDefaultRule;
+
+impl FormattingRule for SelectorDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        children
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+#[derive(Clone)]
+struct ComplexSelectorDefaultRule;
+
+impl FormattingRule for ComplexSelectorDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let (pseudo_classes, compounds): (Vec<&Child>, Vec<&Child>) = children
+            .iter()
+            .partition(|child| child.node_type == Rule::pseudo_class);
+        let mut formatted = compounds
+            .iter()
+            .map(|child| child.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join(" ");
+        for pseudo_class in pseudo_classes {
+            formatted.push_str(&pseudo_class.formatted_node);
+        }
+        formatted
+    }
+}
+
 #[derive(Clone)]
 struct RemoveWhitespaceRule;
 
@@ -842,8 +897,13 @@ impl FormattingRule for StatementForDefaultRule {
     fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
         let mut formatted_node = String::new();
         let sfpd = children[0].formatted_node.clone();
-        let sfs = children[1].formatted_node.clone();
-        let inner_nodes = children[2].formatted_node.clone();
+        let mut sfs = children[1].formatted_node.clone();
+        if children.len() > 3 {
+            // `key ...` clause
+            sfs.push_str(" ");
+            sfs.push_str(&children[2].formatted_node);
+        }
+        let inner_nodes = children[children.len() - 1].formatted_node.clone();
         let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
         formatted_node
             .push_str(format!("for {} in {} {{\n{}\n}}", sfpd, sfs, inner_nodes_indented).as_str());
@@ -851,6 +911,15 @@ impl FormattingRule for StatementForDefaultRule {
     }
 }
 
+#[derive(Clone)]
+struct StatementForKeyDefaultRule;
+
+impl FormattingRule for StatementForKeyDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        format!("key {}", children[0].formatted_node)
+    }
+}
+
 #[derive(Clone)]
 struct StatementIfDefaultRule;
 
@@ -861,6 +930,103 @@ impl FormattingRule for StatementIfDefaultRule {
         let inner_nodes = children[1].formatted_node.clone();
         let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
         formatted_node.push_str(format!("if {} {{\n{}\n}}", exp, inner_nodes_indented).as_str());
+        // chained `else if` / `else` branches continue on the closing brace's line
+        for branch in &children[2..] {
+            formatted_node.push_str(" ");
+            formatted_node.push_str(&branch.formatted_node);
+        }
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementElseIfDefaultRule;
+
+impl FormattingRule for StatementElseIfDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = String::new();
+        let exp = children[0].formatted_node.clone();
+        let inner_nodes = children[1].formatted_node.clone();
+        let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        formatted_node
+            .push_str(format!("else if {} {{\n{}\n}}", exp, inner_nodes_indented).as_str());
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementElseDefaultRule;
+
+impl FormattingRule for StatementElseDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = String::new();
+        let inner_nodes = children[0].formatted_node.clone();
+        let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        formatted_node.push_str(format!("else {{\n{}\n}}", inner_nodes_indented).as_str());
+        formatted_node
+    }
+}
+
+#[derive(Clone)]
+struct StatementMatchDefaultRule;
+
+impl FormattingRule for StatementMatchDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let exp = children[0].formatted_node.clone();
+        let arms = children[1..]
+            .iter()
+            .map(|arm| arm.formatted_node.clone())
+            .collect::<Vec<String>>()
+            .join("\n");
+        if arms.is_empty() {
+            return format!("match {} {{}}", exp);
+        }
+        let arms_indented = indent_every_line_of_string(arms);
+        format!("match {} {{\n{}\n}}", exp, arms_indented)
+    }
+}
+
+#[derive(Clone)]
+struct StatementMatchArmDefaultRule;
+
+impl FormattingRule for StatementMatchArmDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let pattern = children[0].formatted_node.clone();
+        // the guard, if any, sits between the pattern and the inner nodes
+        let guard = children[1..children.len() - 1]
+            .iter()
+            .map(|guard| format!(" {}", guard.formatted_node))
+            .collect::<String>();
+        let inner_nodes = children[children.len() - 1].formatted_node.clone();
+        let inner_nodes_indented = indent_every_line_of_string(inner_nodes);
+        format!("{}{} => {{\n{}\n}}", pattern, guard, inner_nodes_indented)
+    }
+}
+
+#[derive(Clone)]
+struct StatementMatchGuardDefaultRule;
+
+impl FormattingRule for StatementMatchGuardDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        format!("if {}", children[0].formatted_node)
+    }
+}
+
+#[derive(Clone)]
+struct StatementMatchPatternDefaultRule;
+
+impl FormattingRule for StatementMatchPatternDefaultRule {
+    fn format(&self, _node: Pair<Rule>, children: Vec<Child>) -> String {
+        let mut formatted_node = children[0].formatted_node.clone();
+        // the enum payload bindings, if any
+        if children.len() > 1 {
+            let bindings = children[1..]
+                .iter()
+                .map(|binding| binding.formatted_node.clone())
+                .collect::<Vec<String>>()
+                .join(", ");
+            formatted_node.push_str(&format!("({})", bindings));
+        }
         formatted_node
     }
 }
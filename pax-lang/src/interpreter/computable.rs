@@ -1,12 +1,12 @@
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
-use pax_runtime_api::{
-    functions::call_function, CoercionRules, Functions, Numeric, PaxValue, Percent, Rotation, Size,
-};
+use pax_runtime_api::{functions::call_function, PaxFunction, PaxValue};
 
 use super::{
-    property_resolution::IdentifierResolver, PaxAccessor, PaxExpression, PaxIdentifier, PaxInfix,
-    PaxPostfix, PaxPrefix, PaxPrimary, PaxUnit,
+    evaluation,
+    property_resolution::{DependencyCollector, IdentifierResolver},
+    PaxAccessor, PaxExpression, PaxIdentifier, PaxInfix, PaxPostfix, PaxPrefix, PaxPrimary,
+    TemplateSegment,
 };
 
 /// Trait for expression types that can be computed to a value
@@ -33,51 +33,13 @@ impl Computable for PaxPrimary {
                 .resolve(i.name.clone())?
                 .read_pax_value_ref(|mut value| {
                     for accessor in accessors {
-                        match accessor {
-                            PaxAccessor::Tuple(index) => {
-                                if let PaxValue::Vec(v) = value {
-                                    value = v.into_iter().nth(*index).ok_or_else(|| {
-                                        format!(
-                                            "paxel interpreter: tuple index out of bounds: {:?}",
-                                            index
-                                        )
-                                    })?;
-                                } else {
-                                    return Err(
-                                        "Tuple access must be performed on a tuple".to_string()
-                                    );
-                                }
-                            }
+                        value = match accessor {
+                            PaxAccessor::Tuple(index) => evaluation::access_tuple(value, *index)?,
                             PaxAccessor::List(index) => {
-                                if let PaxValue::Vec(v) = value {
-                                    let index = Numeric::try_coerce(index.compute(idr.clone())?)?
-                                        .to_int()
-                                        as usize;
-                                    value = v.into_iter().nth(index).ok_or_else(|| {
-                                        format!(
-                                            "paxel interpreter: list index out of bounds: {:?}",
-                                            index
-                                        )
-                                    })?;
-                                } else {
-                                    return Err(
-                                        "List access must be performed on a list".to_string()
-                                    );
-                                }
+                                evaluation::access_list(value, index.compute(idr.clone())?)?
                             }
-                            PaxAccessor::Struct(field) => {
-                                if let PaxValue::Object(obj) = value {
-                                    value = obj
-                                        .into_iter()
-                                        .find_map(|(n, v)| (n == field).then_some(v))
-                                        .ok_or(format!("Field not found: {}", field))?;
-                                } else {
-                                    return Err(
-                                        "Struct access must be performed on an object".to_string()
-                                    );
-                                }
-                            }
-                        }
+                            PaxAccessor::Struct(field) => evaluation::access_field(value, field)?,
+                        };
                     }
                     Ok(value.clone())
                 }),
@@ -86,10 +48,55 @@ impl Computable for PaxPrimary {
                     .map(|(k, v)| Result::<_, String>::Ok((k.clone(), v.compute(idr.clone())?)))
                     .collect::<Result<_, _>>()?,
             )),
-            PaxPrimary::Range(start, end) => {
+            PaxPrimary::Range {
+                start,
+                end,
+                inclusive,
+                step,
+            } => {
                 let start = start.compute(idr.clone())?;
-                let end = end.compute(idr)?;
-                Ok(PaxValue::Range(Box::new(start), Box::new(end)))
+                let end = end.compute(idr.clone())?;
+                let step = step.as_ref().map(|s| s.compute(idr)).transpose()?;
+                evaluation::build_range(start, end, *inclusive, step)
+            }
+            PaxPrimary::Lambda(params, body) => {
+                // free variables are captured by value: the enclosing expression
+                // depends on them, and is recomputed when they change
+                let mut captured = HashMap::new();
+                for name in self.collect_dependencies() {
+                    if let Ok(variable) = idr.resolve(name.clone()) {
+                        captured.insert(name, variable.get_as_pax_value());
+                    }
+                }
+                let params = params.clone();
+                let body = body.clone();
+                Ok(PaxValue::Function(PaxFunction::new(
+                    self.to_string(),
+                    move |args| {
+                        if args.len() != params.len() {
+                            return Err(format!(
+                                "Expected {} arguments for lambda, found {}",
+                                params.len(),
+                                args.len()
+                            ));
+                        }
+                        let mut scope = captured.clone();
+                        scope.extend(params.iter().cloned().zip(args));
+                        body.compute(Rc::new(scope))
+                    },
+                )))
+            }
+            PaxPrimary::TemplateString(segments) => {
+                let mut string = String::new();
+                for segment in segments {
+                    match segment {
+                        TemplateSegment::Text { text, .. } => string.push_str(text),
+                        TemplateSegment::Expression(e) => {
+                            evaluation::interpolate(&mut string, e.compute(idr.clone())?)
+                        }
+                    }
+                }
+                Ok(PaxValue::String(string))
             }
             PaxPrimary::Tuple(t) => {
                 let tuple = t
@@ -107,20 +114,9 @@ impl Computable for PaxPrimary {
             }
             PaxPrimary::Grouped(expr, unit) => {
                 let expr_val = expr.compute(idr.clone())?;
-                if let Some(unit) = unit {
-                    match Numeric::try_coerce(expr_val) {
-                        Ok(n) => match unit {
-                            PaxUnit::Percent => Ok(PaxValue::Percent(Percent(n))),
-                            PaxUnit::Pixels => Ok(PaxValue::Size(Size::Pixels(n))),
-                            PaxUnit::Radians => Ok(PaxValue::Rotation(Rotation::Radians(n))),
-                            PaxUnit::Degrees => Ok(PaxValue::Rotation(Rotation::Degrees(n))),
-                        },
-                        Err(e) => Err(format!(
-                            "A grouped expression with a unit must be of type numeric: {e:?}"
-                        )),
-                    }
-                } else {
-                    Ok(expr_val)
+                match unit {
+                    Some(unit) => evaluation::apply_unit(expr_val, unit),
+                    None => Ok(expr_val),
                 }
             }
             PaxPrimary::FunctionOrEnum(scope, name_or_variant, args) => {
@@ -129,15 +125,7 @@ impl Computable for PaxPrimary {
                     .map(|a| a.compute(idr.clone()))
                     .collect::<Result<Vec<PaxValue>, String>>()?;
 
-                if Functions::has_function(scope, name_or_variant) {
-                    return call_function(scope.clone(), name_or_variant.clone(), args);
-                }
-
-                Ok(PaxValue::Enum(Box::new((
-                    scope.clone(),
-                    name_or_variant.clone(),
-                    args,
-                ))))
+                evaluation::call_function_or_enum(scope, name_or_variant, args)
             }
         }
     }
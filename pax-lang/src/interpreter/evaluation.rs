@@ -0,0 +1,106 @@
+//! Evaluation steps shared by the interpreter and by the Rust code `pax-compiler`
+//! generates for expressions in release builds, so both produce the same values
+//! and errors.
+
+use pax_runtime_api::{
+    functions::call_function, CoercionRules, Functions, Numeric, PaxRange, PaxValue, Percent,
+    Rotation, Size, TransitionDuration,
+};
+
+use super::PaxUnit;
+
+/// `value.0`
+pub fn access_tuple(value: &PaxValue, index: usize) -> Result<&PaxValue, String> {
+    if let PaxValue::Vec(v) = value {
+        v.get(index)
+            .ok_or_else(|| format!("paxel interpreter: tuple index out of bounds: {:?}", index))
+    } else {
+        Err("Tuple access must be performed on a tuple".to_string())
+    }
+}
+
+/// `value[index]`
+pub fn access_list(value: &PaxValue, index: PaxValue) -> Result<&PaxValue, String> {
+    if let PaxValue::Vec(v) = value {
+        let index = Numeric::try_coerce(index)?.to_int() as usize;
+        v.get(index)
+            .ok_or_else(|| format!("paxel interpreter: list index out of bounds: {:?}", index))
+    } else {
+        Err("List access must be performed on a list".to_string())
+    }
+}
+
+/// `value.field`
+pub fn access_field<'a>(value: &'a PaxValue, field: &str) -> Result<&'a PaxValue, String> {
+    if let PaxValue::Object(obj) = value {
+        obj.iter()
+            .find_map(|(n, v)| (n == field).then_some(v))
+            .ok_or(format!("Field not found: {}", field))
+    } else {
+        Err("Struct access must be performed on an object".to_string())
+    }
+}
+
+/// `start..end`, `start..=end` and `start..end step n`
+pub fn build_range(
+    start: PaxValue,
+    end: PaxValue,
+    inclusive: bool,
+    step: Option<PaxValue>,
+) -> Result<PaxValue, String> {
+    let step = step.map(isize::try_coerce).transpose()?.unwrap_or(1);
+    let range = PaxRange::new(
+        isize::try_coerce(start)?,
+        isize::try_coerce(end)?,
+        inclusive,
+        step,
+    )?;
+    Ok(PaxValue::Range(Box::new(range)))
+}
+
+/// `(value)%`, `(value)px`, `(value)rad` and `(value)deg`, and durations `(value)ms` and
+/// `(value)s`, which are kept in milliseconds
+pub fn apply_unit(value: PaxValue, unit: &PaxUnit) -> Result<PaxValue, String> {
+    match Numeric::try_coerce(value) {
+        Ok(n) => match unit {
+            PaxUnit::Percent => Ok(PaxValue::Percent(Percent(n))),
+            PaxUnit::Pixels => Ok(PaxValue::Size(Size::Pixels(n))),
+            PaxUnit::Radians => Ok(PaxValue::Rotation(Rotation::Radians(n))),
+            PaxUnit::Degrees => Ok(PaxValue::Rotation(Rotation::Degrees(n))),
+            PaxUnit::Milliseconds => {
+                Ok(PaxValue::Duration(TransitionDuration::Millis(n.to_float())))
+            }
+            PaxUnit::Seconds => Ok(PaxValue::Duration(TransitionDuration::Millis(
+                n.to_float() * 1000.0,
+            ))),
+        },
+        Err(e) => Err(format!(
+            "A grouped expression with a unit must be of type numeric: {e:?}"
+        )),
+    }
+}
+
+/// `Scope::name(args)`: a call if a function is registered under that name,
+/// an enum variant otherwise
+pub fn call_function_or_enum(
+    scope: &str,
+    name_or_variant: &str,
+    args: Vec<PaxValue>,
+) -> Result<PaxValue, String> {
+    if Functions::has_function(scope, name_or_variant) {
+        return call_function(scope.to_string(), name_or_variant.to_string(), args);
+    }
+    Ok(PaxValue::Enum(Box::new((
+        scope.to_string(),
+        name_or_variant.to_string(),
+        args,
+    ))))
+}
+
+/// Appends an interpolated `${value}` to a template string
+pub fn interpolate(string: &mut String, value: PaxValue) {
+    match value {
+        PaxValue::String(s) => string.push_str(&s),
+        value => string.push_str(&value.to_string()),
+    }
+}
@@ -12,6 +12,7 @@ use std::rc::Rc;
 use crate::{deserializer::from_pax_ast, get_pax_pratt_parser, parse_pax_pairs, Rule};
 
 pub(crate) mod computable;
+pub mod evaluation;
 pub mod property_resolution;
 #[cfg(test)]
 mod tests;
@@ -48,9 +49,31 @@ pub enum PaxPrimary {
     Identifier(PaxIdentifier, Vec<PaxAccessor>),
     Object(Vec<(String, PaxExpression)>),
     FunctionOrEnum(String, String, Vec<PaxExpression>),
-    Range(PaxExpression, PaxExpression),
+    Range {
+        start: PaxExpression,
+        end: PaxExpression,
+        /// `..=` rather than `..`
+        inclusive: bool,
+        /// The `2` in `0..10 step 2`
+        step: Option<PaxExpression>,
+    },
     Tuple(Vec<PaxExpression>),
     List(Vec<PaxExpression>),
+    /// A lambda like `|x| x * 2`: its parameter names and body
+    Lambda(Vec<String>, Box<PaxExpression>),
+    /// A backtick string with interpolated expressions, like `Total: ${count}`
+    TemplateString(Vec<TemplateSegment>),
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub enum TemplateSegment {
+    /// Literal text, with its escape sequences decoded into `text`. `raw` is the text
+    /// as written, and is only used to display the expression again
+    Text {
+        raw: String,
+        text: String,
+    },
+    Expression(PaxExpression),
 }
 
 impl Display for PaxPrimary {
@@ -68,6 +91,8 @@ impl Display for PaxPrimary {
                             PaxUnit::Pixels => "px",
                             PaxUnit::Radians => "rad",
                             PaxUnit::Degrees => "deg",
+                            PaxUnit::Milliseconds => "ms",
+                            PaxUnit::Seconds => "s",
                         }
                     )
                 } else {
@@ -116,7 +141,24 @@ impl Display for PaxPrimary {
                 }
                 Ok(())
             }
-            PaxPrimary::Range(s, e) => write!(f, "{}..{}", s, e),
+            PaxPrimary::Range {
+                start,
+                end,
+                inclusive,
+                step,
+            } => {
+                write!(
+                    f,
+                    "{}{}{}",
+                    start,
+                    if *inclusive { "..=" } else { ".." },
+                    end
+                )?;
+                if let Some(step) = step {
+                    write!(f, " step {}", step)?;
+                }
+                Ok(())
+            }
             PaxPrimary::Tuple(t) => {
                 write!(f, "(")?;
                 for (i, e) in t.iter().enumerate() {
@@ -139,6 +181,17 @@ impl Display for PaxPrimary {
                 write!(f, "]")?;
                 Ok(())
             }
+            PaxPrimary::Lambda(params, body) => write!(f, "|{}| {}", params.join(", "), body),
+            PaxPrimary::TemplateString(segments) => {
+                write!(f, "`")?;
+                for segment in segments {
+                    match segment {
+                        TemplateSegment::Text { raw, .. } => write!(f, "{}", raw)?,
+                        TemplateSegment::Expression(e) => write!(f, "${{{}}}", e)?,
+                    }
+                }
+                write!(f, "`")
+            }
         }
     }
 }
@@ -155,6 +208,8 @@ pub enum PaxUnit {
     Pixels,
     Radians,
     Degrees,
+    Milliseconds,
+    Seconds,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
@@ -166,26 +221,26 @@ pub enum PaxAccessor {
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct PaxPrefix {
-    operator: PaxOperator,
-    rhs: Box<PaxExpression>,
+    pub operator: PaxOperator,
+    pub rhs: Box<PaxExpression>,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct PaxInfix {
-    operator: PaxOperator,
-    lhs: Box<PaxExpression>,
-    rhs: Box<PaxExpression>,
+    pub operator: PaxOperator,
+    pub lhs: Box<PaxExpression>,
+    pub rhs: Box<PaxExpression>,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct PaxPostfix {
-    operator: PaxOperator,
-    lhs: Box<PaxExpression>,
+    pub operator: PaxOperator,
+    pub lhs: Box<PaxExpression>,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct PaxOperator {
-    name: String,
+    pub name: String,
 }
 
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
@@ -207,6 +262,29 @@ impl PaxIdentifier {
     }
 }
 
+/// Decodes the escape sequences of the text of a template string: `` \` ``, `\$`, `\\`,
+/// `\n`, `\r`, `\t`, `\0`, `\'` and `\"`
+fn unescape_template_text(raw: &str) -> Result<String, String> {
+    let mut text = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        text.push(match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some(c @ ('`' | '$' | '\\' | '\'' | '"')) => c,
+            Some(c) => return Err(format!("unknown escape \\{} in template string", c)),
+            None => return Err("template string ends with a lone \\".to_string()),
+        });
+    }
+    Ok(text)
+}
+
 /// Parse a pax expression into a computable AST
 pub fn parse_pax_expression(expr: &str) -> Result<PaxExpression, String> {
     let parsed_expr = parse_pax_pairs(Rule::expression_body, expr)
@@ -288,6 +366,14 @@ fn recurse_pratt_parse(
                             Box::new(expr_val),
                             Some(PaxUnit::Degrees),
                         )))),
+                        "ms" => Ok(PaxExpression::Primary(Box::new(PaxPrimary::Grouped(
+                            Box::new(expr_val),
+                            Some(PaxUnit::Milliseconds),
+                        )))),
+                        "s" => Ok(PaxExpression::Primary(Box::new(PaxPrimary::Grouped(
+                            Box::new(expr_val),
+                            Some(PaxUnit::Seconds),
+                        )))),
                         _ => Err(format!("Unsupported unit: {}", unit)),
                     }
                 } else {
@@ -362,11 +448,55 @@ fn recurse_pratt_parse(
                 let mut inner = primary.into_inner();
                 let start_rule = Pairs::single(inner.next().unwrap());
                 let start = recurse_pratt_parse(start_rule, pratt_parser)?;
-                // xo_range_exclusive
-                inner.next();
+                // xo_range_exclusive | xo_range_inclusive
+                let inclusive = inner.next().unwrap().as_rule() == Rule::xo_range_inclusive;
                 let end_rule = Pairs::single(inner.next().unwrap());
                 let end = recurse_pratt_parse(end_rule, pratt_parser)?;
-                let value = PaxPrimary::Range(start, end);
+                let step = if let Some(step) = inner.next() {
+                    // xo_range_step = { "step" ~ (xo_literal | xo_symbol) }
+                    let step_rule = Pairs::single(step.into_inner().next().unwrap());
+                    Some(recurse_pratt_parse(step_rule, pratt_parser)?)
+                } else {
+                    None
+                };
+                let value = PaxPrimary::Range {
+                    start,
+                    end,
+                    inclusive,
+                    step,
+                };
+                let exp = PaxExpression::Primary(Box::new(value));
+                Ok(exp)
+            }
+            Rule::xo_lambda => {
+                let mut inner = primary.into_inner();
+                let mut params = vec![];
+                while inner.peek().unwrap().as_rule() == Rule::identifier {
+                    params.push(inner.next().unwrap().as_str().trim().to_string());
+                }
+                let body = recurse_pratt_parse(inner.next().unwrap().into_inner(), pratt_parser)?;
+                let value = PaxPrimary::Lambda(params, Box::new(body));
+                let exp = PaxExpression::Primary(Box::new(value));
+                Ok(exp)
+            }
+            Rule::xo_template_string => {
+                let segments = primary
+                    .into_inner()
+                    .map(|segment| match segment.as_rule() {
+                        Rule::xo_template_expression => {
+                            let body = segment.into_inner().next().unwrap();
+                            Ok(TemplateSegment::Expression(recurse_pratt_parse(
+                                body.into_inner(),
+                                pratt_parser,
+                            )?))
+                        }
+                        _ => Ok(TemplateSegment::Text {
+                            raw: segment.as_str().to_string(),
+                            text: unescape_template_text(segment.as_str())?,
+                        }),
+                    })
+                    .collect::<Result<Vec<TemplateSegment>, String>>()?;
+                let value = PaxPrimary::TemplateString(segments);
                 let exp = PaxExpression::Primary(Box::new(value));
                 Ok(exp)
             }
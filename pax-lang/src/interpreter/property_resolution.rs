@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use pax_runtime_api::{PaxValue, Property, Variable};
 
-use super::{PaxExpression, PaxInfix, PaxPostfix, PaxPrefix, PaxPrimary};
+use super::{
+    PaxAccessor, PaxExpression, PaxInfix, PaxPostfix, PaxPrefix, PaxPrimary, TemplateSegment,
+};
 
 /// Trait for resolving identifiers to values
 /// This is implemented by RuntimePropertyStackFrame
@@ -30,7 +32,15 @@ impl DependencyCollector for PaxPrimary {
         let ret = match self {
             PaxPrimary::Literal(_) => vec![],
             PaxPrimary::Grouped(expr, _) => expr.collect_dependencies(),
-            PaxPrimary::Identifier(i, _) => vec![i.name.clone()],
+            PaxPrimary::Identifier(i, accessors) => {
+                let mut deps = vec![i.name.clone()];
+                for accessor in accessors {
+                    if let PaxAccessor::List(index) = accessor {
+                        deps.extend(index.collect_dependencies());
+                    }
+                }
+                deps
+            }
             PaxPrimary::Object(o) => o
                 .iter()
                 .flat_map(|(_, v)| v.collect_dependencies())
@@ -38,13 +48,31 @@ impl DependencyCollector for PaxPrimary {
             PaxPrimary::FunctionOrEnum(_, _, args) => {
                 args.iter().flat_map(|a| a.collect_dependencies()).collect()
             }
-            PaxPrimary::Range(start, end) => {
+            PaxPrimary::Range {
+                start, end, step, ..
+            } => {
                 let mut deps = start.collect_dependencies();
                 deps.extend(end.collect_dependencies());
+                if let Some(step) = step {
+                    deps.extend(step.collect_dependencies());
+                }
                 deps
             }
             PaxPrimary::Tuple(t) => t.iter().flat_map(|e| e.collect_dependencies()).collect(),
             PaxPrimary::List(l) => l.iter().flat_map(|e| e.collect_dependencies()).collect(),
+            PaxPrimary::TemplateString(segments) => segments
+                .iter()
+                .flat_map(|s| match s {
+                    TemplateSegment::Text { .. } => vec![],
+                    TemplateSegment::Expression(e) => e.collect_dependencies(),
+                })
+                .collect(),
+            // parameters are bound when the lambda is called, not by the enclosing scope
+            PaxPrimary::Lambda(params, body) => body
+                .collect_dependencies()
+                .into_iter()
+                .filter(|d| !params.contains(d))
+                .collect(),
         };
         let deduped_deps: std::collections::HashSet<String> = ret.into_iter().collect();
         deduped_deps.into_iter().collect()
@@ -1,7 +1,8 @@
 use std::{collections::HashMap, rc::Rc};
 
 use pax_runtime_api::{
-    functions::Functions, CoercionRules, Color, ColorChannel, Numeric, PaxValue, Size,
+    functions::Functions, CoercionRules, Color, ColorChannel, Numeric, PaxRange, PaxValue, Size,
+    ToPaxValue, TransitionDuration,
 };
 
 use crate::{interpreter::compute_paxel, DependencyCollector};
@@ -218,6 +219,30 @@ fn test_parentheses_units() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn test_duration_units() {
+    let idr = initialize_test_resolver();
+    let expr = "(0.1 + 0.4)s + 250ms";
+    let expected = PaxValue::Duration(TransitionDuration::Millis(750.0));
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(expected, result);
+}
+
+#[test]
+fn test_durations_coerce_like_rust() {
+    let idr = initialize_test_resolver();
+    let millis = compute_paxel("2 * 150ms", idr.clone()).unwrap();
+    assert_eq!(
+        TransitionDuration::try_coerce(millis),
+        Ok(TransitionDuration::Millis(300.0))
+    );
+    let frames = compute_paxel("20 + 10", idr).unwrap();
+    assert_eq!(
+        TransitionDuration::try_coerce(frames),
+        Ok(TransitionDuration::Frames(30))
+    );
+}
+
 #[test]
 fn test_adding_strings() {
     let idr = initialize_test_resolver();
@@ -227,6 +252,53 @@ fn test_adding_strings() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn test_template_string() {
+    let idr = initialize_test_resolver();
+    let expr = "`Total: ${a + b} items, ${'quoted'}`";
+    let expected = PaxValue::String("Total: 14 items, quoted".to_string());
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(expected, result);
+
+    let expr = "`plain` + 'single'";
+    let expected = PaxValue::String("plainsingle".to_string());
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(expected, result);
+
+    let expr = "`${a}px: ${ Math::len([a, b]) }`";
+    assert_eq!(
+        "`${a}px: ${Math::len([a, b])}`",
+        parse_pax_expression(expr).unwrap().to_string()
+    );
+}
+
+#[test]
+fn test_template_string_escapes() {
+    let idr = initialize_test_resolver();
+    let expr = r"`\`quoted\` \${a} costs \\${a}\nnext\tline`";
+    let expected = PaxValue::String("`quoted` ${a} costs \\10\nnext\tline".to_string());
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(expected, result);
+    // escapes are displayed as written
+    assert_eq!(expr, parse_pax_expression(expr).unwrap().to_string());
+
+    assert!(parse_pax_expression(r"`unknown \q escape`").is_err());
+}
+
+#[test]
+fn test_string_functions() {
+    let idr = initialize_test_resolver();
+    let expr = "String::format_number(-1234567.891, 2, \",\")";
+    let expected = PaxValue::String("-1,234,567.89".to_string());
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(expected, result);
+
+    let expr = "String::pad_start(String::to_upper(String::substring(\"abcdef\", 1, 3)), 4, \"0\")";
+    let expected = PaxValue::String("00BC".to_string());
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(expected, result);
+}
+
 #[test]
 fn test_function_call() {
     let idr = initialize_test_resolver();
@@ -305,16 +377,78 @@ fn test_nested_object_expression() {
     assert_eq!(expected, result);
 }
 
+fn range(start: isize, end: isize, inclusive: bool, step: isize) -> PaxValue {
+    PaxValue::Range(Box::new(
+        PaxRange::new(start, end, inclusive, step).unwrap(),
+    ))
+}
+
 #[test]
 fn test_range_expression() {
     let idr = initialize_test_resolver();
-    let expr = "a..b";
-    let expected = PaxValue::Range(
-        Box::new(PaxValue::Numeric(Numeric::I64(10))),
-        Box::new(PaxValue::Numeric(Numeric::I64(4))),
+    let result = compute_paxel("a..b", idr).unwrap();
+    assert_eq!(range(10, 4, false, 1), result);
+}
+
+#[test]
+fn test_inclusive_range_expression() {
+    let idr = initialize_test_resolver();
+    let result = compute_paxel("b..=a", idr.clone()).unwrap();
+    assert_eq!(range(4, 10, true, 1), result);
+    assert_eq!(result.to_string(), "4..=10");
+
+    // the end is kept as is, rather than made exclusive
+    let expr = format!("0..={}", isize::MAX);
+    let result = compute_paxel(&expr, idr.clone()).unwrap();
+    assert_eq!(range(0, isize::MAX, true, 1), result);
+
+    let result = compute_paxel("Math::len(b..=a)", idr).unwrap();
+    assert_eq!(result, 7.to_pax_value());
+}
+
+#[test]
+fn test_stepped_range_expression() {
+    let idr = initialize_test_resolver();
+    let result = compute_paxel("0..=a step b", idr.clone()).unwrap();
+    assert_eq!(range(0, 10, true, 4), result);
+    let PaxValue::Range(stepped) = result else {
+        unreachable!()
+    };
+    assert_eq!(stepped.iter().collect::<Vec<_>>(), vec![0, 4, 8]);
+
+    let result = compute_paxel("a..0 step -3", idr.clone()).unwrap();
+    assert_eq!(range(10, 0, false, -3), result);
+    assert_eq!(result.to_string(), "10..0 step -3");
+    let result = compute_paxel("List::reverse(a..0 step -3)", idr.clone()).unwrap();
+    assert_eq!(
+        result,
+        PaxValue::Vec(
+            [1, 4, 7, 10]
+                .into_iter()
+                .map(|i: isize| i.to_pax_value())
+                .collect()
+        )
     );
-    let result = compute_paxel(expr, idr).unwrap();
+
+    assert!(compute_paxel("0..a step 0", idr).is_err());
+}
+
+#[test]
+fn test_list_functions() {
+    let idr = initialize_test_resolver();
+    let expr = "List::reverse(List::take(b..a, 3))";
+    let expected = PaxValue::Vec(
+        [6, 5, 4]
+            .into_iter()
+            .map(|i| PaxValue::Numeric(Numeric::ISize(i)))
+            .collect(),
+    );
+    let result = compute_paxel(expr, idr.clone()).unwrap();
     assert_eq!(expected, result);
+
+    let expr = "List::contains([1, 2, 3], 2)";
+    let result = compute_paxel(expr, idr).unwrap();
+    assert_eq!(PaxValue::Bool(true), result);
 }
 
 #[test]
@@ -399,6 +533,35 @@ fn test_collect_dependencies() {
     assert_eq!(expected, result);
 }
 
+#[test]
+fn test_lambda_dependencies() {
+    let expr = "List::map(l, |x| x * a)";
+    let mut result = PaxExpression::collect_dependencies(&parse_pax_expression(expr).unwrap());
+    result.sort();
+    assert_eq!(vec!["a".to_string(), "l".to_string()], result);
+}
+
+#[test]
+fn test_lambda_expression() {
+    let idr = initialize_test_resolver();
+    let expr = "List::map([1, 2, 3], |x| x * b)";
+    let expected = PaxValue::Vec(
+        [4, 8, 12]
+            .into_iter()
+            .map(|i| PaxValue::Numeric(Numeric::I64(i)))
+            .collect(),
+    );
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(expected, result);
+
+    let expr = "List::reduce(List::filter(0..a, |x| (x %% 2) == 0), 0, |acc, x| acc + x)";
+    let result = compute_paxel(expr, idr.clone()).unwrap();
+    assert_eq!(PaxValue::Numeric(Numeric::ISize(20)), result);
+
+    let expr = "List::map([1], |x, y| x + y)";
+    assert!(compute_paxel(expr, idr).is_err());
+}
+
 #[test]
 fn test_negative_size() {
     let idr = initialize_test_resolver();
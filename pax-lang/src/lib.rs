@@ -38,6 +38,7 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::identifier => "identifier".to_string(),
         Rule::pascal_identifier => "identifier".to_string(),
         Rule::event_id => "@HANDLER_NAME".to_string(),
+        Rule::event_phase => "event phase (.capture)".to_string(),
         Rule::attribute_key_value_pair => "setting key-value pair".to_string(),
         Rule::attribute_event_binding => "handler binding".to_string(),
         Rule::double_binding => "two-way binding".to_string(),
@@ -49,7 +50,11 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::settings_block_declaration => "settings block".to_string(),
         Rule::selector_block => "selector block".to_string(),
         Rule::literal_object => "literal object".to_string(),
-        Rule::selector => "selector (e.g. .CLASS_NAME or #ID_NAME)".to_string(),
+        Rule::selector => "selector (e.g. .CLASS_NAME, #ID_NAME, Text.title or .card .label)".to_string(),
+        Rule::complex_selector => "selector (e.g. .card .label)".to_string(),
+        Rule::compound_selector => "selector (e.g. Text.title)".to_string(),
+        Rule::selector_qualifier => "class or id selector (e.g. .CLASS_NAME or #ID_NAME)".to_string(),
+        Rule::pseudo_class => "pseudo-class (one of :hover, :pressed, :focused or :disabled)".to_string(),
         Rule::settings_key_value_pair => "setting key-value pair".to_string(),
         Rule::settings_event_binding => "handler binding".to_string(),
         Rule::settings_key => "setting key (e.g. PROPERTY_NAME: )".to_string(),
@@ -63,7 +68,7 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::literal_number => "number".to_string(),
         Rule::literal_number_integer => "integer".to_string(),
         Rule::literal_number_float => "float".to_string(),
-        Rule::literal_number_unit => "unit (px, %, rad, deg)".to_string(),
+        Rule::literal_number_unit => "unit (px, %, rad, deg, ms, s)".to_string(),
         Rule::literal_tuple => "tuple".to_string(),
         Rule::literal_tuple_access => "tuple access".to_string(),
         Rule::literal_enum_value => "enum".to_string(),
@@ -97,22 +102,37 @@ fn renamed_rules(rule: &Rule) -> String {
         Rule::xo_sub => "-".to_string(),
         Rule::xo_tern_then => "then".to_string(),
         Rule::xo_tern_else => "else".to_string(),
-        Rule::xo_range => "range (e.g. 0..5 or i..j)".to_string(),
+        Rule::xo_range => "range (e.g. 0..5, i..=j or 0..10 step 2)".to_string(),
         Rule::xo_range_exclusive => "..".to_string(),
+        Rule::xo_range_inclusive => "..=".to_string(),
+        Rule::xo_range_step => "range step (e.g. step 2)".to_string(),
         Rule::xo_literal => "literal value".to_string(),
         Rule::xo_object => "literal object".to_string(),
         Rule::xo_object_settings_key_value_pair => "setting key-value pair".to_string(),
         Rule::xo_symbol => "identifier".to_string(),
         Rule::xo_tuple => "tuple (e.g. (1,2) )".to_string(),
         Rule::xo_list => "list (e.g. [1,2] )".to_string(),
+        Rule::xo_lambda => "lambda (e.g. |x| x * 2)".to_string(),
+        Rule::xo_template_string => "template string (e.g. `Total: ${count}`)".to_string(),
+        Rule::xo_template_text => "template string text".to_string(),
+        Rule::xo_template_expression => "template string expression (e.g. ${count})".to_string(),
         Rule::xo_enum_or_function_call => "enum, function call".to_string(),
         Rule::xo_enum_or_function_args_list => "args list".to_string(),
-        Rule::statement_control_flow => "if, for, slot".to_string(),
+        Rule::statement_control_flow => "if, for, match, slot".to_string(),
         Rule::statement_if => "if".to_string(),
+        Rule::statement_else_if => "else if".to_string(),
+        Rule::statement_else => "else".to_string(),
         Rule::statement_for => "for".to_string(),
         Rule::statement_slot => "slot".to_string(),
+        Rule::statement_match => "match".to_string(),
+        Rule::statement_match_arm => "match arm (e.g. State::Error(message) => { ... })".to_string(),
+        Rule::statement_match_pattern => "match pattern (e.g. State::Error(message), _ )".to_string(),
+        Rule::statement_match_guard => "match guard (e.g. if code > 500 )".to_string(),
+        Rule::statement_match_wildcard => "_".to_string(),
+        Rule::statement_match_variant => "enum variant (e.g. State::Loading )".to_string(),
         Rule::statement_for_predicate_declaration => "for predicate (e.g. i, (elem,i) )".to_string(),
         Rule::statement_for_source => "for source (e.g. 0..5 )".to_string(),
+        Rule::statement_for_key => "for key (e.g. key elem.id )".to_string(),
         Rule::literal_list => "list".to_string(),
         Rule::literal_option => "option".to_string(),
         Rule::literal_some => "Some".to_string(),
@@ -723,6 +723,7 @@ pub fn helpers(
                 register_function(
                     stringify!(#struct_name).to_string(),
                     stringify!(#func_name).to_string(),
+                    #arg_count..=#arg_count,
                     Arc::new(move |args: Vec<PaxValue>| -> Result<PaxValue, String> {
                         if args.len() != #arg_count {
                             return Err(format!("Expected {} arguments for function {}", #arg_count, stringify!(#func_name)));
@@ -89,12 +89,16 @@ fn recurse_visit_tag_pairs_for_pascal_identifiers(
                 Rule::statement_if => 1,
                 Rule::statement_for => 2,
                 Rule::statement_slot => 0,
+                Rule::statement_match => 0,
                 _ => {
                     unreachable!("Parsing error 944491032: {:?}", matched_tag.as_rule());
                 }
             };
 
-            let prospective_inner_nodes = matched_tag.into_inner().nth(n).expect("WRONG nth");
+            let mut matched_tag_pairs = matched_tag
+                .into_inner()
+                .filter(|pair| pair.as_rule() != Rule::statement_for_key);
+            let prospective_inner_nodes = matched_tag_pairs.nth(n).expect("WRONG nth");
             match prospective_inner_nodes.as_rule() {
                 Rule::inner_nodes => {
                     let inner_nodes = prospective_inner_nodes;
@@ -116,6 +120,22 @@ fn recurse_visit_tag_pairs_for_pascal_identifiers(
                     );
                 }
             }
+
+            //`else if` / `else` branches trail the `if`'s own inner nodes, and `match`
+            //arms trail the matched expression
+            for branch in matched_tag_pairs {
+                if let Some(inner_nodes) = branch
+                    .into_inner()
+                    .find(|pair| pair.as_rule() == Rule::inner_nodes)
+                {
+                    inner_nodes.into_inner().for_each(|sub_tag_pair| {
+                        recurse_visit_tag_pairs_for_pascal_identifiers(
+                            sub_tag_pair,
+                            Rc::clone(&pascal_identifiers),
+                        );
+                    })
+                }
+            }
         }
         Rule::comment => {}
         _ => {
@@ -0,0 +1,730 @@
+//! Compiles the PAXEL expressions of a manifest to Rust, for release builds.
+//!
+//! Each expression is assigned an id, stored in its `ExpressionInfo`, and becomes the body
+//! of a `fn(&[Variable]) -> Result<T, String>` reading its dependencies from the variables
+//! it's passed, in the order of the dependency list computed here. `T` and the types of the
+//! intermediate values follow the types inferred by [`crate::type_checking`]: operations
+//! on values of known types are emitted as plain Rust, and only values of unknown types go
+//! through the `PaxValue` operations the interpreter uses (see
+//! `pax_lang::interpreter::evaluation`), so that both produce the same values and errors.
+//! Expressions using constructs that can't be emitted as Rust literals are skipped, and stay
+//! interpreted at runtime.
+//!
+//! Expressions assigned to a property of a primitive or singleton type also get a version
+//! returning that type, registered as a `TypedExpressionFn`, so that the runtime stores
+//! their values without going through `PaxValue` where the types line up.
+
+use pax_lang::interpreter::{PaxAccessor, PaxExpression, PaxPrimary, PaxUnit, TemplateSegment};
+use pax_lang::DependencyCollector;
+use pax_runtime_api::{Numeric, PaxValue, Rotation, Size};
+use serde::Serialize;
+
+use crate::type_checking::{infer_type, type_of, visit_expressions, ExpressionType, Scope};
+use crate::{
+    ComponentDefinition, ExpressionInfo, LiteralBlockDefinition, PaxManifest, PaxType,
+    SettingElement, SettingsBlockElement, TypeId, TypeTable, ValueDefinition,
+};
+
+#[derive(Serialize, Debug)]
+pub struct CompiledExpressionDefinition {
+    /// The id assigned to the expression's `ExpressionInfo`, which the runtime looks
+    /// compiled expressions up by
+    pub id: usize,
+    pub dependencies: Vec<String>,
+    pub return_type: String,
+    pub body: String,
+    /// The Rust type of the property the expression is assigned to, or `return_type`
+    /// when that isn't known
+    pub property_type: String,
+    /// Converts `value`, the result of the expression, to `property_type`. `None` when
+    /// the two are the same type
+    pub conversion: Option<String>,
+}
+
+/// Assigns an id to every expression of `manifest` and compiles them. The result is
+/// indexed by id, with `None` for expressions that can't be compiled
+pub fn compile_expressions(
+    manifest: &mut PaxManifest,
+) -> Vec<Option<CompiledExpressionDefinition>> {
+    let mut compiled = vec![];
+    for component in manifest.components.values_mut() {
+        compile_component_expressions(component, &manifest.type_table, &mut compiled);
+    }
+    compiled
+}
+
+/// Assigns ids to the expressions of `component` following those already in `compiled`,
+/// and compiles them into it
+pub fn compile_component_expressions(
+    component: &mut ComponentDefinition,
+    type_table: &TypeTable,
+    compiled: &mut Vec<Option<CompiledExpressionDefinition>>,
+) {
+    for_each_expression_mut(component, &mut |info| {
+        info.id = Some(compiled.len());
+        compiled.push(None);
+    });
+    visit_expressions(component, type_table, &mut |info, scope, property_type| {
+        let Some(id) = info.id else {
+            return;
+        };
+        if compiled[id].is_none() {
+            compiled[id] = compile_expression(info, scope, property_type, type_table);
+        }
+    });
+}
+
+/// Compiles the expression of `info`, which must have been assigned an id, given the
+/// types of the symbols in `scope` and the type of the property it's assigned to
+pub fn compile_expression(
+    info: &ExpressionInfo,
+    scope: &Scope,
+    property_type: Option<&TypeId>,
+    type_table: &TypeTable,
+) -> Option<CompiledExpressionDefinition> {
+    let mut emitter = Emitter {
+        scope,
+        type_table,
+        dependencies: vec![],
+        locals: vec![],
+        in_lambda: false,
+        local_count: 0,
+    };
+    let code = emitter.emit(&info.expression)?;
+    let (property_type, conversion) = match property_type {
+        Some(property_type) => convert(code.ty, property_type, type_table),
+        None => (code.ty.name().to_string(), None),
+    };
+    Some(CompiledExpressionDefinition {
+        id: info.id?,
+        dependencies: emitter.dependencies,
+        return_type: code.ty.name().to_string(),
+        body: format!("Ok({})", code.rust),
+        property_type,
+        conversion,
+    })
+}
+
+/// The Rust type to store a value of type `ty` assigned to a property of `property_type`
+/// as, and the conversion of the value to it. Values are coerced the way the runtime
+/// coerces `PaxValue`s unless they already have the property's type
+fn convert(
+    ty: RustType,
+    property_type: &TypeId,
+    type_table: &TypeTable,
+) -> (String, Option<String>) {
+    let name = property_type.get_unique_identifier();
+    let is_numeric_primitive = match property_type.get_pax_type() {
+        PaxType::Primitive { pascal_identifier } => {
+            pascal_identifier != "bool" && pascal_identifier != "String"
+        }
+        PaxType::Singleton { .. } => false,
+        // other type ids don't name a Rust type as is
+        _ => return (ty.name().to_string(), None),
+    };
+    let conversion = match (ty, RustType::of(&type_of(property_type, type_table))) {
+        (RustType::Numeric, RustType::Numeric) if is_numeric_primitive => {
+            "value.into()".to_string()
+        }
+        (RustType::Value, _) => format!("<{}>::try_coerce(value)?", name),
+        (ty, property_ty) if ty == property_ty => return (ty.name().to_string(), None),
+        _ => format!("<{}>::try_coerce(value.to_pax_value())?", name),
+    };
+    (name, Some(conversion))
+}
+
+fn for_each_expression_mut(
+    component: &mut ComponentDefinition,
+    f: &mut impl FnMut(&mut ExpressionInfo),
+) {
+    for node in component
+        .template
+        .iter_mut()
+        .flat_map(|t| t.get_nodes_mut())
+    {
+        for setting in node.settings.iter_mut().flatten() {
+            for_each_setting_expression_mut(setting, f);
+        }
+        if let Some(cfsd) = &mut node.control_flow_settings {
+            [
+                &mut cfsd.condition_expression,
+                &mut cfsd.slot_index_expression,
+                &mut cfsd.repeat_source_expression,
+                &mut cfsd.repeat_key_expression,
+                &mut cfsd.match_expression,
+            ]
+            .into_iter()
+            .flatten()
+            .for_each(&mut *f);
+            if let Some(guard) = cfsd
+                .match_arm_pattern
+                .as_mut()
+                .and_then(|p| p.guard.as_mut())
+            {
+                f(guard);
+            }
+        }
+    }
+    for element in component.settings.iter_mut().flatten() {
+        if let SettingsBlockElement::SelectorBlock(_, block) = element {
+            for_each_block_expression_mut(block, f);
+        }
+    }
+}
+
+fn for_each_block_expression_mut(
+    block: &mut LiteralBlockDefinition,
+    f: &mut impl FnMut(&mut ExpressionInfo),
+) {
+    for element in &mut block.elements {
+        for_each_setting_expression_mut(element, f);
+    }
+}
+
+fn for_each_setting_expression_mut(
+    setting: &mut SettingElement,
+    f: &mut impl FnMut(&mut ExpressionInfo),
+) {
+    match setting {
+        SettingElement::Setting(_, ValueDefinition::Expression(info)) => f(info),
+        SettingElement::Setting(_, ValueDefinition::Block(block)) => {
+            for_each_block_expression_mut(block, f)
+        }
+        _ => {}
+    }
+}
+
+/// Rust type of emitted code. Values of types that aren't known statically are `PaxValue`s
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RustType {
+    Bool,
+    Numeric,
+    String,
+    Size,
+    Percent,
+    Rotation,
+    Color,
+    Value,
+}
+
+impl RustType {
+    fn of(expression_type: &ExpressionType) -> Self {
+        match expression_type {
+            ExpressionType::Bool => RustType::Bool,
+            ExpressionType::Numeric => RustType::Numeric,
+            ExpressionType::String => RustType::String,
+            ExpressionType::Size => RustType::Size,
+            ExpressionType::Percent => RustType::Percent,
+            ExpressionType::Rotation => RustType::Rotation,
+            ExpressionType::Color => RustType::Color,
+            _ => RustType::Value,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            RustType::Bool => "bool",
+            RustType::Numeric => "Numeric",
+            RustType::String => "String",
+            RustType::Size => "Size",
+            RustType::Percent => "Percent",
+            RustType::Rotation => "Rotation",
+            RustType::Color => "Color",
+            RustType::Value => "PaxValue",
+        }
+    }
+}
+
+/// Emitted Rust expression, of type `ty`
+struct Code {
+    rust: String,
+    ty: RustType,
+}
+
+impl Code {
+    fn new(rust: String, ty: RustType) -> Self {
+        Self { rust, ty }
+    }
+
+    fn value(rust: String) -> Self {
+        Self::new(rust, RustType::Value)
+    }
+
+    /// The emitted expression as a `PaxValue`
+    fn into_value(self) -> String {
+        match self.ty {
+            RustType::Value => self.rust,
+            _ => format!("({}).to_pax_value()", self.rust),
+        }
+    }
+}
+
+struct Emitter<'a> {
+    /// Types of the symbols the expression can read
+    scope: &'a Scope,
+    type_table: &'a TypeTable,
+    /// Symbols read from the stack, in the order of the variables passed to the compiled fn
+    dependencies: Vec<String>,
+    /// Lambda parameters and captured values in scope, with the Rust locals holding them
+    locals: Vec<(String, String)>,
+    in_lambda: bool,
+    local_count: usize,
+}
+
+impl<'a> Emitter<'a> {
+    fn emit(&mut self, expression: &PaxExpression) -> Option<Code> {
+        Some(match expression {
+            PaxExpression::Primary(p) => self.emit_primary(p)?,
+            PaxExpression::Prefix(p) => {
+                let rhs = self.emit(&p.rhs)?;
+                match (p.operator.name.as_str(), rhs.ty) {
+                    ("-", RustType::Numeric) => {
+                        Code::new(format!("(-({}))", rhs.rust), RustType::Numeric)
+                    }
+                    ("!", RustType::Bool) => {
+                        Code::new(format!("(!({}))", rhs.rust), RustType::Bool)
+                    }
+                    ("-", _) => Code::value(format!("(-({}))", rhs.into_value())),
+                    ("!", _) => Code::value(format!("({}).op_not()", rhs.into_value())),
+                    (op, _) => Code::value(call_math(op, &[rhs.into_value()])),
+                }
+            }
+            PaxExpression::Postfix(p) => Code::value(call_math(
+                &p.operator.name,
+                &[self.emit(&p.lhs)?.into_value()],
+            )),
+            PaxExpression::Infix(p) => {
+                let lhs = self.emit(&p.lhs)?;
+                let rhs = self.emit(&p.rhs)?;
+                emit_infix(&p.operator.name, lhs, rhs)
+            }
+        })
+    }
+
+    fn emit_primary(&mut self, primary: &PaxPrimary) -> Option<Code> {
+        Some(match primary {
+            PaxPrimary::Literal(value) => emit_typed_literal(value)?,
+            PaxPrimary::Grouped(e, None) => {
+                let inner = self.emit(e)?;
+                Code::new(format!("({})", inner.rust), inner.ty)
+            }
+            PaxPrimary::Grouped(e, Some(unit)) => {
+                let inner = self.emit(e)?;
+                if inner.ty == RustType::Numeric {
+                    let n = inner.rust;
+                    return Some(match unit {
+                        PaxUnit::Percent => Code::new(format!("Percent({})", n), RustType::Percent),
+                        PaxUnit::Pixels => {
+                            Code::new(format!("Size::Pixels({})", n), RustType::Size)
+                        }
+                        PaxUnit::Radians => {
+                            Code::new(format!("Rotation::Radians({})", n), RustType::Rotation)
+                        }
+                        PaxUnit::Degrees => {
+                            Code::new(format!("Rotation::Degrees({})", n), RustType::Rotation)
+                        }
+                        PaxUnit::Milliseconds => Code::value(format!(
+                            "PaxValue::Duration(TransitionDuration::Millis(({}).to_float()))",
+                            n
+                        )),
+                        PaxUnit::Seconds => Code::value(format!(
+                            "PaxValue::Duration(TransitionDuration::Millis(({}).to_float() * 1000.0))",
+                            n
+                        )),
+                    });
+                }
+                let unit = match unit {
+                    PaxUnit::Percent => "Percent",
+                    PaxUnit::Pixels => "Pixels",
+                    PaxUnit::Radians => "Radians",
+                    PaxUnit::Degrees => "Degrees",
+                    PaxUnit::Milliseconds => "Milliseconds",
+                    PaxUnit::Seconds => "Seconds",
+                };
+                Code::value(format!(
+                    "evaluation::apply_unit({}, &PaxUnit::{})?",
+                    inner.into_value(),
+                    unit
+                ))
+            }
+            PaxPrimary::Identifier(identifier, accessors) => {
+                let mut steps = String::new();
+                for accessor in accessors {
+                    steps += &match accessor {
+                        PaxAccessor::Tuple(index) => {
+                            format!("let value = evaluation::access_tuple(value, {})?; ", index)
+                        }
+                        PaxAccessor::List(index) => format!(
+                            "let value = evaluation::access_list(value, {})?; ",
+                            self.emit(index)?.into_value()
+                        ),
+                        PaxAccessor::Struct(field) => {
+                            format!(
+                                "let value = evaluation::access_field(value, {:?})?; ",
+                                field
+                            )
+                        }
+                    };
+                }
+                if let Some(local) = self.local(&identifier.name) {
+                    return Some(Code::value(if accessors.is_empty() {
+                        format!("{}.clone()", local)
+                    } else {
+                        format!("{{ let value = &{}; {}value.clone() }}", local, steps)
+                    }));
+                }
+                let ty = if accessors.is_empty() {
+                    self.scope
+                        .get(&identifier.name)
+                        .map(RustType::of)
+                        .unwrap_or(RustType::Value)
+                } else {
+                    let expression = PaxExpression::Primary(Box::new(primary.clone()));
+                    RustType::of(&infer_type(&expression, self.scope, self.type_table))
+                };
+                let variable = self.variable(&identifier.name)?;
+                match (ty, accessors.is_empty()) {
+                    (RustType::Value, true) => Code::value(format!("{}.get_as_pax_value()", variable)),
+                    (ty, true) => Code::new(format!("{}.get_as::<{}>()?", variable, ty.name()), ty),
+                    (RustType::Value, false) => Code::value(format!(
+                        "{}.read_pax_value_ref(|value| -> Result<PaxValue, String> {{ {}Ok(value.clone()) }})?",
+                        variable, steps
+                    )),
+                    (ty, false) => Code::new(
+                        format!(
+                            "{}.read_pax_value_ref(|value| -> Result<{ty}, String> {{ {}{ty}::try_coerce(value.clone()) }})?",
+                            variable,
+                            steps,
+                            ty = ty.name()
+                        ),
+                        ty,
+                    ),
+                }
+            }
+            PaxPrimary::Object(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(k, v)| {
+                        Some(format!(
+                            "({:?}.to_string(), {})",
+                            k,
+                            self.emit(v)?.into_value()
+                        ))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Code::value(format!("PaxValue::Object(vec![{}])", fields.join(", ")))
+            }
+            PaxPrimary::FunctionOrEnum(scope, name, args) => Code::value(format!(
+                "evaluation::call_function_or_enum({:?}, {:?}, vec![{}])?",
+                scope,
+                name,
+                self.emit_values(args)?
+            )),
+            PaxPrimary::Range {
+                start,
+                end,
+                inclusive,
+                step,
+            } => {
+                let start = self.emit(start)?.into_value();
+                let end = self.emit(end)?.into_value();
+                let step = match step {
+                    Some(step) => format!("Some({})", self.emit(step)?.into_value()),
+                    None => "None".to_string(),
+                };
+                Code::value(format!(
+                    "evaluation::build_range({}, {}, {}, {})?",
+                    start, end, inclusive, step
+                ))
+            }
+            PaxPrimary::Tuple(elems) | PaxPrimary::List(elems) => {
+                Code::value(format!("PaxValue::Vec(vec![{}])", self.emit_values(elems)?))
+            }
+            PaxPrimary::Lambda(params, body) => {
+                Code::value(self.emit_lambda(primary, params, body)?)
+            }
+            PaxPrimary::TemplateString(segments) => {
+                let mut pushes = String::new();
+                for segment in segments {
+                    pushes += &match segment {
+                        TemplateSegment::Text { text, .. } => {
+                            format!("string.push_str({:?}); ", text)
+                        }
+                        TemplateSegment::Expression(e) => {
+                            let code = self.emit(e)?;
+                            match code.ty {
+                                RustType::String => format!("string.push_str(&{}); ", code.rust),
+                                _ => format!(
+                                    "evaluation::interpolate(&mut string, {}); ",
+                                    code.into_value()
+                                ),
+                            }
+                        }
+                    };
+                }
+                Code::new(
+                    format!("{{ let mut string = String::new(); {}string }}", pushes),
+                    RustType::String,
+                )
+            }
+        })
+    }
+
+    /// Lambdas capture the values of their free variables when created, like the
+    /// interpreter's, since the variables themselves can't be sent across threads
+    fn emit_lambda(
+        &mut self,
+        lambda: &PaxPrimary,
+        params: &[String],
+        body: &PaxExpression,
+    ) -> Option<String> {
+        let mut free = lambda.collect_dependencies();
+        free.sort();
+        let mut captures = String::new();
+        let mut inner_locals = vec![];
+        for name in free {
+            let value = match self.local(&name) {
+                Some(local) => format!("{}.clone()", local),
+                None => format!("{}.get_as_pax_value()", self.variable(&name)?),
+            };
+            let local = self.new_local();
+            captures += &format!("let {} = {}; ", local, value);
+            inner_locals.push((name, local));
+        }
+        let mut bindings = String::new();
+        for param in params {
+            let local = self.new_local();
+            bindings += &format!("let {} = args.next().unwrap(); ", local);
+            inner_locals.push((param.clone(), local));
+        }
+
+        let outer_locals = std::mem::replace(&mut self.locals, inner_locals);
+        let outer_in_lambda = std::mem::replace(&mut self.in_lambda, true);
+        let body = self.emit(body);
+        self.locals = outer_locals;
+        self.in_lambda = outer_in_lambda;
+
+        Some(format!(
+            "{{ {captures}PaxValue::Function(PaxFunction::new({source:?}.to_string(), move |args: Vec<PaxValue>| -> Result<PaxValue, String> {{ \
+             if args.len() != {n} {{ return Err(format!(\"Expected {{}} arguments for lambda, found {{}}\", {n}, args.len())); }} \
+             let mut args = args.into_iter(); {bindings}Ok({body}) }})) }}",
+            captures = captures,
+            source = lambda.to_string(),
+            n = params.len(),
+            bindings = bindings,
+            body = body?.into_value(),
+        ))
+    }
+
+    /// Emits `expressions` as a comma separated list of `PaxValue`s
+    fn emit_values(&mut self, expressions: &[PaxExpression]) -> Option<String> {
+        let emitted = expressions
+            .iter()
+            .map(|e| Some(self.emit(e)?.into_value()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(emitted.join(", "))
+    }
+
+    fn local(&self, name: &str) -> Option<String> {
+        self.locals
+            .iter()
+            .rev()
+            .find_map(|(n, local)| (n == name).then(|| local.clone()))
+    }
+
+    fn new_local(&mut self) -> String {
+        self.local_count += 1;
+        format!("local_{}", self.local_count)
+    }
+
+    /// The variable holding a symbol read from the stack. Lambda bodies only see
+    /// captured values, as the variables aren't available when they're called
+    fn variable(&mut self, name: &str) -> Option<String> {
+        if self.in_lambda {
+            return None;
+        }
+        let index = match self.dependencies.iter().position(|d| d == name) {
+            Some(index) => index,
+            None => {
+                self.dependencies.push(name.to_string());
+                self.dependencies.len() - 1
+            }
+        };
+        Some(format!("vars[{}]", index))
+    }
+}
+
+/// Binary operators. Both operands are evaluated, in order, like the interpreter does:
+/// `&&` and `||` on bools don't short circuit
+fn emit_infix(operator: &str, lhs: Code, rhs: Code) -> Code {
+    let typed = |rust: String, ty| Some(Code::new(rust, ty));
+    let (l, r) = (&lhs.rust, &rhs.rust);
+    let emitted = match (operator, lhs.ty, rhs.ty) {
+        (op @ ("+" | "-" | "*" | "/"), RustType::Numeric, RustType::Numeric) => {
+            typed(format!("(({}) {} ({}))", l, op, r), RustType::Numeric)
+        }
+        ("%%", RustType::Numeric, RustType::Numeric) => {
+            typed(format!("(({}) % ({}))", l, r), RustType::Numeric)
+        }
+        ("^", RustType::Numeric, RustType::Numeric) => {
+            typed(format!("({}).pow({})", l, r), RustType::Numeric)
+        }
+        (op @ ("min" | "max"), RustType::Numeric, RustType::Numeric) => {
+            typed(format!("({}).{}({})", l, op, r), RustType::Numeric)
+        }
+        ("+", RustType::String, RustType::String) => {
+            typed(format!("(({}) + &({}))", l, r), RustType::String)
+        }
+        ("&&", RustType::Bool, RustType::Bool) => {
+            typed(format!("(({}) & ({}))", l, r), RustType::Bool)
+        }
+        ("||", RustType::Bool, RustType::Bool) => {
+            typed(format!("(({}) | ({}))", l, r), RustType::Bool)
+        }
+        (
+            op @ (">" | ">=" | "<" | "<="),
+            RustType::Numeric | RustType::String | RustType::Bool,
+            _,
+        ) if lhs.ty == rhs.ty => typed(format!("(({}) {} ({}))", l, op, r), RustType::Bool),
+        (op @ ("==" | "!="), ty, _) if ty != RustType::Value && ty == rhs.ty => {
+            typed(format!("(({}) {} ({}))", l, op, r), RustType::Bool)
+        }
+        _ => None,
+    };
+    if let Some(code) = emitted {
+        return code;
+    }
+
+    let (lhs, rhs) = (lhs.into_value(), rhs.into_value());
+    Code::value(match operator {
+        op @ ("+" | "-" | "*" | "/") => format!("(({}) {} ({}))", lhs, op, rhs),
+        "%%" => format!("(({}) % ({}))", lhs, rhs),
+        op @ ("==" | "!=" | ">" | ">=" | "<" | "<=") => {
+            format!("PaxValue::Bool(({}) {} ({}))", lhs, op, rhs)
+        }
+        "^" => format!("({}).pow({})", lhs, rhs),
+        "&&" => format!("({}).op_and({})", lhs, rhs),
+        "||" => format!("({}).op_or({})", lhs, rhs),
+        "min" => format!("({}).min({})", lhs, rhs),
+        "max" => format!("({}).max({})", lhs, rhs),
+        op => call_math(op, &[lhs, rhs]),
+    })
+}
+
+fn call_math(operator: &str, args: &[String]) -> String {
+    format!(
+        "functions::call_function(\"Math\".to_string(), {:?}.to_string(), vec![{}])?",
+        operator,
+        args.join(", ")
+    )
+}
+
+fn emit_typed_literal(value: &PaxValue) -> Option<Code> {
+    Some(match value {
+        PaxValue::Bool(b) => Code::new(b.to_string(), RustType::Bool),
+        PaxValue::Numeric(n) => Code::new(emit_numeric(n)?, RustType::Numeric),
+        PaxValue::String(s) => Code::new(format!("{:?}.to_string()", s), RustType::String),
+        PaxValue::Size(size) => Code::new(emit_size(size)?, RustType::Size),
+        PaxValue::Percent(p) => Code::new(
+            format!("Percent({})", emit_numeric(&p.0)?),
+            RustType::Percent,
+        ),
+        PaxValue::Rotation(rotation) => Code::new(emit_rotation(rotation)?, RustType::Rotation),
+        PaxValue::Color(color) => Code::new(emit_color(color)?, RustType::Color),
+        value => Code::value(emit_literal(value)?),
+    })
+}
+
+fn emit_literal(value: &PaxValue) -> Option<String> {
+    Some(match value {
+        PaxValue::Bool(b) => format!("PaxValue::Bool({})", b),
+        PaxValue::Numeric(n) => format!("PaxValue::Numeric({})", emit_numeric(n)?),
+        PaxValue::String(s) => format!("PaxValue::String({:?}.to_string())", s),
+        PaxValue::Size(size) => format!("PaxValue::Size({})", emit_size(size)?),
+        PaxValue::Percent(p) => format!("PaxValue::Percent(Percent({}))", emit_numeric(&p.0)?),
+        PaxValue::Rotation(rotation) => format!("PaxValue::Rotation({})", emit_rotation(rotation)?),
+        PaxValue::Color(color) => format!("PaxValue::Color(Box::new({}))", emit_color(color)?),
+        PaxValue::Duration(duration) => {
+            format!("PaxValue::Duration(TransitionDuration::{:?})", duration)
+        }
+        PaxValue::Option(option) => match option.as_ref() {
+            Some(v) => format!("PaxValue::Option(Box::new(Some({})))", emit_literal(v)?),
+            None => "PaxValue::Option(Box::new(None))".to_string(),
+        },
+        PaxValue::Vec(elems) => format!("PaxValue::Vec(vec![{}])", emit_literals(elems)?),
+        PaxValue::Object(fields) => {
+            let fields = fields
+                .iter()
+                .map(|(k, v)| Some(format!("({:?}.to_string(), {})", k, emit_literal(v)?)))
+                .collect::<Option<Vec<_>>>()?;
+            format!("PaxValue::Object(vec![{}])", fields.join(", "))
+        }
+        PaxValue::Enum(variant) => {
+            let (scope, name, args) = variant.as_ref();
+            format!(
+                "PaxValue::Enum(Box::new(({:?}.to_string(), {:?}.to_string(), vec![{}])))",
+                scope,
+                name,
+                emit_literals(args)?
+            )
+        }
+        _ => return None,
+    })
+}
+
+fn emit_literals(values: &[PaxValue]) -> Option<String> {
+    let emitted = values
+        .iter()
+        .map(emit_literal)
+        .collect::<Option<Vec<_>>>()?;
+    Some(emitted.join(", "))
+}
+
+/// Named colors only, e.g. `BLACK`
+fn emit_color(color: &pax_runtime_api::Color) -> Option<String> {
+    let name = format!("{:?}", color);
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    Some(format!("Color::{}", name))
+}
+
+fn emit_rotation(rotation: &Rotation) -> Option<String> {
+    let (unit, n) = match rotation {
+        Rotation::Radians(n) => ("Radians", n),
+        Rotation::Degrees(n) => ("Degrees", n),
+        Rotation::Percent(n) => ("Percent", n),
+    };
+    Some(format!("Rotation::{}({})", unit, emit_numeric(n)?))
+}
+
+fn emit_size(size: &Size) -> Option<String> {
+    Some(match size {
+        Size::Pixels(n) => format!("Size::Pixels({})", emit_numeric(n)?),
+        Size::Percent(n) => format!("Size::Percent({})", emit_numeric(n)?),
+        Size::Combined(pixels, percent) => format!(
+            "Size::Combined({}, {})",
+            emit_numeric(pixels)?,
+            emit_numeric(percent)?
+        ),
+    })
+}
+
+fn emit_numeric(n: &Numeric) -> Option<String> {
+    let (variant, value) = match n {
+        Numeric::I8(v) => ("I8", format!("{}i8", v)),
+        Numeric::I16(v) => ("I16", format!("{}i16", v)),
+        Numeric::I32(v) => ("I32", format!("{}i32", v)),
+        Numeric::I64(v) => ("I64", format!("{}i64", v)),
+        Numeric::U8(v) => ("U8", format!("{}u8", v)),
+        Numeric::U16(v) => ("U16", format!("{}u16", v)),
+        Numeric::U32(v) => ("U32", format!("{}u32", v)),
+        Numeric::U64(v) => ("U64", format!("{}u64", v)),
+        Numeric::ISize(v) => ("ISize", format!("{}isize", v)),
+        Numeric::USize(v) => ("USize", format!("{}usize", v)),
+        Numeric::F64(v) if v.is_finite() => ("F64", format!("{:?}f64", v)),
+        Numeric::F32(v) if v.is_finite() => ("F32", format!("{:?}f32", v)),
+        _ => return None,
+    };
+    Some(format!("Numeric::{}({})", variant, value))
+}
@@ -3,10 +3,13 @@ use std::collections::{BTreeMap, HashMap};
 
 use crate::{
     constants::{COMMON_PROPERTIES, COMMON_PROPERTIES_TYPE},
+    selectors::PseudoClass,
     PaxManifest, PropertyDefinition, SettingElement, SettingsBlockElement, TemplateNodeDefinition,
-    TypeId, ValueDefinition,
+    TemplateNodeId, TypeId, ValueDefinition,
 };
 
+pub mod expressions;
+
 #[derive(Serialize, Debug)]
 pub struct ComponentInfo {
     pub type_id: TypeId,
@@ -75,9 +78,21 @@ impl PaxManifest {
         add("mouse_out", "MouseOut");
         add("double_click", "DoubleClick");
         add("context_menu", "ContextMenu");
+        add("pointer_down", "PointerDown");
+        add("pointer_move", "PointerMove");
+        add("pointer_up", "PointerUp");
+        add("pointer_cancel", "PointerCancel");
+        add("drag_start", "DragStart");
+        add("drag", "Drag");
+        add("drag_end", "DragEnd");
+        add("pinch", "Pinch");
+        add("long_press", "LongPress");
+        add("swipe", "Swipe");
         add("wheel", "Wheel");
         add("drop", "Drop");
         add("focused", "Focus");
+        add("focus", "FocusIn");
+        add("blur", "FocusOut");
         add("select_start", "SelectStart");
 
         map.insert("pre_render".to_string(), None);
@@ -118,7 +133,7 @@ impl PaxManifest {
                     if let SettingsBlockElement::Handler(key, values) = setting {
                         for value in values {
                             let event_args = event_map
-                                .get(key.token_value.as_str())
+                                .get(event_name(&key.token_value))
                                 .expect("custom handlers not supported in settings block")
                                 .as_ref();
                             handler_data.push(HandlerInfo {
@@ -138,7 +153,7 @@ impl PaxManifest {
                             if let SettingElement::Setting(key, value) = setting {
                                 if let ValueDefinition::EventBindingTarget(e) = value {
                                     let event_args = event_map
-                                        .get(key.token_value.as_str())
+                                        .get(event_name(&key.token_value))
                                         .and_then(|v| v.as_ref());
                                     handler_data.push(HandlerInfo {
                                         name: self.clean_handler(e.name.clone()),
@@ -165,39 +180,51 @@ impl PaxManifest {
     pub fn get_inline_properties(
         &self,
         containing_component_type_id: &TypeId,
-        tnd: &TemplateNodeDefinition,
+        node_id: &TemplateNodeId,
     ) -> BTreeMap<String, ValueDefinition> {
-        let component = self.components.get(&containing_component_type_id).unwrap();
-        let settings =
-            Self::merge_inline_settings_with_settings_block(&tnd.settings, &component.settings);
-        let mut map = BTreeMap::new();
-        if let Some(settings) = &settings {
-            for setting in settings {
-                if let SettingElement::Setting(key, value) = setting {
-                    match value {
-                        ValueDefinition::LiteralValue(_)
-                        | ValueDefinition::Block(_)
-                        | ValueDefinition::Expression(_)
-                        | ValueDefinition::Identifier(_)
-                        | ValueDefinition::DoubleBinding(_) => {
-                            map.insert(key.token_value.clone(), value.clone());
-                        }
-                        ValueDefinition::EventBindingTarget(_) | ValueDefinition::Undefined => {}
-                    }
+        let component = self.components.get(containing_component_type_id).unwrap();
+        let settings = component
+            .template
+            .as_ref()
+            .and_then(|template| template.get_merged_settings(node_id, &component.settings));
+        settings_to_properties(settings.iter().flatten())
+    }
+
+    /// The state variants of a node's properties, in increasing order of precedence
+    pub fn get_inline_state_variants(
+        &self,
+        containing_component_type_id: &TypeId,
+        node_id: &TemplateNodeId,
+    ) -> Vec<InlineStateVariant> {
+        let component = self.components.get(containing_component_type_id).unwrap();
+        let Some(template) = &component.template else {
+            return Vec::new();
+        };
+        template
+            .get_state_variants(node_id, &component.settings)
+            .into_iter()
+            .map(|variant| {
+                let mut properties = settings_to_properties(variant.settings.iter());
+                InlineStateVariant {
+                    states: variant.states,
+                    transition: properties.remove("transition"),
+                    easing: properties.remove("easing"),
+                    properties,
                 }
-            }
-        }
-        map
+            })
+            .collect()
     }
 
     pub fn get_inline_common_properties(
         &self,
         containing_component_type_id: &TypeId,
-        tnd: &TemplateNodeDefinition,
+        node_id: &TemplateNodeId,
     ) -> BTreeMap<String, ValueDefinition> {
         let component = self.components.get(containing_component_type_id).unwrap();
-        let settings =
-            Self::merge_inline_settings_with_settings_block(&tnd.settings, &component.settings);
+        let settings = component
+            .template
+            .as_ref()
+            .and_then(|template| template.get_merged_settings(node_id, &component.settings));
         let mut map = BTreeMap::new();
         if let Some(settings) = &settings {
             for setting in settings {
@@ -238,114 +265,44 @@ impl PaxManifest {
         }
         handlers
     }
+}
 
-    fn pull_matched_identifiers_from_inline(
-        inline_settings: &Option<Vec<SettingElement>>,
-        s: String,
-    ) -> Vec<String> {
-        let mut ret = Vec::new();
-        if let Some(val) = inline_settings {
-            let matched_settings = val.iter().filter(|e| match e {
-                SettingElement::Setting(token, _) => token.token_value == s.as_str(),
-                _ => false,
-            });
-            for e in matched_settings {
-                if let SettingElement::Setting(_, value) = e {
-                    match value {
-                        ValueDefinition::Identifier(s) => ret.push(s.name.clone()),
-                        _ => {}
-                    };
-                }
-            }
-        }
-        ret
-    }
-
-    fn pull_settings_with_selector(
-        settings: &Option<Vec<SettingsBlockElement>>,
-        selector: String,
-    ) -> Option<Vec<SettingElement>> {
-        settings.as_ref().and_then(|val| {
-            let mut merged_setting = Vec::new();
-            for settings_value in val.iter() {
-                match settings_value {
-                    SettingsBlockElement::SelectorBlock(token, value) => {
-                        if token.token_value == selector {
-                            merged_setting.extend(value.elements.clone());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            (!merged_setting.is_empty()).then(|| merged_setting)
-        })
-    }
-
-    pub fn merge_inline_settings_with_settings_block(
-        inline_settings: &Option<Vec<SettingElement>>,
-        settings_block: &Option<Vec<SettingsBlockElement>>,
-    ) -> Option<Vec<SettingElement>> {
-        // collect id settings
-        let ids = Self::pull_matched_identifiers_from_inline(&inline_settings, "id".to_string());
-
-        let mut id_settings = Vec::new();
-        if ids.len() == 1 {
-            if let Some(settings) =
-                Self::pull_settings_with_selector(&settings_block, format!("#{}", ids[0]))
-            {
-                id_settings.extend(settings.clone());
-            }
-        } else if ids.len() > 1 {
-            panic!("Specified more than one id inline!");
-        }
-
-        // collect all class settings
-        let classes =
-            Self::pull_matched_identifiers_from_inline(&inline_settings, "class".to_string());
-
-        let mut class_settings = Vec::new();
-        for class in classes {
-            if let Some(settings) =
-                Self::pull_settings_with_selector(&settings_block, format!(".{}", class))
-            {
-                class_settings.extend(settings.clone());
-            }
-        }
-
-        let mut map = BTreeMap::new();
-
-        // Iterate in reverse order of priority (class, then id, then inline)
-        for e in class_settings.into_iter() {
-            if let SettingElement::Setting(key, _) = e.clone() {
-                map.insert(key, e);
-            }
-        }
+/// The event a handler is bound to, without the phase of capture-phase handlers such as
+/// `@click.capture`
+fn event_name(handler_key: &str) -> &str {
+    handler_key.strip_suffix(".capture").unwrap_or(handler_key)
+}
 
-        for e in id_settings.into_iter() {
-            if let SettingElement::Setting(key, _) = e.clone() {
-                map.insert(key, e);
-            }
-        }
+/// The properties a node overrides while in all of `states`, e.g. from `.card:hover { ... }`
+pub struct InlineStateVariant {
+    pub states: Vec<PseudoClass>,
+    /// The `transition` of the block: how long to ease between the overridden and resting
+    /// values for, e.g. `200ms`
+    pub transition: Option<ValueDefinition>,
+    /// The `easing` curve of the transition, e.g. `EasingCurve::Spring(170, 26, 1)`
+    pub easing: Option<ValueDefinition>,
+    pub properties: BTreeMap<String, ValueDefinition>,
+}
 
-        let mut merged = Vec::new();
-        if let Some(inline) = inline_settings.clone() {
-            for e in inline.iter() {
-                if let SettingElement::Setting(key, _) = e.clone() {
-                    map.remove(&key);
+fn settings_to_properties<'a>(
+    settings: impl Iterator<Item = &'a SettingElement>,
+) -> BTreeMap<String, ValueDefinition> {
+    let mut map = BTreeMap::new();
+    for setting in settings {
+        if let SettingElement::Setting(key, value) = setting {
+            match value {
+                ValueDefinition::LiteralValue(_)
+                | ValueDefinition::Block(_)
+                | ValueDefinition::Expression(_)
+                | ValueDefinition::Identifier(_)
+                | ValueDefinition::DoubleBinding(_) => {
+                    map.insert(key.token_value.clone(), value.clone());
                 }
+                ValueDefinition::EventBindingTarget(_) | ValueDefinition::Undefined => {}
             }
-            let unique_setting_block_settings: Vec<SettingElement> =
-                map.values().cloned().collect();
-            merged.extend(inline);
-            merged.extend(unique_setting_block_settings);
-        }
-
-        if merged.len() > 0 {
-            Some(merged)
-        } else {
-            None
         }
     }
+    map
 }
 
 #[derive(Serialize, Deserialize)]
@@ -2,3 +2,40 @@ pub use pax_runtime_api::constants::*;
 pub const CARTRIDGE_PARTIAL_STRUCT_ID: &'static str = "Cartridge";
 pub const DEFINITION_TO_INSTANCE_TRAVERSER_PARTIAL_STRUCT_ID: &'static str =
     "DefinitionToInstanceTraverser";
+
+/// Events dispatched through the capture and bubble phases, from the root down to their
+/// target and back up. The others, such as `mouse_over`, `focus` or custom events, only
+/// reach their target, so can't have capture-phase handlers like `@click.capture`.
+pub const BUBBLING_EVENTS: &[&str] = &[
+    "scroll",
+    "clap",
+    "touch_start",
+    "touch_move",
+    "touch_end",
+    "key_down",
+    "key_up",
+    "key_press",
+    "checkbox_change",
+    "textbox_change",
+    "text_input",
+    "textbox_input",
+    "button_click",
+    "mouse_down",
+    "mouse_up",
+    "mouse_move",
+    "double_click",
+    "context_menu",
+    "click",
+    "pointer_down",
+    "pointer_move",
+    "pointer_up",
+    "pointer_cancel",
+    "drag_start",
+    "drag",
+    "drag_end",
+    "pinch",
+    "long_press",
+    "swipe",
+    "wheel",
+    "drop",
+];
@@ -18,6 +18,8 @@ pub mod utils;
 pub mod cartridge_generation;
 pub mod code_serialization;
 pub mod constants;
+pub mod selectors;
+pub mod type_checking;
 
 /// Definition container for an entire Pax cartridge
 #[serde_with::serde_as]
@@ -244,6 +246,8 @@ pub enum PaxType {
     If,
     Slot,
     Repeat,
+    Match,
+    MatchArm,
     Comment,
     BlankComponent {
         pascal_identifier: String,
@@ -282,6 +286,8 @@ impl CoercionRules for PaxType {
                     "If" => Ok(PaxType::If),
                     "Slot" => Ok(PaxType::Slot),
                     "Repeat" => Ok(PaxType::Repeat),
+                    "Match" => Ok(PaxType::Match),
+                    "MatchArm" => Ok(PaxType::MatchArm),
                     "Comment" => Ok(PaxType::Comment),
                     "BlankComponent" => {
                         let pascal_identifier = String::try_coerce(args[0].clone())?;
@@ -339,6 +345,16 @@ impl ToPaxValue for PaxType {
                 "Repeat".to_string(),
                 vec![],
             ))),
+            PaxType::Match => PaxValue::Enum(Box::new((
+                "PaxType".to_string(),
+                "Match".to_string(),
+                vec![],
+            ))),
+            PaxType::MatchArm => PaxValue::Enum(Box::new((
+                "PaxType".to_string(),
+                "MatchArm".to_string(),
+                vec![],
+            ))),
             PaxType::Comment => PaxValue::Enum(Box::new((
                 "PaxType".to_string(),
                 "Comment".to_string(),
@@ -400,6 +416,8 @@ impl Display for PaxType {
             PaxType::If => write!(f, "If"),
             PaxType::Slot => write!(f, "Slot"),
             PaxType::Repeat => write!(f, "Repeat"),
+            PaxType::Match => write!(f, "Match"),
+            PaxType::MatchArm => write!(f, "MatchArm"),
             PaxType::Comment => write!(f, "Comment"),
             PaxType::BlankComponent { pascal_identifier } => write!(f, "{}", pascal_identifier),
             PaxType::Primitive { pascal_identifier } => write!(f, "{}", pascal_identifier),
@@ -536,6 +554,26 @@ impl TypeId {
         }
     }
 
+    pub fn build_match() -> Self {
+        TypeId {
+            pax_type: PaxType::Match,
+            import_path: None,
+            is_intoable_downstream_type: false,
+            _type_id: "Match".to_string(),
+            _type_id_escaped: "Match".to_string(),
+        }
+    }
+
+    pub fn build_match_arm() -> Self {
+        TypeId {
+            pax_type: PaxType::MatchArm,
+            import_path: None,
+            is_intoable_downstream_type: false,
+            _type_id: "MatchArm".to_string(),
+            _type_id_escaped: "MatchArm".to_string(),
+        }
+    }
+
     pub fn build_slot() -> Self {
         TypeId {
             pax_type: PaxType::Slot,
@@ -672,9 +710,12 @@ impl TypeId {
             PaxType::Primitive { pascal_identifier }
             | PaxType::Singleton { pascal_identifier }
             | PaxType::BlankComponent { pascal_identifier } => Some(pascal_identifier.clone()),
-            PaxType::If | PaxType::Slot | PaxType::Repeat | PaxType::Comment => {
-                Some(self.pax_type.to_string())
-            }
+            PaxType::If
+            | PaxType::Slot
+            | PaxType::Repeat
+            | PaxType::Match
+            | PaxType::MatchArm
+            | PaxType::Comment => Some(self.pax_type.to_string()),
             _ => None,
         }
     }
@@ -1335,6 +1376,39 @@ impl ComponentTemplate {
         current_location
     }
 
+    /// Returns the `else if` / `else` branches chained to the `if` node `id`, in order
+    pub fn get_else_branches(&self, id: &TemplateNodeId) -> Vec<TemplateNodeId> {
+        let Some(siblings) = self.get_siblings(id) else {
+            return vec![];
+        };
+        siblings
+            .iter()
+            .skip_while(|sibling| *sibling != id)
+            .skip(1)
+            .take_while(|sibling| self.nodes.get(sibling).is_some_and(|n| n.is_else_branch()))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the `if` node that heads the conditional chain containing `id`. Nodes that
+    /// aren't `else if` / `else` branches are their own chain head.
+    pub fn get_conditional_chain_head(&self, id: &TemplateNodeId) -> TemplateNodeId {
+        let Some(siblings) = self.get_siblings(id) else {
+            return id.clone();
+        };
+        let mut head = id.clone();
+        for sibling in siblings.iter().take_while(|sibling| *sibling != id) {
+            if !self.nodes.get(sibling).is_some_and(|n| n.is_else_branch()) {
+                head = sibling.clone();
+            }
+        }
+        if self.nodes.get(id).is_some_and(|n| n.is_else_branch()) {
+            head
+        } else {
+            id.clone()
+        }
+    }
+
     pub fn get_siblings(&self, id: &TemplateNodeId) -> Option<VecDeque<TemplateNodeId>> {
         if self.root.contains(id) {
             Some(self.root.clone())
@@ -1366,12 +1440,70 @@ impl ComponentTemplate {
         self.children.clone()
     }
 
+    /// Merges the settings of the `@settings` blocks matching each node into its inline
+    /// settings. Selectors are matched against the template as written, so settings
+    /// merged into a node don't affect which blocks match its descendants.
     pub fn merge_with_settings(&mut self, settings_block: &Option<Vec<SettingsBlockElement>>) {
-        for node in self.get_nodes_mut() {
-            node.settings = PaxManifest::merge_inline_settings_with_settings_block(
-                &mut node.settings,
-                settings_block,
-            );
+        let merged: Vec<_> = self
+            .nodes
+            .keys()
+            .map(|id| (id.clone(), self.get_merged_settings(id, settings_block)))
+            .collect();
+        for (id, settings) in merged {
+            if let Some(node) = self.nodes.get_mut(&id) {
+                node.settings = settings;
+            }
+        }
+    }
+
+    /// The settings of a node: its inline settings, then those of the `@settings` blocks
+    /// matching it that aren't set inline. Inline settings take precedence over every
+    /// block, and blocks over each other by specificity, then source order.
+    pub fn get_merged_settings(
+        &self,
+        id: &TemplateNodeId,
+        settings_block: &Option<Vec<SettingsBlockElement>>,
+    ) -> Option<Vec<SettingElement>> {
+        let node = self.get_node(id)?;
+        if node.control_flow_settings.is_some() {
+            return node.settings.clone();
+        }
+        let mut map = BTreeMap::new();
+        let matched =
+            selectors::matching_settings(self, id, settings_block.as_deref().unwrap_or_default());
+        for e in matched {
+            if let SettingElement::Setting(key, _) = &e {
+                map.insert(key.clone(), e);
+            }
+        }
+
+        let mut merged = node.settings.clone().unwrap_or_default();
+        for e in &merged {
+            if let SettingElement::Setting(key, _) = e {
+                map.remove(key);
+            }
+        }
+        merged.extend(map.into_values());
+        (!merged.is_empty()).then_some(merged)
+    }
+
+    /// The settings overriding those of a node while it's in an interaction state, from the
+    /// `@settings` blocks with pseudo-class selectors matching it, in increasing order of
+    /// precedence. These override inline settings too.
+    pub fn get_state_variants(
+        &self,
+        id: &TemplateNodeId,
+        settings_block: &Option<Vec<SettingsBlockElement>>,
+    ) -> Vec<selectors::StateVariant> {
+        match self.get_node(id) {
+            Some(node) if node.control_flow_settings.is_none() => {
+                selectors::matching_state_variants(
+                    self,
+                    id,
+                    settings_block.as_deref().unwrap_or_default(),
+                )
+            }
+            _ => Vec::new(),
         }
     }
 }
@@ -1394,6 +1526,13 @@ pub struct TemplateNodeDefinition {
 }
 
 impl TemplateNodeDefinition {
+    /// Whether this TND is an `else if` / `else` branch of a conditional chain
+    pub fn is_else_branch(&self) -> bool {
+        self.control_flow_settings
+            .as_ref()
+            .is_some_and(|cfsd| cfsd.is_else_branch)
+    }
+
     pub fn get_node_type(&self) -> NodeType {
         if let Some(cfsd) = &self.control_flow_settings {
             NodeType::ControlFlow(Box::new(cfsd.clone()))
@@ -1630,6 +1769,40 @@ impl ControlFlowRepeatPredicateDefinition {
     }
 }
 
+/// Pattern of a `match` arm, for example the `State::Error(message)` in
+/// `match self.state { State::Error(message) => { ... } }`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(crate = "pax_message::serde")]
+pub struct MatchArmPatternDefinition {
+    /// Enum variant as written, like `State::Error` or `Error`; `None` for the `_` wildcard
+    pub variant_path: Option<String>,
+    /// Symbols bound to the variant's payload, in order
+    pub bindings: Vec<String>,
+    /// The `if` guard that must also hold for this arm to be selected, evaluated with
+    /// the bindings in scope
+    #[serde(default)]
+    pub guard: Option<ExpressionInfo>,
+}
+
+impl MatchArmPatternDefinition {
+    /// The variant name this arm matches, without any enum qualifier
+    pub fn get_variant_name(&self) -> Option<String> {
+        self.variant_path
+            .as_ref()
+            .and_then(|path| path.rsplit("::").next())
+            .map(|name| name.to_string())
+    }
+
+    /// The name of the enum this arm's variant is qualified with, like `State` for
+    /// `State::Error`. `None` for unqualified variants, which match any enum
+    pub fn get_enum_name(&self) -> Option<String> {
+        self.variant_path
+            .as_ref()
+            .and_then(|path| path.rsplit("::").nth(1))
+            .map(|name| name.to_string())
+    }
+}
+
 /// Container for storing parsed control flow information, for
 /// example the string (PAXEL) representations of condition / slot / repeat
 /// expressions and the related vtable ids (for "punching" during expression compilation)
@@ -1640,6 +1813,20 @@ pub struct ControlFlowSettingsDefinition {
     pub slot_index_expression: Option<ExpressionInfo>,
     pub repeat_predicate_definition: Option<ControlFlowRepeatPredicateDefinition>,
     pub repeat_source_expression: Option<ExpressionInfo>,
+    /// Identity of each element of a `for` source (the `elem.id` in `key elem.id`), evaluated
+    /// with the predicate symbols in scope. Used to track rows across reorders
+    #[serde(default)]
+    pub repeat_key_expression: Option<ExpressionInfo>,
+    /// IFF this is a `match` statement: the value whose enum variant selects an arm
+    #[serde(default)]
+    pub match_expression: Option<ExpressionInfo>,
+    /// IFF this is an arm of a `match` statement: the pattern selecting it
+    #[serde(default)]
+    pub match_arm_pattern: Option<MatchArmPatternDefinition>,
+    /// Marks an `else if` (with `condition_expression`) or `else` (without) branch, which
+    /// continues the conditional chain of the nearest preceding `if` sibling
+    #[serde(default)]
+    pub is_else_branch: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -1647,6 +1834,13 @@ pub struct ControlFlowSettingsDefinition {
 pub struct ExpressionInfo {
     pub expression: PaxExpression,
     pub dependencies: Vec<String>,
+    /// Where this expression appears in its template, if it was parsed from one
+    #[serde(default)]
+    pub location: Option<LocationInfo>,
+    /// Index of the Rust function this expression was compiled to, assigned when
+    /// generating release cartridges. Expressions without one are interpreted
+    #[serde(default)]
+    pub id: Option<usize>,
 }
 
 impl Display for ExpressionInfo {
@@ -1660,6 +1854,8 @@ impl ExpressionInfo {
         Self {
             dependencies: expr.collect_dependencies(),
             expression: expr,
+            location: None,
+            id: None,
         }
     }
 }
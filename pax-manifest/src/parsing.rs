@@ -1,7 +1,7 @@
 use crate::*;
 use pax_lang::interpreter::parse_pax_expression_from_pair;
 use pax_lang::{from_pax, parse_pax_expression, parse_pax_str, Pair, Pairs, Rule, Span};
-use pax_runtime_api::{Color, Fill, Size, Stroke};
+use pax_runtime_api::{Color, Fill, Size, Stroke, StrokeAlignment, StrokeCap, StrokeJoin};
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 pub fn parse_template_from_component_definition_string(
@@ -107,46 +107,39 @@ fn recurse_visit_tag_pairs_for_template(
             };
         }
         Rule::statement_control_flow => {
-            /* statement_control_flow = {(statement_if | statement_for | statement_slot)} */
+            /* statement_control_flow = {(statement_if | statement_for | statement_match | statement_slot)} */
 
             let any_tag_pair = any_tag_pair.into_inner().next().unwrap();
             let _template_node_definition = match any_tag_pair.as_rule() {
                 Rule::statement_if => {
+                    /* statement_if = {"if" ~ expression_body ~ "{" ~ inner_nodes ~ "}" ~ statement_else_if* ~ statement_else?} */
                     let mut statement_if = any_tag_pair.into_inner();
                     let expression_body = statement_if.next().unwrap();
-                    let condition_expression =
-                        parse_pax_expression(expression_body.as_str()).unwrap();
-                    let expression_info = ExpressionInfo::new(condition_expression);
-
-                    //`if` TemplateNodeDefinition
-                    let template_node = TemplateNodeDefinition {
-                        control_flow_settings: Some(ControlFlowSettingsDefinition {
-                            condition_expression: Some(expression_info),
-                            slot_index_expression: None,
-                            repeat_predicate_definition: None,
-                            repeat_source_expression: None,
-                        }),
-                        type_id: TypeId::build_if(),
-                        settings: None,
-                        raw_comment_string: None,
-                    };
-
-                    let id = match location {
-                        TreeLocation::Root => ctx.template.add_root_node_back(template_node),
-                        TreeLocation::Parent(id) => ctx.template.add_child_back(id, template_node),
-                    };
-
-                    let prospective_inner_nodes = statement_if.next();
+                    let inner_nodes = statement_if.next().unwrap();
+                    add_conditional_branch(
+                        ctx,
+                        Some(expression_body),
+                        inner_nodes,
+                        false,
+                        pax,
+                        location.clone(),
+                    );
 
-                    if let Some(inner_nodes) = prospective_inner_nodes {
-                        inner_nodes.into_inner().for_each(|sub_tag_pair| {
-                            recurse_visit_tag_pairs_for_template(
-                                ctx,
-                                sub_tag_pair,
-                                pax,
-                                TreeLocation::Parent(id.clone().get_template_node_id()),
-                            );
-                        })
+                    //each `else if` / `else` becomes a sibling `if` TemplateNodeDefinition, flagged
+                    //as continuing the chain of the preceding `if`
+                    for branch in statement_if {
+                        let is_else_if = branch.as_rule() == Rule::statement_else_if;
+                        let mut branch_pairs = branch.into_inner();
+                        let expression_body = is_else_if.then(|| branch_pairs.next().unwrap());
+                        let inner_nodes = branch_pairs.next().unwrap();
+                        add_conditional_branch(
+                            ctx,
+                            expression_body,
+                            inner_nodes,
+                            true,
+                            pax,
+                            location.clone(),
+                        );
                     }
                 }
                 Rule::statement_for => {
@@ -155,7 +148,16 @@ fn recurse_visit_tag_pairs_for_template(
                     let mut predicate_declaration = for_statement.next().unwrap().into_inner();
                     let source = for_statement.next().unwrap();
 
-                    let prospective_inner_nodes = for_statement.next();
+                    let mut prospective_inner_nodes = for_statement.next();
+                    if let Some(key) = prospective_inner_nodes
+                        .clone()
+                        .filter(|pair| pair.as_rule() == Rule::statement_for_key)
+                    {
+                        /* statement_for_key = { "key" ~ expression_body } */
+                        let expression_body = key.into_inner().next().unwrap();
+                        cfavd.repeat_key_expression = Some(parse_expression_info(&expression_body));
+                        prospective_inner_nodes = for_statement.next();
+                    }
 
                     if predicate_declaration.clone().count() > 1 {
                         //tuple, like the `elem, i` in `for (elem, i) in self.some_list`
@@ -176,8 +178,7 @@ fn recurse_visit_tag_pairs_for_template(
 
                     let inner_source = source.into_inner().next().unwrap();
                     /* statement_for_source = { xo_range | xo_symbol } */
-                    let repeat_source_definition =
-                        ExpressionInfo::new(parse_pax_expression(inner_source.as_str()).unwrap());
+                    let repeat_source_definition = parse_expression_info(&inner_source);
                     cfavd.repeat_source_expression = Some(repeat_source_definition);
 
                     //`for` TemplateNodeDefinition
@@ -204,18 +205,72 @@ fn recurse_visit_tag_pairs_for_template(
                         })
                     }
                 }
+                Rule::statement_match => {
+                    /* statement_match = {"match" ~ expression_body ~ "{" ~ statement_match_arm* ~ "}"} */
+                    let mut statement_match = any_tag_pair.into_inner();
+                    let expression_body = statement_match.next().unwrap();
+                    let match_expression = parse_expression_info(&expression_body);
+                    let template_node = TemplateNodeDefinition {
+                        control_flow_settings: Some(ControlFlowSettingsDefinition {
+                            match_expression: Some(match_expression),
+                            ..Default::default()
+                        }),
+                        type_id: TypeId::build_match(),
+                        settings: None,
+                        raw_comment_string: None,
+                    };
+                    let id = match location {
+                        TreeLocation::Root => ctx.template.add_root_node_back(template_node),
+                        TreeLocation::Parent(id) => ctx.template.add_child_back(id, template_node),
+                    };
+
+                    //each arm becomes a child of the `match`, holding the arm's nodes as its own children
+                    for arm in statement_match {
+                        let mut arm_pairs = arm.into_inner();
+                        let mut pattern = parse_match_arm_pattern(arm_pairs.next().unwrap());
+                        let mut inner_nodes = arm_pairs.next().unwrap();
+                        if inner_nodes.as_rule() == Rule::statement_match_guard {
+                            /* statement_match_guard = {"if" ~ expression_body} */
+                            let expression_body = inner_nodes.into_inner().next().unwrap();
+                            pattern.guard = Some(parse_expression_info(&expression_body));
+                            inner_nodes = arm_pairs.next().unwrap();
+                        }
+                        let arm_node = TemplateNodeDefinition {
+                            control_flow_settings: Some(ControlFlowSettingsDefinition {
+                                match_arm_pattern: Some(pattern),
+                                ..Default::default()
+                            }),
+                            type_id: TypeId::build_match_arm(),
+                            settings: None,
+                            raw_comment_string: None,
+                        };
+                        let arm_id = ctx
+                            .template
+                            .add_child_back(id.clone().get_template_node_id(), arm_node);
+                        inner_nodes.into_inner().for_each(|sub_tag_pair| {
+                            recurse_visit_tag_pairs_for_template(
+                                ctx,
+                                sub_tag_pair,
+                                pax,
+                                TreeLocation::Parent(arm_id.clone().get_template_node_id()),
+                            );
+                        });
+                    }
+                }
                 Rule::statement_slot => {
                     let mut statement_slot = any_tag_pair.into_inner();
                     let expression_body = statement_slot.next().unwrap();
-                    let slot_expression = ExpressionInfo::new(
-                        parse_pax_expression(expression_body.as_str()).unwrap(),
-                    );
+                    let slot_expression = parse_expression_info(&expression_body);
                     let template_node = TemplateNodeDefinition {
                         control_flow_settings: Some(ControlFlowSettingsDefinition {
                             condition_expression: None,
                             slot_index_expression: Some(slot_expression),
                             repeat_predicate_definition: None,
                             repeat_source_expression: None,
+                            repeat_key_expression: None,
+                            match_expression: None,
+                            match_arm_pattern: None,
+                            is_else_branch: false,
                         }),
                         type_id: TypeId::build_slot(),
                         settings: None,
@@ -254,6 +309,66 @@ fn recurse_visit_tag_pairs_for_template(
     }
 }
 
+fn parse_match_arm_pattern(pattern: Pair<Rule>) -> MatchArmPatternDefinition {
+    /* statement_match_pattern = { statement_match_wildcard | (statement_match_variant ~ ("(" ~ (identifier ~ ("," ~ identifier)*)? ~ ")")?) } */
+    let mut pattern_pairs = pattern.into_inner();
+    let head = pattern_pairs.next().unwrap();
+    match head.as_rule() {
+        Rule::statement_match_wildcard => MatchArmPatternDefinition::default(),
+        Rule::statement_match_variant => MatchArmPatternDefinition {
+            variant_path: Some(head.as_str().to_owned()),
+            bindings: pattern_pairs
+                .map(|binding| binding.as_str().to_owned())
+                .collect(),
+            guard: None,
+        },
+        _ => unreachable!("Parsing error: {:?}", head.as_rule()),
+    }
+}
+
+/// Adds an `if`, `else if` or `else` branch TemplateNodeDefinition at `location` and recurses into its inner nodes
+fn add_conditional_branch(
+    ctx: &mut TemplateNodeParseContext,
+    expression_body: Option<Pair<Rule>>,
+    inner_nodes: Pair<Rule>,
+    is_else_branch: bool,
+    pax: &str,
+    location: TreeLocation,
+) {
+    let condition_expression =
+        expression_body.map(|expression_body| parse_expression_info(&expression_body));
+
+    let template_node = TemplateNodeDefinition {
+        control_flow_settings: Some(ControlFlowSettingsDefinition {
+            condition_expression,
+            slot_index_expression: None,
+            repeat_predicate_definition: None,
+            repeat_source_expression: None,
+            repeat_key_expression: None,
+            match_expression: None,
+            match_arm_pattern: None,
+            is_else_branch,
+        }),
+        type_id: TypeId::build_if(),
+        settings: None,
+        raw_comment_string: None,
+    };
+
+    let id = match location {
+        TreeLocation::Root => ctx.template.add_root_node_back(template_node),
+        TreeLocation::Parent(id) => ctx.template.add_child_back(id, template_node),
+    };
+
+    inner_nodes.into_inner().for_each(|sub_tag_pair| {
+        recurse_visit_tag_pairs_for_template(
+            ctx,
+            sub_tag_pair,
+            pax,
+            TreeLocation::Parent(id.clone().get_template_node_id()),
+        );
+    })
+}
+
 fn parse_literal_function(literal_function_full: Pair<Rule>) -> Token {
     let literal_function = literal_function_full.clone().into_inner().next().unwrap();
 
@@ -263,10 +378,17 @@ fn parse_literal_function(literal_function_full: Pair<Rule>) -> Token {
 }
 
 fn parse_event_id(event_id_full: Pair<Rule>) -> Token {
-    let event_id = event_id_full.clone().into_inner().next().unwrap();
+    let mut event_id_pairs = event_id_full.into_inner();
+    let event_id = event_id_pairs.next().unwrap();
+
+    // capture-phase handlers are keyed by their event name and phase, e.g. `click.capture`
+    let mut event_key = event_id.as_str().to_string();
+    if let Some(event_phase) = event_id_pairs.next() {
+        event_key.push_str(event_phase.as_str());
+    }
 
     let event_id_location = span_to_location(&event_id.as_span());
-    let event_id_token = Token::new(event_id.as_str().to_string(), event_id_location);
+    let event_id_token = Token::new(event_key, event_id_location);
     event_id_token
 }
 
@@ -360,9 +482,13 @@ pub fn parse_value_definition(value: Pair<Rule>) -> ValueDefinition {
             }
         }
         Rule::expression_body => {
+            let location = span_to_location(&value.as_span());
             let expression =
                 parse_pax_expression_from_pair(value).expect("Unable to parse expression");
-            ValueDefinition::Expression(ExpressionInfo::new(expression))
+            ValueDefinition::Expression(ExpressionInfo {
+                location: Some(location),
+                ..ExpressionInfo::new(expression)
+            })
         }
         Rule::identifier => {
             let identifier = PaxIdentifier::new(value.as_str());
@@ -471,11 +597,26 @@ pub fn parse_settings_from_component_definition_string(
                                     let raw_selector = selector_block_pairs.next().unwrap();
                                     let raw_value_location =
                                         span_to_location(&raw_selector.as_span());
+                                    // normalized, see `selectors`
                                     let selector: String = raw_selector
-                                        .as_str()
-                                        .chars()
-                                        .filter(|c| !c.is_whitespace())
-                                        .collect();
+                                        .into_inner()
+                                        .map(|complex_selector| {
+                                            let (pseudo_classes, compounds): (Vec<_>, Vec<_>) =
+                                                complex_selector.into_inner().partition(|p| {
+                                                    p.as_rule() == Rule::pseudo_class
+                                                });
+                                            let mut normalized = compounds
+                                                .iter()
+                                                .map(|compound| compound.as_str())
+                                                .collect::<Vec<_>>()
+                                                .join(" ");
+                                            for pseudo_class in pseudo_classes {
+                                                normalized.push_str(pseudo_class.as_str());
+                                            }
+                                            normalized
+                                        })
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
                                     let token = Token::new(selector, raw_value_location);
                                     let literal_object = selector_block_pairs.next().unwrap();
 
@@ -657,6 +798,14 @@ pub fn assemble_type_definition(
     (ctx, new_def)
 }
 
+/// Parses the PAXEL expression in `pair`, recording where it appears in the template
+fn parse_expression_info(pair: &Pair<Rule>) -> ExpressionInfo {
+    ExpressionInfo {
+        location: Some(span_to_location(&pair.as_span())),
+        ..ExpressionInfo::new(parse_pax_expression(pair.as_str()).unwrap())
+    }
+}
+
 /// Given a Pest Span returns starting and ending (line,col)
 fn span_to_location(span: &Span) -> LocationInfo {
     let start = (
@@ -964,15 +1113,55 @@ impl Reflectable for Stroke {
                 },
                 PropertyDefinition {
                     name: "width".to_string(),
-                    flags: flags,
+                    flags: flags.clone(),
                     type_id: Size::get_type_id(),
                 },
+                PropertyDefinition {
+                    name: "fill".to_string(),
+                    flags: flags.clone(),
+                    type_id: Option::<Fill>::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "dash".to_string(),
+                    flags: flags.clone(),
+                    type_id: Vec::<f64>::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "dash_offset".to_string(),
+                    flags: flags.clone(),
+                    type_id: f64::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "cap".to_string(),
+                    flags: flags.clone(),
+                    type_id: StrokeCap::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "join".to_string(),
+                    flags: flags.clone(),
+                    type_id: StrokeJoin::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "miter_limit".to_string(),
+                    flags: flags.clone(),
+                    type_id: f64::get_type_id(),
+                },
+                PropertyDefinition {
+                    name: "alignment".to_string(),
+                    flags,
+                    type_id: StrokeAlignment::get_type_id(),
+                },
             ],
         };
 
         if !ctx.type_table.contains_key(&type_id) {
             ctx.type_table.insert(type_id, td);
         }
+        let (ctx, _) = Option::<Fill>::parse_to_manifest(ctx);
+        let (ctx, _) = Vec::<f64>::parse_to_manifest(ctx);
+        let (ctx, _) = StrokeCap::parse_to_manifest(ctx);
+        let (ctx, _) = StrokeJoin::parse_to_manifest(ctx);
+        let (mut ctx, _) = StrokeAlignment::parse_to_manifest(ctx);
         let color_type_id = Color::get_type_id();
         if !ctx.type_table.contains_key(&color_type_id) {
             ctx.type_table.insert(
@@ -1066,6 +1255,91 @@ impl Reflectable for pax_runtime_api::PathElement {
     }
 }
 
+impl Reflectable for pax_runtime_api::Effect {
+    fn get_import_path() -> String {
+        "pax_engine::api::Effect".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "Effect".to_string()
+    }
+
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
+impl Reflectable for pax_runtime_api::BlendMode {
+    fn get_import_path() -> String {
+        "pax_engine::api::BlendMode".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "BlendMode".to_string()
+    }
+
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
+impl Reflectable for StrokeCap {
+    fn get_import_path() -> String {
+        "pax_engine::api::StrokeCap".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "StrokeCap".to_string()
+    }
+
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
+impl Reflectable for StrokeJoin {
+    fn get_import_path() -> String {
+        "pax_engine::api::StrokeJoin".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "StrokeJoin".to_string()
+    }
+
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
+impl Reflectable for StrokeAlignment {
+    fn get_import_path() -> String {
+        "pax_engine::api::StrokeAlignment".to_string()
+    }
+
+    fn get_self_pascal_identifier() -> String {
+        "StrokeAlignment".to_string()
+    }
+
+    fn get_type_id() -> TypeId {
+        TypeId::build_singleton(
+            &Self::get_import_path(),
+            Some(&Self::get_self_pascal_identifier()),
+        )
+    }
+}
+
 impl Reflectable for pax_runtime_api::ColorChannel {
     fn get_import_path() -> String {
         "pax_engine::api::ColorChannel".to_string()
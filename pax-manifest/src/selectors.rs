@@ -0,0 +1,286 @@
+//! CSS-style selectors joining the blocks of a component's `@settings` to the nodes of its
+//! template, e.g. `.primary, .secondary`, `Text.title`, `Rectangle` or `.card .label`.
+//!
+//! Selectors are stored in their normalized form in the token of a
+//! [`SettingsBlockElement::SelectorBlock`]: compound selectors separated by a single space,
+//! and the selectors of a list by `", "`.
+//!
+//! Selectors ending with pseudo-classes, e.g. `.card:hover`, only apply while the node is in
+//! those interaction states. Their blocks aren't merged into the settings of the nodes they
+//! match, but become [`StateVariant`]s the runtime applies and removes as the state changes.
+
+use crate::{
+    ComponentTemplate, SettingElement, SettingsBlockElement, TemplateNodeDefinition,
+    TemplateNodeId, ValueDefinition,
+};
+
+/// The number of id, class (including pseudo-class) and type selectors in a selector.
+/// Blocks with a higher specificity override those with a lower one, and later blocks
+/// override earlier ones of the same specificity.
+pub type Specificity = (usize, usize, usize);
+
+/// A comma-separated list of selectors, matching nodes matched by any of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorList(pub Vec<Selector>);
+
+/// A chain of compound selectors separated by descendant combinators. The last one
+/// matches the node itself, and the others its ancestors, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector(pub Vec<CompoundSelector>);
+
+/// A type selector with classes and ids, all of which a node must match, e.g. `Text.title`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompoundSelector {
+    pub type_name: Option<String>,
+    pub classes: Vec<String>,
+    pub ids: Vec<String>,
+    /// Only allowed on the last compound of a selector
+    pub pseudo_classes: Vec<PseudoClass>,
+}
+
+/// An interaction state of a node, tracked by the runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PseudoClass {
+    /// `:hover`, while the pointer is over the node or one of its descendants
+    Hover,
+    /// `:pressed`, from a pointer press on the node or one of its descendants until release
+    Pressed,
+    /// `:focused`, while the node or one of its descendants has keyboard focus
+    Focused,
+    /// `:disabled`, while the `disabled` property of the node is true
+    Disabled,
+}
+
+impl PseudoClass {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "hover" => Some(PseudoClass::Hover),
+            "pressed" => Some(PseudoClass::Pressed),
+            "focused" => Some(PseudoClass::Focused),
+            "disabled" => Some(PseudoClass::Disabled),
+            _ => None,
+        }
+    }
+}
+
+/// The settings of a block that apply to a node only while it's in all of `states`
+#[derive(Debug, Clone)]
+pub struct StateVariant {
+    pub states: Vec<PseudoClass>,
+    pub settings: Vec<SettingElement>,
+}
+
+impl SelectorList {
+    pub fn parse(selector: &str) -> Self {
+        SelectorList(
+            selector
+                .split(',')
+                .map(|s| Selector(s.split_whitespace().map(CompoundSelector::parse).collect()))
+                .filter(|s| !s.0.is_empty())
+                .collect(),
+        )
+    }
+
+    /// The highest specificity among the selectors of this list matching the node in any
+    /// interaction state, if any
+    pub fn match_specificity(
+        &self,
+        template: &ComponentTemplate,
+        id: &TemplateNodeId,
+    ) -> Option<Specificity> {
+        self.0
+            .iter()
+            .filter(|selector| selector.states().is_empty() && selector.matches(template, id))
+            .map(Selector::specificity)
+            .max()
+    }
+
+    /// The interaction states in which selectors of this list match the node, each with the
+    /// highest specificity of the selectors matching it in that state
+    pub fn match_states(
+        &self,
+        template: &ComponentTemplate,
+        id: &TemplateNodeId,
+    ) -> Vec<(Vec<PseudoClass>, Specificity)> {
+        let mut matched: Vec<(Vec<PseudoClass>, Specificity)> = Vec::new();
+        for selector in &self.0 {
+            let mut states = selector.states().to_vec();
+            if states.is_empty() || !selector.matches(template, id) {
+                continue;
+            }
+            states.sort();
+            states.dedup();
+            let specificity = selector.specificity();
+            match matched.iter_mut().find(|(s, _)| *s == states) {
+                Some((_, max)) => *max = specificity.max(*max),
+                None => matched.push((states, specificity)),
+            }
+        }
+        matched
+    }
+}
+
+impl Selector {
+    pub fn specificity(&self) -> Specificity {
+        self.0.iter().fold((0, 0, 0), |(ids, classes, types), c| {
+            (
+                ids + c.ids.len(),
+                classes + c.classes.len() + c.pseudo_classes.len(),
+                types + c.type_name.is_some() as usize,
+            )
+        })
+    }
+
+    /// The interaction states a node must be in for this selector to apply
+    pub fn states(&self) -> &[PseudoClass] {
+        self.0
+            .last()
+            .map(|subject| subject.pseudo_classes.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether this selector matches the node, regardless of its interaction state
+    pub fn matches(&self, template: &ComponentTemplate, id: &TemplateNodeId) -> bool {
+        let Some((subject, ancestors)) = self.0.split_last() else {
+            return false;
+        };
+        if !template.get_node(id).is_some_and(|n| subject.matches(n)) {
+            return false;
+        }
+        // match the remaining compounds right to left against the closest ancestors
+        // matching them; descendant combinators never need to backtrack
+        let mut remaining = ancestors.iter().rev().peekable();
+        let mut current = template.get_parent(id);
+        while let (Some(compound), Some(ancestor_id)) = (remaining.peek(), &current) {
+            if template
+                .get_node(ancestor_id)
+                .is_some_and(|n| compound.matches(n))
+            {
+                remaining.next();
+            }
+            current = template.get_parent(ancestor_id);
+        }
+        remaining.peek().is_none()
+    }
+}
+
+impl CompoundSelector {
+    pub fn parse(compound: &str) -> Self {
+        let mut selector = CompoundSelector::default();
+        let (mut rest, pseudo_classes) = compound.split_once(':').unwrap_or((compound, ""));
+        selector.pseudo_classes = pseudo_classes
+            .split(':')
+            .filter_map(PseudoClass::parse)
+            .collect();
+        if !rest.starts_with(['.', '#']) {
+            let end = rest.find(['.', '#']).unwrap_or(rest.len());
+            selector.type_name = Some(rest[..end].to_string());
+            rest = &rest[end..];
+        }
+        while !rest.is_empty() {
+            let end = rest[1..].find(['.', '#']).map_or(rest.len(), |i| i + 1);
+            let name = rest[1..end].to_string();
+            if rest.starts_with('.') {
+                selector.classes.push(name);
+            } else {
+                selector.ids.push(name);
+            }
+            rest = &rest[end..];
+        }
+        selector
+    }
+
+    /// Control-flow nodes have no settings, and are never matched
+    pub fn matches(&self, node: &TemplateNodeDefinition) -> bool {
+        if node.control_flow_settings.is_some() || node.raw_comment_string.is_some() {
+            return false;
+        }
+        let type_matches = match &self.type_name {
+            Some(type_name) => node.type_id.get_pascal_identifier().as_ref() == Some(type_name),
+            None => true,
+        };
+        type_matches
+            && self
+                .classes
+                .iter()
+                .all(|class| inline_identifiers(node, "class").any(|c| c == class))
+            && self
+                .ids
+                .iter()
+                .all(|id| inline_identifiers(node, "id").any(|i| i == id))
+    }
+}
+
+/// The values of the inline `class=...` or `id=...` settings of a node
+fn inline_identifiers<'a>(
+    node: &'a TemplateNodeDefinition,
+    key: &'a str,
+) -> impl Iterator<Item = &'a String> + 'a {
+    node.settings
+        .iter()
+        .flatten()
+        .filter_map(move |setting| match setting {
+            SettingElement::Setting(token, ValueDefinition::Identifier(ident))
+                if token.token_value == key =>
+            {
+                Some(&ident.name)
+            }
+            _ => None,
+        })
+}
+
+/// The settings of the blocks matching a node, in increasing order of precedence
+pub fn matching_settings(
+    template: &ComponentTemplate,
+    id: &TemplateNodeId,
+    settings_block: &[SettingsBlockElement],
+) -> Vec<SettingElement> {
+    let mut matched: Vec<(Specificity, &Vec<SettingElement>)> = settings_block
+        .iter()
+        .filter_map(|element| match element {
+            SettingsBlockElement::SelectorBlock(token, block) => {
+                let specificity =
+                    SelectorList::parse(&token.token_value).match_specificity(template, id)?;
+                Some((specificity, &block.elements))
+            }
+            _ => None,
+        })
+        .collect();
+    // stable, so source order is kept between blocks of the same specificity
+    matched.sort_by_key(|(specificity, _)| *specificity);
+    matched
+        .into_iter()
+        .flat_map(|(_, elements)| elements.iter().cloned())
+        .collect()
+}
+
+/// The state variants of the blocks matching a node, in increasing order of precedence
+pub fn matching_state_variants(
+    template: &ComponentTemplate,
+    id: &TemplateNodeId,
+    settings_block: &[SettingsBlockElement],
+) -> Vec<StateVariant> {
+    let mut matched: Vec<(Specificity, StateVariant)> = settings_block
+        .iter()
+        .flat_map(|element| match element {
+            SettingsBlockElement::SelectorBlock(token, block) => {
+                SelectorList::parse(&token.token_value)
+                    .match_states(template, id)
+                    .into_iter()
+                    .map(|(states, specificity)| {
+                        (
+                            specificity,
+                            StateVariant {
+                                states,
+                                settings: block.elements.clone(),
+                            },
+                        )
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        })
+        .collect();
+    matched.sort_by_key(|(specificity, _)| *specificity);
+    matched.into_iter().map(|(_, variant)| variant).collect()
+}
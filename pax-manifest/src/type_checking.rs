@@ -0,0 +1,667 @@
+//! Static checks of the PAXEL expressions in component templates and `@settings` blocks.
+//!
+//! Every symbol an expression reads is resolved against the scope it is evaluated in: the
+//! component's properties, plus the predicate symbols of enclosing `for` loops and the payload
+//! bindings of enclosing `match` arms. Operand types are inferred from the `TypeTable` and
+//! checked against the arithmetic supported by `PaxValue`, and calls to registered functions
+//! are checked against their registered arity. Anything whose type can't be known statically
+//! is treated as `Unknown` and accepted, so that these checks never reject a valid program.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use pax_lang::interpreter::{PaxAccessor, PaxUnit, TemplateSegment};
+use pax_runtime_api::{Functions, PaxValue};
+
+use crate::constants::BUBBLING_EVENTS;
+use crate::{
+    get_common_properties_as_property_definitions, ComponentDefinition, ComponentTemplate,
+    ControlFlowRepeatPredicateDefinition, ExpressionInfo, LiteralBlockDefinition, LocationInfo,
+    PaxExpression, PaxManifest, PaxPrimary, PaxType, SettingElement, SettingsBlockElement,
+    TemplateNodeId, Token, TypeId, TypeTable, ValueDefinition, SUPPORTED_NUMERIC_PRIMITIVES,
+};
+
+/// A problem found in an expression of a component, reported as a build error
+#[derive(Debug, Clone)]
+pub struct ExpressionError {
+    pub component: TypeId,
+    /// The file the component's template was read from
+    pub file_path: Option<String>,
+    /// Where the offending expression appears in the template, if known
+    pub location: Option<LocationInfo>,
+    pub message: String,
+}
+
+impl Display for ExpressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Statically inferred type of an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionType {
+    Bool,
+    Numeric,
+    String,
+    Size,
+    Percent,
+    Rotation,
+    Color,
+    /// A `TransitionDuration`, such as `300ms`
+    Duration,
+    List(Box<ExpressionType>),
+    /// A struct with known fields
+    Struct(TypeId),
+    Function,
+    Unknown,
+}
+
+impl Display for ExpressionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionType::Bool => write!(f, "bool"),
+            ExpressionType::Numeric => write!(f, "Numeric"),
+            ExpressionType::String => write!(f, "String"),
+            ExpressionType::Size => write!(f, "Size"),
+            ExpressionType::Percent => write!(f, "Percent"),
+            ExpressionType::Rotation => write!(f, "Rotation"),
+            ExpressionType::Color => write!(f, "Color"),
+            ExpressionType::Duration => write!(f, "TransitionDuration"),
+            ExpressionType::List(elem) => write!(f, "List<{}>", elem),
+            ExpressionType::Struct(type_id) => write!(
+                f,
+                "{}",
+                type_id
+                    .get_pascal_identifier()
+                    .unwrap_or_else(|| type_id.get_unique_identifier())
+            ),
+            ExpressionType::Function => write!(f, "function"),
+            ExpressionType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Types of the symbols an expression can read
+pub type Scope = HashMap<String, ExpressionType>;
+
+/// Checks the expressions of every component with a template or `@settings` block.
+/// Builtin functions must be registered (see `Functions::register_all_functions`)
+/// for calls to them to be recognized.
+pub fn check_manifest(manifest: &PaxManifest) -> Vec<ExpressionError> {
+    manifest
+        .components
+        .values()
+        .flat_map(|component| check_component(component, &manifest.type_table))
+        .collect()
+}
+
+pub fn check_component(
+    component: &ComponentDefinition,
+    type_table: &TypeTable,
+) -> Vec<ExpressionError> {
+    check(component, type_table, &mut |_, _, _| {})
+}
+
+/// Calls `visit` with each expression of `component` checked, along with the types of the
+/// symbols in scope where it is evaluated and the type of the property it's assigned to,
+/// where known
+pub fn visit_expressions(
+    component: &ComponentDefinition,
+    type_table: &TypeTable,
+    visit: &mut ExpressionVisitor,
+) {
+    check(component, type_table, visit);
+}
+
+/// The inferred type of `expression`, `Unknown` where it doesn't type check
+pub fn infer_type(
+    expression: &PaxExpression,
+    scope: &Scope,
+    type_table: &TypeTable,
+) -> ExpressionType {
+    infer(expression, scope, type_table, &mut vec![])
+}
+
+/// Called with an expression, its scope and the type of the property it's assigned to
+pub type ExpressionVisitor<'a> = dyn FnMut(&ExpressionInfo, &Scope, Option<&TypeId>) + 'a;
+
+fn check(
+    component: &ComponentDefinition,
+    type_table: &TypeTable,
+    visit: &mut ExpressionVisitor,
+) -> Vec<ExpressionError> {
+    let mut checker = Checker {
+        type_table,
+        component,
+        visit,
+        errors: vec![],
+    };
+    let mut scope: Scope = get_common_properties_as_property_definitions()
+        .iter()
+        .chain(
+            type_table
+                .get(&component.type_id)
+                .map(|td| td.property_definitions.iter())
+                .into_iter()
+                .flatten(),
+        )
+        .map(|pd| (pd.name.clone(), checker.type_of(&pd.type_id)))
+        .collect();
+
+    if let Some(template) = &component.template {
+        checker.check_nodes(template, &template.get_root(), &scope);
+
+        // selectors can match nodes inside loops and match arms, so their symbols are in scope
+        for node in template.get_nodes() {
+            if let Some(cfsd) = &node.control_flow_settings {
+                if let Some(predicate) = &cfsd.repeat_predicate_definition {
+                    scope.extend(
+                        predicate
+                            .get_symbols()
+                            .into_iter()
+                            .map(|symbol| (symbol, ExpressionType::Unknown)),
+                    );
+                }
+                if let Some(pattern) = &cfsd.match_arm_pattern {
+                    scope.extend(
+                        pattern
+                            .bindings
+                            .iter()
+                            .map(|binding| (binding.clone(), ExpressionType::Unknown)),
+                    );
+                }
+            }
+        }
+    }
+    for element in component.settings.iter().flatten() {
+        match element {
+            SettingsBlockElement::SelectorBlock(_, block) => {
+                checker.check_block(block, None, &scope)
+            }
+            SettingsBlockElement::Handler(event, _) => checker.check_event_phase(event),
+            _ => {}
+        }
+    }
+    checker.errors
+}
+
+struct Checker<'a> {
+    type_table: &'a TypeTable,
+    component: &'a ComponentDefinition,
+    visit: &'a mut ExpressionVisitor<'a>,
+    errors: Vec<ExpressionError>,
+}
+
+impl<'a> Checker<'a> {
+    fn report(&mut self, location: Option<&LocationInfo>, message: String) {
+        self.errors.push(ExpressionError {
+            component: self.component.type_id.clone(),
+            file_path: self
+                .component
+                .template
+                .as_ref()
+                .and_then(|t| t.get_file_path()),
+            location: location.cloned(),
+            message,
+        });
+    }
+
+    fn check_nodes(&mut self, template: &ComponentTemplate, ids: &[TemplateNodeId], scope: &Scope) {
+        for id in ids {
+            let Some(node) = template.get_node(id) else {
+                continue;
+            };
+            let children = template.get_children(id).unwrap_or_default();
+            let Some(cfsd) = &node.control_flow_settings else {
+                for setting in node.settings.iter().flatten() {
+                    self.check_setting(setting, Some(&node.type_id), scope);
+                }
+                self.check_nodes(template, &children, scope);
+                continue;
+            };
+
+            if let Some(condition) = &cfsd.condition_expression {
+                self.check_expression_info(
+                    condition,
+                    Some(&TypeId::build_primitive("bool")),
+                    scope,
+                );
+            }
+            for info in [&cfsd.slot_index_expression, &cfsd.match_expression]
+                .into_iter()
+                .flatten()
+            {
+                self.check_expression_info(info, None, scope);
+            }
+
+            let mut inner_scope = scope.clone();
+            if let Some(predicate) = &cfsd.repeat_predicate_definition {
+                let elem_type = match cfsd
+                    .repeat_source_expression
+                    .as_ref()
+                    .map(|info| self.check_expression_info(info, None, scope))
+                {
+                    Some(ExpressionType::List(elem)) => *elem,
+                    _ => ExpressionType::Unknown,
+                };
+                match predicate {
+                    ControlFlowRepeatPredicateDefinition::ElemId(elem) => {
+                        inner_scope.insert(elem.clone(), elem_type);
+                    }
+                    ControlFlowRepeatPredicateDefinition::ElemIdIndexId(elem, i) => {
+                        inner_scope.insert(elem.clone(), elem_type);
+                        inner_scope.insert(i.clone(), ExpressionType::Numeric);
+                    }
+                }
+                if let Some(key) = &cfsd.repeat_key_expression {
+                    self.check_expression_info(key, None, &inner_scope);
+                }
+            }
+            if let Some(pattern) = &cfsd.match_arm_pattern {
+                for binding in &pattern.bindings {
+                    inner_scope.insert(binding.clone(), ExpressionType::Unknown);
+                }
+                if let Some(guard) = &pattern.guard {
+                    self.check_expression_info(guard, None, &inner_scope);
+                }
+            }
+            self.check_nodes(template, &children, &inner_scope);
+        }
+    }
+
+    /// Checks the settings of `block`, which set the properties of `owner` when known
+    fn check_block(
+        &mut self,
+        block: &LiteralBlockDefinition,
+        owner: Option<&TypeId>,
+        scope: &Scope,
+    ) {
+        for element in &block.elements {
+            self.check_setting(element, owner, scope);
+        }
+    }
+
+    fn check_setting(&mut self, setting: &SettingElement, owner: Option<&TypeId>, scope: &Scope) {
+        let SettingElement::Setting(token, value) = setting else {
+            return;
+        };
+        let property_type = owner.and_then(|owner| self.property_type(owner, &token.token_value));
+        match value {
+            ValueDefinition::Expression(info) => {
+                self.check_expression_info(info, property_type.as_ref(), scope);
+            }
+            // `id` and `class` name the node rather than reading a symbol
+            ValueDefinition::Identifier(_)
+                if token.token_value == "id" || token.token_value == "class" => {}
+            ValueDefinition::Identifier(ident) | ValueDefinition::DoubleBinding(ident) => {
+                self.check_symbol(&ident.name, token, scope);
+            }
+            ValueDefinition::Block(block) => self.check_block(block, property_type.as_ref(), scope),
+            ValueDefinition::EventBindingTarget(_) => self.check_event_phase(token),
+            ValueDefinition::LiteralValue(_) | ValueDefinition::Undefined => {}
+        }
+    }
+
+    /// Capture-phase handlers, e.g. `@click.capture`, are only run for bubbling events
+    fn check_event_phase(&mut self, event: &Token) {
+        let Some(name) = event.token_value.strip_suffix(".capture") else {
+            return;
+        };
+        if !BUBBLING_EVENTS.contains(&name) {
+            self.report(
+                event.token_location.as_ref(),
+                format!("`{}` events don't bubble, so can't be captured", name),
+            );
+        }
+    }
+
+    fn check_symbol(&mut self, name: &str, token: &Token, scope: &Scope) {
+        let name = name.trim_start_matches("self.").trim_start_matches("this.");
+        let root = name.split('.').next().unwrap_or(name);
+        if !root.starts_with('$') && !scope.contains_key(root) {
+            self.report(
+                token.token_location.as_ref(),
+                format!("undefined symbol `{}`", root),
+            );
+        }
+    }
+
+    fn check_expression_info(
+        &mut self,
+        info: &ExpressionInfo,
+        property_type: Option<&TypeId>,
+        scope: &Scope,
+    ) -> ExpressionType {
+        (self.visit)(info, scope, property_type);
+        let mut messages = vec![];
+        let expression_type = infer(&info.expression, scope, self.type_table, &mut messages);
+        for message in messages {
+            self.report(info.location.as_ref(), message);
+        }
+        expression_type
+    }
+
+    fn type_of(&self, type_id: &TypeId) -> ExpressionType {
+        type_of(type_id, self.type_table)
+    }
+
+    /// The type of the property `name` of `owner`, a node or a struct set with a block
+    fn property_type(&self, owner: &TypeId, name: &str) -> Option<TypeId> {
+        self.type_table
+            .get(owner)
+            .and_then(|td| td.property_definitions.iter().find(|pd| pd.name == name))
+            .map(|pd| pd.type_id.clone())
+            .or_else(|| {
+                get_common_properties_as_property_definitions()
+                    .into_iter()
+                    .find(|pd| pd.name == name)
+                    .map(|pd| pd.type_id)
+            })
+    }
+}
+
+/// The expression type of values of a property type
+pub fn type_of(type_id: &TypeId, type_table: &TypeTable) -> ExpressionType {
+    match type_id.get_pax_type() {
+        PaxType::Primitive { pascal_identifier } => match pascal_identifier.as_str() {
+            "bool" => ExpressionType::Bool,
+            "String" => ExpressionType::String,
+            p if SUPPORTED_NUMERIC_PRIMITIVES.contains(&p) => ExpressionType::Numeric,
+            _ => ExpressionType::Unknown,
+        },
+        PaxType::Singleton { pascal_identifier } => match pascal_identifier.as_str() {
+            "Numeric" => ExpressionType::Numeric,
+            "Size" => ExpressionType::Size,
+            "Percent" => ExpressionType::Percent,
+            "Rotation" => ExpressionType::Rotation,
+            "Color" => ExpressionType::Color,
+            "String" => ExpressionType::String,
+            "TransitionDuration" => ExpressionType::Duration,
+            _ => match type_table.get(type_id) {
+                Some(td) if !td.property_definitions.is_empty() => {
+                    ExpressionType::Struct(type_id.clone())
+                }
+                _ => ExpressionType::Unknown,
+            },
+        },
+        PaxType::Vector { .. } => ExpressionType::List(Box::new(
+            type_table
+                .get(type_id)
+                .and_then(|td| td.inner_iterable_type_id.as_ref())
+                .map(|inner| type_of(inner, type_table))
+                .unwrap_or(ExpressionType::Unknown),
+        )),
+        PaxType::Range { .. } => ExpressionType::List(Box::new(ExpressionType::Numeric)),
+        _ => ExpressionType::Unknown,
+    }
+}
+
+fn type_of_literal(value: &PaxValue) -> ExpressionType {
+    match value {
+        PaxValue::Bool(_) => ExpressionType::Bool,
+        PaxValue::Numeric(_) => ExpressionType::Numeric,
+        PaxValue::String(_) => ExpressionType::String,
+        PaxValue::Size(_) => ExpressionType::Size,
+        PaxValue::Percent(_) => ExpressionType::Percent,
+        PaxValue::Color(_) => ExpressionType::Color,
+        PaxValue::Rotation(_) => ExpressionType::Rotation,
+        PaxValue::Duration(_) => ExpressionType::Duration,
+        PaxValue::Vec(_) => ExpressionType::List(Box::new(ExpressionType::Unknown)),
+        PaxValue::Function(_) => ExpressionType::Function,
+        _ => ExpressionType::Unknown,
+    }
+}
+
+/// Infers the type of `expression`, pushing a message for each problem found
+fn infer(
+    expression: &PaxExpression,
+    scope: &Scope,
+    type_table: &TypeTable,
+    errors: &mut Vec<String>,
+) -> ExpressionType {
+    let infer_in =
+        |e: &PaxExpression, errors: &mut Vec<String>| infer(e, scope, type_table, errors);
+    match expression {
+        PaxExpression::Primary(primary) => infer_primary(primary, scope, type_table, errors),
+        PaxExpression::Prefix(prefix) => {
+            let rhs = infer_in(&prefix.rhs, errors);
+            unary_result(&prefix.operator.name, &rhs).unwrap_or_else(|| {
+                errors.push(format!(
+                    "cannot apply `{}` to {}",
+                    prefix.operator.name, rhs
+                ));
+                ExpressionType::Unknown
+            })
+        }
+        PaxExpression::Postfix(postfix) => {
+            infer_in(&postfix.lhs, errors);
+            ExpressionType::Unknown
+        }
+        PaxExpression::Infix(infix) => {
+            let lhs = infer_in(&infix.lhs, errors);
+            let rhs = infer_in(&infix.rhs, errors);
+            binary_result(&infix.operator.name, &lhs, &rhs).unwrap_or_else(|| {
+                errors.push(format!(
+                    "cannot apply `{}` to {} and {}",
+                    infix.operator.name, lhs, rhs
+                ));
+                ExpressionType::Unknown
+            })
+        }
+    }
+}
+
+fn infer_primary(
+    primary: &PaxPrimary,
+    scope: &Scope,
+    type_table: &TypeTable,
+    errors: &mut Vec<String>,
+) -> ExpressionType {
+    let infer_in =
+        |e: &PaxExpression, errors: &mut Vec<String>| infer(e, scope, type_table, errors);
+    match primary {
+        PaxPrimary::Literal(value) => type_of_literal(value),
+        PaxPrimary::Grouped(inner, unit) => {
+            let inner = infer_in(inner, errors);
+            match unit {
+                None => inner,
+                Some(PaxUnit::Percent) => ExpressionType::Percent,
+                Some(PaxUnit::Pixels) => ExpressionType::Size,
+                Some(PaxUnit::Radians | PaxUnit::Degrees) => ExpressionType::Rotation,
+                Some(PaxUnit::Milliseconds | PaxUnit::Seconds) => ExpressionType::Duration,
+            }
+        }
+        PaxPrimary::Identifier(ident, accessors) => {
+            if ident.name.starts_with('$') {
+                for accessor in accessors {
+                    if let PaxAccessor::List(index) = accessor {
+                        infer_in(index, errors);
+                    }
+                }
+                return ExpressionType::Unknown;
+            }
+            let Some(mut current) = scope.get(&ident.name).cloned() else {
+                errors.push(format!("undefined symbol `{}`", ident.name));
+                return ExpressionType::Unknown;
+            };
+            for accessor in accessors {
+                current = match (accessor, current) {
+                    (PaxAccessor::List(index), current) => {
+                        infer_in(index, errors);
+                        match current {
+                            ExpressionType::List(elem) => *elem,
+                            _ => ExpressionType::Unknown,
+                        }
+                    }
+                    (PaxAccessor::Struct(field), ExpressionType::Struct(type_id)) => {
+                        let field_type = type_table.get(&type_id).and_then(|td| {
+                            td.property_definitions
+                                .iter()
+                                .find(|pd| &pd.name == field)
+                                .map(|pd| type_of(&pd.type_id, type_table))
+                        });
+                        match field_type {
+                            Some(field_type) => field_type,
+                            None => {
+                                errors.push(format!(
+                                    "no field `{}` on {}",
+                                    field,
+                                    ExpressionType::Struct(type_id)
+                                ));
+                                return ExpressionType::Unknown;
+                            }
+                        }
+                    }
+                    _ => ExpressionType::Unknown,
+                };
+            }
+            current
+        }
+        PaxPrimary::Object(fields) => {
+            for (_, value) in fields {
+                infer_in(value, errors);
+            }
+            ExpressionType::Unknown
+        }
+        PaxPrimary::FunctionOrEnum(scope_name, name, args) => {
+            for arg in args {
+                infer_in(arg, errors);
+            }
+            // anything that isn't a registered function constructs an enum variant
+            if !Functions::has_function(scope_name, name) {
+                return ExpressionType::Unknown;
+            }
+            if let Some(arity) = Functions::get_arity(scope_name, name) {
+                if !arity.contains(&args.len()) {
+                    let expected = if arity.start() == arity.end() {
+                        arity.start().to_string()
+                    } else if *arity.end() == usize::MAX {
+                        format!("at least {}", arity.start())
+                    } else {
+                        format!("{} to {}", arity.start(), arity.end())
+                    };
+                    errors.push(format!(
+                        "`{}::{}` takes {} argument(s) but {} were supplied",
+                        scope_name,
+                        name,
+                        expected,
+                        args.len()
+                    ));
+                }
+            }
+            match scope_name.as_str() {
+                "Color" => ExpressionType::Color,
+                _ => ExpressionType::Unknown,
+            }
+        }
+        PaxPrimary::Range {
+            start, end, step, ..
+        } => {
+            for bound in [Some(start), Some(end), step.as_ref()]
+                .into_iter()
+                .flatten()
+            {
+                let bound_type = infer_in(bound, errors);
+                if !matches!(
+                    bound_type,
+                    ExpressionType::Numeric | ExpressionType::Unknown
+                ) {
+                    errors.push(format!(
+                        "range bounds must be Numeric, found {}",
+                        bound_type
+                    ));
+                }
+            }
+            ExpressionType::List(Box::new(ExpressionType::Numeric))
+        }
+        PaxPrimary::Tuple(elems) => {
+            for elem in elems {
+                infer_in(elem, errors);
+            }
+            ExpressionType::Unknown
+        }
+        PaxPrimary::List(elems) => {
+            let types: Vec<_> = elems.iter().map(|e| infer_in(e, errors)).collect();
+            let elem = match types.split_first() {
+                Some((first, rest)) if rest.iter().all(|t| t == first) => first.clone(),
+                _ => ExpressionType::Unknown,
+            };
+            ExpressionType::List(Box::new(elem))
+        }
+        PaxPrimary::Lambda(params, body) => {
+            let mut body_scope = scope.clone();
+            for param in params {
+                body_scope.insert(param.clone(), ExpressionType::Unknown);
+            }
+            infer(body, &body_scope, type_table, errors);
+            ExpressionType::Function
+        }
+        PaxPrimary::TemplateString(segments) => {
+            for segment in segments {
+                if let TemplateSegment::Expression(e) = segment {
+                    infer_in(e, errors);
+                }
+            }
+            ExpressionType::String
+        }
+    }
+}
+
+/// Result type of a prefix operator, `None` if it isn't defined for `operand`
+fn unary_result(operator: &str, operand: &ExpressionType) -> Option<ExpressionType> {
+    use ExpressionType::*;
+    match (operator, operand) {
+        (_, Unknown) => Some(Unknown),
+        ("-", Numeric | Size | Percent | Rotation) => Some(operand.clone()),
+        ("!", Bool) => Some(Bool),
+        ("-" | "!", _) => None,
+        _ => Some(Unknown),
+    }
+}
+
+/// Result type of an infix operator, `None` if it isn't defined for `lhs` and `rhs`.
+/// Mirrors the operator implementations of `PaxValue`
+fn binary_result(
+    operator: &str,
+    lhs: &ExpressionType,
+    rhs: &ExpressionType,
+) -> Option<ExpressionType> {
+    use ExpressionType::*;
+    if *lhs == Unknown || *rhs == Unknown {
+        return Some(match operator {
+            "==" | "!=" | ">" | ">=" | "<" | "<=" | "&&" | "||" => Bool,
+            _ => Unknown,
+        });
+    }
+    match (operator, lhs, rhs) {
+        ("+", Numeric, Numeric) => Some(Numeric),
+        ("+", String, String | Numeric) | ("+", Numeric, String) => Some(String),
+        ("+", Size, Size) => Some(Size),
+        ("+", Percent, Percent) => Some(Percent),
+        ("+", Percent, Size) | ("+", Size, Percent) => Some(Size),
+        ("+", Bool, Numeric) | ("+", Numeric, Bool) => Some(Numeric),
+        ("+", Size, Numeric) | ("+", Numeric, Size) => Some(Size),
+        ("+", Numeric, Percent) | ("+", Percent, Numeric) => Some(Size),
+        ("-", Numeric, Numeric) => Some(Numeric),
+        ("-", Size, Size | Percent | Numeric) | ("-", Percent | Numeric, Size) => Some(Size),
+        ("-", Percent, Percent) => Some(Percent),
+        ("+" | "-", Duration, Duration) => Some(Duration),
+        ("*", Numeric, Numeric) => Some(Numeric),
+        ("*", Bool, Numeric) | ("*", Numeric, Bool) => Some(Numeric),
+        ("*", Bool, Percent) | ("*", Percent, Bool) => Some(Percent),
+        ("*", Size, Numeric) | ("*", Numeric, Size) => Some(Size),
+        ("*", Duration, Numeric) | ("*", Numeric, Duration) => Some(Duration),
+        ("/", Numeric, Numeric) => Some(Numeric),
+        ("/", Size, Numeric) | ("/", Numeric, Size) => Some(Size),
+        ("/", Duration, Numeric) => Some(Duration),
+        ("^" | "%%", Numeric, Numeric) => Some(Numeric),
+        ("==" | "!=", _, _) => Some(Bool),
+        (">" | ">=" | "<" | "<=", Bool, Bool)
+        | (">" | ">=" | "<" | "<=", Numeric, Numeric)
+        | (">" | ">=" | "<" | "<=", String, String) => Some(Bool),
+        ("&&" | "||", Bool, Bool) => Some(Bool),
+        ("+" | "-" | "*" | "/" | "^" | "%%" | ">" | ">=" | "<" | "<=" | "&&" | "||", _, _) => None,
+        _ => Some(Unknown),
+    }
+}
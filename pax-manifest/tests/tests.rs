@@ -2,7 +2,20 @@
 #[cfg(feature = "parsing")]
 mod tests {
 
-    use pax_manifest::{utils, ValueDefinition};
+    use std::collections::HashMap;
+
+    use pax_manifest::cartridge_generation::expressions::{
+        compile_component_expressions, CompiledExpressionDefinition,
+    };
+    use pax_manifest::code_serialization::press_code_serialization_template;
+    use pax_manifest::parsing::{assemble_component_definition, ParsingContext};
+    use pax_manifest::pax_runtime_api::Functions;
+    use pax_manifest::selectors::PseudoClass;
+    use pax_manifest::type_checking::{check_component, visit_expressions, ExpressionError};
+    use pax_manifest::{
+        utils, ComponentDefinition, ComponentTemplate, PropertyDefinition, SettingElement,
+        SettingsBlockElement, TemplateNodeId, TypeDefinition, TypeId, TypeTable, ValueDefinition,
+    };
 
     #[test]
     fn test_parse_empty() {
@@ -44,4 +57,566 @@ mod tests {
         let res = utils::parse_value("{5 + 3}this_shouldn't succeed");
         assert!(matches!(res, Err(_)));
     }
+
+    fn assemble_test_component(pax: &str) -> ComponentDefinition {
+        let template_map: HashMap<String, TypeId> = ["Group", "Rectangle", "Text"]
+            .into_iter()
+            .map(|ident| {
+                (
+                    ident.to_string(),
+                    TypeId::build_singleton(&format!("pax_std::{}", ident), Some(ident)),
+                )
+            })
+            .collect();
+        let (_, component) = assemble_component_definition(
+            ParsingContext::default(),
+            pax,
+            true,
+            template_map,
+            "crate",
+            TypeId::build_singleton("crate::Example", None),
+            "src/lib.rs",
+        );
+        component
+    }
+
+    #[test]
+    fn test_parse_else_if_chain() {
+        let component = assemble_test_component(
+            "if self.a { <Group/> } else if self.b { <Rectangle/> } else { <Text/> }",
+        );
+
+        let template = component.template.as_ref().unwrap();
+        let root = template.get_root();
+        assert_eq!(root.len(), 3);
+        assert!(!template.get_node(&root[0]).unwrap().is_else_branch());
+        assert_eq!(template.get_else_branches(&root[0]), root[1..].to_vec());
+        assert_eq!(template.get_conditional_chain_head(&root[2]), root[0]);
+        let else_settings = template.get_node(&root[2]).unwrap();
+        assert!(else_settings
+            .control_flow_settings
+            .as_ref()
+            .unwrap()
+            .condition_expression
+            .is_none());
+
+        let serialized = press_code_serialization_template(component).unwrap();
+        assert!(serialized.contains("} else if b {"));
+        assert!(serialized.contains("} else {"));
+    }
+
+    #[test]
+    fn test_parse_keyed_for() {
+        let component =
+            assemble_test_component("for (elem, i) in self.items key elem.id { <Rectangle/> }");
+
+        let template = component.template.as_ref().unwrap();
+        let root = template.get_root();
+        assert_eq!(root.len(), 1);
+        let settings = template
+            .get_node(&root[0])
+            .unwrap()
+            .control_flow_settings
+            .clone()
+            .unwrap();
+        assert_eq!(
+            settings.repeat_key_expression.unwrap().to_string(),
+            "elem.id"
+        );
+        assert_eq!(template.get_children(&root[0]).unwrap().len(), 1);
+
+        let serialized = press_code_serialization_template(component).unwrap();
+        assert!(serialized.contains("for (elem, i) in items key elem.id {"));
+    }
+
+    #[test]
+    fn test_parse_match() {
+        let component = assemble_test_component(
+            "match self.state { State::Loading => { <Group/> } State::Error(message, code) if code >= 500 => { <Text/> }, _ => { <Rectangle/> } }",
+        );
+
+        let template = component.template.as_ref().unwrap();
+        let root = template.get_root();
+        assert_eq!(root.len(), 1);
+        let arms = template.get_children(&root[0]).unwrap();
+        let patterns: Vec<_> = arms
+            .iter()
+            .map(|arm| {
+                let arm_node = template.get_node(arm).unwrap();
+                assert_eq!(template.get_children(arm).unwrap().len(), 1);
+                arm_node
+                    .control_flow_settings
+                    .as_ref()
+                    .unwrap()
+                    .match_arm_pattern
+                    .clone()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(patterns.len(), 3);
+        assert_eq!(patterns[0].get_variant_name().as_deref(), Some("Loading"));
+        assert_eq!(patterns[0].get_enum_name().as_deref(), Some("State"));
+        assert_eq!(patterns[1].bindings, vec!["message", "code"]);
+        assert_eq!(
+            patterns[1].guard.as_ref().map(|guard| guard.to_string()),
+            Some("code >= 500".to_owned())
+        );
+        assert_eq!(patterns[2].variant_path, None);
+
+        let serialized = press_code_serialization_template(component).unwrap();
+        assert!(serialized.contains("match state {"));
+        assert!(serialized.contains("State::Error(message, code) if code >= 500 => {"));
+        assert!(serialized.contains("_ => {"));
+    }
+
+    fn type_check(pax: &str) -> Vec<ExpressionError> {
+        Functions::register_all_functions();
+        let component = assemble_test_component(pax);
+        check_component(&component, &test_type_table(&component))
+    }
+
+    /// Types of the properties `count: usize`, `items: Vec<String>` and `offset: Size`
+    fn test_type_table(component: &ComponentDefinition) -> TypeTable {
+        let property = |name: &str, type_id: TypeId| PropertyDefinition {
+            name: name.to_string(),
+            flags: Default::default(),
+            type_id,
+        };
+        let items = TypeId::build_vector("String");
+        let mut type_table = TypeTable::new();
+        type_table.insert(
+            items.clone(),
+            TypeDefinition {
+                type_id: items.clone(),
+                inner_iterable_type_id: Some(TypeId::build_primitive("String")),
+                property_definitions: vec![],
+            },
+        );
+        type_table.insert(
+            component.type_id.clone(),
+            TypeDefinition {
+                type_id: component.type_id.clone(),
+                inner_iterable_type_id: None,
+                property_definitions: vec![
+                    property("count", TypeId::build_primitive("usize")),
+                    property("items", items),
+                    property(
+                        "offset",
+                        TypeId::build_singleton("pax_engine::api::Size", None),
+                    ),
+                ],
+            },
+        );
+        type_table
+    }
+
+    fn type_check_messages(pax: &str) -> Vec<String> {
+        type_check(pax).into_iter().map(|e| e.message).collect()
+    }
+
+    #[test]
+    fn test_type_check_valid_template() {
+        let errors = type_check_messages(
+            "for (item, i) in self.items { <Text text={`${i}: ${String::to_upper(item)}`} x={self.offset + 10px} /> }
+            if List::any(self.items, |s| String::len(s) > self.count) { <Rectangle width={(count * 10)%} /> }",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    }
+
+    #[test]
+    fn test_type_check_undefined_symbol() {
+        let errors = type_check("<Rectangle width={self.cuont * 2} />\n<Text text={item} />");
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(errors[0].message, "undefined symbol `cuont`");
+        assert_eq!(errors[0].location.as_ref().unwrap().start_line_col, (0, 18));
+        assert_eq!(errors[1].message, "undefined symbol `item`");
+        assert_eq!(errors[1].location.as_ref().unwrap().start_line_col, (1, 12));
+    }
+
+    #[test]
+    fn test_type_check_operand_types() {
+        let errors = type_check_messages(
+            r#"<Rectangle x={self.offset + "px"} /> <Group y={!self.count} />"#,
+        );
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("cannot apply `+` to Size and String"));
+        assert!(errors[1].contains("cannot apply `!` to Numeric"));
+    }
+
+    #[test]
+    fn test_type_check_function_arity() {
+        let errors = type_check_messages(
+            "<Rectangle width={Math::min(self.count)} text={List::slice(self.items, 1)} />",
+        );
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("`Math::min` takes 2 argument(s) but 1 were supplied")));
+        assert!(errors.iter().any(|e| e.contains("`List::slice` takes 3")));
+    }
+
+    /// The expressions of `pax` compiled, keyed by their source
+    fn compile(pax: &str) -> HashMap<String, CompiledExpressionDefinition> {
+        Functions::register_all_functions();
+        let mut component = assemble_test_component(pax);
+        let type_table = test_type_table(&component);
+        let mut compiled = vec![];
+        compile_component_expressions(&mut component, &type_table, &mut compiled);
+
+        let mut sources = HashMap::new();
+        visit_expressions(&component, &type_table, &mut |info, _, _| {
+            sources.insert(info.id.unwrap(), info.to_string());
+        });
+        assert_eq!(sources.len(), compiled.len());
+        compiled
+            .into_iter()
+            .enumerate()
+            .map(|(id, expression)| {
+                let expression = expression.unwrap();
+                assert_eq!(expression.id, id);
+                (sources.remove(&id).unwrap(), expression)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compile_expressions_assigns_ids() {
+        let compiled = compile(
+            "for (item, i) in self.items key item { <Text text={`${i}: ${item}`} /> }
+            if self.count > 2 { <Rectangle width={(count * 10)%} /> }
+            <Group class=card />
+            @settings { .card { x: {(self.count)px} } }",
+        );
+        let mut sources: Vec<_> = compiled.keys().map(String::as_str).collect();
+        sources.sort();
+        assert_eq!(
+            sources,
+            [
+                "(count * 10)%",
+                "(count)px",
+                "`${i}: ${item}`",
+                "count > 2",
+                "item",
+                "items"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compile_expressions_with_inferred_types() {
+        let compiled = compile(
+            "for (item, i) in self.items key item { <Text text={`${i}: ${String::to_upper(item)}`} /> }
+            if self.count > 2 && self.count < 10 { <Rectangle width={(count * 10)%} x={self.offset + 10px} /> }",
+        );
+
+        let percent = &compiled["(count * 10)%"];
+        assert_eq!(percent.return_type, "Percent");
+        assert_eq!(percent.dependencies, ["count"]);
+        assert_eq!(
+            percent.body,
+            "Ok(Percent(((vars[0].get_as::<Numeric>()?) * (Numeric::I64(10i64)))))"
+        );
+
+        let condition = &compiled["count > 2 && count < 10"];
+        assert_eq!(condition.return_type, "bool");
+        assert!(!condition.body.contains("PaxValue"), "{}", condition.body);
+
+        // the key is an element of `items: Vec<String>`
+        assert_eq!(compiled["item"].return_type, "String");
+        assert_eq!(compiled["item"].body, "Ok(vars[0].get_as::<String>()?)");
+
+        let text = &compiled["`${i}: ${String::to_upper(item)}`"];
+        assert_eq!(text.return_type, "String");
+        assert_eq!(text.dependencies, ["i", "item"]);
+        assert!(text
+            .body
+            .contains("evaluation::call_function_or_enum(\"String\", \"to_upper\""));
+
+        // sizes are added by `PaxValue`, which combines pixels and percentages
+        let offset = &compiled["offset + 10px"];
+        assert_eq!(offset.return_type, "PaxValue");
+        assert_eq!(
+            offset.body,
+            "Ok((((vars[0].get_as::<Size>()?).to_pax_value()) + ((Size::Pixels(Numeric::I64(10i64))).to_pax_value())))"
+        );
+    }
+
+    #[test]
+    fn test_compile_expressions_to_property_types() {
+        let compiled = compile(
+            "if self.count > 2 { <Rectangle width={(count * 10)%} x={self.offset + 10px} opacity={count / 2} height={(self.count)px} /> }",
+        );
+
+        // values already of the property's type are stored as is
+        let condition = &compiled["count > 2"];
+        assert_eq!(condition.property_type, "bool");
+        assert_eq!(condition.conversion, None);
+        let height = &compiled["(count)px"];
+        assert_eq!(height.property_type, "Size");
+        assert_eq!(height.conversion, None);
+
+        let opacity = &compiled["count / 2"];
+        assert_eq!(opacity.property_type, "f64");
+        assert_eq!(opacity.conversion.as_deref(), Some("value.into()"));
+
+        // others are coerced like the runtime coerces `PaxValue`s
+        let width = &compiled["(count * 10)%"];
+        assert_eq!(width.property_type, "pax_engine::api::Size");
+        assert_eq!(
+            width.conversion.as_deref(),
+            Some("<pax_engine::api::Size>::try_coerce(value.to_pax_value())?")
+        );
+        let x = &compiled["offset + 10px"];
+        assert_eq!(
+            x.conversion.as_deref(),
+            Some("<pax_engine::api::Size>::try_coerce(value)?")
+        );
+    }
+
+    #[test]
+    fn test_compile_lambda_captures() {
+        let compiled =
+            compile("if List::any(self.items, |s| String::len(s) > self.count) { <Rectangle /> }");
+        let condition = compiled.values().next().unwrap();
+        assert_eq!(condition.return_type, "PaxValue");
+        assert_eq!(condition.dependencies, ["items", "count"]);
+        // lambda parameters and captured values are `PaxValue`s
+        assert!(condition
+            .body
+            .contains("let local_1 = vars[1].get_as_pax_value();"));
+        assert!(condition.body.contains("PaxValue::Bool(("));
+    }
+
+    /// The template node with `id=node_id`
+    fn find_node<'a>(template: &'a ComponentTemplate, node_id: &str) -> Option<&'a TemplateNodeId> {
+        template.get_ids().into_iter().find(|id| {
+            template
+                .get_node(id)
+                .unwrap()
+                .settings
+                .iter()
+                .flatten()
+                .any(|s| {
+                    matches!(s, SettingElement::Setting(k, ValueDefinition::Identifier(i))
+                    if k.token_value == "id" && i.name == node_id)
+                })
+        })
+    }
+
+    /// The merged value of `key` on the node with `id=node_id`
+    fn merged_setting(component: &ComponentDefinition, node_id: &str, key: &str) -> Option<String> {
+        let template = component.template.as_ref().unwrap();
+        let id = find_node(template, node_id)?;
+        template
+            .get_merged_settings(id, &component.settings)?
+            .into_iter()
+            .find_map(|s| match s {
+                SettingElement::Setting(k, v) if k.token_value == key => Some(v.to_string()),
+                _ => None,
+            })
+    }
+
+    #[test]
+    fn test_settings_selectors() {
+        let component = assemble_test_component(
+            r#"<Group class=card>
+                <Text id=a class=label class=title />
+            </Group>
+            <Text id=b class=label />
+            <Rectangle id=c class=primary />
+            <Rectangle id=d class=secondary fill=RED />
+
+            @settings {
+                .primary,
+                .secondary { fill: BLUE, width: 10px }
+                Rectangle { height: 5px, width: 20px }
+                .card   .label { text: "inside" }
+                .label { text: "outside", x: 1px }
+                Text.title { x: 2px }
+            }"#,
+        );
+        let tokens: Vec<_> = component
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|e| match e {
+                SettingsBlockElement::SelectorBlock(token, _) => Some(token.token_value.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                ".primary, .secondary",
+                "Rectangle",
+                ".card .label",
+                ".label",
+                "Text.title"
+            ]
+        );
+
+        // descendant and compound selectors are more specific than a single class
+        assert_eq!(
+            merged_setting(&component, "a", "text").unwrap(),
+            "\"inside\""
+        );
+        assert_eq!(merged_setting(&component, "a", "x").unwrap(), "2px");
+        assert_eq!(
+            merged_setting(&component, "b", "text").unwrap(),
+            "\"outside\""
+        );
+        assert_eq!(merged_setting(&component, "b", "x").unwrap(), "1px");
+        // classes override types, and inline settings override every block
+        assert_eq!(merged_setting(&component, "c", "width").unwrap(), "10px");
+        assert_eq!(merged_setting(&component, "c", "height").unwrap(), "5px");
+        assert_eq!(merged_setting(&component, "c", "fill").unwrap(), "BLUE");
+        assert_eq!(merged_setting(&component, "d", "fill").unwrap(), "RED");
+        assert_eq!(merged_setting(&component, "d", "width").unwrap(), "10px");
+    }
+
+    #[test]
+    fn test_settings_state_variants() {
+        let component = assemble_test_component(
+            r#"<Group id=card class=card>
+                <Rectangle id=a class=background fill=RED />
+            </Group>
+            <Rectangle id=b class=background />
+
+            @settings {
+                .background { fill: GREEN }
+                .card .background:hover { fill: BLUE, transition: 200ms, easing: EasingCurve::CubicBezier(0.4, 0, 0.2, 1) }
+                .background:pressed:hover,
+                #b:hover { fill: WHITE }
+                .card:disabled { opacity: 0.5 }
+            }"#,
+        );
+        let tokens: Vec<_> = component
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|e| match e {
+                SettingsBlockElement::SelectorBlock(token, _) => Some(token.token_value.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            tokens,
+            [
+                ".background",
+                ".card .background:hover",
+                ".background:pressed:hover, #b:hover",
+                ".card:disabled"
+            ]
+        );
+
+        // state blocks aren't merged into the resting settings
+        assert_eq!(merged_setting(&component, "a", "fill").unwrap(), "RED");
+        assert_eq!(merged_setting(&component, "b", "fill").unwrap(), "GREEN");
+        assert_eq!(merged_setting(&component, "card", "opacity"), None);
+
+        let template = component.template.as_ref().unwrap();
+        let variants = |node_id: &str| {
+            template
+                .get_state_variants(find_node(template, node_id).unwrap(), &component.settings)
+                .into_iter()
+                .map(|variant| {
+                    let fill = variant.settings.iter().find_map(|s| match s {
+                        SettingElement::Setting(k, v) if k.token_value == "fill" => {
+                            Some(v.to_string())
+                        }
+                        _ => None,
+                    });
+                    (variant.states, fill)
+                })
+                .collect::<Vec<_>>()
+        };
+        // ordered by specificity, pseudo-classes counting as classes
+        assert_eq!(
+            variants("a"),
+            [
+                (vec![PseudoClass::Hover], Some("BLUE".to_string())),
+                (
+                    vec![PseudoClass::Hover, PseudoClass::Pressed],
+                    Some("WHITE".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            variants("b"),
+            [
+                (
+                    vec![PseudoClass::Hover, PseudoClass::Pressed],
+                    Some("WHITE".to_string())
+                ),
+                (vec![PseudoClass::Hover], Some("WHITE".to_string())),
+            ]
+        );
+        assert_eq!(variants("card"), [(vec![PseudoClass::Disabled], None)]);
+    }
+
+    #[test]
+    fn test_capture_phase_event_bindings() {
+        let component = assemble_test_component(
+            r#"<Group id=surface @click.capture=self.intercept @click=self.handle_click />
+
+            @settings {
+                @mouse_down.capture: self.grab
+            }"#,
+        );
+        let template = component.template.as_ref().unwrap();
+        let id = find_node(template, "surface").unwrap();
+        let inline_handlers: Vec<_> = template
+            .get_node(id)
+            .unwrap()
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|s| match s {
+                SettingElement::Setting(k, ValueDefinition::EventBindingTarget(e)) => {
+                    Some((k.token_value.as_str(), e.name.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            inline_handlers,
+            [
+                ("click.capture", "self.intercept"),
+                ("click", "self.handle_click")
+            ]
+        );
+        let settings_handlers: Vec<_> = component
+            .settings
+            .iter()
+            .flatten()
+            .filter_map(|e| match e {
+                SettingsBlockElement::Handler(token, _) => Some(token.token_value.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(settings_handlers, ["mouse_down.capture"]);
+    }
+
+    #[test]
+    fn test_capture_phase_requires_bubbling_event() {
+        let errors = type_check(
+            r#"<Group @click.capture=self.intercept @mouse_over.capture=self.hover />
+
+            @settings {
+                @mount.capture: self.mount
+                @focus.capture: self.focus
+                @pointer_down.capture: self.grab
+            }"#,
+        );
+        let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "`mouse_over` events don't bubble, so can't be captured",
+                "`mount` events don't bubble, so can't be captured",
+                "`focus` events don't bubble, so can't be captured",
+            ]
+        );
+        assert!(errors.iter().all(|e| e.location.is_some()));
+    }
 }
@@ -91,6 +91,10 @@ pub enum NativeInterrupt {
     Scrollbar(ScrollbarInterruptArgs),
     DropFile(DropFileArgs),
     Screenshot(ImageLoadInterruptArgs),
+    PointerDown(PointerInterruptArgs),
+    PointerMove(PointerInterruptArgs),
+    PointerUp(PointerInterruptArgs),
+    PointerCancel(PointerInterruptArgs),
 }
 
 #[derive(Deserialize)]
@@ -321,6 +325,25 @@ pub struct MouseUpInterruptArgs {
     pub modifiers: Vec<ModifierKeyMessage>,
 }
 
+#[derive(Deserialize, Clone)]
+#[repr(C)]
+pub enum PointerTypeMessage {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+#[derive(Deserialize)]
+#[repr(C)]
+pub struct PointerInterruptArgs {
+    pub pointer_id: i64,
+    pub pointer_type: PointerTypeMessage,
+    pub x: f64,
+    pub y: f64,
+    pub button: MouseButtonMessage,
+    pub modifiers: Vec<ModifierKeyMessage>,
+}
+
 #[derive(Deserialize)]
 #[repr(C)]
 pub struct MouseOverInterruptArgs {
@@ -431,6 +454,7 @@ pub struct CheckboxPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub checked: Option<bool>,
 }
 
@@ -442,6 +466,7 @@ pub struct NativeImagePatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub url: Option<String>,
     pub fit: Option<String>,
 }
@@ -454,6 +479,7 @@ pub struct YoutubeVideoPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub url: Option<String>,
 }
 
@@ -467,6 +493,7 @@ pub struct DropdownPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub background: Option<ColorMessage>,
     pub stroke_color: Option<ColorMessage>,
     pub stroke_width: Option<f64>,
@@ -489,6 +516,7 @@ pub struct RadioSetPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
 }
 
 #[cfg_attr(debug_assertions, derive(Debug))]
@@ -503,6 +531,7 @@ pub struct SliderPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub accent: Option<ColorMessage>,
     pub background: Option<ColorMessage>,
     pub border_radius: Option<f64>,
@@ -516,6 +545,7 @@ pub struct TextboxPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub text: Option<String>,
     pub background: Option<ColorMessage>,
     pub stroke_color: Option<ColorMessage>,
@@ -541,6 +571,7 @@ pub struct ButtonPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub content: Option<String>,
     pub color: Option<ColorMessage>,
     pub style: Option<TextStyleMessage>,
@@ -589,6 +620,7 @@ pub struct TextPatch {
     pub transform: Option<Vec<f64>>,
     pub size_x: Option<f64>,
     pub size_y: Option<f64>,
+    pub opacity: Option<f64>,
     pub style: Option<TextStyleMessage>,
     pub style_link: Option<TextStyleMessage>,
 }
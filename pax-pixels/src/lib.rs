@@ -8,7 +8,9 @@ pub use lyon::geom::{point, Angle};
 pub use lyon::path::builder::BorderRadii;
 pub use lyon::path::Path;
 pub use lyon::path::Winding;
+pub use lyon::tessellation::{LineCap, LineJoin};
 pub use render_backend::Image;
+pub use render_context::BlendMode;
 pub use render_context::Color;
 pub use render_context::Fill;
 pub use render_context::GradientStop;
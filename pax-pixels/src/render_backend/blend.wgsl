@@ -0,0 +1,172 @@
+struct BlendParams {
+    // the index of the blend mode, in the order of `BlendMode`
+    mode: u32,
+    opacity: f32,
+    _pad: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> params: BlendParams;
+@group(0) @binding(1) var source: texture_2d<f32>;
+@group(0) @binding(2) var backdrop: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+};
+
+// a single triangle covering the whole target
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    return out;
+}
+
+// Composites the premultiplied source over the premultiplied backdrop it was copied
+// from, following https://www.w3.org/TR/compositing-1/#blending
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coord = vec2<i32>(in.clip_position.xy);
+    let src = textureLoad(source, coord, 0) * params.opacity;
+    let dst = textureLoad(backdrop, coord, 0);
+    let blended = blend(unpremultiply(dst), unpremultiply(src));
+    let rgb = src.rgb * (1.0 - dst.a) + dst.rgb * (1.0 - src.a) + src.a * dst.a * blended;
+    return vec4<f32>(rgb, src.a + dst.a * (1.0 - src.a));
+}
+
+fn unpremultiply(color: vec4<f32>) -> vec3<f32> {
+    if color.a <= 0.0 {
+        return vec3<f32>(0.0);
+    }
+    return clamp(color.rgb / color.a, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn blend(cb: vec3<f32>, cs: vec3<f32>) -> vec3<f32> {
+    switch params.mode {
+        case 12u: {
+            return set_lum(set_sat(cs, sat(cb)), lum(cb));
+        }
+        case 13u: {
+            return set_lum(set_sat(cb, sat(cs)), lum(cb));
+        }
+        case 14u: {
+            return set_lum(cs, lum(cb));
+        }
+        case 15u: {
+            return set_lum(cb, lum(cs));
+        }
+        default: {
+            return vec3<f32>(
+                blend_channel(cb.r, cs.r),
+                blend_channel(cb.g, cs.g),
+                blend_channel(cb.b, cs.b),
+            );
+        }
+    }
+}
+
+// the separable blend modes, applied to each color channel on its own
+fn blend_channel(cb: f32, cs: f32) -> f32 {
+    switch params.mode {
+        case 1u: {
+            return cb * cs;
+        }
+        case 2u: {
+            return screen(cb, cs);
+        }
+        case 3u: {
+            return hard_light(cs, cb);
+        }
+        case 4u: {
+            return min(cb, cs);
+        }
+        case 5u: {
+            return max(cb, cs);
+        }
+        case 6u: {
+            if cb <= 0.0 {
+                return 0.0;
+            } else if cs >= 1.0 {
+                return 1.0;
+            }
+            return min(1.0, cb / (1.0 - cs));
+        }
+        case 7u: {
+            if cb >= 1.0 {
+                return 1.0;
+            } else if cs <= 0.0 {
+                return 0.0;
+            }
+            return 1.0 - min(1.0, (1.0 - cb) / cs);
+        }
+        case 8u: {
+            return hard_light(cb, cs);
+        }
+        case 9u: {
+            if cs <= 0.5 {
+                return cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb);
+            }
+            var d = sqrt(cb);
+            if cb <= 0.25 {
+                d = ((16.0 * cb - 12.0) * cb + 4.0) * cb;
+            }
+            return cb + (2.0 * cs - 1.0) * (d - cb);
+        }
+        case 10u: {
+            return abs(cb - cs);
+        }
+        case 11u: {
+            return cb + cs - 2.0 * cb * cs;
+        }
+        default: {
+            return cs;
+        }
+    }
+}
+
+fn screen(cb: f32, cs: f32) -> f32 {
+    return cb + cs - cb * cs;
+}
+
+fn hard_light(cb: f32, cs: f32) -> f32 {
+    if cs <= 0.5 {
+        return cb * 2.0 * cs;
+    }
+    return screen(cb, 2.0 * cs - 1.0);
+}
+
+fn lum(c: vec3<f32>) -> f32 {
+    return dot(c, vec3<f32>(0.3, 0.59, 0.11));
+}
+
+fn clip_color(c: vec3<f32>) -> vec3<f32> {
+    let l = lum(c);
+    let n = min(c.r, min(c.g, c.b));
+    let x = max(c.r, max(c.g, c.b));
+    var clipped = c;
+    if n < 0.0 {
+        clipped = l + (clipped - l) * l / (l - n);
+    }
+    if x > 1.0 {
+        clipped = l + (clipped - l) * (1.0 - l) / (x - l);
+    }
+    return clipped;
+}
+
+fn set_lum(c: vec3<f32>, l: f32) -> vec3<f32> {
+    return clip_color(c + (l - lum(c)));
+}
+
+fn sat(c: vec3<f32>) -> f32 {
+    return max(c.r, max(c.g, c.b)) - min(c.r, min(c.g, c.b));
+}
+
+// scales the channels so that the smallest is 0 and the largest is s
+fn set_sat(c: vec3<f32>, s: f32) -> vec3<f32> {
+    let mn = min(c.r, min(c.g, c.b));
+    let mx = max(c.r, max(c.g, c.b));
+    if mx <= mn {
+        return vec3<f32>(0.0);
+    }
+    return (c - mn) * s / (mx - mn);
+}
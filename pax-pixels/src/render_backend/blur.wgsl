@@ -0,0 +1,48 @@
+struct BlurParams {
+    // one texel along the direction of the pass, in texture coordinates
+    direction: vec2<f32>,
+    sigma: f32,
+    _pad: f32,
+};
+
+@group(0) @binding(0) var<uniform> params: BlurParams;
+@group(0) @binding(1) var source: texture_2d<f32>;
+@group(0) @binding(2) var source_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texture_coord: vec2<f32>,
+};
+
+// a single triangle covering the whole target
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+    out.texture_coord = uv;
+    return out;
+}
+
+const MAX_TAPS: i32 = 64;
+
+// One pass of a separable gaussian blur. Wide blurs are sampled sparsely, relying on
+// linear filtering between the taps.
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let extent = ceil(params.sigma * 3.0);
+    let step = max(1.0, extent / f32(MAX_TAPS));
+    var sum = vec4<f32>(0.0);
+    var weights = 0.0;
+    for (var i = -MAX_TAPS; i <= MAX_TAPS; i++) {
+        let offset = f32(i) * step;
+        if (abs(offset) > extent) {
+            continue;
+        }
+        let weight = exp(-(offset * offset) / (2.0 * params.sigma * params.sigma));
+        let coord = in.texture_coord + params.direction * offset;
+        sum += weight * textureSampleLevel(source, source_sampler, coord, 0.0);
+        weights += weight;
+    }
+    return sum / weights;
+}
@@ -8,6 +8,8 @@ use wgpu::{
     TextureUsages, TextureView,
 };
 
+mod blend;
+mod blur;
 pub mod data;
 mod gpu_resources;
 pub mod stencil;
@@ -15,7 +17,10 @@ mod texture;
 
 use data::{GpuGlobals, GpuPrimitive, GpuVertex};
 
-use crate::{render_backend::texture::TextureRenderer, Box2D, Transform2D};
+use crate::{
+    render_backend::{blend::BlendRenderer, blur::BlurRenderer, texture::TextureRenderer},
+    BlendMode, Box2D, Transform2D,
+};
 
 use self::{
     data::{GpuColor, GpuGradient, GpuTransform},
@@ -77,9 +82,23 @@ pub struct RenderBackend<'w> {
 
     index_count: u64,
 
+    // offscreen textures that groups are drawn into, innermost last
+    groups: Vec<Group>,
+    spare_group_textures: Vec<wgpu::Texture>,
+
     // plugins / extensions
     texture_renderer: TextureRenderer,
     stencil_renderer: StencilRenderer,
+    blur_renderer: BlurRenderer,
+    blend_renderer: BlendRenderer,
+}
+
+struct Group {
+    texture: wgpu::Texture,
+    opacity: f32,
+    // standard deviation of the blur applied before compositing, in logical pixels
+    blur: f32,
+    blend_mode: BlendMode,
 }
 
 impl<'w> RenderBackend<'w> {
@@ -131,7 +150,8 @@ impl<'w> RenderBackend<'w> {
 
         const TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
         let surface_config = SurfaceConfiguration {
-            usage: TextureUsages::RENDER_ATTACHMENT,
+            // copied from when blurring the backdrop
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
             format: TEXTURE_FORMAT,
             width: config.initial_width,
             height: config.initial_height,
@@ -293,6 +313,8 @@ impl<'w> RenderBackend<'w> {
             Self::create_pipeline(&device, surface_config.format, primitive_bind_group_layout);
 
         let texture_renderer = TextureRenderer::new(&device);
+        let blur_renderer = BlurRenderer::new(&device);
+        let blend_renderer = BlendRenderer::new(&device);
         let stencil_renderer = StencilRenderer::new(
             &device,
             config.initial_width,
@@ -306,6 +328,8 @@ impl<'w> RenderBackend<'w> {
         let mut backend = Self {
             texture_renderer,
             stencil_renderer,
+            blur_renderer,
+            blend_renderer,
             _adapter: adapter,
             surface,
             device,
@@ -323,6 +347,8 @@ impl<'w> RenderBackend<'w> {
             gradients_buffer,
             globals,
             index_count: 0,
+            groups: Vec::new(),
+            spare_group_textures: Vec::new(),
         };
         backend.globals.dpr = initial_dpr;
         backend.resize(initial_width, initial_height);
@@ -427,6 +453,155 @@ impl<'w> RenderBackend<'w> {
         );
         self.stencil_renderer.resize(&self.device, width, height);
         self.surface.configure(&self.device, &self.surface_config);
+        self.spare_group_textures.clear();
+    }
+
+    /// An offscreen texture the size of the screen, with undefined contents
+    fn take_texture(&mut self) -> wgpu::Texture {
+        let (width, height) = (self.surface_config.width, self.surface_config.height);
+        match self.spare_group_textures.pop() {
+            Some(texture) if texture.width() == width && texture.height() == height => texture,
+            _ => self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Group Texture"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.surface_config.format,
+                usage: TextureUsages::RENDER_ATTACHMENT
+                    | TextureUsages::TEXTURE_BINDING
+                    | TextureUsages::COPY_SRC
+                    | TextureUsages::COPY_DST,
+                view_formats: &[],
+            }),
+        }
+    }
+
+    /// Redirects all drawing into an offscreen texture until the matching
+    /// `pop_group`, which blurs it by `blur` and composites it with the given opacity
+    /// and blend mode
+    pub(crate) fn push_group(&mut self, opacity: f32, blur: f32, blend_mode: BlendMode) {
+        let texture = self.take_texture();
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self::clear_view(&self.device, &self.queue, &view);
+        self.stencil_renderer.set_aside(&self.device, &self.queue);
+        self.groups.push(Group {
+            texture,
+            opacity,
+            blur,
+            blend_mode,
+        });
+    }
+
+    pub(crate) fn pop_group(&mut self) {
+        let Some(group) = self.groups.pop() else {
+            log::warn!("render backend: popped a group that wasn't pushed");
+            return;
+        };
+        self.stencil_renderer.restore_set_aside();
+        if group.blur > 0.0 {
+            let scratch = self.take_texture();
+            self.blur_renderer.blur(
+                &self.device,
+                &self.queue,
+                &group.texture,
+                &scratch,
+                group.blur * self.globals.dpr as f32,
+            );
+            self.spare_group_textures.push(scratch);
+        }
+        let view = group
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let (screen_surface, target) = self.get_target_texture();
+        if group.blend_mode == BlendMode::Normal {
+            self.texture_renderer.composite(
+                &self.device,
+                &self.queue,
+                &target,
+                &self.globals_buffer,
+                &self.stencil_renderer,
+                &view,
+                self.globals.resolution,
+                group.opacity,
+                false,
+            );
+        } else {
+            let backdrop = self.take_texture();
+            self.copy_target(screen_surface.as_ref(), &backdrop);
+            self.blend_renderer.blend(
+                &self.device,
+                &self.queue,
+                &target,
+                &self.stencil_renderer,
+                &view,
+                &backdrop.create_view(&wgpu::TextureViewDescriptor::default()),
+                group.blend_mode,
+                group.opacity,
+            );
+            self.spare_group_textures.push(backdrop);
+        }
+        if let Some(screen_surface) = screen_surface {
+            screen_surface.present();
+        }
+        self.spare_group_textures.push(group.texture);
+    }
+
+    /// Blurs what has been drawn so far to the current target, where it isn't clipped
+    pub(crate) fn blur_backdrop(&mut self, sigma: f32) {
+        let backdrop = self.take_texture();
+        let scratch = self.take_texture();
+        let (screen_surface, target) = self.get_target_texture();
+        self.copy_target(screen_surface.as_ref(), &backdrop);
+        self.blur_renderer.blur(
+            &self.device,
+            &self.queue,
+            &backdrop,
+            &scratch,
+            sigma * self.globals.dpr as f32,
+        );
+        let view = backdrop.create_view(&wgpu::TextureViewDescriptor::default());
+        self.texture_renderer.composite(
+            &self.device,
+            &self.queue,
+            &target,
+            &self.globals_buffer,
+            &self.stencil_renderer,
+            &view,
+            self.globals.resolution,
+            1.0,
+            true,
+        );
+        if let Some(screen_surface) = screen_surface {
+            screen_surface.present();
+        }
+        self.spare_group_textures.push(scratch);
+        self.spare_group_textures.push(backdrop);
+    }
+
+    /// Copies what has been drawn to the current target into `texture`. `screen_surface`
+    /// is the surface texture returned by `get_target_texture`, if any.
+    fn copy_target(&self, screen_surface: Option<&SurfaceTexture>, texture: &wgpu::Texture) {
+        let source = match (screen_surface, self.groups.last()) {
+            (Some(screen_surface), _) => &screen_surface.texture,
+            (None, Some(group)) => &group.texture,
+            (None, None) => unreachable!("the target is either the screen or a group"),
+        };
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Backdrop Encoder"),
+            });
+        encoder.copy_texture_to_texture(
+            source.as_image_copy(),
+            texture.as_image_copy(),
+            texture.size(),
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
     }
 
     fn write_buffers(&mut self, buffers: &mut CpuBuffers) {
@@ -488,7 +663,7 @@ impl<'w> RenderBackend<'w> {
     }
 
     pub(crate) fn render_primitives(&mut self, buffers: &mut CpuBuffers) {
-        let (screen_surface, screen_texture) = self.get_screen_texture();
+        let (screen_surface, target) = self.get_target_texture();
         let mut encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -500,7 +675,7 @@ impl<'w> RenderBackend<'w> {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &screen_texture,
+                    view: &target,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Load,
@@ -530,7 +705,9 @@ impl<'w> RenderBackend<'w> {
 
         //render primitives
         self.queue.submit(std::iter::once(encoder.finish()));
-        screen_surface.present();
+        if let Some(screen_surface) = screen_surface {
+            screen_surface.present();
+        }
     }
 
     fn get_screen_texture(&self) -> (SurfaceTexture, TextureView) {
@@ -541,12 +718,29 @@ impl<'w> RenderBackend<'w> {
         (screen_surface, screen_texture)
     }
 
+    /// The view to draw to: the innermost group if there is one, otherwise the
+    /// screen, in which case the surface texture needs to be presented afterwards
+    fn get_target_texture(&self) -> (Option<SurfaceTexture>, TextureView) {
+        match self.groups.last() {
+            Some(group) => (
+                None,
+                group
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default()),
+            ),
+            None => {
+                let (screen_surface, screen_texture) = self.get_screen_texture();
+                (Some(screen_surface), screen_texture)
+            }
+        }
+    }
+
     pub(crate) fn render_image(&mut self, image: &Image, transform: Transform2D, rect: Box2D) {
-        let (screen_surface, screen_texture) = self.get_screen_texture();
+        let (screen_surface, target) = self.get_target_texture();
         self.texture_renderer.render_image(
             &self.device,
             &self.queue,
-            &screen_texture,
+            &target,
             &self.globals_buffer,
             &self.stencil_renderer,
             &image.rgba,
@@ -554,23 +748,28 @@ impl<'w> RenderBackend<'w> {
             transform,
             rect,
         );
-        screen_surface.present();
+        if let Some(screen_surface) = screen_surface {
+            screen_surface.present();
+        }
     }
 
     pub(crate) fn clear(&mut self) {
         self.stencil_renderer.clear(&self.device, &self.queue);
         let (screen_surface, screen_texture) = self.get_screen_texture();
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
+        Self::clear_view(&self.device, &self.queue, &screen_texture);
+        screen_surface.present();
+    }
+
+    fn clear_view(device: &Device, queue: &wgpu::Queue, view: &TextureView) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Encoder"),
+        });
 
         {
             let _r = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &screen_texture,
+                    view,
                     resolve_target: None,
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -587,8 +786,7 @@ impl<'w> RenderBackend<'w> {
                 occlusion_query_set: None,
             });
         }
-        self.queue.submit(std::iter::once(encoder.finish()));
-        screen_surface.present();
+        queue.submit(std::iter::once(encoder.finish()));
     }
 }
 
@@ -12,12 +12,22 @@ pub struct StencilRenderer {
     stencil_view: wgpu::TextureView,
     stencil_layer: u32,
     stencil_geometry_stack: Vec<VertexBuffers<Vertex, u16>>,
+    // stencils set aside while drawing into groups, innermost last
+    set_aside: Vec<SetAsideStencil>,
+    spare_textures: Vec<(wgpu::Texture, wgpu::TextureView)>,
     width: u32,
     height: u32,
     stencil_bind_group: wgpu::BindGroup,
     _stencil_bind_group_layout: wgpu::BindGroupLayout,
 }
 
+struct SetAsideStencil {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    layer: u32,
+    geometry_stack: Vec<VertexBuffers<Vertex, u16>>,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
 pub struct Vertex {
@@ -192,6 +202,8 @@ impl StencilRenderer {
             height,
             stencil_layer: 0,
             stencil_geometry_stack: vec![],
+            set_aside: vec![],
+            spare_textures: vec![],
             stencil_bind_group,
             _stencil_bind_group_layout: stencil_bind_group_layout,
         }
@@ -230,6 +242,39 @@ impl StencilRenderer {
             Self::create_stencil_texture(device, width, height);
         self.width = width;
         self.height = height;
+        self.spare_textures.clear();
+    }
+
+    /// Sets the current stencil aside until `restore_set_aside`, starting over with one
+    /// that doesn't clip anything. Groups are drawn this way, and are only clipped once
+    /// they're composited, so that blurring them doesn't reveal where they were clipped.
+    pub fn set_aside(&mut self, device: &Device, queue: &Queue) {
+        let (width, height) = (self.width, self.height);
+        let (texture, view) = match self.spare_textures.pop() {
+            Some((texture, view)) if texture.width() == width && texture.height() == height => {
+                (texture, view)
+            }
+            _ => Self::create_stencil_texture(device, width, height),
+        };
+        self.set_aside.push(SetAsideStencil {
+            texture: std::mem::replace(&mut self.stencil_texture, texture),
+            view: std::mem::replace(&mut self.stencil_view, view),
+            layer: self.stencil_layer,
+            geometry_stack: std::mem::take(&mut self.stencil_geometry_stack),
+        });
+        self.clear(device, queue);
+    }
+
+    pub fn restore_set_aside(&mut self) {
+        let Some(stencil) = self.set_aside.pop() else {
+            log::warn!("stencil renderer: restored a stencil that wasn't set aside");
+            return;
+        };
+        let texture = std::mem::replace(&mut self.stencil_texture, stencil.texture);
+        let view = std::mem::replace(&mut self.stencil_view, stencil.view);
+        self.spare_textures.push((texture, view));
+        self.stencil_layer = stencil.layer;
+        self.stencil_geometry_stack = stencil.geometry_stack;
     }
 
     pub fn push_stencil(
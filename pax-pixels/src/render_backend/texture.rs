@@ -17,12 +17,15 @@ pub struct TextureRenderer {
 
     texture_sampler: wgpu::Sampler,
     texture_pipeline: wgpu::RenderPipeline,
+    // draws textures that already have their alpha premultiplied, such as groups
+    composite_pipeline: wgpu::RenderPipeline,
+    // draws premultiplied textures over what's underneath instead of blending with it
+    replace_pipeline: wgpu::RenderPipeline,
     texture_bind_group_layout: wgpu::BindGroupLayout,
 }
 
 impl TextureRenderer {
     pub fn new(device: &wgpu::Device) -> Self {
-        const TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
         let texture_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Texture Shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("textures.wgsl").into()),
@@ -67,59 +70,27 @@ impl TextureRenderer {
                 push_constant_ranges: &[],
             });
 
-        let texture_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Texture Pipeline"),
-            layout: Some(&texture_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &texture_shader,
-                entry_point: "vs_main",
-                buffers: &[TextureVertex::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &texture_shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: TEXTURE_FORMAT,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: wgpu::TextureFormat::Stencil8,
-                depth_write_enabled: false,
-                depth_compare: wgpu::CompareFunction::Always,
-                stencil: wgpu::StencilState {
-                    front: wgpu::StencilFaceState {
-                        compare: wgpu::CompareFunction::Equal,
-                        fail_op: wgpu::StencilOperation::Keep,
-                        depth_fail_op: wgpu::StencilOperation::Keep,
-                        pass_op: wgpu::StencilOperation::Keep,
-                    },
-                    back: wgpu::StencilFaceState::IGNORE,
-                    read_mask: !0,
-                    write_mask: !0,
-                },
-                bias: Default::default(),
-            }),
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
-            cache: None,
-        });
+        let texture_pipeline = create_pipeline(
+            device,
+            &texture_pipeline_layout,
+            &texture_shader,
+            "fs_main",
+            wgpu::BlendState::ALPHA_BLENDING,
+        );
+        let composite_pipeline = create_pipeline(
+            device,
+            &texture_pipeline_layout,
+            &texture_shader,
+            "fs_composite",
+            wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        );
+        let replace_pipeline = create_pipeline(
+            device,
+            &texture_pipeline_layout,
+            &texture_shader,
+            "fs_composite",
+            wgpu::BlendState::REPLACE,
+        );
 
         let vertices = [TextureVertex::default(); 6];
         let vertices_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -147,6 +118,8 @@ impl TextureRenderer {
             indices_buffer,
             texture_sampler,
             texture_pipeline,
+            composite_pipeline,
+            replace_pipeline,
             texture_bind_group_layout,
         }
     }
@@ -163,31 +136,7 @@ impl TextureRenderer {
         transform: Transform2D,
         location: Box2D,
     ) {
-        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Texture Encoder"),
-        });
-        let points = get_transformed_corners(&location, &transform);
         let height = rgba.len() as u32 / (rgba_width * 4);
-        let verts = [
-            TextureVertex {
-                position: points[0].to_array(),
-                texture_coord: [0.0, 0.0],
-            },
-            TextureVertex {
-                position: points[1].to_array(),
-                texture_coord: [1.0, 0.0],
-            },
-            TextureVertex {
-                position: points[2].to_array(),
-                texture_coord: [0.0, 1.0],
-            },
-            TextureVertex {
-                position: points[3].to_array(),
-                texture_coord: [1.0, 1.0],
-            },
-        ];
-        queue.write_buffer(&self.vertices_buffer, 0, bytemuck::cast_slice(&verts));
-
         let size = wgpu::Extent3d {
             width: rgba_width,
             height,
@@ -221,6 +170,96 @@ impl TextureRenderer {
         );
 
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.draw_quad(
+            device,
+            queue,
+            target,
+            globals,
+            stencil_renderer,
+            &texture_view,
+            &self.texture_pipeline,
+            get_transformed_corners(&location, &transform),
+            1.0,
+        );
+    }
+
+    /// Draws a texture holding premultiplied colors, covering the whole `target`.
+    /// With `replace` set it overwrites the target where not clipped, instead of
+    /// being drawn over it.
+    pub fn composite(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        globals: &wgpu::Buffer,
+        stencil_renderer: &StencilRenderer,
+        texture_view: &wgpu::TextureView,
+        resolution: [f32; 2],
+        opacity: f32,
+        replace: bool,
+    ) {
+        let [width, height] = resolution;
+        let pipeline = if replace {
+            &self.replace_pipeline
+        } else {
+            &self.composite_pipeline
+        };
+        self.draw_quad(
+            device,
+            queue,
+            target,
+            globals,
+            stencil_renderer,
+            texture_view,
+            pipeline,
+            [
+                Point::new(0.0, 0.0),
+                Point::new(width, 0.0),
+                Point::new(0.0, height),
+                Point::new(width, height),
+            ],
+            opacity,
+        );
+    }
+
+    fn draw_quad(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        target: &wgpu::TextureView,
+        globals: &wgpu::Buffer,
+        stencil_renderer: &StencilRenderer,
+        texture_view: &wgpu::TextureView,
+        pipeline: &wgpu::RenderPipeline,
+        points: [Point<f32>; 4],
+        opacity: f32,
+    ) {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Texture Encoder"),
+        });
+        let verts = [
+            TextureVertex {
+                position: points[0].to_array(),
+                texture_coord: [0.0, 0.0],
+                opacity,
+            },
+            TextureVertex {
+                position: points[1].to_array(),
+                texture_coord: [1.0, 0.0],
+                opacity,
+            },
+            TextureVertex {
+                position: points[2].to_array(),
+                texture_coord: [0.0, 1.0],
+                opacity,
+            },
+            TextureVertex {
+                position: points[3].to_array(),
+                texture_coord: [1.0, 1.0],
+                opacity,
+            },
+        ];
+        queue.write_buffer(&self.vertices_buffer, 0, bytemuck::cast_slice(&verts));
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &self.texture_bind_group_layout,
@@ -231,7 +270,7 @@ impl TextureRenderer {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
+                    resource: wgpu::BindingResource::TextureView(texture_view),
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
@@ -265,7 +304,7 @@ impl TextureRenderer {
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-            render_pass.set_pipeline(&self.texture_pipeline);
+            render_pass.set_pipeline(pipeline);
             render_pass.set_bind_group(0, &bind_group, &[]);
             render_pass.set_stencil_reference(stencil_index);
             render_pass.set_vertex_buffer(0, self.vertices_buffer.slice(..));
@@ -281,12 +320,13 @@ impl TextureRenderer {
 pub(crate) struct TextureVertex {
     pub position: [f32; 2],
     pub texture_coord: [f32; 2],
+    pub opacity: f32,
 }
 
 impl TextureVertex {
     pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
-        const ATTRIBS: [wgpu::VertexAttribute; 2] =
-            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2];
+        const ATTRIBS: [wgpu::VertexAttribute; 3] =
+            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32];
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<TextureVertex>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -295,6 +335,68 @@ impl TextureVertex {
     }
 }
 
+fn create_pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    fragment_entry_point: &str,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Texture Pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[TextureVertex::desc()],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: fragment_entry_point,
+            targets: &[Some(wgpu::ColorTargetState {
+                format: TextureFormat::Rgba16Float,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Stencil8,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState {
+                front: wgpu::StencilFaceState {
+                    compare: wgpu::CompareFunction::Equal,
+                    fail_op: wgpu::StencilOperation::Keep,
+                    depth_fail_op: wgpu::StencilOperation::Keep,
+                    pass_op: wgpu::StencilOperation::Keep,
+                },
+                back: wgpu::StencilFaceState::IGNORE,
+                read_mask: !0,
+                write_mask: !0,
+            },
+            bias: Default::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
 fn get_transformed_corners(box2d: &Box2D, transform: &Transform2D) -> [Point<f32>; 4] {
     let min = box2d.min;
     let max = box2d.max;
@@ -10,7 +10,11 @@ use lyon::lyon_tessellation::FillOptions;
 use lyon::lyon_tessellation::FillTessellator;
 use lyon::lyon_tessellation::FillVertex;
 use lyon::lyon_tessellation::VertexBuffers;
+use lyon::path::iterator::PathIterator;
 use lyon::path::Path;
+use lyon::path::PathEvent;
+use lyon::tessellation::LineCap;
+use lyon::tessellation::LineJoin;
 use lyon::tessellation::StrokeOptions;
 use lyon::tessellation::StrokeTessellator;
 use lyon::tessellation::StrokeVertex;
@@ -27,8 +31,12 @@ pub struct WgpuRenderer<'w> {
     render_backend: RenderBackend<'w>,
     // these reference indicies in the CpuBuffer "transforms"
     transform_index_stack: Vec<usize>,
-    // tuble of transform stack index to go to, and clip depth to go to
-    saves: Vec<(usize, usize)>,
+    // tuble of transform stack index to go to, clip depth to go to, and blend mode
+    // to go back to
+    saves: Vec<(usize, usize, BlendMode)>,
+    blend_mode: BlendMode,
+    // the blend modes to go back to once the groups being drawn are popped
+    group_blend_modes: Vec<BlendMode>,
     tolerance: f32,
 }
 
@@ -47,6 +55,8 @@ impl<'w> WgpuRenderer<'w> {
             tolerance: 0.5, //TODO expose as option
             transform_index_stack: vec![],
             saves: vec![],
+            blend_mode: BlendMode::Normal,
+            group_blend_modes: vec![],
         }
     }
 
@@ -60,9 +70,34 @@ impl<'w> WgpuRenderer<'w> {
         )
     }
 
-    pub fn stroke_path(&mut self, path: Path, stroke_fill: Fill, stroke_width: f32) {
+    /// Draws with `draw`, in a group of its own if it isn't blended normally, since
+    /// primitives are rendered in batches with a fixed blend state
+    fn blended(&mut self, draw: impl FnOnce(&mut Self)) {
+        if self.blend_mode == BlendMode::Normal {
+            draw(self);
+        } else {
+            self.push_group(1.0, 0.0);
+            draw(self);
+            self.pop_group();
+        }
+    }
+
+    pub fn stroke_path(&mut self, path: Path, stroke_fill: Fill, stroke: &Stroke) {
+        self.blended(|this| this.tessellate_stroke(path, stroke_fill, stroke));
+    }
+
+    fn tessellate_stroke(&mut self, path: Path, stroke_fill: Fill, stroke: &Stroke) {
+        let path = if stroke.dash.is_empty() {
+            path
+        } else {
+            dashed(&path, &stroke.dash, stroke.dash_offset, self.tolerance)
+        };
         let prim_id = self.push_primitive_def(stroke_fill);
-        let options = StrokeOptions::tolerance(self.tolerance).with_line_width(stroke_width);
+        let options = StrokeOptions::tolerance(self.tolerance)
+            .with_line_width(stroke.weight)
+            .with_line_cap(stroke.cap)
+            .with_line_join(stroke.join)
+            .with_miter_limit(stroke.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT));
         let mut geometry_builder =
             BuffersBuilder::new(&mut self.buffers.geometry, |vertex: StrokeVertex| {
                 GpuVertex {
@@ -78,6 +113,10 @@ impl<'w> WgpuRenderer<'w> {
     }
 
     pub fn fill_path(&mut self, path: Path, fill: Fill) {
+        self.blended(|this| this.tessellate_fill(path, fill));
+    }
+
+    fn tessellate_fill(&mut self, path: Path, fill: Fill) {
         let prim_id = self.push_primitive_def(fill);
         let options = FillOptions::tolerance(self.tolerance);
         let mut geometry_builder =
@@ -148,12 +187,9 @@ impl<'w> WgpuRenderer<'w> {
         prim_id
     }
 
-    pub fn clear(&mut self) {
-        self.render_backend.clear();
-    }
-
-    pub fn draw_image(&mut self, image: &Image, rect: Box2D) {
-        let transform = self.current_transform();
+    /// Renders the primitives drawn so far, so that what comes next is drawn on top of
+    /// them. Transforms are kept since they're still referenced by the transform stack.
+    fn render_pending(&mut self) {
         if self.buffers.primitives.len() > 0 {
             self.render_backend.render_primitives(&mut self.buffers);
             let CpuBuffers {
@@ -169,7 +205,18 @@ impl<'w> WgpuRenderer<'w> {
             colors.clear();
             gradients.clear();
         }
-        self.render_backend.render_image(image, transform, rect);
+    }
+
+    pub fn clear(&mut self) {
+        self.render_backend.clear();
+    }
+
+    pub fn draw_image(&mut self, image: &Image, rect: Box2D) {
+        self.blended(|this| {
+            let transform = this.current_transform();
+            this.render_pending();
+            this.render_backend.render_image(image, transform, rect);
+        });
     }
 
     pub fn flush(&mut self) {
@@ -182,18 +229,56 @@ impl<'w> WgpuRenderer<'w> {
 
     pub fn save(&mut self) {
         let transform_len = self.transform_index_stack.len();
-        self.saves
-            .push((transform_len, self.render_backend.get_clip_depth() as usize));
+        self.saves.push((
+            transform_len,
+            self.render_backend.get_clip_depth() as usize,
+            self.blend_mode,
+        ));
     }
 
     pub fn restore(&mut self) {
-        if let Some((t_pen, clip_depth)) = self.saves.pop() {
+        if let Some((t_pen, clip_depth, blend_mode)) = self.saves.pop() {
             self.transform_index_stack.truncate(t_pen);
             self.render_backend
                 .reset_stencil_depth_to(clip_depth as u32);
+            self.blend_mode = blend_mode;
+        }
+    }
+
+    /// Sets how what's drawn next blends with what's underneath it, until the
+    /// matching `restore`
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    /// Draws everything until the matching `pop_group` into an offscreen texture,
+    /// which is then blurred by a gaussian with standard deviation `blur` and
+    /// composited with the given opacity and the current blend mode. What's drawn into
+    /// the group blends normally until set otherwise.
+    pub fn push_group(&mut self, opacity: f32, blur: f32) {
+        self.render_pending();
+        let blend_mode = std::mem::take(&mut self.blend_mode);
+        self.render_backend.push_group(opacity, blur, blend_mode);
+        self.group_blend_modes.push(blend_mode);
+    }
+
+    pub fn pop_group(&mut self) {
+        self.render_pending();
+        self.render_backend.pop_group();
+        if let Some(blend_mode) = self.group_blend_modes.pop() {
+            self.blend_mode = blend_mode;
         }
     }
 
+    /// Blurs what has been drawn so far within `path`
+    pub fn blur_backdrop(&mut self, path: Path, sigma: f32) {
+        self.render_pending();
+        self.save();
+        self.clip(path);
+        self.render_backend.blur_backdrop(sigma);
+        self.restore();
+    }
+
     pub fn transform(&mut self, transform: Transform2D) {
         let new_ind = self.buffers.transforms.len();
         self.buffers.transforms.push(GpuTransform {
@@ -207,21 +292,7 @@ impl<'w> WgpuRenderer<'w> {
     pub fn clip(&mut self, path: Path) {
         // fine to transform on CPU - shouldn't be large meshes
         let path = path.transformed(&self.current_transform());
-        if self.buffers.primitives.len() > 0 {
-            self.render_backend.render_primitives(&mut self.buffers);
-            let CpuBuffers {
-                geometry,
-                primitives,
-                transforms: _,
-                colors,
-                gradients,
-            } = &mut self.buffers;
-            geometry.vertices.clear();
-            geometry.indices.clear();
-            primitives.clear();
-            colors.clear();
-            gradients.clear();
-        }
+        self.render_pending();
         let options = FillOptions::tolerance(self.tolerance);
         let mut geometry = VertexBuffers::new();
         let mut geometry_builder =
@@ -352,7 +423,151 @@ pub enum Fill {
     },
 }
 
+/// How paths are stroked, with lengths in the coordinates of the path
+#[derive(Debug, Clone)]
 pub struct Stroke {
-    pub color: Color,
     pub weight: f32,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f32,
+    /// Lengths of alternating dashes and gaps. Solid if empty.
+    pub dash: Vec<f32>,
+    pub dash_offset: f32,
+}
+
+impl Stroke {
+    pub fn new(weight: f32) -> Self {
+        Self {
+            weight,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: StrokeOptions::DEFAULT_MITER_LIMIT,
+            dash: Vec::new(),
+            dash_offset: 0.0,
+        }
+    }
+}
+
+/// Splits `path` into a subpath per dash, restarting the pattern at each subpath like
+/// SVG does. Curves are flattened, as they would be when tessellated anyway.
+fn dashed(path: &Path, dash: &[f32], offset: f32, tolerance: f32) -> Path {
+    // lengths alternate between dashes and gaps, so an odd number of them is repeated
+    let pattern = if dash.len() % 2 == 1 {
+        dash.repeat(2)
+    } else {
+        dash.to_vec()
+    };
+    let period: f32 = pattern.iter().sum();
+    if pattern
+        .iter()
+        .any(|length| !length.is_finite() || *length < 0.0)
+        || period <= 0.0
+    {
+        return path.clone();
+    }
+    let mut dasher = Dasher {
+        builder: Path::builder(),
+        pattern,
+        index: 0,
+        remaining: 0.0,
+    };
+    for event in path.iter().flattened(tolerance) {
+        match event {
+            PathEvent::Begin { at } => dasher.begin(at, offset.rem_euclid(period)),
+            PathEvent::Line { from, to } => dasher.walk(from, to),
+            PathEvent::End { last, first, close } => {
+                if close {
+                    dasher.walk(last, first);
+                }
+                dasher.end();
+            }
+            // flattening leaves only lines
+            PathEvent::Quadratic { .. } | PathEvent::Cubic { .. } => {}
+        }
+    }
+    dasher.builder.build()
+}
+
+struct Dasher {
+    builder: lyon::path::path::Builder,
+    pattern: Vec<f32>,
+    // the length of the pattern currently being walked, and what's left of it. Even
+    // lengths are dashes, odd ones gaps.
+    index: usize,
+    remaining: f32,
+}
+
+impl Dasher {
+    fn drawing(&self) -> bool {
+        self.index % 2 == 0
+    }
+
+    fn begin(&mut self, at: Point2D, mut skip: f32) {
+        self.index = 0;
+        self.remaining = self.pattern[0];
+        while skip > 0.0 && skip >= self.remaining && self.index + 1 < self.pattern.len() {
+            skip -= self.remaining;
+            self.index += 1;
+            self.remaining = self.pattern[self.index];
+        }
+        self.remaining = (self.remaining - skip).max(0.0);
+        if self.drawing() {
+            self.builder.begin(at);
+        }
+    }
+
+    fn walk(&mut self, mut from: Point2D, to: Point2D) {
+        loop {
+            let length = (to - from).length();
+            let step = self.remaining.min(length);
+            if step > 0.0 {
+                let next = from.lerp(to, step / length);
+                if self.drawing() {
+                    self.builder.line_to(next);
+                }
+                self.remaining -= step;
+                from = next;
+            }
+            if self.remaining > 0.0 {
+                return;
+            }
+            // on to the next length. Dashes begin even if they're empty, so that zero
+            // length dashes still get caps.
+            self.end();
+            self.index = (self.index + 1) % self.pattern.len();
+            self.remaining = self.pattern[self.index];
+            if self.drawing() {
+                self.builder.begin(from);
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        if self.drawing() {
+            self.builder.end(false);
+        }
+    }
+}
+
+/// How what's drawn combines with what's underneath it, as defined by
+/// https://www.w3.org/TR/compositing-1/#blending
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
 }
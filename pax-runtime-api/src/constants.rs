@@ -6,6 +6,8 @@ pub const DEGREES: &str = "Degrees";
 pub const RADIANS: &str = "Radians";
 pub const PIXELS: &str = "Pixels";
 pub const PERCENT: &str = "Percent";
+pub const DURATION: &str = "Duration";
+pub const MILLIS: &str = "Millis";
 pub const INTEGER: &str = "Integer";
 pub const I64: &str = "I64";
 pub const F64: &str = "F64";
@@ -65,4 +67,9 @@ pub const COMMON_PROPERTIES_TYPE: &[(&str, &str)] = &[
     ("width", "pax_engine::api::Size"),
     ("height", "pax_engine::api::Size"),
     ("_raycastable", "bool"),
+    ("disabled", "bool"),
+    ("focusable", "bool"),
+    ("tab_index", "isize"),
+    ("opacity", "f64"),
+    ("blend_mode", "pax_engine::api::BlendMode"),
 ];
@@ -0,0 +1,517 @@
+//! Easing curves for transitions: the standard set of CSS and Penner easings, cubic Béziers,
+//! steps, and springs. From PAXEL, curves are written as enum values, e.g.
+//! `EasingCurve::OutCubic`, `EasingCurve::CubicBezier(0.4, 0.0, 0.2, 1.0)`,
+//! `EasingCurve::Steps(4)` or `EasingCurve::Spring(170, 26, 1)`.
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use crate::{Interpolatable, TransitionDuration};
+
+#[derive(Clone, Default)]
+pub enum EasingCurve {
+    #[default]
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    InQuart,
+    OutQuart,
+    InOutQuart,
+    InExpo,
+    OutExpo,
+    InOutExpo,
+    InSine,
+    OutSine,
+    InOutSine,
+    InBack,
+    OutBack,
+    InOutBack,
+    InElastic,
+    OutElastic,
+    InOutElastic,
+    InBounce,
+    OutBounce,
+    InOutBounce,
+    /// The CSS `ease` keyword
+    Ease,
+    /// The CSS `ease-in` keyword
+    EaseIn,
+    /// The CSS `ease-out` keyword
+    EaseOut,
+    /// The CSS `ease-in-out` keyword
+    EaseInOut,
+    /// CSS `cubic-bezier(x1, y1, x2, y2)`: the curve from (0, 0) to (1, 1) with control points
+    /// (x1, y1) and (x2, y2)
+    CubicBezier(f64, f64, f64, f64),
+    /// CSS `steps(n)`: jumps to the next of `n` even steps at the end of each interval
+    Steps(u32),
+    /// A spring, stretched to the duration of the transition. When a transition with a spring
+    /// interrupts another one, it starts out with the velocity the property was moving at.
+    Spring(Spring),
+    /// A curve evaluated by a Rust closure, made with [`EasingCurve::custom`]. It can't be
+    /// written in PAXEL, so converting it to a `PaxValue` is one-way: the `Custom` variant it
+    /// converts to can't be coerced back.
+    Custom(Rc<dyn Fn(f64) -> f64>),
+}
+
+impl Interpolatable for EasingCurve {}
+
+struct EasingEvaluators {}
+impl EasingEvaluators {
+    fn linear(t: f64) -> f64 {
+        t
+    }
+    #[allow(dead_code)]
+    fn none(t: f64) -> f64 {
+        if t == 1.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+    fn in_quad(t: f64) -> f64 {
+        t * t
+    }
+    fn out_quad(t: f64) -> f64 {
+        1.0 - (1.0 - t) * (1.0 - t)
+    }
+    fn in_out_quad(t: f64) -> f64 {
+        if t < 0.5 {
+            2.0 * t * t
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+        }
+    }
+    fn in_cubic(t: f64) -> f64 {
+        t.powi(3)
+    }
+    fn out_cubic(t: f64) -> f64 {
+        1.0 - (1.0 - t).powi(3)
+    }
+    fn in_out_cubic(t: f64) -> f64 {
+        if t < 0.5 {
+            4.0 * t.powi(3)
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+    }
+    fn in_quart(t: f64) -> f64 {
+        t.powi(4)
+    }
+    fn out_quart(t: f64) -> f64 {
+        1.0 - (1.0 - t).powi(4)
+    }
+    fn in_out_quart(t: f64) -> f64 {
+        if t < 0.5 {
+            8.0 * t.powi(4)
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(4) / 2.0
+        }
+    }
+    fn in_expo(t: f64) -> f64 {
+        if t <= 0.0 {
+            0.0
+        } else {
+            2f64.powf(10.0 * t - 10.0)
+        }
+    }
+    fn out_expo(t: f64) -> f64 {
+        if t >= 1.0 {
+            1.0
+        } else {
+            1.0 - 2f64.powf(-10.0 * t)
+        }
+    }
+    fn in_out_expo(t: f64) -> f64 {
+        if t <= 0.0 {
+            0.0
+        } else if t >= 1.0 {
+            1.0
+        } else if t < 0.5 {
+            2f64.powf(20.0 * t - 10.0) / 2.0
+        } else {
+            (2.0 - 2f64.powf(-20.0 * t + 10.0)) / 2.0
+        }
+    }
+    fn in_sine(t: f64) -> f64 {
+        1.0 - (t * PI / 2.0).cos()
+    }
+    fn out_sine(t: f64) -> f64 {
+        (t * PI / 2.0).sin()
+    }
+    fn in_out_sine(t: f64) -> f64 {
+        -((PI * t).cos() - 1.0) / 2.0
+    }
+    fn in_back(t: f64) -> f64 {
+        const C1: f64 = 1.70158;
+        const C3: f64 = C1 + 1.00;
+        C3 * t * t * t - C1 * t * t
+    }
+    fn out_back(t: f64) -> f64 {
+        const C1: f64 = 1.70158;
+        const C3: f64 = C1 + 1.00;
+        1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+    }
+
+    fn in_out_back(t: f64) -> f64 {
+        const C1: f64 = 1.70158;
+        const C2: f64 = C1 * 1.525;
+        if t < 0.5 {
+            ((2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2)) / 2.0
+        } else {
+            ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+        }
+    }
+    fn in_elastic(t: f64) -> f64 {
+        const C4: f64 = 2.0 * PI / 3.0;
+        if t <= 0.0 {
+            0.0
+        } else if t >= 1.0 {
+            1.0
+        } else {
+            -(2f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * C4).sin()
+        }
+    }
+    fn out_elastic(t: f64) -> f64 {
+        const C4: f64 = 2.0 * PI / 3.0;
+        if t <= 0.0 {
+            0.0
+        } else if t >= 1.0 {
+            1.0
+        } else {
+            2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * C4).sin() + 1.0
+        }
+    }
+    fn in_out_elastic(t: f64) -> f64 {
+        const C5: f64 = 2.0 * PI / 4.5;
+        if t <= 0.0 {
+            0.0
+        } else if t >= 1.0 {
+            1.0
+        } else if t < 0.5 {
+            -(2f64.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+        } else {
+            2f64.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin() / 2.0 + 1.0
+        }
+    }
+    fn in_bounce(t: f64) -> f64 {
+        1.0 - Self::out_bounce(1.0 - t)
+    }
+    fn out_bounce(t: f64) -> f64 {
+        const N1: f64 = 7.5625;
+        const D1: f64 = 2.75;
+        if t < 1.0 / D1 {
+            N1 * t * t
+        } else if t < 2.0 / D1 {
+            let t = t - 1.5 / D1;
+            N1 * t * t + 0.75
+        } else if t < 2.5 / D1 {
+            let t = t - 2.25 / D1;
+            N1 * t * t + 0.9375
+        } else {
+            let t = t - 2.625 / D1;
+            N1 * t * t + 0.984375
+        }
+    }
+    fn in_out_bounce(t: f64) -> f64 {
+        if t < 0.5 {
+            (1.0 - Self::out_bounce(1.0 - 2.0 * t)) / 2.0
+        } else {
+            (1.0 + Self::out_bounce(2.0 * t - 1.0)) / 2.0
+        }
+    }
+    fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, t: f64) -> f64 {
+        if t <= 0.0 || t >= 1.0 {
+            return t.clamp(0.0, 1.0);
+        }
+        // coordinate along one axis of the curve at parameter `s`
+        let bezier = |a: f64, b: f64, s: f64| {
+            3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s * s * (1.0 - s) + s.powi(3)
+        };
+        let slope = |a: f64, b: f64, s: f64| {
+            3.0 * a * (1.0 - s).powi(2) + 6.0 * (b - a) * s * (1.0 - s) + 3.0 * (1.0 - b) * s * s
+        };
+        // find the parameter where the curve is at x = t, by Newton's method while it
+        // converges and bisection otherwise
+        let mut s = t;
+        for _ in 0..8 {
+            let error = bezier(x1, x2, s) - t;
+            if error.abs() < 1e-7 {
+                return bezier(y1, y2, s);
+            }
+            let derivative = slope(x1, x2, s);
+            if derivative.abs() < 1e-6 {
+                break;
+            }
+            s -= error / derivative;
+        }
+        let (mut low, mut high) = (0.0, 1.0);
+        s = t;
+        for _ in 0..50 {
+            let x = bezier(x1, x2, s);
+            if (x - t).abs() < 1e-7 {
+                break;
+            }
+            if x < t {
+                low = s;
+            } else {
+                high = s;
+            }
+            s = (low + high) / 2.0;
+        }
+        bezier(y1, y2, s)
+    }
+    fn steps(n: u32, t: f64) -> f64 {
+        if t >= 1.0 {
+            return 1.0;
+        }
+        let n = n.max(1) as f64;
+        (t.max(0.0) * n).floor() / n
+    }
+}
+
+impl EasingCurve {
+    /// A curve that maps time `t ∈ [0,1]` to progress with `evaluator`, e.g.
+    /// `EasingCurve::custom(|t| t * t)`
+    pub fn custom(evaluator: impl Fn(f64) -> f64 + 'static) -> Self {
+        EasingCurve::Custom(Rc::new(evaluator))
+    }
+
+    //for a time on the unit interval `t ∈ [0,1]`, given a value `t`,
+    // find the interpolated value `vt` between `v0` and `v1` given the self-contained easing curve
+    pub fn interpolate<T: Interpolatable>(&self, v0: &T, v1: &T, t: f64) -> T /*vt*/ {
+        v0.interpolate(v1, self.evaluate(t))
+    }
+
+    /// The progress of a transition along this curve at time `t ∈ [0,1]`
+    pub fn evaluate(&self, t: f64) -> f64 {
+        match self {
+            EasingCurve::Linear => EasingEvaluators::linear(t),
+            EasingCurve::InQuad => EasingEvaluators::in_quad(t),
+            EasingCurve::OutQuad => EasingEvaluators::out_quad(t),
+            EasingCurve::InOutQuad => EasingEvaluators::in_out_quad(t),
+            EasingCurve::InCubic => EasingEvaluators::in_cubic(t),
+            EasingCurve::OutCubic => EasingEvaluators::out_cubic(t),
+            EasingCurve::InOutCubic => EasingEvaluators::in_out_cubic(t),
+            EasingCurve::InQuart => EasingEvaluators::in_quart(t),
+            EasingCurve::OutQuart => EasingEvaluators::out_quart(t),
+            EasingCurve::InOutQuart => EasingEvaluators::in_out_quart(t),
+            EasingCurve::InExpo => EasingEvaluators::in_expo(t),
+            EasingCurve::OutExpo => EasingEvaluators::out_expo(t),
+            EasingCurve::InOutExpo => EasingEvaluators::in_out_expo(t),
+            EasingCurve::InSine => EasingEvaluators::in_sine(t),
+            EasingCurve::OutSine => EasingEvaluators::out_sine(t),
+            EasingCurve::InOutSine => EasingEvaluators::in_out_sine(t),
+            EasingCurve::InBack => EasingEvaluators::in_back(t),
+            EasingCurve::OutBack => EasingEvaluators::out_back(t),
+            EasingCurve::InOutBack => EasingEvaluators::in_out_back(t),
+            EasingCurve::InElastic => EasingEvaluators::in_elastic(t),
+            EasingCurve::OutElastic => EasingEvaluators::out_elastic(t),
+            EasingCurve::InOutElastic => EasingEvaluators::in_out_elastic(t),
+            EasingCurve::InBounce => EasingEvaluators::in_bounce(t),
+            EasingCurve::OutBounce => EasingEvaluators::out_bounce(t),
+            EasingCurve::InOutBounce => EasingEvaluators::in_out_bounce(t),
+            EasingCurve::Ease => EasingEvaluators::cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            EasingCurve::EaseIn => EasingEvaluators::cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            EasingCurve::EaseOut => EasingEvaluators::cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            EasingCurve::EaseInOut => EasingEvaluators::cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            EasingCurve::CubicBezier(x1, y1, x2, y2) => {
+                EasingEvaluators::cubic_bezier(*x1, *y1, *x2, *y2, t)
+            }
+            EasingCurve::Steps(n) => EasingEvaluators::steps(*n, t),
+            EasingCurve::Spring(spring) => spring.evaluate(t),
+            EasingCurve::Custom(evaluator) => (*evaluator)(t),
+        }
+    }
+}
+
+/// Distance from the resting position below which a spring is considered settled
+const SPRING_REST_DISTANCE: f64 = 0.001;
+
+/// A damped spring with the target value as its resting position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f64,
+    pub damping: f64,
+    pub mass: f64,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self {
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+        }
+    }
+}
+
+impl Spring {
+    pub fn new(stiffness: f64, damping: f64, mass: f64) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass,
+        }
+    }
+
+    /// How long the spring takes to come to rest from a standstill. Transitions of this
+    /// duration play the spring at its natural speed.
+    pub fn duration(&self) -> TransitionDuration {
+        TransitionDuration::Millis(self.settling_time() * 1000.0)
+    }
+
+    /// Position along the transition at `t ∈ [0,1]` of the settling time, starting from rest
+    fn evaluate(&self, t: f64) -> f64 {
+        if t >= 1.0 {
+            return 1.0;
+        }
+        1.0 - self.displacement(t * self.settling_time(), 1.0, 0.0)
+    }
+
+    /// Progress added at `t ∈ [0,1]` of the settling time by starting out with a velocity of
+    /// one transition per unit of `t`
+    pub(crate) fn launch(&self, t: f64) -> f64 {
+        let settling_time = self.settling_time();
+        self.displacement(t * settling_time, 0.0, 1.0 / settling_time)
+    }
+
+    fn angular_frequency(&self) -> f64 {
+        (self.stiffness / self.mass.max(f64::EPSILON)).sqrt()
+    }
+
+    fn damping_ratio(&self) -> f64 {
+        self.damping / (2.0 * (self.stiffness * self.mass).max(f64::EPSILON).sqrt())
+    }
+
+    /// Displacement from rest after `time` seconds, from displacement `x0` and velocity `v0`
+    fn displacement(&self, time: f64, x0: f64, v0: f64) -> f64 {
+        let omega = self.angular_frequency();
+        let zeta = self.damping_ratio();
+        if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            (-zeta * omega * time).exp()
+                * (x0 * (omega_d * time).cos()
+                    + (v0 + zeta * omega * x0) / omega_d * (omega_d * time).sin())
+        } else if zeta == 1.0 {
+            (-omega * time).exp() * (x0 + (v0 + omega * x0) * time)
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let (r1, r2) = (-omega * (zeta - root), -omega * (zeta + root));
+            let c1 = (v0 - r2 * x0) / (r1 - r2);
+            c1 * (r1 * time).exp() + (x0 - c1) * (r2 * time).exp()
+        }
+    }
+
+    /// Seconds until the spring stays within [`SPRING_REST_DISTANCE`] of rest
+    fn settling_time(&self) -> f64 {
+        let omega = self.angular_frequency();
+        let zeta = self.damping_ratio();
+        let time = if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            // the oscillation is bounded by an exponentially decaying envelope
+            let amplitude = (1.0 + (zeta * omega / omega_d).powi(2)).sqrt();
+            (amplitude / SPRING_REST_DISTANCE).ln() / (zeta * omega)
+        } else {
+            // without oscillation, the displacement only decreases
+            let mut time = 0.0;
+            while time < 60.0 && self.displacement(time, 1.0, 0.0) > SPRING_REST_DISTANCE {
+                time += 1.0 / 60.0;
+            }
+            time
+        };
+        if time.is_finite() {
+            time.clamp(1.0 / 60.0, 60.0)
+        } else {
+            60.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_curve_endpoints() {
+        let curves = [
+            EasingCurve::Linear,
+            EasingCurve::InOutQuad,
+            EasingCurve::OutCubic,
+            EasingCurve::InOutQuart,
+            EasingCurve::InOutExpo,
+            EasingCurve::InOutSine,
+            EasingCurve::InOutBack,
+            EasingCurve::InOutElastic,
+            EasingCurve::InOutBounce,
+            EasingCurve::Ease,
+            EasingCurve::CubicBezier(0.4, 0.0, 0.2, 1.0),
+            EasingCurve::Steps(4),
+            EasingCurve::Spring(Spring::default()),
+        ];
+        for curve in curves {
+            assert_close(curve.evaluate(0.0), 0.0);
+            assert_close(curve.evaluate(1.0), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_cubic_bezier() {
+        // a bezier with control points on the diagonal is linear
+        let linear = EasingCurve::CubicBezier(0.25, 0.25, 0.75, 0.75);
+        for t in [0.1, 0.3, 0.5, 0.9] {
+            assert_close(linear.evaluate(t), t);
+        }
+        // ease-in-out is symmetric around its midpoint
+        assert_close(EasingCurve::EaseInOut.evaluate(0.5), 0.5);
+        assert_close(
+            EasingCurve::EaseInOut.evaluate(0.2),
+            1.0 - EasingCurve::EaseInOut.evaluate(0.8),
+        );
+        assert!(EasingCurve::EaseIn.evaluate(0.5) < 0.5);
+        assert!(EasingCurve::EaseOut.evaluate(0.5) > 0.5);
+    }
+
+    #[test]
+    fn test_steps() {
+        let steps = EasingCurve::Steps(4);
+        assert_eq!(steps.evaluate(0.2), 0.0);
+        assert_eq!(steps.evaluate(0.25), 0.25);
+        assert_eq!(steps.evaluate(0.6), 0.5);
+        assert_eq!(steps.evaluate(0.99), 0.75);
+    }
+
+    #[test]
+    fn test_spring() {
+        let bouncy = Spring::new(300.0, 10.0, 1.0);
+        let curve = EasingCurve::Spring(bouncy);
+        let overshoot = (1..100)
+            .map(|i| curve.evaluate(i as f64 / 100.0))
+            .fold(0.0, f64::max);
+        assert!(overshoot > 1.0);
+        assert_close(curve.evaluate(0.999), 1.0);
+
+        let TransitionDuration::Millis(millis) = bouncy.duration() else {
+            panic!("springs settle in milliseconds");
+        };
+        let stiffer = Spring::new(1200.0, 20.0, 1.0);
+        assert!(matches!(stiffer.duration(), TransitionDuration::Millis(m) if m < millis));
+
+        // an overdamped spring approaches its target without overshooting
+        let overdamped = EasingCurve::Spring(Spring::new(100.0, 50.0, 1.0));
+        let mut last = 0.0;
+        for i in 1..=100 {
+            let progress = overdamped.evaluate(i as f64 / 100.0);
+            assert!(progress >= last && progress <= 1.0);
+            last = progress;
+        }
+    }
+}
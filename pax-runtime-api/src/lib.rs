@@ -8,7 +8,7 @@ use crate::math::Space;
 use kurbo::BezPath;
 pub use pax_message::*;
 pub use pax_value::numeric::Numeric;
-pub use pax_value::{CoercionRules, ImplToFromPaxAny, PaxValue, ToPaxValue};
+pub use pax_value::{CoercionRules, ImplToFromPaxAny, PaxFunction, PaxRange, PaxValue, ToPaxValue};
 use piet::UnitPoint;
 use properties::{PropertyValue, UntypedProperty};
 pub mod cursor;
@@ -25,12 +25,16 @@ use std::hash::Hash;
 use std::rc::{Rc, Weak};
 
 pub mod constants;
+pub mod easing;
 pub mod math;
 pub mod pax_value;
 pub mod properties;
+pub mod timeline;
 
+pub use easing::{EasingCurve, Spring};
 pub use pax_value::functions;
 pub use properties::Property;
+pub use timeline::{Keyframe, Timeline, TimelineLoop};
 
 pub use pax_value::functions::register_function;
 pub use pax_value::functions::Functions;
@@ -42,18 +46,115 @@ pub use pax_message::serde;
 use serde::{Deserialize, Serialize};
 
 pub struct TransitionQueueEntry<T> {
-    pub duration_frames: u64,
+    pub duration: TransitionDuration,
     pub curve: EasingCurve,
     pub ending_value: T,
 }
 
+/// How long a transition takes. `Millis` keeps the speed of an animation independent of the
+/// frame rate, while `Frames` steps it deterministically with each tick, e.g. for tests.
+/// Durations convert from [`std::time::Duration`] and from frame counts (`u64`). In PAXEL,
+/// plain numbers are frame counts too, and millisecond durations are written as `300ms` or `0.3s`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(crate = "crate::serde")]
+pub enum TransitionDuration {
+    Millis(f64),
+    Frames(u64),
+}
+
+impl Default for TransitionDuration {
+    fn default() -> Self {
+        Self::Millis(0.0)
+    }
+}
+
+impl From<std::time::Duration> for TransitionDuration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self::Millis(duration.as_secs_f64() * 1000.0)
+    }
+}
+
+impl From<u64> for TransitionDuration {
+    fn from(frames: u64) -> Self {
+        Self::Frames(frames)
+    }
+}
+
+impl Interpolatable for TransitionDuration {}
+
+impl TransitionDuration {
+    /// How far through a transition of this duration started at `origin` is at `now`,
+    /// exceeding 1 once it's over
+    fn progress(&self, origin: TransitionTime, now: TransitionTime) -> f64 {
+        let (elapsed, duration) = match *self {
+            Self::Millis(millis) => (now.millis - origin.millis, millis),
+            Self::Frames(frames) => (
+                now.frames.saturating_sub(origin.frames) as f64,
+                frames as f64,
+            ),
+        };
+        if duration <= 0.0 {
+            if elapsed > 0.0 {
+                f64::INFINITY
+            } else {
+                1.0
+            }
+        } else {
+            elapsed / duration
+        }
+    }
+
+    /// When a transition of this duration started at `origin` ended, for a `now` after that.
+    /// The time on the clock it isn't measured by is estimated from the time passed on both.
+    fn end(&self, origin: TransitionTime, now: TransitionTime) -> TransitionTime {
+        let elapsed_frames = now.frames.saturating_sub(origin.frames) as f64;
+        let elapsed_millis = now.millis - origin.millis;
+        match *self {
+            Self::Millis(millis) => TransitionTime {
+                frames: origin.frames + (elapsed_frames * millis / elapsed_millis) as u64,
+                millis: origin.millis + millis,
+            },
+            Self::Frames(frames) => TransitionTime {
+                frames: origin.frames + frames,
+                millis: origin.millis + elapsed_millis * frames as f64 / elapsed_frames,
+            },
+        }
+    }
+}
+
+/// A moment on both of the clocks transitions are timed by
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransitionTime {
+    pub frames: u64,
+    pub millis: f64,
+}
+
+/// How an offscreen group is drawn back onto the layer below it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Compositing {
+    pub opacity: f64,
+    /// Standard deviation in pixels of a gaussian blur applied to the group
+    pub blur: f64,
+}
+
+impl Default for Compositing {
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            blur: 0.0,
+        }
+    }
+}
+
 // The Pax render trait that allows for drawing on multiple
 // layers.
 // TODO migrate from using kurbo types to our own?
 pub trait RenderContext {
     //drawing
     fn fill(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill);
-    fn stroke(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill, width: f64);
+    /// Strokes `path` as described by `style`, whose width and dash lengths are in the
+    /// current coordinates of `layer`
+    fn stroke(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill, style: &kurbo::Stroke);
 
     // clip/transform
     fn save(&mut self, layer: usize);
@@ -61,6 +162,22 @@ pub trait RenderContext {
     fn clip(&mut self, layer: usize, path: BezPath);
     fn transform(&mut self, layer: usize, affine: kurbo::Affine);
 
+    // groups
+    /// Draws everything until the matching `pop_group` into an offscreen layer, which
+    /// is then composited onto `layer` as described by `compositing`. Clips in effect
+    /// when the group is pushed apply once it's composited, not while drawing into it.
+    fn push_group(&mut self, layer: usize, compositing: Compositing);
+    fn pop_group(&mut self, layer: usize);
+    /// Sets how what's drawn next on `layer` blends with what's underneath it, until the
+    /// matching `restore`. Groups blend as a whole once composited, and what's drawn
+    /// into them blends normally until set otherwise.
+    fn set_blend_mode(&mut self, layer: usize, blend_mode: BlendMode);
+
+    // filters
+    /// Blurs what has already been drawn on `layer` within `path`, with a gaussian of
+    /// standard deviation `sigma` in pixels.
+    fn blur_backdrop(&mut self, layer: usize, path: BezPath, sigma: f64);
+
     // images
     fn load_image(&mut self, identifier: &str, image: &[u8], width: usize, height: usize);
     fn draw_image(&mut self, layer: usize, image_path: &str, rect: kurbo::Rect);
@@ -79,7 +196,7 @@ pub trait RenderContext {
 impl<T> std::fmt::Debug for TransitionQueueEntry<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TransitionQueueEntry")
-            .field("duration_frames", &self.duration_frames)
+            .field("duration", &self.duration)
             // .field("ending_value", &self.ending_value)
             .finish()
     }
@@ -158,6 +275,7 @@ impl Space for Window {}
 pub struct Event<T> {
     pub args: T,
     cancelled: Rc<Cell<bool>>,
+    propagation_stopped: Rc<Cell<bool>>,
 }
 
 impl<T: Clone + 'static> ImplToFromPaxAny for Event<T> {}
@@ -167,6 +285,7 @@ impl<T> Event<T> {
         Self {
             args,
             cancelled: Default::default(),
+            propagation_stopped: Default::default(),
         }
     }
 
@@ -177,6 +296,17 @@ impl<T> Event<T> {
     pub fn cancelled(&self) -> bool {
         self.cancelled.get()
     }
+
+    /// Keeps this event from reaching the handlers of any further nodes, in either the
+    /// capture or the bubble phase. Unlike [`Event::prevent_default`], this doesn't cancel
+    /// the native default action.
+    pub fn stop_propagation(&self) {
+        self.propagation_stopped.set(true);
+    }
+
+    pub fn propagation_stopped(&self) -> bool {
+        self.propagation_stopped.get()
+    }
 }
 
 impl<T> Deref for Event<T> {
@@ -288,6 +418,14 @@ pub struct KeyPress {
 #[derive(Clone)]
 pub struct Focus {}
 
+/// Node gained keyboard focus.
+#[derive(Clone)]
+pub struct FocusIn {}
+
+/// Node lost keyboard focus.
+#[derive(Clone)]
+pub struct FocusOut {}
+
 // Mouse Events
 
 /// Common properties in mouse events.
@@ -424,6 +562,153 @@ pub struct ContextMenu {
     pub mouse: MouseEventArgs,
 }
 
+// Pointer Events
+
+/// Common properties in pointer events, which unify mouse, touch and pen input.
+#[derive(Clone)]
+pub struct PointerEventArgs {
+    /// Identifies the pointer across its events, e.g. one finger of a multi-touch gesture
+    pub pointer_id: i64,
+    pub pointer_type: PointerType,
+    pub x: f64,
+    pub y: f64,
+    pub button: MouseButton,
+    pub modifiers: Vec<ModifierKey>,
+}
+
+impl From<&PointerInterruptArgs> for PointerEventArgs {
+    fn from(value: &PointerInterruptArgs) -> Self {
+        PointerEventArgs {
+            pointer_id: value.pointer_id,
+            pointer_type: PointerType::from(value.pointer_type.clone()),
+            x: value.x,
+            y: value.y,
+            button: MouseButton::from(value.button.clone()),
+            modifiers: value.modifiers.iter().map(ModifierKey::from).collect(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum PointerType {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+impl From<PointerTypeMessage> for PointerType {
+    fn from(value: PointerTypeMessage) -> Self {
+        match value {
+            PointerTypeMessage::Mouse => PointerType::Mouse,
+            PointerTypeMessage::Touch => PointerType::Touch,
+            PointerTypeMessage::Pen => PointerType::Pen,
+        }
+    }
+}
+
+/// User presses a pointer over an element.
+#[derive(Clone)]
+pub struct PointerDown {
+    pub pointer: PointerEventArgs,
+}
+
+/// User moves a pointer over an element, or anywhere while the element captures it.
+#[derive(Clone)]
+pub struct PointerMove {
+    pub pointer: PointerEventArgs,
+}
+
+/// User releases a pointer over an element, or anywhere while the element captures it.
+#[derive(Clone)]
+pub struct PointerUp {
+    pub pointer: PointerEventArgs,
+}
+
+/// The platform stops tracking a pointer, e.g. when a touch turns into a scroll.
+#[derive(Clone)]
+pub struct PointerCancel {
+    pub pointer: PointerEventArgs,
+}
+
+// Gestures
+
+/// Common properties in drag events. Distances are in px and velocities in px per
+/// millisecond.
+#[derive(Clone)]
+pub struct DragEventArgs {
+    pub pointer_id: i64,
+    pub pointer_type: PointerType,
+    /// Where the pointer was pressed
+    pub start_x: f64,
+    pub start_y: f64,
+    pub x: f64,
+    pub y: f64,
+    /// Distance moved since the previous drag event
+    pub delta_x: f64,
+    pub delta_y: f64,
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
+
+/// User presses a pointer on an element and moves it past the drag threshold.
+#[derive(Clone)]
+pub struct DragStart {
+    pub drag: DragEventArgs,
+}
+
+/// User moves a pointer dragging an element, wherever the pointer is.
+#[derive(Clone)]
+pub struct Drag {
+    pub drag: DragEventArgs,
+}
+
+/// User releases a pointer dragging an element, or the platform cancels it.
+#[derive(Clone)]
+pub struct DragEnd {
+    pub drag: DragEventArgs,
+}
+
+/// User moves two pointers pressed on an element, e.g. to zoom or rotate it.
+#[derive(Clone)]
+pub struct Pinch {
+    /// Midpoint between the pointers
+    pub center_x: f64,
+    pub center_y: f64,
+    /// Distance between the pointers relative to when the pinch started
+    pub scale: f64,
+    /// Angle of the line between the pointers relative to when the pinch started
+    pub rotation: Rotation,
+}
+
+/// User holds a pointer on an element without moving it.
+#[derive(Clone)]
+pub struct LongPress {
+    pub pointer_id: i64,
+    pub pointer_type: PointerType,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SwipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// User quickly moves a pointer pressed on an element and releases it.
+#[derive(Clone)]
+pub struct Swipe {
+    pub direction: SwipeDirection,
+    /// Where the pointer was released
+    pub x: f64,
+    pub y: f64,
+    /// In px per millisecond
+    pub velocity_x: f64,
+    pub velocity_y: f64,
+}
+
 /// A Size value that can be either a concrete pixel value
 /// or a percent of parent bounds.
 
@@ -624,6 +909,21 @@ pub struct CommonProperties {
     pub unclippable: Property<Option<bool>>,
     pub _raycastable: Property<Option<bool>>,
     pub _suspended: Property<Option<bool>>,
+    /// Matched by `:disabled` selectors in `@settings`. Disabled nodes can't be focused.
+    pub disabled: Property<Option<bool>>,
+    /// Whether this node can receive keyboard focus, by clicking it, Tab traversal or
+    /// `NodeContext::focus`
+    pub focusable: Property<Option<bool>>,
+    /// Position of this node in Tab traversal. Positive indices come first, in ascending
+    /// order, followed by the focusable nodes without one in tree order. Nodes with a
+    /// negative index are skipped.
+    pub tab_index: Property<Option<isize>>,
+    /// Opacity of this node and its descendants, from `0.0` to `1.0`. Applies to the
+    /// node as a whole, so overlapping children don't show through each other.
+    pub opacity: Property<Option<f64>>,
+    /// How this node blends with what's drawn underneath it. Like `opacity`, applies to
+    /// the node as a whole. Only affects canvas drawing, not native elements.
+    pub blend_mode: Property<Option<BlendMode>>,
 }
 
 impl CommonProperties {
@@ -679,6 +979,11 @@ impl CommonProperties {
             unclippable,
             _raycastable,
             _suspended,
+            disabled,
+            focusable,
+            tab_index,
+            opacity,
+            blend_mode,
             // NOTE: remember to add an entry to the hashmap bellow as well
         } = self;
 
@@ -747,6 +1052,26 @@ impl CommonProperties {
                 "_suspended".to_string(),
                 Variable::new_from_typed_property(_suspended.clone()),
             ),
+            (
+                "disabled".to_string(),
+                Variable::new_from_typed_property(disabled.clone()),
+            ),
+            (
+                "focusable".to_string(),
+                Variable::new_from_typed_property(focusable.clone()),
+            ),
+            (
+                "tab_index".to_string(),
+                Variable::new_from_typed_property(tab_index.clone()),
+            ),
+            (
+                "opacity".to_string(),
+                Variable::new_from_typed_property(opacity.clone()),
+            ),
+            (
+                "blend_mode".to_string(),
+                Variable::new_from_typed_property(blend_mode.clone()),
+            ),
         ])
     }
 }
@@ -767,10 +1092,19 @@ pub struct TransitionManager<T> {
     queue: VecDeque<TransitionQueueEntry<T>>,
     /// The value we are currently transitioning from
     transition_checkpoint_value: T,
+    /// The value a frame before the checkpoint, if the property was moving when the current
+    /// transition interrupted the previous one. Springs start out with that velocity.
+    launch_value: Option<T>,
     /// The time the current transition started
-    origin_frames_elapsed: u64,
+    origin: TransitionTime,
 }
 
+/// The time between frames that velocities carried over between transitions are measured over
+const VELOCITY_SAMPLE: TransitionTime = TransitionTime {
+    frames: 1,
+    millis: 1000.0 / 60.0,
+};
+
 #[cfg(debug_assertions)]
 impl<T> std::fmt::Debug for TransitionManager<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -782,115 +1116,83 @@ impl<T> std::fmt::Debug for TransitionManager<T> {
 }
 
 impl<T: Interpolatable> TransitionManager<T> {
-    pub fn new(value: T, current_time: u64) -> Self {
+    pub fn new(value: T, current_time: TransitionTime) -> Self {
         Self {
             queue: VecDeque::new(),
             transition_checkpoint_value: value,
-            origin_frames_elapsed: current_time,
+            launch_value: None,
+            origin: current_time,
         }
     }
 
+    /// The value at the end of the last transition that completed
+    pub fn checkpoint_value(&self) -> &T {
+        &self.transition_checkpoint_value
+    }
+
     pub fn push_transition(&mut self, transition: TransitionQueueEntry<T>) {
         self.queue.push_back(transition);
     }
 
-    pub fn reset_transitions(&mut self, current_time: u64) {
+    pub fn reset_transitions(&mut self, current_time: TransitionTime) {
         // update current value as to ease from this position
         let eased_value = self.compute_eased_value(current_time);
+        let previous_time = TransitionTime {
+            frames: current_time.frames.saturating_sub(VELOCITY_SAMPLE.frames),
+            millis: current_time.millis - VELOCITY_SAMPLE.millis,
+        };
+        self.launch_value = eased_value
+            .is_some()
+            .then(|| self.value_at(previous_time))
+            .flatten();
         self.transition_checkpoint_value =
             eased_value.unwrap_or(self.transition_checkpoint_value.clone());
         self.queue.clear();
-        self.origin_frames_elapsed = current_time;
+        self.origin = current_time;
     }
 
-    pub fn compute_eased_value(&mut self, frames_elapsed: u64) -> Option<T> {
-        let global_fe = frames_elapsed;
-        let origin_fe = &mut self.origin_frames_elapsed;
-
+    pub fn compute_eased_value(&mut self, current_time: TransitionTime) -> Option<T> {
         // Fast-forward transitions that have already passed
-        while global_fe - *origin_fe > self.queue.front()?.duration_frames {
+        while self
+            .queue
+            .front()?
+            .duration
+            .progress(self.origin, current_time)
+            > 1.0
+        {
             let curr = self.queue.pop_front()?;
-            *origin_fe += curr.duration_frames;
+            self.origin = curr.duration.end(self.origin, current_time);
             self.transition_checkpoint_value = curr.ending_value;
+            self.launch_value = None;
         }
-        let current_transition = self.queue.front()?;
-        let local_fe = global_fe - *origin_fe;
-        let progress = local_fe as f64 / current_transition.duration_frames as f64;
-        let interpolated_val = current_transition.curve.interpolate(
-            &self.transition_checkpoint_value,
-            &current_transition.ending_value,
-            progress,
-        );
-        Some(interpolated_val)
+        self.value_at(current_time)
     }
-}
-
-pub enum EasingCurve {
-    Linear,
-    InQuad,
-    OutQuad,
-    InBack,
-    OutBack,
-    InOutBack,
-    Custom(Box<dyn Fn(f64) -> f64>),
-}
-
-struct EasingEvaluators {}
-impl EasingEvaluators {
-    fn linear(t: f64) -> f64 {
-        t
-    }
-    #[allow(dead_code)]
-    fn none(t: f64) -> f64 {
-        if t == 1.0 {
-            1.0
-        } else {
-            0.0
-        }
-    }
-    fn in_quad(t: f64) -> f64 {
-        t * t
-    }
-    fn out_quad(t: f64) -> f64 {
-        1.0 - (1.0 - t) * (1.0 - t)
-    }
-    fn in_back(t: f64) -> f64 {
-        const C1: f64 = 1.70158;
-        const C3: f64 = C1 + 1.00;
-        C3 * t * t * t - C1 * t * t
-    }
-    fn out_back(t: f64) -> f64 {
-        const C1: f64 = 1.70158;
-        const C3: f64 = C1 + 1.00;
-        1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
-    }
-
-    fn in_out_back(t: f64) -> f64 {
-        const C1: f64 = 1.70158;
-        const C2: f64 = C1 * 1.525;
-        if t < 0.5 {
-            ((2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2)) / 2.0
-        } else {
-            ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
-        }
-    }
-}
 
-impl EasingCurve {
-    //for a time on the unit interval `t ∈ [0,1]`, given a value `t`,
-    // find the interpolated value `vt` between `v0` and `v1` given the self-contained easing curve
-    pub fn interpolate<T: Interpolatable>(&self, v0: &T, v1: &T, t: f64) -> T /*vt*/ {
-        let multiplier = match self {
-            EasingCurve::Linear => EasingEvaluators::linear(t),
-            EasingCurve::InQuad => EasingEvaluators::in_quad(t),
-            EasingCurve::OutQuad => EasingEvaluators::out_quad(t),
-            EasingCurve::InBack => EasingEvaluators::in_back(t),
-            EasingCurve::OutBack => EasingEvaluators::out_back(t),
-            EasingCurve::InOutBack => EasingEvaluators::in_out_back(t),
-            EasingCurve::Custom(evaluator) => (*evaluator)(t),
+    /// The value of the current transition at `time`
+    fn value_at(&self, time: TransitionTime) -> Option<T> {
+        let current_transition = self.queue.front()?;
+        let progress = current_transition
+            .duration
+            .progress(self.origin, time)
+            .max(0.0);
+        let from = &self.transition_checkpoint_value;
+        let to = &current_transition.ending_value;
+        let eased = current_transition.curve.interpolate(from, to, progress);
+        let (EasingCurve::Spring(spring), Some(launch_value)) =
+            (&current_transition.curve, &self.launch_value)
+        else {
+            return Some(eased);
         };
-
-        v0.interpolate(v1, multiplier)
+        // carry the velocity over, moving the eased value on by launch / sample times the
+        // change from launch_value to from, where sample is the progress made over the
+        // velocity sample
+        let sample = current_transition
+            .duration
+            .progress(TransitionTime::default(), VELOCITY_SAMPLE);
+        if !sample.is_finite() || sample <= 0.0 {
+            return Some(eased);
+        }
+        Some(eased.extrapolate(launch_value, from, spring.launch(progress) / sample))
     }
 }
 
@@ -910,6 +1212,14 @@ where
     fn interpolate(&self, _other: &Self, _t: f64) -> Self {
         self.clone()
     }
+
+    /// `self` moved on by `t` times the change from `from` to `to`, e.g. to carry the
+    /// velocity of an interrupted transition over into the next one. Numbers and the types
+    /// built on them, such as sizes, rotations, colors and transforms, extrapolate; by
+    /// default, `self` is returned as is.
+    fn extrapolate(&self, _from: &Self, _to: &Self, _t: f64) -> Self {
+        self.clone()
+    }
 }
 
 impl<I: Interpolatable> Interpolatable for std::ops::Range<I> {
@@ -923,16 +1233,36 @@ impl<T: ?Sized + Clone> Interpolatable for HashSet<T> {}
 impl<T: ?Sized + Clone> Interpolatable for VecDeque<T> {}
 impl<T: ?Sized> Interpolatable for Rc<T> {}
 impl<T: Interpolatable> Interpolatable for Weak<T> {}
-impl<T1: Interpolatable, T2: Interpolatable> Interpolatable for (T1, T2) {}
+impl<T1: Interpolatable, T2: Interpolatable> Interpolatable for (T1, T2) {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        (
+            self.0.interpolate(&other.0, t),
+            self.1.interpolate(&other.1, t),
+        )
+    }
+}
+
+impl<I: Interpolatable, const N: usize> Interpolatable for [I; N] {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        std::array::from_fn(|i| self[i].interpolate(&other[i], t))
+    }
+
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        std::array::from_fn(|i| self[i].extrapolate(&from[i], &to[i], t))
+    }
+}
 
 impl<I: Interpolatable> Interpolatable for Vec<I> {
+    /// Eases element-wise. Vecs of different lengths have no element-wise correspondence,
+    /// so they hold the start value until `t` reaches 1, then take the end value.
     fn interpolate(&self, other: &Self, t: f64) -> Self {
-        //FUTURE: could revisit the following assertion/constraint, perhaps with a "don't-care" approach to disjoint vec elements
-        assert_eq!(
-            self.len(),
-            other.len(),
-            "cannot interpolate between vecs of different lengths"
-        );
+        if self.len() != other.len() {
+            return if t >= 1.0 {
+                other.clone()
+            } else {
+                self.clone()
+            };
+        }
 
         self.iter()
             .enumerate()
@@ -951,6 +1281,10 @@ impl Interpolatable for f64 {
     fn interpolate(&self, other: &f64, t: f64) -> f64 {
         self + (*other - self) * t
     }
+
+    fn extrapolate(&self, from: &f64, to: &f64, t: f64) -> f64 {
+        self + (to - from) * t
+    }
 }
 
 impl Interpolatable for bool {
@@ -963,82 +1297,124 @@ impl Interpolatable for usize {
     fn interpolate(&self, other: &usize, t: f64) -> usize {
         (*self as f64 + (*other - self) as f64 * t) as usize
     }
+
+    fn extrapolate(&self, from: &usize, to: &usize, t: f64) -> usize {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as usize
+    }
 }
 
 impl Interpolatable for isize {
     fn interpolate(&self, other: &isize, t: f64) -> isize {
         (*self as f64 + (*other - self) as f64 * t) as isize
     }
+
+    fn extrapolate(&self, from: &isize, to: &isize, t: f64) -> isize {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as isize
+    }
 }
 
 impl Interpolatable for i64 {
     fn interpolate(&self, other: &i64, t: f64) -> i64 {
         (*self as f64 + (*other - self) as f64 * t) as i64
     }
+
+    fn extrapolate(&self, from: &i64, to: &i64, t: f64) -> i64 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as i64
+    }
 }
 
 impl Interpolatable for i128 {
     fn interpolate(&self, other: &i128, t: f64) -> i128 {
         (*self as f64 + (*other - self) as f64 * t) as i128
     }
+
+    fn extrapolate(&self, from: &i128, to: &i128, t: f64) -> i128 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as i128
+    }
 }
 
 impl Interpolatable for u128 {
     fn interpolate(&self, other: &u128, t: f64) -> u128 {
         (*self as f64 + (*other - self) as f64 * t) as u128
     }
+
+    fn extrapolate(&self, from: &u128, to: &u128, t: f64) -> u128 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as u128
+    }
 }
 
 impl Interpolatable for u64 {
     fn interpolate(&self, other: &u64, t: f64) -> u64 {
         (*self as f64 + (*other - self) as f64 * t) as u64
     }
+
+    fn extrapolate(&self, from: &u64, to: &u64, t: f64) -> u64 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as u64
+    }
 }
 
 impl Interpolatable for u8 {
     fn interpolate(&self, other: &u8, t: f64) -> u8 {
         (*self as f64 + (*other - *self) as f64 * t) as u8
     }
+
+    fn extrapolate(&self, from: &u8, to: &u8, t: f64) -> u8 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as u8
+    }
 }
 
 impl Interpolatable for u16 {
     fn interpolate(&self, other: &u16, t: f64) -> u16 {
         (*self as f64 + (*other - *self) as f64 * t) as u16
     }
+
+    fn extrapolate(&self, from: &u16, to: &u16, t: f64) -> u16 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as u16
+    }
 }
 
 impl Interpolatable for u32 {
     fn interpolate(&self, other: &u32, t: f64) -> u32 {
         (*self as f64 + (*other - *self) as f64 * t) as u32
     }
+
+    fn extrapolate(&self, from: &u32, to: &u32, t: f64) -> u32 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as u32
+    }
 }
 
 impl Interpolatable for i8 {
     fn interpolate(&self, other: &i8, t: f64) -> i8 {
         (*self as f64 + (*other - *self) as f64 * t) as i8
     }
+
+    fn extrapolate(&self, from: &i8, to: &i8, t: f64) -> i8 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as i8
+    }
 }
 
 impl Interpolatable for i16 {
     fn interpolate(&self, other: &i16, t: f64) -> i16 {
         (*self as f64 + (*other - *self) as f64 * t) as i16
     }
+
+    fn extrapolate(&self, from: &i16, to: &i16, t: f64) -> i16 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as i16
+    }
 }
 
 impl Interpolatable for i32 {
     fn interpolate(&self, other: &i32, t: f64) -> i32 {
         (*self as f64 + (*other - *self) as f64 * t) as i32
     }
+
+    fn extrapolate(&self, from: &i32, to: &i32, t: f64) -> i32 {
+        (*self as f64 + (*to as f64 - *from as f64) * t) as i32
+    }
 }
 
 impl Interpolatable for String {}
 
-pub struct Timeline {
-    pub playhead_position: usize,
-    pub frame_count: usize,
-    pub is_playing: bool,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Layer {
     Native,
@@ -1486,6 +1862,51 @@ impl Color {
 
         [h, s, l, a]
     }
+
+    /// Returns the color in the OKLab space as `[L, a, b, alpha]`
+    pub fn to_oklab(&self) -> [f64; 4] {
+        let [r, g, b, alpha] = self.to_rgba_0_1();
+        let [r, g, b] = [r, g, b].map(srgb_to_linear);
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        [
+            0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+            alpha,
+        ]
+    }
+
+    /// Builds a color from `[L, a, b, alpha]` in the OKLab space, clipping it to the sRGB gamut
+    pub fn from_oklab([lightness, a, b, alpha]: [f64; 4]) -> Self {
+        let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+        let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+        let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+        let rgb = [
+            4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        ]
+        .map(|c| linear_to_srgb(c).clamp(0.0, 1.0));
+        Self::from_rgba_0_1([rgb[0], rgb[1], rgb[2], alpha.clamp(0.0, 1.0)])
+    }
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
 }
 
 //hsl_to_rgb logic borrowed & modified from https://github.com/emgyrz/colorsys.rs, licensed MIT Copyright (c) 2019 mz <emgyrz@gmail.com>
@@ -1574,16 +1995,31 @@ impl PartialEq<ColorMessage> for Color {
     }
 }
 impl Interpolatable for Color {
+    /// Mixes the colors in OKLab, so that midpoints keep the perceived lightness of their ends
+    /// rather than darkening as they would in sRGB. The color channels are premultiplied by
+    /// alpha, so fading in from `TRANSPARENT` doesn't pass through its underlying white.
     fn interpolate(&self, other: &Self, t: f64) -> Self {
-        let rgba_s = self.to_rgba_0_1();
-        let rgba_o = other.to_rgba_0_1();
-        let rgba_i = [
-            rgba_s[0].interpolate(&rgba_o[0], t),
-            rgba_s[1].interpolate(&rgba_o[1], t),
-            rgba_s[2].interpolate(&rgba_o[2], t),
-            rgba_s[3].interpolate(&rgba_o[3], t),
-        ];
-        Color::from_rgba_0_1(rgba_i)
+        let lab_s = self.to_oklab();
+        let lab_o = other.to_oklab();
+        let alpha = lab_s[3].interpolate(&lab_o[3], t).clamp(0.0, 1.0);
+        let mut lab_i = [0.0, 0.0, 0.0, alpha];
+        for i in 0..3 {
+            lab_i[i] = if alpha > 0.0 {
+                (lab_s[i] * lab_s[3]).interpolate(&(lab_o[i] * lab_o[3]), t) / alpha
+            } else {
+                lab_s[i].interpolate(&lab_o[i], t)
+            };
+        }
+        Color::from_oklab(lab_i)
+    }
+
+    /// Moves on in OKLab, the space colors are mixed in
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        let [lab_s, lab_f, lab_t] = [self, from, to].map(Color::to_oklab);
+        let mut lab_e: [f64; 4] =
+            std::array::from_fn(|i| lab_s[i].extrapolate(&lab_f[i], &lab_t[i], t));
+        lab_e[3] = lab_e[3].clamp(0.0, 1.0);
+        Color::from_oklab(lab_e)
     }
 }
 
@@ -1612,10 +2048,19 @@ impl Default for Rotation {
 
 impl Interpolatable for Rotation {
     fn interpolate(&self, other: &Self, t: f64) -> Self {
-        Self::Percent(Numeric::F64(
-            other.to_float_0_1() - self.to_float_0_1() * t / 100.0,
+        Self::Degrees(Numeric::F64(
+            self.get_as_degrees()
+                .interpolate(&other.get_as_degrees(), t),
         ))
     }
+
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        Self::Degrees(Numeric::F64(self.get_as_degrees().extrapolate(
+            &from.get_as_degrees(),
+            &to.get_as_degrees(),
+            t,
+        )))
+    }
 }
 
 impl Rotation {
@@ -1691,14 +2136,58 @@ pub enum PathElement {
     Close,
 }
 
-impl Interpolatable for PathElement {}
+impl Interpolatable for PathElement {
+    /// Eases the points of elements of the same kind. Elements of different kinds hold the
+    /// start value until `t` reaches 1, then take the end value.
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Self::Point(x0, y0), Self::Point(x1, y1)) => {
+                Self::Point(x0.interpolate(x1, t), y0.interpolate(y1, t))
+            }
+            (Self::Quadratic(x0, y0), Self::Quadratic(x1, y1)) => {
+                Self::Quadratic(x0.interpolate(x1, t), y0.interpolate(y1, t))
+            }
+            (Self::Cubic(x0, y0, x1, y1), Self::Cubic(x2, y2, x3, y3)) => Self::Cubic(
+                x0.interpolate(x2, t),
+                y0.interpolate(y2, t),
+                x1.interpolate(x3, t),
+                y1.interpolate(y3, t),
+            ),
+            _ if t >= 1.0 => other.clone(),
+            _ => self.clone(),
+        }
+    }
+}
 impl HelperFunctions for PathElement {}
 
+/// The outline of a shape. Native elements only use its `color` and `width`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(crate = "crate::serde")]
 pub struct Stroke {
     pub color: Property<Color>,
     pub width: Property<Size>,
+    /// Paints the stroke instead of `color` when set, for example with a gradient
+    pub fill: Property<Option<Fill>>,
+    /// Lengths in pixels of alternating dashes and gaps, starting with a dash. Empty for
+    /// a solid stroke. Odd numbers of lengths are repeated to make an even number.
+    pub dash: Property<Vec<f64>>,
+    /// How far into the dash pattern the stroke starts, in pixels
+    pub dash_offset: Property<f64>,
+    pub cap: Property<StrokeCap>,
+    pub join: Property<StrokeJoin>,
+    /// How long miter joins can get relative to the width of the stroke before they're
+    /// beveled instead
+    pub miter_limit: Property<f64>,
+    pub alignment: Property<StrokeAlignment>,
+}
+
+impl Stroke {
+    /// What the stroke is painted with: its `fill` if set, otherwise its `color`
+    pub fn paint(&self) -> Fill {
+        self.fill
+            .get()
+            .unwrap_or_else(|| Fill::Solid(self.color.get()))
+    }
 }
 
 impl Default for Stroke {
@@ -1706,23 +2195,95 @@ impl Default for Stroke {
         Self {
             color: Default::default(),
             width: Property::new(Size::Pixels(Numeric::F64(0.0))),
+            fill: Default::default(),
+            dash: Default::default(),
+            dash_offset: Default::default(),
+            cap: Default::default(),
+            join: Default::default(),
+            miter_limit: Property::new(4.0),
+            alignment: Default::default(),
         }
     }
 }
 
 impl PartialEq for Stroke {
     fn eq(&self, other: &Self) -> bool {
-        self.color.get() == other.color.get() && self.width.get() == other.width.get()
+        self.color.get() == other.color.get()
+            && self.width.get() == other.width.get()
+            && self.fill.get() == other.fill.get()
+            && self.dash.get() == other.dash.get()
+            && self.dash_offset.get() == other.dash_offset.get()
+            && self.cap.get() == other.cap.get()
+            && self.join.get() == other.join.get()
+            && self.miter_limit.get() == other.miter_limit.get()
+            && self.alignment.get() == other.alignment.get()
     }
 }
 
 impl Interpolatable for Stroke {
-    fn interpolate(&self, _other: &Self, _t: f64) -> Self {
-        // TODO interpolation
-        self.clone()
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            color: Property::new(self.color.get().interpolate(&other.color.get(), t)),
+            width: Property::new(self.width.get().interpolate(&other.width.get(), t)),
+            fill: Property::new(self.fill.get().interpolate(&other.fill.get(), t)),
+            dash: Property::new(self.dash.get().interpolate(&other.dash.get(), t)),
+            dash_offset: Property::new(
+                self.dash_offset
+                    .get()
+                    .interpolate(&other.dash_offset.get(), t),
+            ),
+            cap: Property::new(self.cap.get()),
+            join: Property::new(self.join.get()),
+            miter_limit: Property::new(
+                self.miter_limit
+                    .get()
+                    .interpolate(&other.miter_limit.get(), t),
+            ),
+            alignment: Property::new(self.alignment.get()),
+        }
     }
 }
 
+/// How the ends of open paths and of dashes are drawn
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(crate = "crate::serde")]
+pub enum StrokeCap {
+    /// Ends exactly at the end of the path
+    #[default]
+    Butt,
+    Round,
+    /// Extends past the end of the path by half the width of the stroke
+    Square,
+}
+
+/// How the corners of a path are drawn
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(crate = "crate::serde")]
+pub enum StrokeJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// Where a stroke is drawn relative to the outline of a closed shape. Open paths are
+/// always stroked centered.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(crate = "crate::serde")]
+pub enum StrokeAlignment {
+    Inside,
+    #[default]
+    Center,
+    Outside,
+}
+
+impl Interpolatable for StrokeCap {}
+impl HelperFunctions for StrokeCap {}
+impl Interpolatable for StrokeJoin {}
+impl HelperFunctions for StrokeJoin {}
+impl Interpolatable for StrokeAlignment {}
+impl HelperFunctions for StrokeAlignment {}
+
 pub enum NavigationTarget {
     Current,
     New,
@@ -1764,13 +2325,181 @@ impl Hash for Stroke {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.width.get().hash(state);
         self.color.get().hash(state);
+        self.fill.get().hash(state);
+        for length in self.dash.get() {
+            length.to_bits().hash(state);
+        }
+        self.dash_offset.get().to_bits().hash(state);
+        self.cap.get().hash(state);
+        self.join.get().hash(state);
+        self.miter_limit.get().to_bits().hash(state);
+        self.alignment.get().hash(state);
+    }
+}
+
+/// A shadow cast by, or into, a shape. Offsets, blur and spread are in pixels and follow
+/// CSS `box-shadow`: a blur of `b` softens the edge over roughly `b` pixels each way.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "crate::serde")]
+pub struct Shadow {
+    pub offset_x: f64,
+    pub offset_y: f64,
+    pub blur: f64,
+    /// Grows (or with a negative value, shrinks) the shadow before blurring
+    pub spread: f64,
+    pub color: Color,
+}
+
+impl Interpolatable for Shadow {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            offset_x: self.offset_x.interpolate(&other.offset_x, t),
+            offset_y: self.offset_y.interpolate(&other.offset_y, t),
+            blur: self.blur.interpolate(&other.blur, t),
+            spread: self.spread.interpolate(&other.spread, t),
+            color: self.color.interpolate(&other.color, t),
+        }
     }
 }
 
+/// A visual effect applied to a drawing primitive. From PAXEL, e.g.
+/// `Effect::DropShadow(0, 4, 12, 0, rgba(0, 0, 0, 64))` or `Effect::BackdropBlur(20)`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "crate::serde")]
+pub enum Effect {
+    /// A shadow behind the shape, visible outside of it
+    DropShadow(Shadow),
+    /// A shadow cast onto the inside of the shape, drawn over its fill
+    InnerShadow(Shadow),
+    /// Blurs the shape itself, with a radius in pixels like CSS `filter: blur()`
+    LayerBlur(f64),
+    /// Blurs whatever was drawn behind the shape, within its outline, with a radius in
+    /// pixels like CSS `backdrop-filter: blur()`
+    BackdropBlur(f64),
+}
+
+impl Interpolatable for Effect {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Self::DropShadow(s), Self::DropShadow(o)) => Self::DropShadow(s.interpolate(o, t)),
+            (Self::InnerShadow(s), Self::InnerShadow(o)) => Self::InnerShadow(s.interpolate(o, t)),
+            (Self::LayerBlur(s), Self::LayerBlur(o)) => Self::LayerBlur(s.interpolate(o, t)),
+            (Self::BackdropBlur(s), Self::BackdropBlur(o)) => {
+                Self::BackdropBlur(s.interpolate(o, t))
+            }
+            // different kinds of effects don't ease into each other
+            _ => self.clone(),
+        }
+    }
+}
+impl HelperFunctions for Effect {}
+
+/// How colors being drawn combine with the colors already underneath them, following
+/// the separable and non-separable blend modes of CSS `mix-blend-mode`. From PAXEL,
+/// e.g. `BlendMode::Multiply`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(crate = "crate::serde")]
+pub enum BlendMode {
+    #[default]
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 16] = [
+        BlendMode::Normal,
+        BlendMode::Multiply,
+        BlendMode::Screen,
+        BlendMode::Overlay,
+        BlendMode::Darken,
+        BlendMode::Lighten,
+        BlendMode::ColorDodge,
+        BlendMode::ColorBurn,
+        BlendMode::HardLight,
+        BlendMode::SoftLight,
+        BlendMode::Difference,
+        BlendMode::Exclusion,
+        BlendMode::Hue,
+        BlendMode::Saturation,
+        BlendMode::Color,
+        BlendMode::Luminosity,
+    ];
+
+    /// The name of this mode in CSS, such as `color-dodge`
+    pub fn css_name(&self) -> &'static str {
+        match self {
+            BlendMode::Normal => "normal",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+            BlendMode::Overlay => "overlay",
+            BlendMode::Darken => "darken",
+            BlendMode::Lighten => "lighten",
+            BlendMode::ColorDodge => "color-dodge",
+            BlendMode::ColorBurn => "color-burn",
+            BlendMode::HardLight => "hard-light",
+            BlendMode::SoftLight => "soft-light",
+            BlendMode::Difference => "difference",
+            BlendMode::Exclusion => "exclusion",
+            BlendMode::Hue => "hue",
+            BlendMode::Saturation => "saturation",
+            BlendMode::Color => "color",
+            BlendMode::Luminosity => "luminosity",
+        }
+    }
+}
+
+// blend modes don't ease, they switch at the start of a transition
+impl Interpolatable for BlendMode {}
+impl HelperFunctions for BlendMode {}
+
 impl Interpolatable for Fill {
-    fn interpolate(&self, _other: &Self, _t: f64) -> Self {
-        // TODO interpolation
-        self.clone()
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        match (self, other) {
+            (Fill::Solid(s), Fill::Solid(o)) => Fill::Solid(s.interpolate(o, t)),
+            (Fill::LinearGradient(s), Fill::LinearGradient(o)) => {
+                Fill::LinearGradient(s.interpolate(o, t))
+            }
+            (Fill::RadialGradient(s), Fill::RadialGradient(o)) => {
+                Fill::RadialGradient(s.interpolate(o, t))
+            }
+            (_, Fill::Solid(_)) => self.interpolate(&other.promote_to(self), t),
+            _ => self.promote_to(other).interpolate(other, t),
+        }
+    }
+}
+
+impl Interpolatable for LinearGradient {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            start: self.start.interpolate(&other.start, t),
+            end: self.end.interpolate(&other.end, t),
+            stops: GradientStop::interpolate_stops(&self.stops, &other.stops, t),
+        }
+    }
+}
+
+impl Interpolatable for RadialGradient {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        Self {
+            end: self.end.interpolate(&other.end, t),
+            start: self.start.interpolate(&other.start, t),
+            radius: self.radius.interpolate(&other.radius, t),
+            stops: GradientStop::interpolate_stops(&self.stops, &other.stops, t),
+        }
     }
 }
 
@@ -1802,6 +2531,51 @@ impl GradientStop {
     pub fn get(color: Color, position: Size) -> GradientStop {
         GradientStop { position, color }
     }
+
+    /// Blends two lists of stops. Lists of the same length are blended stop by stop, moving
+    /// each stop towards its counterpart; otherwise both gradients are sampled at every
+    /// position either of them has a stop at.
+    fn interpolate_stops(from: &[GradientStop], to: &[GradientStop], t: f64) -> Vec<GradientStop> {
+        if from.len() == to.len() {
+            return from
+                .iter()
+                .zip(to)
+                .map(|(s, o)| GradientStop {
+                    position: s.position.interpolate(&o.position, t),
+                    color: s.color.interpolate(&o.color, t),
+                })
+                .collect();
+        }
+        let mut positions: Vec<f64> = from.iter().chain(to).map(|s| s.percent()).collect();
+        positions.sort_by(f64::total_cmp);
+        positions.dedup();
+        positions
+            .into_iter()
+            .map(|position| GradientStop {
+                position: Size::Percent(position.into()),
+                color: Self::sample(from, position).interpolate(&Self::sample(to, position), t),
+            })
+            .collect()
+    }
+
+    fn percent(&self) -> f64 {
+        self.position.get_pixels(100.0)
+    }
+
+    /// The color of the gradient at `percent`, mixed between its surrounding stops in OKLab
+    /// like colors are eased, so that easing a fill doesn't shift the colors of its stops
+    fn sample(stops: &[GradientStop], percent: f64) -> Color {
+        let Some(after) = stops.iter().position(|s| s.percent() >= percent) else {
+            return stops.last().map(|s| s.color.clone()).unwrap_or_default();
+        };
+        if after == 0 {
+            return stops[0].color.clone();
+        }
+        let (before, after) = (&stops[after - 1], &stops[after]);
+        let span = after.percent() - before.percent();
+        let t = (percent - before.percent()) / span;
+        before.color.interpolate(&after.color, t)
+    }
 }
 
 impl Default for Fill {
@@ -1811,6 +2585,33 @@ impl Default for Fill {
 }
 
 impl Fill {
+    /// Restates this fill as the same kind of fill as `like`, for easing between them. A solid
+    /// color becomes a gradient of that color, and a gradient takes on the geometry of `like`.
+    fn promote_to(&self, like: &Fill) -> Fill {
+        let stops = |positions: &[GradientStop]| match self {
+            Fill::Solid(color) => positions
+                .iter()
+                .map(|stop| GradientStop::get(color.clone(), stop.position))
+                .collect(),
+            Fill::LinearGradient(gradient) => gradient.stops.clone(),
+            Fill::RadialGradient(gradient) => gradient.stops.clone(),
+        };
+        match like {
+            Fill::Solid(_) => self.clone(),
+            Fill::LinearGradient(gradient) => Fill::LinearGradient(LinearGradient {
+                start: gradient.start,
+                end: gradient.end,
+                stops: stops(&gradient.stops),
+            }),
+            Fill::RadialGradient(gradient) => Fill::RadialGradient(RadialGradient {
+                end: gradient.end,
+                start: gradient.start,
+                radius: gradient.radius,
+                stops: stops(&gradient.stops),
+            }),
+        }
+    }
+
     pub fn to_unit_point((x, y): (Size, Size), (width, height): (f64, f64)) -> UnitPoint {
         let normalized_x = match x {
             Size::Pixels(val) => val.to_float() / width,
@@ -1915,6 +2716,28 @@ impl Interpolatable for Size {
             },
         }
     }
+
+    /// Moves the pixel and percent parts on separately, combining them if `self` gains a
+    /// part it didn't have
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        fn parts(size: &Size) -> (f64, f64) {
+            match size {
+                Size::Pixels(pix) => (pix.to_float(), 0.0),
+                Size::Percent(per) => (0.0, per.to_float()),
+                Size::Combined(pix, per) => (pix.to_float(), per.to_float()),
+            }
+        }
+        let (pix, per) = parts(self);
+        let (from_pix, from_per) = parts(from);
+        let (to_pix, to_per) = parts(to);
+        let pix = pix.extrapolate(&from_pix, &to_pix, t);
+        let per = per.extrapolate(&from_per, &to_per, t);
+        match self {
+            Self::Pixels(_) if per == 0.0 => Self::Pixels(Numeric::F64(pix)),
+            Self::Percent(_) if pix == 0.0 => Self::Percent(Numeric::F64(per)),
+            _ => Self::Combined(Numeric::F64(pix), Numeric::F64(per)),
+        }
+    }
 }
 
 impl Default for Size {
@@ -1975,7 +2798,93 @@ pub struct Transform2D {
     pub skew: Option<[Rotation; 2]>,
 }
 
-impl Interpolatable for Transform2D {}
+impl Interpolatable for Transform2D {
+    /// Eases each component of the transform, treating a missing component as the identity.
+    /// `anchor` defaults to a layout-computed position, so it only eases between set values.
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        fn with_identity<T: Interpolatable>(
+            s: &Option<T>,
+            o: &Option<T>,
+            identity: T,
+            t: f64,
+        ) -> Option<T> {
+            match (s, o) {
+                (None, None) => None,
+                _ => Some(
+                    s.as_ref()
+                        .unwrap_or(&identity)
+                        .interpolate(o.as_ref().unwrap_or(&identity), t),
+                ),
+            }
+        }
+        let zero = Size::Pixels(Numeric::F64(0.0));
+        let one = Size::Percent(Numeric::F64(100.0));
+        Transform2D {
+            previous: with_identity(
+                &self.previous.as_deref().cloned(),
+                &other.previous.as_deref().cloned(),
+                Transform2D::default(),
+                t,
+            )
+            .map(Box::new),
+            rotate: with_identity(&self.rotate, &other.rotate, Rotation::ZERO(), t),
+            translate: with_identity(&self.translate, &other.translate, [zero, zero], t),
+            anchor: match (&self.anchor, &other.anchor) {
+                (Some(s), Some(o)) => Some(s.interpolate(o, t)),
+                _ => self.anchor,
+            },
+            scale: with_identity(&self.scale, &other.scale, [one, one], t),
+            skew: with_identity(&self.skew, &other.skew, [Rotation::ZERO(); 2], t),
+        }
+    }
+
+    /// Moves each component on, treating a missing component as the identity like
+    /// `interpolate` does
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        fn with_identity<T: Interpolatable>(
+            s: &Option<T>,
+            f: &Option<T>,
+            o: &Option<T>,
+            identity: T,
+            t: f64,
+        ) -> Option<T> {
+            match (s, f, o) {
+                (None, None, None) => None,
+                _ => Some(s.as_ref().unwrap_or(&identity).extrapolate(
+                    f.as_ref().unwrap_or(&identity),
+                    o.as_ref().unwrap_or(&identity),
+                    t,
+                )),
+            }
+        }
+        let zero = Size::Pixels(Numeric::F64(0.0));
+        let one = Size::Percent(Numeric::F64(100.0));
+        Transform2D {
+            previous: with_identity(
+                &self.previous.as_deref().cloned(),
+                &from.previous.as_deref().cloned(),
+                &to.previous.as_deref().cloned(),
+                Transform2D::default(),
+                t,
+            )
+            .map(Box::new),
+            rotate: with_identity(&self.rotate, &from.rotate, &to.rotate, Rotation::ZERO(), t),
+            translate: with_identity(
+                &self.translate,
+                &from.translate,
+                &to.translate,
+                [zero, zero],
+                t,
+            ),
+            anchor: match (&self.anchor, &from.anchor, &to.anchor) {
+                (Some(s), Some(f), Some(o)) => Some(s.extrapolate(f, o, t)),
+                _ => self.anchor,
+            },
+            scale: with_identity(&self.scale, &from.scale, &to.scale, [one, one], t),
+            skew: with_identity(&self.skew, &from.skew, &to.skew, [Rotation::ZERO(); 2], t),
+        }
+    }
+}
 
 impl Mul for Transform2D {
     type Output = Transform2D;
@@ -2046,4 +2955,290 @@ impl Variable {
     pub fn read_pax_value_ref<V>(&self, f: impl FnOnce(&PaxValue) -> V) -> V {
         self.converted_to_pax_value.read(f)
     }
+
+    /// The value coerced to `T`, for code that knows the type of this variable statically
+    pub fn get_as<T: CoercionRules>(&self) -> Result<T, String> {
+        self.converted_to_pax_value
+            .read(|value| T::try_coerce(value.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_rgba(color: &Color, expected: [f64; 4]) {
+        let rgba = color.to_rgba_0_1();
+        for i in 0..4 {
+            assert!(
+                (rgba[i] - expected[i]).abs() < 1e-3,
+                "{:?} != {:?}",
+                rgba,
+                expected
+            );
+        }
+    }
+
+    fn percent(stop: &GradientStop) -> f64 {
+        stop.position.get_pixels(100.0)
+    }
+
+    #[test]
+    fn test_color_interpolation() {
+        assert_rgba(
+            &Color::RED.interpolate(&Color::BLUE, 0.0),
+            Color::RED.to_rgba_0_1(),
+        );
+        assert_rgba(
+            &Color::RED.interpolate(&Color::BLUE, 1.0),
+            Color::BLUE.to_rgba_0_1(),
+        );
+
+        // black to white passes through a perceptual mid gray rather than sRGB's 50%
+        let gray = Color::BLACK.interpolate(&Color::WHITE, 0.5).to_oklab();
+        assert!((gray[0] - 0.5).abs() < 1e-3);
+
+        // fading in from transparent keeps the hue of the target
+        let faded = Color::TRANSPARENT.interpolate(&Color::RED, 0.5);
+        let red = Color::RED.to_rgba_0_1();
+        assert_rgba(&faded, [red[0], red[1], red[2], 0.5]);
+    }
+
+    #[test]
+    fn test_fill_interpolation() {
+        let gradient = Fill::linearGradient(
+            (Size::Percent(0.into()), Size::Percent(0.into())),
+            (Size::Percent(100.into()), Size::Percent(0.into())),
+            vec![
+                GradientStop::get(Color::WHITE, Size::Percent(0.into())),
+                GradientStop::get(Color::BLACK, Size::Percent(100.into())),
+            ],
+        );
+
+        // a solid color is promoted to a gradient of its color
+        let Fill::LinearGradient(promoted) = Fill::Solid(Color::RED).interpolate(&gradient, 0.0)
+        else {
+            panic!("expected a linear gradient");
+        };
+        assert_eq!(promoted.stops.len(), 2);
+        assert_rgba(&promoted.stops[1].color, Color::RED.to_rgba_0_1());
+        let Fill::LinearGradient(reached) = gradient.interpolate(&Fill::Solid(Color::RED), 1.0)
+        else {
+            panic!("expected a linear gradient");
+        };
+        assert_rgba(&reached.stops[0].color, Color::RED.to_rgba_0_1());
+
+        // gradients with different stops are resampled at all of their positions
+        let three_stops = Fill::linearGradient(
+            (Size::Percent(0.into()), Size::Percent(0.into())),
+            (Size::Percent(0.into()), Size::Percent(100.into())),
+            vec![
+                GradientStop::get(Color::BLACK, Size::Percent(0.into())),
+                GradientStop::get(Color::WHITE, Size::Percent(25.into())),
+                GradientStop::get(Color::WHITE, Size::Percent(100.into())),
+            ],
+        );
+        let Fill::LinearGradient(mixed) = gradient.interpolate(&three_stops, 0.0) else {
+            panic!("expected a linear gradient");
+        };
+        assert_eq!(
+            mixed.stops.iter().map(percent).collect::<Vec<_>>(),
+            [0.0, 25.0, 100.0]
+        );
+        // sampled in OKLab, as colors are eased
+        assert!((mixed.stops[1].color.to_oklab()[0] - 0.75).abs() < 1e-3);
+        let Fill::LinearGradient(halfway) = gradient.interpolate(&three_stops, 0.5) else {
+            panic!("expected a linear gradient");
+        };
+        assert_eq!(halfway.end.0.get_pixels(100.0), 50.0);
+        assert_eq!(halfway.end.1.get_pixels(100.0), 50.0);
+    }
+
+    #[test]
+    fn test_stroke_and_path_interpolation() {
+        let thin = Stroke {
+            color: Property::new(Color::BLACK),
+            width: Property::new(Size::Pixels(1.into())),
+            ..Default::default()
+        };
+        let thick = Stroke {
+            color: Property::new(Color::WHITE),
+            width: Property::new(Size::Pixels(5.into())),
+            ..Default::default()
+        };
+        assert_eq!(
+            thin.interpolate(&thick, 0.5).width.get(),
+            Size::Pixels(3.into())
+        );
+
+        let path = |x: f64| {
+            vec![
+                PathElement::Point(Size::Pixels(0.into()), Size::Pixels(0.into())),
+                PathElement::Line,
+                PathElement::Point(Size::Pixels(x.into()), Size::Pixels(10.into())),
+            ]
+        };
+        assert_eq!(path(0.0).interpolate(&path(10.0), 0.5), path(5.0));
+        // a different structure holds the start until the end, then snaps
+        let mut closed = path(10.0);
+        closed.push(PathElement::Close);
+        assert_eq!(path(0.0).interpolate(&closed, 0.5), path(0.0));
+        assert_eq!(path(0.0).interpolate(&closed, 1.0), closed);
+        let point = PathElement::Point(Size::Pixels(0.into()), Size::Pixels(0.into()));
+        assert_eq!(point.interpolate(&PathElement::Close, 0.99), point);
+        assert_eq!(
+            point.interpolate(&PathElement::Close, 1.0),
+            PathElement::Close
+        );
+    }
+
+    #[test]
+    fn test_transform_interpolation() {
+        let from = Transform2D::translate(Size::Pixels(10.into()), Size::Pixels(0.into()));
+        let to = Transform2D::rotate(Rotation::Degrees(90.into()))
+            * Transform2D::translate(Size::Pixels(20.into()), Size::Pixels(10.into()));
+        let halfway = from.interpolate(&to, 0.5);
+        assert_eq!(
+            halfway.translate,
+            Some([Size::Pixels(15.into()), Size::Pixels(5.into())])
+        );
+        assert_eq!(halfway.rotate, None);
+        // the missing rotation is eased from zero
+        let previous = halfway.previous.unwrap();
+        assert_eq!(previous.rotate.unwrap().get_as_degrees(), 45.0);
+    }
+
+    #[test]
+    fn test_extrapolation() {
+        let size = Size::Pixels(20.into()).extrapolate(
+            &Size::Pixels(0.into()),
+            &Size::Percent(10.into()),
+            0.5,
+        );
+        assert_eq!(size, Size::Combined(Numeric::F64(20.0), Numeric::F64(5.0)));
+
+        let rotation = Rotation::Degrees(90.into()).extrapolate(
+            &Rotation::Degrees(0.into()),
+            &Rotation::Radians(std::f64::consts::PI.into()),
+            0.5,
+        );
+        assert!((rotation.get_as_degrees() - 180.0).abs() < 1e-9);
+
+        // colors move on in OKLab, with alpha kept in range
+        let gray = Color::BLACK.interpolate(&Color::WHITE, 0.5);
+        let lighter = gray.extrapolate(&Color::BLACK, &gray, 0.5).to_oklab();
+        assert!((lighter[0] - 0.75).abs() < 1e-3);
+        let faded = Color::RED.extrapolate(&Color::TRANSPARENT, &Color::RED, 1.0);
+        assert_eq!(faded.to_rgba_0_1()[3], 1.0);
+
+        let transform = Transform2D::translate(Size::Pixels(10.into()), Size::Pixels(0.into()))
+            .extrapolate(
+                &Transform2D::default(),
+                &Transform2D::rotate(Rotation::Degrees(10.into())),
+                2.0,
+            );
+        assert_eq!(
+            transform.translate,
+            Some([Size::Pixels(10.into()), Size::Pixels(0.into())])
+        );
+        assert_eq!(transform.rotate.unwrap().get_as_degrees(), 20.0);
+    }
+
+    #[test]
+    fn test_effect_coercion_and_interpolation() {
+        let effect = |args: Vec<PaxValue>| {
+            Effect::try_coerce(PaxValue::Enum(Box::new((
+                "Effect".to_string(),
+                "DropShadow".to_string(),
+                args,
+            ))))
+        };
+        let shadow = effect(vec![
+            0.to_pax_value(),
+            PaxValue::Size(Size::Pixels(4.into())),
+            12.to_pax_value(),
+            0.to_pax_value(),
+            Color::BLACK.to_pax_value(),
+        ])
+        .unwrap();
+        let Effect::DropShadow(ref inner) = shadow else {
+            panic!("expected a drop shadow, got {:?}", shadow);
+        };
+        assert_eq!((inner.offset_y, inner.blur), (4.0, 12.0));
+        // the object form written out by to_pax_value coerces back
+        assert_eq!(
+            Effect::try_coerce(shadow.clone().to_pax_value()).unwrap(),
+            shadow
+        );
+        assert!(effect(vec![1.to_pax_value()]).is_err());
+
+        let blurred = Effect::LayerBlur(10.0).interpolate(&Effect::LayerBlur(20.0), 0.5);
+        assert_eq!(blurred, Effect::LayerBlur(15.0));
+        // different kinds of effects snap
+        assert_eq!(
+            Effect::LayerBlur(10.0).interpolate(&shadow, 0.5),
+            Effect::LayerBlur(10.0)
+        );
+    }
+
+    #[test]
+    fn test_stroke_style_coercion() {
+        let unit = |name: &str, variant: &str| {
+            PaxValue::Enum(Box::new((name.to_string(), variant.to_string(), vec![])))
+        };
+        let stroke = Stroke::try_coerce(PaxValue::Object(
+            vec![
+                ("color".to_string(), Color::RED.to_pax_value()),
+                ("width".to_string(), PaxValue::Size(Size::Pixels(2.into()))),
+                (
+                    "dash".to_string(),
+                    PaxValue::Vec(vec![
+                        PaxValue::Size(Size::Pixels(4.into())),
+                        2.to_pax_value(),
+                    ]),
+                ),
+                ("cap".to_string(), unit("StrokeCap", "Round")),
+                ("alignment".to_string(), unit("StrokeAlignment", "Inside")),
+            ]
+            .into_iter()
+            .collect(),
+        ))
+        .unwrap();
+        assert_eq!(stroke.dash.get(), vec![4.0, 2.0]);
+        assert_eq!(stroke.cap.get(), StrokeCap::Round);
+        assert_eq!(stroke.alignment.get(), StrokeAlignment::Inside);
+        // what isn't given keeps its default
+        assert_eq!(stroke.join.get(), StrokeJoin::Miter);
+        assert_eq!(stroke.miter_limit.get(), 4.0);
+        assert_eq!(stroke.paint(), Fill::Solid(Color::RED));
+        // the object written out by to_pax_value coerces back
+        assert_eq!(
+            Stroke::try_coerce(stroke.clone().to_pax_value()).unwrap(),
+            stroke
+        );
+    }
+
+    #[test]
+    fn test_blend_mode_coercion() {
+        for mode in BlendMode::ALL {
+            assert_eq!(BlendMode::try_coerce(mode.to_pax_value()).unwrap(), mode);
+        }
+        let unknown = PaxValue::Enum(Box::new((
+            "BlendMode".to_string(),
+            "Dissolve".to_string(),
+            vec![],
+        )));
+        assert!(BlendMode::try_coerce(unknown).is_err());
+    }
+
+    #[test]
+    fn test_easing_curve_coercion() {
+        let spring = EasingCurve::Spring(Spring::new(300.0, 10.0, 1.0));
+        let coerced = EasingCurve::try_coerce(spring.clone().to_pax_value()).unwrap();
+        assert_eq!(coerced.evaluate(0.3), spring.evaluate(0.3));
+
+        // custom curves only convert one way
+        let custom = EasingCurve::custom(|t| t * t);
+        assert!(EasingCurve::try_coerce(custom.to_pax_value()).is_err());
+    }
 }
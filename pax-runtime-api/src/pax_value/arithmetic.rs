@@ -3,7 +3,7 @@ use std::{
     ops::{Add, Div, Mul, Neg, Not, Rem, Sub},
 };
 
-use crate::{Numeric, PaxValue, Percent, Size, ToPaxValue};
+use crate::{Numeric, PaxValue, Percent, Size, ToPaxValue, TransitionDuration};
 
 use super::{PaxAny, ToFromPaxAny};
 
@@ -38,6 +38,16 @@ impl Add for PaxValue {
             },
             (PaxValue::Numeric(a), PaxValue::Percent(b)) => Size::Combined(a, b.0).to_pax_value(),
             (PaxValue::Percent(a), PaxValue::Numeric(b)) => Size::Combined(b, a.0).to_pax_value(),
+
+            // Durations of the same unit
+            (
+                PaxValue::Duration(TransitionDuration::Millis(a)),
+                PaxValue::Duration(TransitionDuration::Millis(b)),
+            ) => PaxValue::Duration(TransitionDuration::Millis(a + b)),
+            (
+                PaxValue::Duration(TransitionDuration::Frames(a)),
+                PaxValue::Duration(TransitionDuration::Frames(b)),
+            ) => PaxValue::Duration(TransitionDuration::Frames(a + b)),
             (a, b) => {
                 log::warn!("can't add {:?} and {:?}", a, b);
                 PaxValue::default()
@@ -70,6 +80,10 @@ impl Mul for PaxValue {
                 Size::Percent(per) => Size::Percent(per * b).to_pax_value(),
                 Size::Combined(px, per) => Size::Combined(px * b, per * b).to_pax_value(),
             },
+            (PaxValue::Duration(a), PaxValue::Numeric(b))
+            | (PaxValue::Numeric(b), PaxValue::Duration(a)) => {
+                PaxValue::Duration(scale_duration(a, b.to_float()))
+            }
             (a, b) => {
                 log::warn!("can't multiply {:?} and {:?}", a, b);
                 PaxValue::default()
@@ -95,6 +109,14 @@ impl Sub for PaxValue {
                 Size::Percent(per) => Size::Percent(per - b).to_pax_value(),
                 Size::Combined(px, per) => Size::Combined(px - b, per - b).to_pax_value(),
             },
+            (
+                PaxValue::Duration(TransitionDuration::Millis(a)),
+                PaxValue::Duration(TransitionDuration::Millis(b)),
+            ) => PaxValue::Duration(TransitionDuration::Millis(a - b)),
+            (
+                PaxValue::Duration(TransitionDuration::Frames(a)),
+                PaxValue::Duration(TransitionDuration::Frames(b)),
+            ) => PaxValue::Duration(TransitionDuration::Frames(a.saturating_sub(b))),
             (a, b) => {
                 log::warn!("can't subtract {:?} and {:?}", a, b);
                 PaxValue::default()
@@ -115,6 +137,9 @@ impl Div for PaxValue {
                 Size::Percent(per) => Size::Percent(per / b).to_pax_value(),
                 Size::Combined(px, per) => Size::Combined(px / b, per / b).to_pax_value(),
             },
+            (PaxValue::Duration(a), PaxValue::Numeric(b)) => {
+                PaxValue::Duration(scale_duration(a, 1.0 / b.to_float()))
+            }
             (a, b) => {
                 log::warn!("can't divide {:?} and {:?}", a, b);
                 PaxValue::default()
@@ -123,6 +148,16 @@ impl Div for PaxValue {
     }
 }
 
+// Frame counts are rounded to the nearest whole frame
+fn scale_duration(duration: TransitionDuration, factor: f64) -> TransitionDuration {
+    match duration {
+        TransitionDuration::Millis(millis) => TransitionDuration::Millis(millis * factor),
+        TransitionDuration::Frames(frames) => {
+            TransitionDuration::Frames((frames as f64 * factor).round().max(0.0) as u64)
+        }
+    }
+}
+
 impl Neg for PaxValue {
     type Output = Self;
 
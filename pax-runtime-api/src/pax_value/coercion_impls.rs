@@ -7,9 +7,13 @@ use std::ops::Range;
 use crate::{
     impl_default_coercion_rule,
     math::{Transform2, Vector2},
-    Color, ColorChannel, Fill, GradientStop, LinearGradient, Numeric, PathElement, PaxValue,
-    Percent, Property, RadialGradient, Rotation, Size, Stroke, Transform2D,
+    BlendMode, Color, ColorChannel, Effect, Fill, GradientStop, LinearGradient, Numeric,
+    PathElement, PaxFunction, PaxRange, PaxValue, Percent, Property, RadialGradient, Rotation,
+    Shadow, Size, Stroke, StrokeAlignment, StrokeCap, StrokeJoin, Transform2D, TransitionDuration,
 };
+use crate::{EasingCurve, Spring};
+
+use super::ToPaxValue;
 
 // Default coercion rules:
 // call Into::<first param>::into() on contents of second enum variant
@@ -31,6 +35,8 @@ impl_default_coercion_rule!(f64, PaxValue::Numeric);
 impl_default_coercion_rule!(isize, PaxValue::Numeric);
 impl_default_coercion_rule!(usize, PaxValue::Numeric);
 
+impl_default_coercion_rule!(PaxFunction, PaxValue::Function);
+
 pub trait CoercionRules
 where
     Self: Sized + 'static,
@@ -309,19 +315,152 @@ impl CoercionRules for GradientStop {
     }
 }
 
+// Plain numbers are frame counts, as they are for `u64`s in Rust, while `300ms` and `0.3s`
+// evaluate to durations in milliseconds
+impl CoercionRules for TransitionDuration {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        Ok(match pax_value {
+            PaxValue::Duration(duration) => duration,
+            PaxValue::Numeric(frames) => TransitionDuration::Frames(frames.to_int().max(0) as u64),
+            PaxValue::Enum(contents) => {
+                let (_, variant, args) = *contents;
+                let length = Numeric::try_coerce(
+                    args.into_iter()
+                        .next()
+                        .ok_or_else(|| format!("TransitionDuration::{variant} needs a length"))?,
+                )?;
+                match variant.as_str() {
+                    "Millis" => TransitionDuration::Millis(length.to_float()),
+                    "Frames" => TransitionDuration::Frames(length.to_int().max(0) as u64),
+                    _ => {
+                        return Err(format!(
+                            "failed to coerce TransitionDuration: unknown enum variant {:?}",
+                            variant
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "{:?} can't be coerced into a TransitionDuration",
+                    pax_value
+                ))
+            }
+        })
+    }
+}
+
+impl CoercionRules for EasingCurve {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        let PaxValue::Enum(contents) = pax_value else {
+            return Err(format!(
+                "{:?} can't be coerced into an EasingCurve",
+                pax_value
+            ));
+        };
+        let (_, variant, args) = *contents;
+        let args = args
+            .into_iter()
+            .map(|arg| Numeric::try_coerce(arg).map(|n| n.to_float()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(match (variant.as_str(), args.as_slice()) {
+            ("Linear", []) => EasingCurve::Linear,
+            ("InQuad", []) => EasingCurve::InQuad,
+            ("OutQuad", []) => EasingCurve::OutQuad,
+            ("InOutQuad", []) => EasingCurve::InOutQuad,
+            ("InCubic", []) => EasingCurve::InCubic,
+            ("OutCubic", []) => EasingCurve::OutCubic,
+            ("InOutCubic", []) => EasingCurve::InOutCubic,
+            ("InQuart", []) => EasingCurve::InQuart,
+            ("OutQuart", []) => EasingCurve::OutQuart,
+            ("InOutQuart", []) => EasingCurve::InOutQuart,
+            ("InExpo", []) => EasingCurve::InExpo,
+            ("OutExpo", []) => EasingCurve::OutExpo,
+            ("InOutExpo", []) => EasingCurve::InOutExpo,
+            ("InSine", []) => EasingCurve::InSine,
+            ("OutSine", []) => EasingCurve::OutSine,
+            ("InOutSine", []) => EasingCurve::InOutSine,
+            ("InBack", []) => EasingCurve::InBack,
+            ("OutBack", []) => EasingCurve::OutBack,
+            ("InOutBack", []) => EasingCurve::InOutBack,
+            ("InElastic", []) => EasingCurve::InElastic,
+            ("OutElastic", []) => EasingCurve::OutElastic,
+            ("InOutElastic", []) => EasingCurve::InOutElastic,
+            ("InBounce", []) => EasingCurve::InBounce,
+            ("OutBounce", []) => EasingCurve::OutBounce,
+            ("InOutBounce", []) => EasingCurve::InOutBounce,
+            ("Ease", []) => EasingCurve::Ease,
+            ("EaseIn", []) => EasingCurve::EaseIn,
+            ("EaseOut", []) => EasingCurve::EaseOut,
+            ("EaseInOut", []) => EasingCurve::EaseInOut,
+            ("CubicBezier", &[x1, y1, x2, y2]) => EasingCurve::CubicBezier(x1, y1, x2, y2),
+            ("Steps", &[n]) => EasingCurve::Steps(n.max(1.0) as u32),
+            ("Spring", []) => EasingCurve::Spring(Spring::default()),
+            ("Spring", &[stiffness, damping, mass]) => {
+                EasingCurve::Spring(Spring::new(stiffness, damping, mass))
+            }
+            ("Custom", _) => {
+                return Err("custom easing curves can't be coerced from a PaxValue".to_string())
+            }
+            _ => {
+                return Err(format!(
+                    "failed to coerce EasingCurve: unknown variant {}{:?}",
+                    variant, args
+                ))
+            }
+        })
+    }
+}
+
 impl CoercionRules for Stroke {
     fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
         Ok(match pax_value {
             PaxValue::Color(color) => Stroke {
                 color: Property::new(*color),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             },
-            PaxValue::Object(map) => {
+            PaxValue::Object(mut map) => {
+                // only the color and width are required
+                let mut optional = |key: &str| {
+                    map.iter()
+                        .position(|(k, _)| k == key)
+                        .map(|i| map.remove(i).1)
+                };
+                let mut stroke = Stroke::default();
+                if let Some(fill) = optional("fill") {
+                    stroke.fill = Property::new(Option::<Fill>::try_coerce(fill)?);
+                }
+                if let Some(dash) = optional("dash") {
+                    let PaxValue::Vec(lengths) = dash else {
+                        return Err(format!(
+                            "failed to convert to Stroke: dash should be a list"
+                        ));
+                    };
+                    let lengths: Result<Vec<_>, _> =
+                        lengths.into_iter().map(coerce_pixels).collect();
+                    stroke.dash = Property::new(lengths?);
+                }
+                if let Some(dash_offset) = optional("dash_offset") {
+                    stroke.dash_offset = Property::new(coerce_pixels(dash_offset)?);
+                }
+                if let Some(cap) = optional("cap") {
+                    stroke.cap = Property::new(StrokeCap::try_coerce(cap)?);
+                }
+                if let Some(join) = optional("join") {
+                    stroke.join = Property::new(StrokeJoin::try_coerce(join)?);
+                }
+                if let Some(miter_limit) = optional("miter_limit") {
+                    stroke.miter_limit = Property::new(f64::try_coerce(miter_limit)?);
+                }
+                if let Some(alignment) = optional("alignment") {
+                    stroke.alignment = Property::new(StrokeAlignment::try_coerce(alignment)?);
+                }
                 let [color, width] = extract_options(["color", "width"], map)
                     .map_err(|e| format!("failed to convert to Stroke: {e}"))?;
-                let color = Property::new(Color::try_coerce(color)?);
-                let width = Property::new(Size::try_coerce(width)?);
-                Stroke { color, width }
+                stroke.color = Property::new(Color::try_coerce(color)?);
+                stroke.width = Property::new(Size::try_coerce(width)?);
+                stroke
             }
             PaxValue::Option(o) => {
                 if let Some(o) = *o {
@@ -335,6 +474,145 @@ impl CoercionRules for Stroke {
     }
 }
 
+/// Lengths in effects and dash patterns are pixels, written either as plain numbers or
+/// as `px`
+fn coerce_pixels(value: PaxValue) -> Result<f64, String> {
+    Ok(match value {
+        PaxValue::Numeric(n) => n.to_float(),
+        PaxValue::Size(Size::Pixels(n)) => n.to_float(),
+        _ => {
+            return Err(format!(
+                "{:?} can't be coerced into a length in pixels",
+                value
+            ))
+        }
+    })
+}
+
+impl CoercionRules for Shadow {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        Ok(match pax_value {
+            PaxValue::Object(map) => {
+                let [offset_x, offset_y, blur, spread, color] =
+                    extract_options(["offset_x", "offset_y", "blur", "spread", "color"], map)
+                        .map_err(|e| format!("failed to convert to Shadow: {e}"))?;
+                Shadow {
+                    offset_x: coerce_pixels(offset_x)?,
+                    offset_y: coerce_pixels(offset_y)?,
+                    blur: coerce_pixels(blur)?,
+                    spread: coerce_pixels(spread)?,
+                    color: Color::try_coerce(color)?,
+                }
+            }
+            PaxValue::Option(o) => {
+                if let Some(o) = *o {
+                    Shadow::try_coerce(o)?
+                } else {
+                    return Err(format!("failed to convert to Shadow"));
+                }
+            }
+            _ => return Err(format!("{:?} can't be coerced into a Shadow", pax_value)),
+        })
+    }
+}
+
+impl CoercionRules for Effect {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        let PaxValue::Enum(contents) = pax_value else {
+            return Err(format!("{:?} can't be coerced into an Effect", pax_value));
+        };
+        let (_, variant, args) = *contents;
+        // shadows are written either positionally, as (x, y, blur, spread, color), or as
+        // a single Shadow object
+        let shadow = |args: Vec<PaxValue>| -> Result<Shadow, String> {
+            match <[PaxValue; 5]>::try_from(args) {
+                Ok([offset_x, offset_y, blur, spread, color]) => Ok(Shadow {
+                    offset_x: coerce_pixels(offset_x)?,
+                    offset_y: coerce_pixels(offset_y)?,
+                    blur: coerce_pixels(blur)?,
+                    spread: coerce_pixels(spread)?,
+                    color: Color::try_coerce(color)?,
+                }),
+                Err(args) if args.len() == 1 => {
+                    Shadow::try_coerce(args.into_iter().next().unwrap())
+                }
+                Err(args) => Err(format!(
+                    "failed to coerce Effect::{}: expected 5 arguments, got {}",
+                    variant,
+                    args.len()
+                )),
+            }
+        };
+        let radius = |args: Vec<PaxValue>| -> Result<f64, String> {
+            match <[PaxValue; 1]>::try_from(args) {
+                Ok([radius]) => coerce_pixels(radius),
+                Err(args) => Err(format!(
+                    "failed to coerce Effect::{}: expected a radius, got {} arguments",
+                    variant,
+                    args.len()
+                )),
+            }
+        };
+        Ok(match variant.as_str() {
+            "DropShadow" => Effect::DropShadow(shadow(args)?),
+            "InnerShadow" => Effect::InnerShadow(shadow(args)?),
+            "LayerBlur" => Effect::LayerBlur(radius(args)?),
+            "BackdropBlur" => Effect::BackdropBlur(radius(args)?),
+            _ => {
+                return Err(format!(
+                    "failed to coerce Effect: unknown variant {}",
+                    variant
+                ))
+            }
+        })
+    }
+}
+
+/// Coerces enums whose variants have no arguments, such as `StrokeCap::Round`, by
+/// matching the name of the variant against the debug names of `variants`
+fn coerce_unit_variant<T: Copy + std::fmt::Debug>(
+    pax_value: PaxValue,
+    name: &str,
+    variants: &[T],
+) -> Result<T, String> {
+    let PaxValue::Enum(contents) = pax_value else {
+        return Err(format!("{:?} can't be coerced into a {}", pax_value, name));
+    };
+    let (_, variant, args) = *contents;
+    variants
+        .iter()
+        .copied()
+        .find(|v| args.is_empty() && format!("{:?}", v) == variant)
+        .ok_or_else(|| format!("failed to coerce {}: unknown variant {}", name, variant))
+}
+
+impl CoercionRules for BlendMode {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        coerce_unit_variant(pax_value, "BlendMode", &BlendMode::ALL)
+    }
+}
+
+impl CoercionRules for StrokeCap {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        use StrokeCap::*;
+        coerce_unit_variant(pax_value, "StrokeCap", &[Butt, Round, Square])
+    }
+}
+
+impl CoercionRules for StrokeJoin {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        use StrokeJoin::*;
+        coerce_unit_variant(pax_value, "StrokeJoin", &[Miter, Round, Bevel])
+    }
+}
+
+impl CoercionRules for StrokeAlignment {
+    fn try_coerce(pax_value: PaxValue) -> Result<Self, String> {
+        use StrokeAlignment::*;
+        coerce_unit_variant(pax_value, "StrokeAlignment", &[Inside, Center, Outside])
+    }
+}
+
 impl CoercionRules for ColorChannel {
     fn try_coerce(value: PaxValue) -> Result<Self, String> {
         Ok(match value {
@@ -533,9 +811,20 @@ impl<T: CoercionRules> CoercionRules for Option<T> {
 impl<T: CoercionRules> CoercionRules for Range<T> {
     fn try_coerce(value: PaxValue) -> Result<Self, String> {
         match value {
-            PaxValue::Range(start, end) => {
-                let start = T::try_coerce(*start)?;
-                let end = T::try_coerce(*end)?;
+            PaxValue::Range(range) => {
+                if range.step() != 1 {
+                    return Err(format!("{} has a step, so isn't a Range", range));
+                }
+                let end = if range.is_inclusive() {
+                    range
+                        .end()
+                        .checked_add(1)
+                        .ok_or_else(|| format!("{} can't be made exclusive", range))?
+                } else {
+                    range.end()
+                };
+                let start = T::try_coerce(range.start().to_pax_value())?;
+                let end = T::try_coerce(end.to_pax_value())?;
                 Ok(start..end)
             }
             PaxValue::Option(mut opt) => {
@@ -560,6 +849,15 @@ impl CoercionRules for PaxValue {
     }
 }
 
+impl CoercionRules for PaxRange {
+    fn try_coerce(value: PaxValue) -> Result<Self, String> {
+        match value {
+            PaxValue::Range(range) => Ok(*range),
+            v => Err(format!("{:?} can't be coerced into a range", v)),
+        }
+    }
+}
+
 impl<T: CoercionRules> CoercionRules for Box<T> {
     fn try_coerce(value: PaxValue) -> Result<Self, String> {
         Ok(Box::new(T::try_coerce(value)?))
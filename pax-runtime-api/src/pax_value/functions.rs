@@ -1,10 +1,11 @@
 use crate::{
     math::{Transform2, Vector2},
-    Color, ColorChannel, Fill, PaxValue, Rotation,
+    Color, ColorChannel, Fill, Numeric, PaxFunction, PaxValue, Rotation,
 };
 use once_cell::sync::Lazy;
 use std::{
     collections::HashMap,
+    ops::RangeInclusive,
     sync::{Arc, RwLock},
 };
 
@@ -12,7 +13,13 @@ use super::{CoercionRules, ToPaxValue};
 
 type FunctionType = Arc<dyn Fn(Vec<PaxValue>) -> Result<PaxValue, String> + Send + Sync>;
 
-static FUNCTIONS: Lazy<Arc<RwLock<HashMap<String, HashMap<String, FunctionType>>>>> =
+struct RegisteredFunction {
+    /// Number of arguments accepted, checked when expressions are type checked and called
+    arity: RangeInclusive<usize>,
+    func: FunctionType,
+}
+
+static FUNCTIONS: Lazy<Arc<RwLock<HashMap<String, HashMap<String, RegisteredFunction>>>>> =
     Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
 
 pub fn print_all_functions() {
@@ -26,12 +33,17 @@ pub fn print_all_functions() {
     }
 }
 
-pub fn register_function(scope: String, name: String, func: FunctionType) {
+pub fn register_function(
+    scope: String,
+    name: String,
+    arity: RangeInclusive<usize>,
+    func: FunctionType,
+) {
     let mut functions = FUNCTIONS.write().unwrap();
     functions
         .entry(scope)
         .or_insert_with(HashMap::new)
-        .insert(name, func);
+        .insert(name, RegisteredFunction { arity, func });
 }
 
 pub fn call_function(scope: String, name: String, args: Vec<PaxValue>) -> Result<PaxValue, String> {
@@ -39,9 +51,20 @@ pub fn call_function(scope: String, name: String, args: Vec<PaxValue>) -> Result
     let scope_funcs = functions
         .get(&scope)
         .ok_or_else(|| format!("Scope {} not found", scope))?;
-    let func = scope_funcs
+    let registered = scope_funcs
         .get(&name)
         .ok_or_else(|| format!("Function {} not found in scope {}", name, scope))?;
+    if !registered.arity.contains(&args.len()) {
+        return Err(format!(
+            "Function {}::{} does not accept {} argument(s)",
+            scope,
+            name,
+            args.len()
+        ));
+    }
+    let func = registered.func.clone();
+    // release the lock, lambda arguments may call other functions
+    drop(functions);
     func(args)
 }
 
@@ -184,10 +207,360 @@ fn len(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     }
     match args.into_iter().next().unwrap() {
         PaxValue::Vec(vec) => Ok(vec.len().to_pax_value()),
+        PaxValue::Range(range) => Ok(range.len().to_pax_value()),
         e => Err(format!("can't get length of {e:?}")),
     }
 }
 
+/// Elements of a list argument. Ranges are expanded into their elements
+fn list_arg(value: PaxValue) -> Result<Vec<PaxValue>, String> {
+    match value {
+        PaxValue::Vec(vec) => Ok(vec),
+        PaxValue::Range(range) => Ok(range.iter().map(|i| i.to_pax_value()).collect()),
+        e => Err(format!("expected a list, found {e:?}")),
+    }
+}
+
+fn index_arg(value: PaxValue) -> Result<usize, String> {
+    let index = isize::try_coerce(value)?;
+    usize::try_from(index).map_err(|_| format!("expected a positive index, found {index}"))
+}
+
+fn list_reverse(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function reverse".to_string());
+    }
+    let mut list = list_arg(args.into_iter().next().unwrap())?;
+    list.reverse();
+    Ok(PaxValue::Vec(list))
+}
+
+fn list_slice(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 3 {
+        return Err("Expected 3 arguments for function slice".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let start = index_arg(itr.next().unwrap())?.min(list.len());
+    let end = index_arg(itr.next().unwrap())?.clamp(start, list.len());
+    Ok(PaxValue::Vec(list[start..end].to_vec()))
+}
+
+fn list_take(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function take".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let n = index_arg(itr.next().unwrap())?;
+    Ok(PaxValue::Vec(list.into_iter().take(n).collect()))
+}
+
+fn list_skip(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function skip".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let n = index_arg(itr.next().unwrap())?;
+    Ok(PaxValue::Vec(list.into_iter().skip(n).collect()))
+}
+
+fn list_concat(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    let mut concatenated = Vec::new();
+    for arg in args {
+        concatenated.extend(list_arg(arg)?);
+    }
+    Ok(PaxValue::Vec(concatenated))
+}
+
+fn list_contains(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function contains".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let value = itr.next().unwrap();
+    Ok(PaxValue::Bool(list.contains(&value)))
+}
+
+fn list_index_of(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function index_of".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let value = itr.next().unwrap();
+    Ok(list.iter().position(|v| v == &value).to_pax_value())
+}
+
+fn list_first(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function first".to_string());
+    }
+    let list = list_arg(args.into_iter().next().unwrap())?;
+    Ok(PaxValue::Option(Box::new(list.into_iter().next())))
+}
+
+fn list_last(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function last".to_string());
+    }
+    let list = list_arg(args.into_iter().next().unwrap())?;
+    Ok(PaxValue::Option(Box::new(list.into_iter().last())))
+}
+
+fn list_sort(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function sort".to_string());
+    }
+    let mut list = list_arg(args.into_iter().next().unwrap())?;
+    list.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(PaxValue::Vec(list))
+}
+
+fn list_map(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function map".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    let mapped = list
+        .into_iter()
+        .map(|v| f.call(vec![v]))
+        .collect::<Result<_, _>>()?;
+    Ok(PaxValue::Vec(mapped))
+}
+
+fn list_filter(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function filter".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    let mut filtered = Vec::new();
+    for v in list {
+        if bool::try_coerce(f.call(vec![v.clone()])?)? {
+            filtered.push(v);
+        }
+    }
+    Ok(PaxValue::Vec(filtered))
+}
+
+fn list_reduce(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 3 {
+        return Err("Expected 3 arguments for function reduce".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let init = itr.next().unwrap();
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    list.into_iter()
+        .try_fold(init, |acc, v| f.call(vec![acc, v]))
+}
+
+fn list_find(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function find".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    for v in list {
+        if bool::try_coerce(f.call(vec![v.clone()])?)? {
+            return Ok(PaxValue::Option(Box::new(Some(v))));
+        }
+    }
+    Ok(PaxValue::Option(Box::new(None)))
+}
+
+fn list_any(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function any".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    for v in list {
+        if bool::try_coerce(f.call(vec![v])?)? {
+            return Ok(PaxValue::Bool(true));
+        }
+    }
+    Ok(PaxValue::Bool(false))
+}
+
+fn list_all(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function all".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    for v in list {
+        if !bool::try_coerce(f.call(vec![v])?)? {
+            return Ok(PaxValue::Bool(false));
+        }
+    }
+    Ok(PaxValue::Bool(true))
+}
+
+/// Sorts a list by the key returned by the given function
+fn list_sort_by(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function sort_by".to_string());
+    }
+    let mut itr = args.into_iter();
+    let list = list_arg(itr.next().unwrap())?;
+    let f = PaxFunction::try_coerce(itr.next().unwrap())?;
+    let mut keyed = list
+        .into_iter()
+        .map(|v| Ok((f.call(vec![v.clone()])?, v)))
+        .collect::<Result<Vec<_>, String>>()?;
+    keyed.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(PaxValue::Vec(keyed.into_iter().map(|(_, v)| v).collect()))
+}
+
+/// Formats a number with a fixed number of decimals, optionally grouping the
+/// digits of its integer part by thousands: `format_number(1234.5, 2, ",")` is "1,234.50"
+fn string_format_number(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err("Expected 2 or 3 arguments for function format_number".to_string());
+    }
+    let mut itr = args.into_iter();
+    let value = Numeric::try_coerce(itr.next().unwrap())?.to_float();
+    let decimals = index_arg(itr.next().unwrap())?;
+    let formatted = format!("{:.*}", decimals, value);
+    let Some(separator) = itr.next() else {
+        return Ok(formatted.to_pax_value());
+    };
+    let separator = String::try_coerce(separator)?;
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer),
+    };
+    let mut grouped = sign.to_string();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(&separator);
+        }
+        grouped.push(c);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+    Ok(grouped.to_pax_value())
+}
+
+fn string_to_upper(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function to_upper".to_string());
+    }
+    let string = String::try_coerce(args.into_iter().next().unwrap())?;
+    Ok(string.to_uppercase().to_pax_value())
+}
+
+fn string_to_lower(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function to_lower".to_string());
+    }
+    let string = String::try_coerce(args.into_iter().next().unwrap())?;
+    Ok(string.to_lowercase().to_pax_value())
+}
+
+fn string_trim(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function trim".to_string());
+    }
+    let string = String::try_coerce(args.into_iter().next().unwrap())?;
+    Ok(string.trim().to_string().to_pax_value())
+}
+
+fn string_split(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function split".to_string());
+    }
+    let mut itr = args.into_iter();
+    let string = String::try_coerce(itr.next().unwrap())?;
+    let separator = String::try_coerce(itr.next().unwrap())?;
+    Ok(PaxValue::Vec(
+        string
+            .split(separator.as_str())
+            .map(|s| s.to_string().to_pax_value())
+            .collect(),
+    ))
+}
+
+fn string_contains(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 2 {
+        return Err("Expected 2 arguments for function contains".to_string());
+    }
+    let mut itr = args.into_iter();
+    let string = String::try_coerce(itr.next().unwrap())?;
+    let pattern = String::try_coerce(itr.next().unwrap())?;
+    Ok(PaxValue::Bool(string.contains(pattern.as_str())))
+}
+
+/// Characters `start..end` of a string, clamped to its length
+fn string_substring(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 3 {
+        return Err("Expected 3 arguments for function substring".to_string());
+    }
+    let mut itr = args.into_iter();
+    let string = String::try_coerce(itr.next().unwrap())?;
+    let start = index_arg(itr.next().unwrap())?;
+    let end = index_arg(itr.next().unwrap())?;
+    let substring: String = string
+        .chars()
+        .skip(start)
+        .take(end.saturating_sub(start))
+        .collect();
+    Ok(substring.to_pax_value())
+}
+
+fn string_len(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    if args.len() != 1 {
+        return Err("Expected 1 argument for function len".to_string());
+    }
+    let string = String::try_coerce(args.into_iter().next().unwrap())?;
+    Ok(string.chars().count().to_pax_value())
+}
+
+fn string_pad(args: Vec<PaxValue>, name: &str, at_start: bool) -> Result<PaxValue, String> {
+    if args.len() != 2 && args.len() != 3 {
+        return Err(format!("Expected 2 or 3 arguments for function {name}"));
+    }
+    let mut itr = args.into_iter();
+    let string = String::try_coerce(itr.next().unwrap())?;
+    let width = index_arg(itr.next().unwrap())?;
+    let fill = match itr.next() {
+        Some(fill) => String::try_coerce(fill)?,
+        None => " ".to_string(),
+    };
+    let missing = width.saturating_sub(string.chars().count());
+    let padding: String = fill.chars().cycle().take(missing).collect();
+    Ok(if at_start {
+        padding + &string
+    } else {
+        string + &padding
+    }
+    .to_pax_value())
+}
+
+fn string_pad_start(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    string_pad(args, "pad_start", true)
+}
+
+fn string_pad_end(args: Vec<PaxValue>) -> Result<PaxValue, String> {
+    string_pad(args, "pad_end", false)
+}
+
 fn rgb(args: Vec<PaxValue>) -> Result<PaxValue, String> {
     if args.len() != 3 {
         return Err("Expected 3 arguments for function rgb".to_string());
@@ -239,6 +612,10 @@ fn hex(args: Vec<PaxValue>) -> Result<PaxValue, String> {
         return Err("Expected 1 argument for function hex".to_string());
     }
     let hex = String::try_coerce(args.into_iter().next().unwrap())?;
+    // `Color::from_hex` expects RRGGBB or RRGGBBAA
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex color {hex:?}"));
+    }
     Ok(Color::from_hex(&hex).to_pax_value())
 }
 
@@ -251,34 +628,257 @@ pub struct Functions;
 impl Functions {
     pub fn register_all_functions() {
         // Math
-        register_function("Math".to_string(), "+".to_string(), Arc::new(add));
-        register_function("Math".to_string(), "-".to_string(), Arc::new(sub_or_neg));
-        register_function("Math".to_string(), "*".to_string(), Arc::new(mul));
-        register_function("Math".to_string(), "/".to_string(), Arc::new(div));
-        register_function("Math".to_string(), "^".to_string(), Arc::new(exp));
-        register_function("Math".to_string(), "%%".to_string(), Arc::new(mod_));
-        register_function("Math".to_string(), "==".to_string(), Arc::new(rel_eq));
-        register_function("Math".to_string(), ">".to_string(), Arc::new(rel_gt));
-        register_function("Math".to_string(), ">=".to_string(), Arc::new(rel_gte));
-        register_function("Math".to_string(), "<".to_string(), Arc::new(rel_lt));
-        register_function("Math".to_string(), "<=".to_string(), Arc::new(rel_lte));
-        register_function("Math".to_string(), "!=".to_string(), Arc::new(rel_neq));
-        register_function("Math".to_string(), "&&".to_string(), Arc::new(bool_and));
-        register_function("Math".to_string(), "||".to_string(), Arc::new(bool_or));
-        register_function("Math".to_string(), "!".to_string(), Arc::new(bool_not));
-        register_function("Math".to_string(), "min".to_string(), Arc::new(min));
-        register_function("Math".to_string(), "max".to_string(), Arc::new(max));
-        register_function("Math".to_string(), "len".to_string(), Arc::new(len));
+        register_function("Math".to_string(), "+".to_string(), 2..=2, Arc::new(add));
+        register_function(
+            "Math".to_string(),
+            "-".to_string(),
+            1..=2,
+            Arc::new(sub_or_neg),
+        );
+        register_function("Math".to_string(), "*".to_string(), 2..=2, Arc::new(mul));
+        register_function("Math".to_string(), "/".to_string(), 2..=2, Arc::new(div));
+        register_function("Math".to_string(), "^".to_string(), 2..=2, Arc::new(exp));
+        register_function("Math".to_string(), "%%".to_string(), 2..=2, Arc::new(mod_));
+        register_function(
+            "Math".to_string(),
+            "==".to_string(),
+            2..=2,
+            Arc::new(rel_eq),
+        );
+        register_function("Math".to_string(), ">".to_string(), 2..=2, Arc::new(rel_gt));
+        register_function(
+            "Math".to_string(),
+            ">=".to_string(),
+            2..=2,
+            Arc::new(rel_gte),
+        );
+        register_function("Math".to_string(), "<".to_string(), 2..=2, Arc::new(rel_lt));
+        register_function(
+            "Math".to_string(),
+            "<=".to_string(),
+            2..=2,
+            Arc::new(rel_lte),
+        );
+        register_function(
+            "Math".to_string(),
+            "!=".to_string(),
+            2..=2,
+            Arc::new(rel_neq),
+        );
+        register_function(
+            "Math".to_string(),
+            "&&".to_string(),
+            2..=2,
+            Arc::new(bool_and),
+        );
+        register_function(
+            "Math".to_string(),
+            "||".to_string(),
+            2..=2,
+            Arc::new(bool_or),
+        );
+        register_function(
+            "Math".to_string(),
+            "!".to_string(),
+            1..=1,
+            Arc::new(bool_not),
+        );
+        register_function("Math".to_string(), "min".to_string(), 2..=2, Arc::new(min));
+        register_function("Math".to_string(), "max".to_string(), 2..=2, Arc::new(max));
+        register_function("Math".to_string(), "len".to_string(), 1..=1, Arc::new(len));
+        // Lists
+        register_function(
+            "List".to_string(),
+            "reverse".to_string(),
+            1..=1,
+            Arc::new(list_reverse),
+        );
+        register_function(
+            "List".to_string(),
+            "slice".to_string(),
+            3..=3,
+            Arc::new(list_slice),
+        );
+        register_function(
+            "List".to_string(),
+            "take".to_string(),
+            2..=2,
+            Arc::new(list_take),
+        );
+        register_function(
+            "List".to_string(),
+            "skip".to_string(),
+            2..=2,
+            Arc::new(list_skip),
+        );
+        register_function(
+            "List".to_string(),
+            "concat".to_string(),
+            0..=usize::MAX,
+            Arc::new(list_concat),
+        );
+        register_function(
+            "List".to_string(),
+            "contains".to_string(),
+            2..=2,
+            Arc::new(list_contains),
+        );
+        register_function(
+            "List".to_string(),
+            "index_of".to_string(),
+            2..=2,
+            Arc::new(list_index_of),
+        );
+        register_function(
+            "List".to_string(),
+            "first".to_string(),
+            1..=1,
+            Arc::new(list_first),
+        );
+        register_function(
+            "List".to_string(),
+            "last".to_string(),
+            1..=1,
+            Arc::new(list_last),
+        );
+        register_function(
+            "List".to_string(),
+            "sort".to_string(),
+            1..=1,
+            Arc::new(list_sort),
+        );
+        register_function(
+            "List".to_string(),
+            "map".to_string(),
+            2..=2,
+            Arc::new(list_map),
+        );
+        register_function(
+            "List".to_string(),
+            "filter".to_string(),
+            2..=2,
+            Arc::new(list_filter),
+        );
+        register_function(
+            "List".to_string(),
+            "reduce".to_string(),
+            3..=3,
+            Arc::new(list_reduce),
+        );
+        register_function(
+            "List".to_string(),
+            "find".to_string(),
+            2..=2,
+            Arc::new(list_find),
+        );
+        register_function(
+            "List".to_string(),
+            "any".to_string(),
+            2..=2,
+            Arc::new(list_any),
+        );
+        register_function(
+            "List".to_string(),
+            "all".to_string(),
+            2..=2,
+            Arc::new(list_all),
+        );
+        register_function(
+            "List".to_string(),
+            "sort_by".to_string(),
+            2..=2,
+            Arc::new(list_sort_by),
+        );
+        // Strings
+        register_function(
+            "String".to_string(),
+            "format_number".to_string(),
+            2..=3,
+            Arc::new(string_format_number),
+        );
+        register_function(
+            "String".to_string(),
+            "to_upper".to_string(),
+            1..=1,
+            Arc::new(string_to_upper),
+        );
+        register_function(
+            "String".to_string(),
+            "to_lower".to_string(),
+            1..=1,
+            Arc::new(string_to_lower),
+        );
+        register_function(
+            "String".to_string(),
+            "trim".to_string(),
+            1..=1,
+            Arc::new(string_trim),
+        );
+        register_function(
+            "String".to_string(),
+            "split".to_string(),
+            2..=2,
+            Arc::new(string_split),
+        );
+        register_function(
+            "String".to_string(),
+            "contains".to_string(),
+            2..=2,
+            Arc::new(string_contains),
+        );
+        register_function(
+            "String".to_string(),
+            "substring".to_string(),
+            3..=3,
+            Arc::new(string_substring),
+        );
+        register_function(
+            "String".to_string(),
+            "len".to_string(),
+            1..=1,
+            Arc::new(string_len),
+        );
+        register_function(
+            "String".to_string(),
+            "pad_start".to_string(),
+            2..=3,
+            Arc::new(string_pad_start),
+        );
+        register_function(
+            "String".to_string(),
+            "pad_end".to_string(),
+            2..=3,
+            Arc::new(string_pad_end),
+        );
         // Colors
-        register_function("Color".to_string(), "rgb".to_string(), Arc::new(rgb));
-        register_function("Color".to_string(), "rgba".to_string(), Arc::new(rgba));
-        register_function("Color".to_string(), "hsl".to_string(), Arc::new(hsl));
-        register_function("Color".to_string(), "hsla".to_string(), Arc::new(hsla));
-        register_function("Color".to_string(), "#".to_string(), Arc::new(hex));
+        register_function("Color".to_string(), "rgb".to_string(), 3..=3, Arc::new(rgb));
+        register_function(
+            "Color".to_string(),
+            "rgba".to_string(),
+            4..=4,
+            Arc::new(rgba),
+        );
+        register_function("Color".to_string(), "hsl".to_string(), 3..=3, Arc::new(hsl));
+        register_function(
+            "Color".to_string(),
+            "hsla".to_string(),
+            4..=4,
+            Arc::new(hsla),
+        );
+        register_function("Color".to_string(), "#".to_string(), 1..=1, Arc::new(hex));
         // Transform2D
         crate::Transform2D::register_all_functions();
     }
 
+    /// The number of arguments `scope::name` accepts, if it is registered
+    pub fn get_arity(scope: &str, name: &str) -> Option<RangeInclusive<usize>> {
+        let functions = FUNCTIONS.read().unwrap();
+        functions
+            .get(scope)
+            .and_then(|scope_funcs| scope_funcs.get(name))
+            .map(|registered| registered.arity.clone())
+    }
+
     pub fn has_function(scope: &str, name: &str) -> bool {
         let functions = FUNCTIONS.read().unwrap();
         if let Some(scope_funcs) = functions.get(scope) {
@@ -334,6 +934,7 @@ impl HelperFunctions for crate::Transform2D {
         register_function(
             "Transform2D".to_string(),
             "scale".to_string(),
+            2..=2,
             Arc::new(|args| {
                 if args.len() != 2 {
                     return Err("Expected 2 arguments for function scale".to_string());
@@ -347,6 +948,7 @@ impl HelperFunctions for crate::Transform2D {
         register_function(
             "Transform2D".to_string(),
             "rotate".to_string(),
+            1..=1,
             Arc::new(|args| {
                 if args.len() != 1 {
                     return Err("Expected 1 argument for function rotate".to_string());
@@ -358,6 +960,7 @@ impl HelperFunctions for crate::Transform2D {
         register_function(
             "Transform2D".to_string(),
             "translate".to_string(),
+            2..=2,
             Arc::new(|args| {
                 if args.len() != 2 {
                     return Err("Expected 2 arguments for function translate".to_string());
@@ -371,6 +974,7 @@ impl HelperFunctions for crate::Transform2D {
         register_function(
             "Transform2D".to_string(),
             "anchor".to_string(),
+            2..=2,
             Arc::new(|args| {
                 if args.len() != 2 {
                     return Err("Expected 2 arguments for function anchor".to_string());
@@ -389,6 +993,7 @@ impl HelperFunctions for Transform2 {
         register_function(
             "Transform2".to_string(),
             "identity".to_string(),
+            0..=0,
             Arc::new(|args| {
                 if args.len() != 0 {
                     return Err("Expected 0 arguments for function identity".to_string());
@@ -399,6 +1004,7 @@ impl HelperFunctions for Transform2 {
         register_function(
             "Transform2".to_string(),
             "scale".to_string(),
+            1..=1,
             Arc::new(|args| {
                 if args.len() != 1 {
                     return Err("Expected 1 argument for function scale".to_string());
@@ -411,6 +1017,7 @@ impl HelperFunctions for Transform2 {
         register_function(
             "Transform2".to_string(),
             "translate".to_string(),
+            1..=1,
             Arc::new(|args| {
                 if args.len() != 1 {
                     return Err("Expected 1 argument for function scale".to_string());
@@ -423,6 +1030,7 @@ impl HelperFunctions for Transform2 {
         register_function(
             "Transform2".to_string(),
             "rotate".to_string(),
+            1..=1,
             Arc::new(|args| {
                 if args.len() != 1 {
                     return Err("Expected 1 argument for function rotate".to_string());
@@ -434,6 +1042,7 @@ impl HelperFunctions for Transform2 {
         register_function(
             "Transform2".to_string(),
             "skew".to_string(),
+            1..=1,
             Arc::new(|args| {
                 if args.len() != 1 {
                     return Err("Expected 1 argument for function skew".to_string());
@@ -444,3 +1053,109 @@ impl HelperFunctions for Transform2 {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered() -> Vec<(String, String, RangeInclusive<usize>, FunctionType)> {
+        Functions::register_all_functions();
+        Transform2::register_all_functions();
+        let functions = FUNCTIONS.read().unwrap();
+        functions
+            .iter()
+            .flat_map(|(scope, funcs)| {
+                funcs.iter().map(|(name, registered)| {
+                    (
+                        scope.clone(),
+                        name.clone(),
+                        registered.arity.clone(),
+                        registered.func.clone(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn numeric_args(count: usize) -> Vec<PaxValue> {
+        vec![PaxValue::Numeric(Numeric::I64(1)); count]
+    }
+
+    /// `count` arguments of the type `scope::name` takes
+    fn args_for(scope: &str, name: &str, count: usize) -> Vec<PaxValue> {
+        match (scope, name) {
+            ("Color", "#") => vec![PaxValue::String("ff0000".to_string()); count],
+            _ => numeric_args(count),
+        }
+    }
+
+    fn is_arity_error(result: &Result<PaxValue, String>) -> bool {
+        matches!(result, Err(e) if e.starts_with("Expected") && e.contains("argument"))
+    }
+
+    #[test]
+    fn every_registered_function_has_an_arity() {
+        for (scope, name, _, _) in registered() {
+            assert!(
+                Functions::get_arity(&scope, &name).is_some(),
+                "{scope}::{name} has no arity"
+            );
+        }
+    }
+
+    #[test]
+    fn declared_arity_matches_function_argument_checks() {
+        for (scope, name, arity, func) in registered() {
+            let (min, max) = (*arity.start(), *arity.end());
+            let variadic = max == usize::MAX;
+            for count in [min, if variadic { min + 1 } else { max }] {
+                assert!(
+                    !is_arity_error(&func(args_for(&scope, &name, count))),
+                    "{scope}::{name} rejects {count} argument(s) within its arity {arity:?}"
+                );
+            }
+            if min > 0 {
+                assert!(
+                    func(args_for(&scope, &name, min - 1)).is_err(),
+                    "{scope}::{name} accepts {} argument(s) below its arity {arity:?}",
+                    min - 1
+                );
+            }
+            if !variadic {
+                assert!(
+                    func(args_for(&scope, &name, max + 1)).is_err(),
+                    "{scope}::{name} accepts {} argument(s) above its arity {arity:?}",
+                    max + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hex_colors_need_six_or_eight_hex_digits() {
+        let hex_color = |digits: &str| hex(vec![PaxValue::String(digits.to_string())]);
+        assert_eq!(
+            hex_color("ff000080"),
+            Ok(Color::from_hex("ff000080").to_pax_value())
+        );
+        assert!(hex_color("00ff00").is_ok());
+        for invalid in ["", "f00", "ff00000", "ff0000000", "gg0000", "ff00é"] {
+            assert_eq!(
+                hex_color(invalid),
+                Err(format!("Invalid hex color {invalid:?}"))
+            );
+        }
+    }
+
+    #[test]
+    fn call_function_rejects_arguments_outside_arity() {
+        Functions::register_all_functions();
+        let err =
+            call_function("List".to_string(), "first".to_string(), numeric_args(2)).unwrap_err();
+        assert!(err.contains("List::first"), "{err}");
+        assert_eq!(
+            call_function("List".to_string(), "concat".to_string(), vec![]),
+            Ok(PaxValue::Vec(vec![]))
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::{Color, Interpolatable, PathElement, Percent, Rotation, Size};
-use std::{any::Any, fmt::Display, rc::Rc};
+use crate::{Color, Interpolatable, PathElement, Percent, Rotation, Size, TransitionDuration};
+use std::{any::Any, fmt::Display, rc::Rc, sync::Arc};
 
 use self::numeric::Numeric;
 pub use coercion_impls::CoercionRules;
@@ -10,8 +10,11 @@ mod coercion_impls;
 pub mod functions;
 mod macros;
 pub mod numeric;
+mod range;
 mod to_from_impls;
 
+pub use range::PaxRange;
+
 pub type RcPaxValue = Rc<PaxValue>;
 /// Container for all internal pax types
 /// Two important traits are related to this type:
@@ -29,12 +32,58 @@ pub enum PaxValue {
     Percent(Percent),
     Color(Box<Color>),
     Rotation(Rotation),
+    Duration(TransitionDuration),
     PathElement(Box<PathElement>),
     Option(Box<Option<PaxValue>>),
     Vec(Vec<PaxValue>),
-    Range(Box<PaxValue>, Box<PaxValue>),
+    Range(Box<PaxRange>),
     Object(Vec<(String, PaxValue)>),
     Enum(Box<(String, String, Vec<PaxValue>)>),
+    #[serde(skip)]
+    Function(PaxFunction),
+}
+
+/// A callable value, such as the PAXEL lambda `|x| x * 2`. Functions registered
+/// with `register_function` can accept these to map, filter, sort or reduce lists.
+#[derive(Clone)]
+pub struct PaxFunction {
+    source: String,
+    callable: Arc<dyn Fn(Vec<PaxValue>) -> Result<PaxValue, String> + Send + Sync>,
+}
+
+impl PaxFunction {
+    pub fn new(
+        source: String,
+        callable: impl Fn(Vec<PaxValue>) -> Result<PaxValue, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            source,
+            callable: Arc::new(callable),
+        }
+    }
+
+    pub fn call(&self, args: Vec<PaxValue>) -> Result<PaxValue, String> {
+        (self.callable)(args)
+    }
+}
+
+impl std::fmt::Debug for PaxFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PaxFunction({})", self.source)
+    }
+}
+
+impl Display for PaxFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+// Two functions are only equal if they are the same instance
+impl PartialEq for PaxFunction {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.callable, &other.callable)
+    }
 }
 
 impl Clone for PaxValue {
@@ -47,15 +96,17 @@ impl Clone for PaxValue {
             PaxValue::Percent(p) => PaxValue::Percent(p.clone()),
             PaxValue::Color(c) => PaxValue::Color(c.clone()),
             PaxValue::Rotation(r) => PaxValue::Rotation(r.clone()),
+            PaxValue::Duration(d) => PaxValue::Duration(*d),
             PaxValue::PathElement(pe) => PaxValue::PathElement(pe.clone()),
             PaxValue::Option(opt) => PaxValue::Option(opt.clone()),
             PaxValue::Vec(v) => PaxValue::Vec(v.clone()),
-            PaxValue::Range(start, end) => PaxValue::Range(start.clone(), end.clone()),
+            PaxValue::Range(range) => PaxValue::Range(range.clone()),
             PaxValue::Object(pairs) => PaxValue::Object(pairs.clone()),
             PaxValue::Enum(contents) => {
                 let (name, variant, values) = contents.as_ref();
                 PaxValue::Enum(Box::new((name.clone(), variant.clone(), values.clone())))
             }
+            PaxValue::Function(function) => PaxValue::Function(function.clone()),
         }
     }
 }
@@ -71,6 +122,12 @@ impl Display for PaxValue {
             PaxValue::Percent(p) => write!(f, "{}", p),
             PaxValue::Color(c) => write!(f, "{}", c),
             PaxValue::Rotation(r) => write!(f, "{}", r),
+            PaxValue::Duration(d) => match d {
+                TransitionDuration::Millis(millis) => write!(f, "{}ms", millis),
+                TransitionDuration::Frames(frames) => {
+                    write!(f, "TransitionDuration::Frames({})", frames)
+                }
+            },
             PaxValue::PathElement(path_elem) => {
                 write!(f, "PathElement::")?;
                 match path_elem.as_ref() {
@@ -99,7 +156,7 @@ impl Display for PaxValue {
                 }
                 write!(f, "]")
             }
-            PaxValue::Range(start, end) => write!(f, "{}..{}", start, end),
+            PaxValue::Range(range) => write!(f, "{}", range),
             PaxValue::Object(o) => {
                 write!(f, "{{")?;
                 for (i, (key, val)) in o.iter().enumerate() {
@@ -129,6 +186,7 @@ impl Display for PaxValue {
                 }
                 Ok(())
             }
+            PaxValue::Function(function) => write!(f, "{}", function),
         }
     }
 }
@@ -233,6 +233,10 @@ impl Interpolatable for Numeric {
     fn interpolate(&self, other: &Self, t: f64) -> Self {
         Numeric::F64(Into::<f64>::into(self).interpolate(&other.into(), t))
     }
+
+    fn extrapolate(&self, from: &Self, to: &Self, t: f64) -> Self {
+        Numeric::F64(Into::<f64>::into(self).extrapolate(&from.into(), &to.into(), t))
+    }
 }
 
 impl From<Size> for Numeric {
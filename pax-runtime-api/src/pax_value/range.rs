@@ -0,0 +1,135 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// A range of integers, like `0..5`, `1..=5` or `10..0 step -2`. Only the bounds are
+/// stored: elements are produced on demand, so large ranges are cheap to build and
+/// to iterate over in a `for`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(crate = "crate::serde")]
+pub struct PaxRange {
+    start: isize,
+    end: isize,
+    inclusive: bool,
+    step: isize,
+}
+
+impl PaxRange {
+    /// Fails if `step` is zero
+    pub fn new(start: isize, end: isize, inclusive: bool, step: isize) -> Result<Self, String> {
+        if step == 0 {
+            return Err("range step can't be zero".to_string());
+        }
+        Ok(Self {
+            start,
+            end,
+            inclusive,
+            step,
+        })
+    }
+
+    pub fn start(&self) -> isize {
+        self.start
+    }
+
+    pub fn end(&self) -> isize {
+        self.end
+    }
+
+    pub fn is_inclusive(&self) -> bool {
+        self.inclusive
+    }
+
+    pub fn step(&self) -> isize {
+        self.step
+    }
+
+    /// Number of elements, computed without overflowing near the bounds of `isize`
+    pub fn len(&self) -> usize {
+        let (start, end, step) = (self.start as i128, self.end as i128, self.step as i128);
+        // distance to the last element that may be included, in the direction of step
+        let span = if step > 0 { end - start } else { start - end };
+        let span = if self.inclusive { span } else { span - 1 };
+        if span < 0 {
+            return 0;
+        }
+        (span / step.abs() + 1) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The element at `index`, if it is within the range
+    pub fn get(&self, index: usize) -> Option<isize> {
+        if index >= self.len() {
+            return None;
+        }
+        // in bounds, so the element lies between start and end
+        Some((self.start as i128 + index as i128 * self.step as i128) as isize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = isize> + '_ {
+        (0..self.len()).filter_map(move |i| self.get(i))
+    }
+}
+
+impl From<std::ops::Range<isize>> for PaxRange {
+    fn from(range: std::ops::Range<isize>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+            inclusive: false,
+            step: 1,
+        }
+    }
+}
+
+impl Display for PaxRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{}{}{}", self.start, operator, self.end)?;
+        if self.step != 1 {
+            write!(f, " step {}", self.step)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PaxRange;
+
+    fn elements(range: PaxRange) -> Vec<isize> {
+        range.iter().collect()
+    }
+
+    #[test]
+    fn ranges_produce_their_elements() {
+        assert_eq!(elements((0..4).into()), vec![0, 1, 2, 3]);
+        assert_eq!(
+            elements(PaxRange::new(0, 4, true, 1).unwrap()),
+            vec![0, 1, 2, 3, 4]
+        );
+        assert_eq!(
+            elements(PaxRange::new(0, 8, true, 4).unwrap()),
+            vec![0, 4, 8]
+        );
+        assert_eq!(elements(PaxRange::new(0, 8, false, 4).unwrap()), vec![0, 4]);
+        assert_eq!(
+            elements(PaxRange::new(10, 0, false, -3).unwrap()),
+            vec![10, 7, 4, 1]
+        );
+        assert!(PaxRange::new(4, 0, false, 1).unwrap().is_empty());
+        assert!(PaxRange::new(0, 4, false, 0).is_err());
+    }
+
+    #[test]
+    fn inclusive_ranges_reach_the_bounds_of_isize() {
+        let range = PaxRange::new(isize::MAX - 2, isize::MAX, true, 1).unwrap();
+        assert_eq!(range.len(), 3);
+        assert_eq!(range.get(2), Some(isize::MAX));
+        let range = PaxRange::new(isize::MIN, isize::MAX, true, isize::MAX).unwrap();
+        assert_eq!(elements(range), vec![isize::MIN, -1, isize::MAX - 1]);
+    }
+}
@@ -5,6 +5,8 @@ use std::cell::RefCell;
 
 use super::ImplToFromPaxAny;
 use super::Numeric;
+use super::PaxFunction;
+use super::PaxRange;
 use super::PaxValue;
 use super::ToPaxValue;
 use crate::impl_to_pax_value;
@@ -12,8 +14,11 @@ use crate::math::Space;
 use crate::math::Transform2;
 use crate::math::Vector2;
 use crate::properties::PropertyValue;
+use crate::BlendMode;
 use crate::Color;
 use crate::ColorChannel;
+use crate::EasingCurve;
+use crate::Effect;
 use crate::Fill;
 use crate::GradientStop;
 use crate::LinearGradient;
@@ -22,9 +27,14 @@ use crate::Percent;
 use crate::Property;
 use crate::RadialGradient;
 use crate::Rotation;
+use crate::Shadow;
 use crate::Size;
 use crate::Stroke;
+use crate::StrokeAlignment;
+use crate::StrokeCap;
+use crate::StrokeJoin;
 use crate::Transform2D;
+use crate::TransitionDuration;
 
 // Primitive types
 impl_to_pax_value!(bool, PaxValue::Bool);
@@ -58,6 +68,7 @@ impl_to_pax_value!(Numeric, PaxValue::Numeric);
 impl_to_pax_value!(Size, PaxValue::Size);
 impl_to_pax_value!(Rotation, PaxValue::Rotation);
 impl_to_pax_value!(Percent, PaxValue::Percent);
+impl_to_pax_value!(PaxFunction, PaxValue::Function);
 
 impl ToPaxValue for PathElement {
     fn to_pax_value(self) -> PaxValue {
@@ -91,12 +102,15 @@ impl<T: ToPaxValue> ToPaxValue for Option<T> {
     }
 }
 
-impl<T: ToPaxValue> ToPaxValue for Range<T> {
+impl ToPaxValue for Range<isize> {
     fn to_pax_value(self) -> PaxValue {
-        PaxValue::Range(
-            Box::new(self.start.to_pax_value()),
-            Box::new(self.end.to_pax_value()),
-        )
+        PaxValue::Range(Box::new(self.into()))
+    }
+}
+
+impl ToPaxValue for PaxRange {
+    fn to_pax_value(self) -> PaxValue {
+        PaxValue::Range(Box::new(self))
     }
 }
 
@@ -162,12 +176,112 @@ impl ToPaxValue for ColorChannel {
     }
 }
 
+// Custom curves can't be written in PAXEL, so they convert one-way, to a `Custom` variant
+// without arguments that coercion rejects
+impl ToPaxValue for EasingCurve {
+    fn to_pax_value(self) -> PaxValue {
+        let (variant, args): (&str, Vec<f64>) = match self {
+            EasingCurve::Linear => ("Linear", vec![]),
+            EasingCurve::InQuad => ("InQuad", vec![]),
+            EasingCurve::OutQuad => ("OutQuad", vec![]),
+            EasingCurve::InOutQuad => ("InOutQuad", vec![]),
+            EasingCurve::InCubic => ("InCubic", vec![]),
+            EasingCurve::OutCubic => ("OutCubic", vec![]),
+            EasingCurve::InOutCubic => ("InOutCubic", vec![]),
+            EasingCurve::InQuart => ("InQuart", vec![]),
+            EasingCurve::OutQuart => ("OutQuart", vec![]),
+            EasingCurve::InOutQuart => ("InOutQuart", vec![]),
+            EasingCurve::InExpo => ("InExpo", vec![]),
+            EasingCurve::OutExpo => ("OutExpo", vec![]),
+            EasingCurve::InOutExpo => ("InOutExpo", vec![]),
+            EasingCurve::InSine => ("InSine", vec![]),
+            EasingCurve::OutSine => ("OutSine", vec![]),
+            EasingCurve::InOutSine => ("InOutSine", vec![]),
+            EasingCurve::InBack => ("InBack", vec![]),
+            EasingCurve::OutBack => ("OutBack", vec![]),
+            EasingCurve::InOutBack => ("InOutBack", vec![]),
+            EasingCurve::InElastic => ("InElastic", vec![]),
+            EasingCurve::OutElastic => ("OutElastic", vec![]),
+            EasingCurve::InOutElastic => ("InOutElastic", vec![]),
+            EasingCurve::InBounce => ("InBounce", vec![]),
+            EasingCurve::OutBounce => ("OutBounce", vec![]),
+            EasingCurve::InOutBounce => ("InOutBounce", vec![]),
+            EasingCurve::Ease => ("Ease", vec![]),
+            EasingCurve::EaseIn => ("EaseIn", vec![]),
+            EasingCurve::EaseOut => ("EaseOut", vec![]),
+            EasingCurve::EaseInOut => ("EaseInOut", vec![]),
+            EasingCurve::CubicBezier(x1, y1, x2, y2) => ("CubicBezier", vec![x1, y1, x2, y2]),
+            EasingCurve::Steps(n) => ("Steps", vec![n as f64]),
+            EasingCurve::Spring(spring) => (
+                "Spring",
+                vec![spring.stiffness, spring.damping, spring.mass],
+            ),
+            EasingCurve::Custom(_) => ("Custom", vec![]),
+        };
+        PaxValue::Enum(Box::new((
+            "EasingCurve".to_string(),
+            variant.to_string(),
+            args.into_iter().map(ToPaxValue::to_pax_value).collect(),
+        )))
+    }
+}
+
+impl_to_pax_value!(TransitionDuration, PaxValue::Duration);
+
 impl ToPaxValue for Stroke {
     fn to_pax_value(self) -> PaxValue {
         PaxValue::Object(
             vec![
                 ("color".to_string(), self.color.get().to_pax_value()),
                 ("width".to_string(), self.width.to_pax_value()),
+                ("fill".to_string(), self.fill.get().to_pax_value()),
+                ("dash".to_string(), self.dash.get().to_pax_value()),
+                (
+                    "dash_offset".to_string(),
+                    self.dash_offset.get().to_pax_value(),
+                ),
+                ("cap".to_string(), self.cap.get().to_pax_value()),
+                ("join".to_string(), self.join.get().to_pax_value()),
+                (
+                    "miter_limit".to_string(),
+                    self.miter_limit.get().to_pax_value(),
+                ),
+                ("alignment".to_string(), self.alignment.get().to_pax_value()),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+}
+
+/// Enums whose variants have no arguments
+macro_rules! impl_to_pax_value_for_unit_variants {
+    ($($type:ident),*) => {
+        $(
+            impl ToPaxValue for $type {
+                fn to_pax_value(self) -> PaxValue {
+                    PaxValue::Enum(Box::new((
+                        stringify!($type).to_string(),
+                        format!("{:?}", self),
+                        vec![],
+                    )))
+                }
+            }
+        )*
+    };
+}
+
+impl_to_pax_value_for_unit_variants!(BlendMode, StrokeCap, StrokeJoin, StrokeAlignment);
+
+impl ToPaxValue for Shadow {
+    fn to_pax_value(self) -> PaxValue {
+        PaxValue::Object(
+            vec![
+                ("offset_x".to_string(), self.offset_x.to_pax_value()),
+                ("offset_y".to_string(), self.offset_y.to_pax_value()),
+                ("blur".to_string(), self.blur.to_pax_value()),
+                ("spread".to_string(), self.spread.to_pax_value()),
+                ("color".to_string(), self.color.to_pax_value()),
             ]
             .into_iter()
             .collect(),
@@ -175,6 +289,22 @@ impl ToPaxValue for Stroke {
     }
 }
 
+impl ToPaxValue for Effect {
+    fn to_pax_value(self) -> PaxValue {
+        let (variant, arg) = match self {
+            Effect::DropShadow(shadow) => ("DropShadow", shadow.to_pax_value()),
+            Effect::InnerShadow(shadow) => ("InnerShadow", shadow.to_pax_value()),
+            Effect::LayerBlur(radius) => ("LayerBlur", radius.to_pax_value()),
+            Effect::BackdropBlur(radius) => ("BackdropBlur", radius.to_pax_value()),
+        };
+        PaxValue::Enum(Box::new((
+            "Effect".to_string(),
+            variant.to_string(),
+            vec![arg],
+        )))
+    }
+}
+
 impl ToPaxValue for GradientStop {
     fn to_pax_value(self) -> PaxValue {
         PaxValue::Object(
@@ -1,6 +1,127 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
 use super::{private::PropertyId, properties_table::PropertyTable};
 
+/// Returned when connecting a property to its new dependencies would make it depend on
+/// itself. Lists the debug names of the properties along the cycle, starting and ending
+/// with the property being connected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyCycle {
+    pub path: Vec<String>,
+    /// Whether the path ends at a property whose dependents couldn't be looked at, because
+    /// it was being recomputed or modified, rather than closing the cycle
+    pub possible: bool,
+}
+
+impl Display for PropertyCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.possible {
+            write!(
+                f,
+                "possible property cycle: {} -> ?",
+                self.path.join(" -> ")
+            )
+        } else {
+            write!(f, "property cycle: {}", self.path.join(" -> "))
+        }
+    }
+}
+
+impl std::error::Error for PropertyCycle {}
+
 impl PropertyTable {
+    /// Errors if id would depend on itself when connected to inbound, by
+    /// looking for a path from id to any of inbound along outbound edges.
+    /// The edges of properties whose data is taken out of the table, e.g. while
+    /// they're being recomputed, can't be followed, so reaching one of them is
+    /// reported as a possible cycle.
+    pub fn check_acyclic(
+        &self,
+        id: PropertyId,
+        inbound: &[PropertyId],
+    ) -> Result<(), PropertyCycle> {
+        if inbound.is_empty() {
+            return Ok(());
+        }
+        let inbound: HashSet<_> = inbound.iter().copied().collect();
+        let mut parents = std::collections::HashMap::new();
+        let mut to_visit = vec![id];
+        let mut reached = inbound.contains(&id).then_some(id);
+        let mut possible = false;
+        while let (None, Some(visiting)) = (reached, to_visit.pop()) {
+            if !self.contains(visiting) {
+                if self.property_map.borrow().contains_key(visiting) {
+                    reached = Some(visiting);
+                    possible = true;
+                }
+                continue;
+            }
+            let outbound =
+                self.with_property_data(visiting, |property_data| property_data.outbound.clone());
+            for dep_id in outbound {
+                if dep_id == id || parents.contains_key(&dep_id) {
+                    continue;
+                }
+                parents.insert(dep_id, visiting);
+                if inbound.contains(&dep_id) {
+                    reached = Some(dep_id);
+                    break;
+                }
+                to_visit.push(dep_id);
+            }
+        }
+        let Some(mut last) = reached else {
+            return Ok(());
+        };
+        let mut path = if possible { vec![last] } else { vec![id, last] };
+        while let Some(&parent) = parents.get(&last) {
+            path.push(parent);
+            last = parent;
+        }
+        Err(PropertyCycle {
+            path: path
+                .into_iter()
+                .rev()
+                .map(|id| {
+                    self.debug_name_opt(id)
+                        .unwrap_or_else(|| format!("{:?}", id))
+                })
+                .collect(),
+            possible,
+        })
+    }
+
+    /// Orders roots and the properties depending on them so that each comes after everything
+    /// it depends on
+    pub fn topological_order(&self, roots: &[PropertyId]) -> Vec<PropertyId> {
+        let mut visited = HashSet::new();
+        let mut post_order = Vec::new();
+        for &root in roots {
+            if !self.contains(root) || !visited.insert(root) {
+                continue;
+            }
+            // depth first along outbound edges, keeping the index of the next edge to follow
+            let mut stack = vec![(root, 0)];
+            while let Some((id, next)) = stack.pop() {
+                let outbound = self.with_property_data(id, |property_data| {
+                    property_data.outbound.get(next).copied()
+                });
+                match outbound {
+                    Some(dep_id) => {
+                        stack.push((id, next + 1));
+                        if self.contains(dep_id) && visited.insert(dep_id) {
+                            stack.push((dep_id, 0));
+                        }
+                    }
+                    None => post_order.push(id),
+                }
+            }
+        }
+        post_order.reverse();
+        post_order
+    }
+
     /// marks dependencies of self dirty recursively
     pub fn dirtify_outbound(&self, id: PropertyId) {
         let mut to_dirtify =
@@ -0,0 +1,185 @@
+//! Introspection of the property graph, for finding properties that recompute too often or
+//! depend on each other in unexpected ways. A [`PropertyGraph`] is a snapshot of every live
+//! property with its name, value, dependencies and recompute count, and can be dumped as
+//! JSON or as DOT for graphviz.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+use slotmap::Key;
+
+use crate::{Color, Numeric, PaxValue, Percent, Rotation, Size};
+
+use super::{
+    properties_table::{PropertyType, TypedPropertyData, PROPERTY_TABLE},
+    PropertyValue,
+};
+
+/// What the inspector can tell about a property without knowing its type
+pub(crate) struct InspectedValue {
+    type_name: &'static str,
+    value: Option<String>,
+    computed: bool,
+}
+
+/// Describes the type erased data of a property of type `T`
+pub(crate) fn inspect<T: PropertyValue>(typed_data: &dyn Any) -> InspectedValue {
+    let typed_data = typed_data
+        .downcast_ref::<TypedPropertyData<T>>()
+        .expect("inspected property data should be of the type it was created with");
+    InspectedValue {
+        type_name: std::any::type_name::<T>(),
+        value: describe(&typed_data.value),
+        computed: matches!(typed_data.property_type, PropertyType::Computed { .. }),
+    }
+}
+
+/// Property values aren't required to be `Debug`, so only the common value types are shown
+fn describe(value: &dyn Any) -> Option<String> {
+    macro_rules! describe_as {
+        ($($type:ty),*) => {
+            $(
+                if let Some(value) = value.downcast_ref::<$type>() {
+                    return Some(format!("{:?}", value));
+                }
+            )*
+        };
+    }
+    describe_as!(
+        bool, char, String, f32, f64, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128,
+        usize, Numeric, Size, Percent, Rotation, Color, PaxValue
+    );
+    None
+}
+
+/// A property in a [`PropertyGraph`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "crate::serde")]
+pub struct PropertyNode {
+    pub id: u64,
+    pub name: Option<String>,
+    pub type_name: &'static str,
+    /// The last computed value, if it is of a type the inspector knows how to show.
+    /// Capturing a graph doesn't recompute dirty properties.
+    pub value: Option<String>,
+    pub computed: bool,
+    pub dirty: bool,
+    /// How many times the value has been recomputed, or eased by a transition
+    pub recomputes: u64,
+    /// The properties this one depends on
+    pub inbound: Vec<u64>,
+    /// The properties depending on this one
+    pub outbound: Vec<u64>,
+}
+
+impl PropertyNode {
+    fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("#{} {}", self.id, self.type_name))
+    }
+}
+
+/// A snapshot of the dependency graph of all properties on this thread
+#[derive(Debug, Clone, Serialize)]
+#[serde(crate = "crate::serde")]
+pub struct PropertyGraph {
+    pub nodes: Vec<PropertyNode>,
+}
+
+impl PropertyGraph {
+    pub fn capture() -> Self {
+        PROPERTY_TABLE.with(|t| {
+            let nodes = t
+                .available_ids()
+                .into_iter()
+                .map(|id| {
+                    let name = t.debug_name_opt(id);
+                    t.with_property_data(id, |property_data| {
+                        let inspected = property_data.inspect();
+                        PropertyNode {
+                            id: id.data().as_ffi(),
+                            name,
+                            type_name: inspected.type_name,
+                            value: inspected.value,
+                            computed: inspected.computed,
+                            dirty: property_data.dirty,
+                            recomputes: property_data.recomputes,
+                            inbound: ids_of(&property_data.inbound),
+                            outbound: ids_of(&property_data.outbound),
+                        }
+                    })
+                })
+                .collect();
+            PropertyGraph { nodes }
+        })
+    }
+
+    pub fn get(&self, id: u64) -> Option<&PropertyNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// The `count` properties recomputed the most since `earlier` was captured, along with
+    /// how many times they were. Capturing a graph every frame and comparing it to the one
+    /// before shows the hottest properties per frame.
+    pub fn hottest_since(
+        &self,
+        earlier: &PropertyGraph,
+        count: usize,
+    ) -> Vec<(&PropertyNode, u64)> {
+        let before: HashMap<_, _> = earlier
+            .nodes
+            .iter()
+            .map(|node| (node.id, node.recomputes))
+            .collect();
+        let mut hottest: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let since = before.get(&node.id).copied().unwrap_or(0);
+                (node, node.recomputes.saturating_sub(since))
+            })
+            .filter(|(_, recomputes)| *recomputes > 0)
+            .collect();
+        hottest.sort_by(|(_, a), (_, b)| b.cmp(a));
+        hottest.truncate(count);
+        hottest
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("property graphs should serialize")
+    }
+
+    /// Renders the graph in the graphviz DOT language, with edges pointing from each
+    /// property to the properties depending on it
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph properties {\n");
+        for node in &self.nodes {
+            let mut label = node.label();
+            if let Some(value) = &node.value {
+                write!(label, "\n{}", value).unwrap();
+            }
+            write!(label, "\nrecomputes: {}", node.recomputes).unwrap();
+            let shape = if node.computed { "ellipse" } else { "box" };
+            writeln!(
+                dot,
+                "    p{} [label={:?}, shape={}];",
+                node.id, label, shape
+            )
+            .unwrap();
+        }
+        for node in &self.nodes {
+            for outbound in &node.outbound {
+                writeln!(dot, "    p{} -> p{};", node.id, outbound).unwrap();
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn ids_of(ids: &[super::private::PropertyId]) -> Vec<u64> {
+    ids.iter().map(|id| id.data().as_ffi()).collect()
+}
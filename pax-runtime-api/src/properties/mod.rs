@@ -2,14 +2,19 @@ use serde::{Deserialize, Serialize};
 use std::{marker::PhantomData, rc::Rc};
 
 mod graph_operations;
+mod inspector;
 mod properties_table;
 #[cfg(test)]
 mod tests;
 mod untyped_property;
 
-use crate::{EasingCurve, Interpolatable, TransitionQueueEntry};
+use crate::{
+    EasingCurve, Interpolatable, TransitionDuration, TransitionQueueEntry, TransitionTime,
+};
 
-use self::properties_table::{PropertyType, PROPERTY_TIME};
+use self::properties_table::{PropertyType, PROPERTY_MILLIS, PROPERTY_TIME};
+pub use graph_operations::PropertyCycle;
+pub use inspector::{PropertyGraph, PropertyNode};
 use properties_table::PROPERTY_TABLE;
 pub use untyped_property::UntypedProperty;
 
@@ -104,20 +109,43 @@ impl<T: PropertyValue> Property<T> {
         }
     }
 
-    pub fn ease_to(&self, end_val: T, time: u64, curve: EasingCurve) {
-        self.ease_to_value(end_val, time, curve, true);
+    /// Whether this property is a subscription, see [`Property::subscription`]
+    fn mark_subscriber(&self) {
+        PROPERTY_TABLE.with(|t| {
+            t.with_property_data_mut(self.untyped.id, |property_data| {
+                property_data.subscriber = true;
+            })
+        });
+    }
+
+    /// Transitions to `end_val` over `duration`, e.g. `Duration::from_millis(300)`,
+    /// starting from the value right now
+    pub fn ease_to(&self, end_val: T, duration: impl Into<TransitionDuration>, curve: EasingCurve) {
+        self.ease_to_value(end_val, duration.into(), curve, true);
     }
 
-    pub fn ease_to_later(&self, end_val: T, time: u64, curve: EasingCurve) {
-        self.ease_to_value(end_val, time, curve, false);
+    /// Transitions to `end_val` over `duration` once the transitions already queued are done
+    pub fn ease_to_later(
+        &self,
+        end_val: T,
+        duration: impl Into<TransitionDuration>,
+        curve: EasingCurve,
+    ) {
+        self.ease_to_value(end_val, duration.into(), curve, false);
     }
 
-    fn ease_to_value(&self, end_val: T, time: u64, curve: EasingCurve, overwrite: bool) {
+    fn ease_to_value(
+        &self,
+        end_val: T,
+        duration: TransitionDuration,
+        curve: EasingCurve,
+        overwrite: bool,
+    ) {
         PROPERTY_TABLE.with(|t| {
             t.transition(
                 self.untyped.id,
                 TransitionQueueEntry {
-                    duration_frames: time,
+                    duration,
                     curve,
                     ending_value: end_val,
                 },
@@ -159,11 +187,19 @@ impl<T: PropertyValue> Property<T> {
 
     /// replaces a properties evaluation/inbounds/value to be the same as
     /// target, while keeping its dependents.
-    /// WARNING: this method can introduce circular dependencies if one is not careful.
     /// Using it wrongly can introduce memory leaks and inconsistent property behavior.
     /// This method can be used to replace an inner value from for example a literal to
-    /// a computed computed, while keeping the link to its dependents
+    /// a computed computed, while keeping the link to its dependents.
+    /// If target depends on self, this would introduce a cycle: the error is logged and
+    /// self is left as it was. See [`Property::try_replace_with`].
     pub fn replace_with(&self, target: Property<T>) {
+        if let Err(cycle) = self.try_replace_with(target) {
+            log::error!("failed to replace property: {}", cycle);
+        }
+    }
+
+    /// Like [`Property::replace_with`], but returns the cycle it would have introduced
+    pub fn try_replace_with(&self, target: Property<T>) -> Result<(), PropertyCycle> {
         PROPERTY_TABLE.with(|t| {
             // we know self contains T, and that target contains T, so this should never panic
             t.replace_property_keep_outbound_connections::<T>(self.untyped.id, target.untyped.id)
@@ -176,6 +212,45 @@ impl<T: PropertyValue> Property<T> {
     }
 }
 
+impl Property<()> {
+    /// A computed property that runs `f` whenever `dependencies` change. Like other
+    /// computed properties, it runs when read, but also right after a [`Property::batch`]
+    /// changing what it depends on closes.
+    pub fn subscription(f: impl Fn() + 'static, dependencies: &[UntypedProperty]) -> Self {
+        let subscription = Self::computed(f, dependencies);
+        subscription.mark_subscriber();
+        subscription
+    }
+
+    /// Runs `f` as a single update: properties set inside it don't dirty their dependents
+    /// until it returns. Then the subscriptions depending on them run once, in topological
+    /// order, so that none of them sees some of the changes but not others, and computed
+    /// properties are evaluated when next read. Computed properties read inside `f` keep
+    /// their value from before the batch. Batches can be nested, the outermost one applies
+    /// the changes.
+    ///
+    /// ```ignore
+    /// Property::batch(|| {
+    ///     x.set(10.0);
+    ///     y.set(20.0);
+    ///     width.set(100.0);
+    /// });
+    /// ```
+    pub fn batch<V>(f: impl FnOnce() -> V) -> V {
+        struct BatchGuard;
+        impl Drop for BatchGuard {
+            fn drop(&mut self) {
+                // if f panicked, still dirty what it changed, but don't run any subscriptions
+                let run_subscribers = !std::thread::panicking();
+                PROPERTY_TABLE.with(|t| t.end_batch(run_subscribers));
+            }
+        }
+        PROPERTY_TABLE.with(|t| t.begin_batch());
+        let _guard = BatchGuard;
+        f()
+    }
+}
+
 impl<T: PropertyValue> Default for Property<T> {
     fn default() -> Self {
         Property::new(T::default())
@@ -212,3 +287,15 @@ pub fn property_table_total_properties_count() -> usize {
 pub fn register_time(prop: &Property<u64>) {
     PROPERTY_TIME.with_borrow_mut(|time| *time = prop.clone());
 }
+
+/// Sets the milliseconds elapsed that time-based transitions are driven by, once a frame
+/// before advancing the time registered with [`register_time`]
+pub fn set_elapsed_millis(millis: f64) {
+    PROPERTY_MILLIS.with(|time| time.set(millis));
+}
+
+/// The current values of the time registered with [`register_time`] and of the
+/// milliseconds elapsed
+pub(crate) fn current_time() -> TransitionTime {
+    properties_table::transition_time()
+}
@@ -1,16 +1,35 @@
-use std::{any::Any, cell::RefCell, rc::Rc};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 use slotmap::{SlotMap, SparseSecondaryMap};
 
-use crate::{Property, TransitionManager, TransitionQueueEntry};
+use crate::{Property, TransitionManager, TransitionQueueEntry, TransitionTime};
 
-use super::{private::PropertyId, PropertyValue};
+use super::{
+    graph_operations::PropertyCycle,
+    inspector::{self, InspectedValue},
+    private::PropertyId,
+    PropertyValue,
+};
 
 thread_local! {
     /// Global property table used to store data backing dirty-dag
     pub(crate) static PROPERTY_TABLE: PropertyTable = PropertyTable::default();
     /// Property time variable, to be used by
     pub(crate) static PROPERTY_TIME: RefCell<Property<u64>> = RefCell::new(Property::new(0));
+    /// Milliseconds elapsed, set once a frame, that time-based transitions are driven by
+    pub(crate) static PROPERTY_MILLIS: Property<f64> = Property::new(0.0);
+}
+
+/// The frame and milliseconds elapsed, read before borrowing the table
+pub(crate) fn transition_time() -> TransitionTime {
+    TransitionTime {
+        frames: PROPERTY_TIME.with_borrow(|time| time.get()),
+        millis: PROPERTY_MILLIS.with(|millis| millis.get()),
+    }
 }
 
 /// The main collection of data associated with a specific property id
@@ -27,9 +46,22 @@ pub struct PropertyData {
     // has been changed. For computed this can be any other props,
     // for literals, only time variable
     pub dirty: bool,
+    // Number of times the value has been recomputed or eased
+    pub recomputes: u64,
+    // Describes the type erased typed data, for the inspector
+    inspect: fn(&dyn Any) -> InspectedValue,
+    // Brings the value up to date without knowing its type, when running subscriptions
+    pub(crate) update: fn(&PropertyTable, PropertyId),
+    // Whether this is a subscription, run when a batch changes what it depends on
+    // instead of waiting to be read, see [`Property::subscription`]
+    pub(crate) subscriber: bool,
 }
 
 impl PropertyData {
+    pub(crate) fn inspect(&self) -> InspectedValue {
+        (self.inspect)(&*self.typed_data)
+    }
+
     fn typed_data<T: 'static>(&mut self) -> &mut TypedPropertyData<T> {
         self.typed_data
             .downcast_mut::<TypedPropertyData<T>>()
@@ -38,10 +70,10 @@ impl PropertyData {
 }
 
 pub struct TypedPropertyData<T> {
-    value: T,
+    pub(crate) value: T,
     transition_manager: Option<TransitionManager<T>>,
     // Specialization data (computed/literal etc)
-    property_type: PropertyType<T>,
+    pub(crate) property_type: PropertyType<T>,
 }
 
 /// Specialization data only needed for different kinds of properties
@@ -61,6 +93,10 @@ pub(crate) struct PropertyTable {
     // Box<dyn Any> is of type Box<Entry<T>> where T is the proptype
     pub(crate) property_map: RefCell<SlotMap<PropertyId, Entry>>,
     debug_names: RefCell<SparseSecondaryMap<PropertyId, String>>,
+    // How many batches are open, see [`PropertyTable::begin_batch`]
+    batch_depth: Cell<usize>,
+    // Properties changed in the open batches, whose dependents are dirtied once they close
+    batched: RefCell<Vec<PropertyId>>,
 }
 
 pub struct Entry {
@@ -95,7 +131,51 @@ impl PropertyTable {
             typed_data.value = new_val;
             property_data.dirty = false;
         });
-        self.dirtify_outbound(id);
+        self.changed(id);
+    }
+
+    /// Dirties the dependents of a changed property, or defers doing so until the open
+    /// batches close
+    fn changed(&self, id: PropertyId) {
+        if self.batch_depth.get() > 0 {
+            self.batched.borrow_mut().push(id);
+        } else {
+            self.dirtify_outbound(id);
+        }
+    }
+
+    /// Opens a batch: until it closes, setting properties doesn't dirty their dependents.
+    /// Batches can be nested, only closing the outermost one has an effect.
+    pub fn begin_batch(&self) {
+        self.batch_depth.set(self.batch_depth.get() + 1);
+    }
+
+    /// Closes a batch. Closing the outermost one dirties the dependents of everything set
+    /// while it was open, and if `run_subscribers`, runs the subscriptions among them in
+    /// topological order. Other computed properties are evaluated when next read.
+    pub fn end_batch(&self, run_subscribers: bool) {
+        let depth = self.batch_depth.get() - 1;
+        self.batch_depth.set(depth);
+        if depth > 0 {
+            return;
+        }
+        let changed = std::mem::take(&mut *self.batched.borrow_mut());
+        for &id in &changed {
+            if self.contains(id) {
+                self.dirtify_outbound(id);
+            }
+        }
+        if run_subscribers {
+            for id in self.topological_order(&changed) {
+                let update = self.with_property_data(id, |property_data| {
+                    (property_data.subscriber && property_data.dirty)
+                        .then_some(property_data.update)
+                });
+                if let Some(update) = update {
+                    update(self, id);
+                }
+            }
+        }
     }
 
     /// Adds a new untyped property entry
@@ -124,6 +204,10 @@ impl PropertyTable {
                         transition_manager: None,
                     }),
                     outbound: Vec::with_capacity(0),
+                    recomputes: 0,
+                    inspect: inspector::inspect::<T>,
+                    update: PropertyTable::update_value::<T>,
+                    subscriber: false,
                 }),
             };
             sm.insert(entry)
@@ -145,7 +229,11 @@ impl PropertyTable {
         overwrite: bool,
     ) {
         let mut should_connect_to_time = false;
-        let (time_id, curr_time) = PROPERTY_TIME.with_borrow(|time| (time.untyped.id, time.get()));
+        let time_ids = [
+            PROPERTY_TIME.with_borrow(|time| time.untyped.id),
+            PROPERTY_MILLIS.with(|millis| millis.untyped.id),
+        ];
+        let curr_time = transition_time();
         self.with_property_data_mut(id, |property_data: &mut PropertyData| {
             let typed_data = property_data.typed_data::<T>();
             let transition_manager = typed_data
@@ -155,14 +243,19 @@ impl PropertyTable {
                 transition_manager.reset_transitions(curr_time);
             }
             transition_manager.push_transition(transition);
-            if !property_data.inbound.contains(&time_id) {
-                should_connect_to_time = true;
-                property_data.inbound.push(time_id);
+            // a zero-length transition takes effect without waiting for the next frame
+            property_data.dirty = true;
+            for time_id in time_ids {
+                if !property_data.inbound.contains(&time_id) {
+                    should_connect_to_time = true;
+                    property_data.inbound.push(time_id);
+                }
             }
         });
         if should_connect_to_time {
             self.connect_inbound(id);
         }
+        self.changed(id);
     }
 
     /// Gives mutable access to a entry in the property table
@@ -235,11 +328,19 @@ impl PropertyTable {
     /// computed / its value to the way target does.
     /// NOTE: source_id and target_id need to both contain
     /// the type T, or else this panics
+    /// Fails without modifying anything if source would end up depending on itself, which
+    /// is looked for by walking everything depending on source, unless target depends on
+    /// nothing.
     pub fn replace_property_keep_outbound_connections<T: Clone + 'static>(
         &self,
         source_id: PropertyId,
         target_id: PropertyId,
-    ) {
+    ) -> Result<(), PropertyCycle> {
+        let new_inbound = self.with_property_data(target_id, |target_property_data| {
+            target_property_data.inbound.clone()
+        });
+        self.check_acyclic(source_id, &new_inbound)?;
+
         // disconnect self from its dependents, in preparation of overwriting
         // with targets inbound. (only does something for computed values)
         self.disconnect_inbound(source_id);
@@ -269,6 +370,7 @@ impl PropertyTable {
         let target_name = self.debug_name(target_id);
         let mut names = self.debug_names.borrow_mut();
         names.insert(source_id, format!("{}", target_name));
+        Ok(())
     }
 
     // re-computes the value if dirty
@@ -285,13 +387,15 @@ impl PropertyTable {
                 PropertyType::Computed { evaluator, .. } => Some(Rc::clone(&evaluator)),
                 PropertyType::Literal => {
                     let tm = typed_data.transition_manager.as_mut()?;
-                    let curr_time = PROPERTY_TIME.with_borrow(|time| time.get());
-                    let value = tm.compute_eased_value(curr_time);
+                    let value = tm.compute_eased_value(transition_time());
                     if let Some(interp_value) = value {
                         typed_data.value = interp_value;
+                        property_data.recomputes += 1;
                     } else {
-                        //transition must be over, let's remove dependencies
+                        //transition must be over, let's settle on its final value
+                        //(in case it wasn't read on its last frame) and remove dependencies
                         remove_dep_from_literal = true;
+                        typed_data.value = tm.checkpoint_value().clone();
                         typed_data.transition_manager = None;
                     }
                     None
@@ -315,6 +419,7 @@ impl PropertyTable {
             self.with_property_data_mut(id, |property_data| {
                 let typed_data = property_data.typed_data();
                 typed_data.value = new_value;
+                property_data.recomputes += 1;
             })
         }
     }
@@ -336,13 +441,31 @@ impl PropertyTable {
     }
 
     pub fn debug_name(&self, id: PropertyId) -> String {
-        self.debug_names
+        self.debug_name_opt(id)
+            .unwrap_or_else(|| "<NO DEBUG NAME>".to_owned())
+    }
+
+    pub(crate) fn debug_name_opt(&self, id: PropertyId) -> Option<String> {
+        self.debug_names.borrow().get(id).cloned()
+    }
+
+    /// Whether the property exists, and its data is in the table
+    pub(crate) fn contains(&self, id: PropertyId) -> bool {
+        self.property_map
             .borrow()
             .get(id)
-            .as_ref()
-            .map(|s| s.as_str())
-            .unwrap_or("<NO DEBUG NAME>")
-            .to_owned()
+            .is_some_and(|entry| entry.data.is_some())
+    }
+
+    /// Ids of the properties whose data is in the table, leaving out those being
+    /// recomputed or modified right now
+    pub(crate) fn available_ids(&self) -> Vec<PropertyId> {
+        self.property_map
+            .borrow()
+            .iter()
+            .filter(|(_, entry)| entry.data.is_some())
+            .map(|(id, _)| id)
+            .collect()
     }
 
     pub(crate) fn total_properties_count(&self) -> usize {
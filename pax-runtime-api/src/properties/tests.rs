@@ -1,4 +1,9 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
+
 use super::*;
+use crate::Size;
 
 #[test]
 fn test_literal_set_get() {
@@ -85,3 +90,301 @@ fn test_recursive_props() {
     }
     assert!(PROPERTY_TABLE.with(|t| t.property_map.borrow().is_empty()));
 }
+
+#[test]
+fn test_time_based_transitions() {
+    let time = Property::new(0);
+    register_time(&time);
+    let tick = |millis: f64| {
+        set_elapsed_millis(millis);
+        time.set(time.get() + 1);
+    };
+    let prop = Property::new(0.0);
+    prop.ease_to(100.0, Duration::from_millis(100), EasingCurve::Linear);
+    prop.ease_to_later(0.0, TransitionDuration::Frames(2), EasingCurve::Linear);
+
+    // dropped frames don't slow it down
+    tick(50.0);
+    assert_eq!(prop.get(), 50.0);
+    tick(75.0);
+    assert_eq!(prop.get(), 75.0);
+    // the frames after it count from the frame it ended on
+    tick(125.0);
+    assert_eq!(prop.get(), 50.0);
+    tick(150.0);
+    assert_eq!(prop.get(), 0.0);
+}
+
+#[test]
+fn test_transition_dirties_dependents() {
+    let time = Property::new(0);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    let p = prop.clone();
+    let doubled = Property::computed(move || p.get() * 2.0, &[prop.untyped()]);
+    assert_eq!(doubled.get(), 0.0);
+
+    // a zero-length transition is visible to dependents without waiting for a tick
+    prop.ease_to(10.0, TransitionDuration::Frames(0), EasingCurve::Linear);
+    assert_eq!(doubled.get(), 20.0);
+    prop.ease_to(30.0, TransitionDuration::Millis(0.0), EasingCurve::Linear);
+    assert_eq!(doubled.get(), 60.0);
+
+    prop.ease_to(50.0, TransitionDuration::Frames(2), EasingCurve::Linear);
+    time.set(time.get() + 1);
+    assert_eq!(doubled.get(), 80.0);
+}
+
+#[test]
+fn test_zero_length_transitions_in_a_queue() {
+    let time = Property::new(0);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    prop.ease_to(10.0, TransitionDuration::Frames(0), EasingCurve::Linear);
+    prop.ease_to_later(20.0, TransitionDuration::Frames(2), EasingCurve::Linear);
+    prop.ease_to_later(40.0, TransitionDuration::Frames(0), EasingCurve::Linear);
+    assert_eq!(prop.get(), 10.0);
+    time.set(time.get() + 1);
+    assert_eq!(prop.get(), 15.0);
+    time.set(time.get() + 1);
+    assert_eq!(prop.get(), 20.0);
+    time.set(time.get() + 1);
+    assert_eq!(prop.get(), 40.0);
+}
+
+#[test]
+fn test_transition_settles_on_its_final_value() {
+    let time = Property::new(0);
+    register_time(&time);
+    let prop = Property::new(0.0);
+    let p = prop.clone();
+    let doubled = Property::computed(move || p.get() * 2.0, &[prop.untyped()]);
+    prop.ease_to(100.0, TransitionDuration::Frames(2), EasingCurve::InQuad);
+
+    // not read while it ran
+    for _ in 0..5 {
+        time.set(time.get() + 1);
+    }
+    assert_eq!(doubled.get(), 200.0);
+    assert_eq!(prop.get(), 100.0);
+
+    // nothing is left over to ease once it's done
+    prop.set(5.0);
+    time.set(time.get() + 1);
+    assert_eq!(prop.get(), 5.0);
+    assert_eq!(doubled.get(), 10.0);
+}
+
+#[test]
+fn test_interrupted_spring_keeps_velocity() {
+    let time = Property::new(0);
+    register_time(&time);
+    let tick = || {
+        time.set(time.get() + 1);
+        set_elapsed_millis(time.get() as f64 * 1000.0 / 60.0);
+    };
+    let prop = Property::new(0.0);
+    prop.ease_to(100.0, TransitionDuration::Frames(10), EasingCurve::Linear);
+    tick();
+    tick();
+    assert_eq!(prop.get(), 20.0);
+
+    // heading back, a spring first carries on in the direction it was moving in
+    let spring = crate::Spring::default();
+    prop.ease_to(0.0, spring.duration(), EasingCurve::Spring(spring));
+    assert!((prop.get() - 20.0).abs() < 1e-9);
+    tick();
+    assert!(prop.get() > 20.0);
+    for _ in 0..600 {
+        tick();
+    }
+    assert!(prop.get().abs() < 1e-9);
+}
+
+#[test]
+fn test_interrupted_size_spring_keeps_velocity() {
+    let time = Property::new(0);
+    register_time(&time);
+    let tick = || {
+        time.set(time.get() + 1);
+        set_elapsed_millis(time.get() as f64 * 1000.0 / 60.0);
+    };
+    let pixels = |size: Size| size.get_pixels(0.0);
+    let prop = Property::new(Size::Pixels(0.into()));
+    prop.ease_to(
+        Size::Pixels(100.into()),
+        TransitionDuration::Frames(10),
+        EasingCurve::Linear,
+    );
+    tick();
+    tick();
+    assert_eq!(pixels(prop.get()), 20.0);
+
+    // retargeted, the spring first carries on in the direction the size was moving in
+    let spring = crate::Spring::default();
+    prop.ease_to(
+        Size::Pixels(0.into()),
+        spring.duration(),
+        EasingCurve::Spring(spring),
+    );
+    tick();
+    assert!(pixels(prop.get()) > 20.0);
+    assert!(matches!(prop.get(), Size::Pixels(_)));
+    for _ in 0..600 {
+        tick();
+    }
+    assert!(pixels(prop.get()).abs() < 1e-9);
+}
+
+#[test]
+fn test_replace_with_cycle() {
+    let a = Property::new_with_name(1, "a");
+    let a_cloned = a.clone();
+    let b = Property::computed_with_name(move || a_cloned.get() + 1, &[a.untyped()], "b");
+    let b_cloned = b.clone();
+    let c = Property::computed_with_name(move || b_cloned.get() * 2, &[b.untyped()], "c");
+
+    let c_cloned = c.clone();
+    let err = a
+        .try_replace_with(Property::computed_with_name(
+            move || c_cloned.get(),
+            &[c.untyped()],
+            "from c",
+        ))
+        .unwrap_err();
+    assert_eq!(err.path, ["a", "b", "c", "a"]);
+    assert!(!err.possible);
+    assert_eq!(err.to_string(), "property cycle: a -> b -> c -> a");
+
+    // the property is left untouched
+    a.set(2);
+    assert_eq!(c.get(), 6);
+    let a_cloned = a.clone();
+    let err = a
+        .try_replace_with(Property::computed(move || a_cloned.get(), &[a.untyped()]))
+        .unwrap_err();
+    assert_eq!(err.path, ["a", "a"]);
+}
+
+#[test]
+fn test_check_acyclic_through_taken_out_properties() {
+    let a = Property::new_with_name(1, "a");
+    let a_cloned = a.clone();
+    let b = Property::computed_with_name(move || a_cloned.get() + 1, &[a.untyped()], "b");
+    let b_cloned = b.clone();
+    let c = Property::computed_with_name(move || b_cloned.get() * 2, &[b.untyped()], "c");
+    assert_eq!(c.get(), 4);
+
+    let (a, b, c) = (a.untyped.id, b.untyped.id, c.untyped.id);
+    PROPERTY_TABLE.with(|t| {
+        assert!(t.check_acyclic(a, &[c]).is_err());
+        // as while b is being recomputed, the path through it can't be followed, so the
+        // cycle is reported as possible
+        t.with_property_data_mut(b, |_| {
+            let err = t.check_acyclic(a, &[c]).unwrap_err();
+            assert!(err.possible);
+            assert_eq!(err.path, ["a", "b"]);
+            assert!(!t.check_acyclic(a, &[b]).unwrap_err().possible);
+        });
+        // nothing to depend on, nothing to walk
+        t.with_property_data_mut(b, |_| assert!(t.check_acyclic(a, &[]).is_ok()));
+    });
+}
+
+#[test]
+fn test_property_graph() {
+    let a = Property::new_with_name(1.0, "a");
+    let a_cloned = a.clone();
+    let b = Property::computed_with_name(move || a_cloned.get() * 2.0, &[a.untyped()], "b");
+    assert_eq!(b.get(), 2.0);
+
+    let before = PropertyGraph::capture();
+    for i in 0..3 {
+        a.set(i as f64);
+        b.get();
+    }
+    let graph = PropertyGraph::capture();
+    let node_a = graph
+        .nodes
+        .iter()
+        .find(|n| n.name.as_deref() == Some("a"))
+        .unwrap();
+    let node_b = graph
+        .nodes
+        .iter()
+        .find(|n| n.name.as_deref() == Some("b"))
+        .unwrap();
+    assert!(!node_a.computed && node_b.computed);
+    assert_eq!(node_b.value.as_deref(), Some("4.0"));
+    assert_eq!(node_b.type_name, "f64");
+    assert_eq!(node_a.outbound, [node_b.id]);
+    assert_eq!(node_b.inbound, [node_a.id]);
+
+    let hottest = graph.hottest_since(&before, 5);
+    assert_eq!(hottest.len(), 1);
+    assert_eq!(hottest[0].0.id, node_b.id);
+    assert_eq!(hottest[0].1, 3);
+
+    let dot = graph.to_dot();
+    assert!(dot.contains(&format!("p{} -> p{};", node_a.id, node_b.id)));
+    assert!(graph.to_json().contains("\"name\": \"b\""));
+}
+
+#[test]
+fn test_batch() {
+    let x = Property::new(1);
+    let y = Property::new(2);
+    let evaluations = Rc::new(Cell::new(0));
+    let (x_cloned, y_cloned, evaluations_cloned) = (x.clone(), y.clone(), evaluations.clone());
+    let sum = Property::computed(
+        move || {
+            evaluations_cloned.set(evaluations_cloned.get() + 1);
+            x_cloned.get() + y_cloned.get()
+        },
+        &[x.untyped(), y.untyped()],
+    );
+    let observed = Rc::new(RefCell::new(Vec::new()));
+    let (sum_cloned, observed_cloned) = (sum.clone(), observed.clone());
+    let subscription = Property::subscription(
+        move || observed_cloned.borrow_mut().push(sum_cloned.get()),
+        &[sum.untyped()],
+    );
+    subscription.get();
+    assert_eq!(*observed.borrow(), [3]);
+
+    let read_inside = Property::batch(|| {
+        x.set(10);
+        Property::batch(|| y.set(20));
+        // neither change has reached the computed yet
+        sum.get()
+    });
+    assert_eq!(read_inside, 3);
+    // closing the batch fired the subscription once, which read the sum with both changes
+    assert_eq!(*observed.borrow(), [3, 30]);
+    assert_eq!(evaluations.get(), 2);
+    subscription.get();
+    assert_eq!(sum.get(), 30);
+    assert_eq!(evaluations.get(), 2);
+}
+
+#[test]
+fn test_batch_leaves_unread_computeds() {
+    let x = Property::new(1);
+    let evaluations = Rc::new(Cell::new(0));
+    let (x_cloned, evaluations_cloned) = (x.clone(), evaluations.clone());
+    let doubled = Property::computed(
+        move || {
+            evaluations_cloned.set(evaluations_cloned.get() + 1);
+            x_cloned.get() * 2
+        },
+        &[x.untyped()],
+    );
+    assert_eq!(doubled.get(), 2);
+    assert_eq!(evaluations.get(), 1);
+
+    Property::batch(|| x.set(5));
+    // no subscription depends on it, so it waits to be read
+    assert_eq!(evaluations.get(), 1);
+    assert_eq!(doubled.get(), 10);
+    assert_eq!(evaluations.get(), 2);
+}
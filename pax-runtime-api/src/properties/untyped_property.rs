@@ -23,7 +23,9 @@ impl Clone for UntypedProperty {
 
 impl Drop for UntypedProperty {
     fn drop(&mut self) {
-        PROPERTY_TABLE.with(|t| {
+        // properties still alive when the thread exits are dropped along with the
+        // table itself, at which point there's nothing left to update
+        let _ = PROPERTY_TABLE.try_with(|t| {
             let ref_count = t.decrease_ref_count(self.id);
             if ref_count == 0 {
                 t.remove_entry(self.id);
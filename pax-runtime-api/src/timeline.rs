@@ -0,0 +1,594 @@
+//! Keyframe animations of several properties at once. A [`Timeline`] plays back by queueing
+//! its keyframes as transitions of the properties it animates, the same way
+//! [`Property::ease_to`] does, and is advanced past the end of each cycle by
+//! [`tick_timelines`]. Keyframes are placed at [`TransitionDuration`] offsets, so a timeline
+//! runs on the same clock as transitions: by frame, to play back deterministically with each
+//! tick, or by milliseconds, to play back at the same speed at any frame rate.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+use crate::properties::{current_time, PropertyValue};
+use crate::{
+    EasingCurve, ImplToFromPaxAny, Interpolatable, Property, TransitionDuration, TransitionTime,
+};
+
+thread_local! {
+    /// Timelines that were played, until they stop
+    static PLAYING_TIMELINES: RefCell<Vec<Weak<RefCell<TimelineState>>>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// The value of a property at an offset into a [`Timeline`]
+pub struct Keyframe<T> {
+    pub offset: TransitionDuration,
+    pub value: T,
+    /// How to ease into this keyframe from the previous one
+    pub curve: EasingCurve,
+}
+
+impl<T> Keyframe<T> {
+    /// A keyframe at `offset` frames, e.g. `Keyframe::new(30, 1.2)`, or at a duration such
+    /// as `Duration::from_millis(500)`
+    pub fn new(offset: impl Into<TransitionDuration>, value: T) -> Self {
+        Self {
+            offset: offset.into(),
+            value,
+            curve: EasingCurve::Linear,
+        }
+    }
+
+    pub fn with_curve(mut self, curve: EasingCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+/// What a [`Timeline`] does when it reaches its end
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TimelineLoop {
+    /// Stops at the end
+    #[default]
+    Once,
+    /// Starts over from the other end
+    Loop,
+    /// Plays back in the opposite direction
+    PingPong,
+}
+
+/// Milliseconds per frame, for offsets on the other clock than the one a timeline runs on
+const FRAME_MILLIS: f64 = 1000.0 / 60.0;
+
+/// The clock a timeline runs on, that of its first keyframe. Positions on it are numbers
+/// of frames or milliseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Clock {
+    #[default]
+    Frames,
+    Millis,
+}
+
+impl Clock {
+    fn of(duration: TransitionDuration) -> Self {
+        match duration {
+            TransitionDuration::Frames(_) => Clock::Frames,
+            TransitionDuration::Millis(_) => Clock::Millis,
+        }
+    }
+
+    /// The position `duration` into the timeline
+    fn position(self, duration: TransitionDuration) -> f64 {
+        match (self, duration) {
+            (Clock::Frames, TransitionDuration::Frames(frames)) => frames as f64,
+            (Clock::Frames, TransitionDuration::Millis(millis)) => (millis / FRAME_MILLIS).round(),
+            (Clock::Millis, TransitionDuration::Frames(frames)) => frames as f64 * FRAME_MILLIS,
+            (Clock::Millis, TransitionDuration::Millis(millis)) => millis,
+        }
+        .max(0.0)
+    }
+
+    fn duration(self, length: f64) -> TransitionDuration {
+        match self {
+            Clock::Frames => TransitionDuration::Frames(length.round().max(0.0) as u64),
+            Clock::Millis => TransitionDuration::Millis(length.max(0.0)),
+        }
+    }
+
+    fn now(self, time: TransitionTime) -> f64 {
+        match self {
+            Clock::Frames => time.frames as f64,
+            Clock::Millis => time.millis,
+        }
+    }
+}
+
+/// A property animated by a timeline
+trait Track {
+    /// Queues the transitions of this track from `playhead` to its end, or back to its start
+    /// if not `forward`, after holding the value at `playhead` for `delay`
+    fn queue(&self, playhead: f64, forward: bool, delay: f64);
+
+    /// Stops the transitions of this track at the value at `playhead`
+    fn hold(&self, playhead: f64);
+}
+
+struct PropertyTrack<T> {
+    property: Property<T>,
+    clock: Clock,
+    /// Sorted by position
+    keyframes: Vec<(f64, T, Rc<EasingCurve>)>,
+}
+
+impl<T: PropertyValue> PropertyTrack<T> {
+    fn value_at(&self, playhead: f64) -> T {
+        let next = self
+            .keyframes
+            .partition_point(|(position, ..)| *position <= playhead);
+        if next == 0 {
+            return self.keyframes[0].1.clone();
+        }
+        let Some((to_position, to, curve)) = self.keyframes.get(next) else {
+            return self.keyframes[next - 1].1.clone();
+        };
+        let (from_position, from, _) = &self.keyframes[next - 1];
+        let progress = (playhead - from_position) / (to_position - from_position);
+        EasingCurve::interpolate(curve, from, to, progress)
+    }
+
+    fn ease_to_later(&self, value: T, length: f64, curve: EasingCurve) {
+        self.property
+            .ease_to_later(value, self.clock.duration(length), curve);
+    }
+}
+
+impl<T: PropertyValue> Track for PropertyTrack<T> {
+    fn queue(&self, playhead: f64, forward: bool, delay: f64) {
+        let value = self.value_at(playhead);
+        self.property
+            .ease_to(value.clone(), self.clock.duration(0.0), EasingCurve::Linear);
+        if delay > 0.0 {
+            self.ease_to_later(value, delay, EasingCurve::Linear);
+        }
+        let (first, last) = (
+            self.keyframes[0].0,
+            self.keyframes[self.keyframes.len() - 1].0,
+        );
+        if forward {
+            if playhead < first {
+                let value = self.keyframes[0].1.clone();
+                self.ease_to_later(value, first - playhead, EasingCurve::Linear);
+            }
+            for segment in self.keyframes.windows(2) {
+                let ((from_position, ..), (to_position, to, curve)) = (&segment[0], &segment[1]);
+                if *to_position <= playhead {
+                    continue;
+                }
+                let start = playhead.max(*from_position);
+                let progress = (start - from_position) / (to_position - from_position);
+                let curve = remaining_curve(curve, progress, 1.0);
+                self.ease_to_later(to.clone(), to_position - start, curve);
+            }
+        } else {
+            if playhead > last {
+                let value = self.keyframes[self.keyframes.len() - 1].1.clone();
+                self.ease_to_later(value, playhead - last, EasingCurve::Linear);
+            }
+            for segment in self.keyframes.windows(2).rev() {
+                let ((from_position, from, _), (to_position, _, curve)) =
+                    (&segment[0], &segment[1]);
+                if *from_position >= playhead {
+                    continue;
+                }
+                let start = playhead.min(*to_position);
+                let progress = (start - from_position) / (to_position - from_position);
+                let curve = remaining_curve(curve, progress, 0.0);
+                self.ease_to_later(from.clone(), start - from_position, curve);
+            }
+        }
+    }
+
+    fn hold(&self, playhead: f64) {
+        self.property.ease_to(
+            self.value_at(playhead),
+            self.clock.duration(0.0),
+            EasingCurve::Linear,
+        );
+    }
+}
+
+/// The part of `curve` from progress `from` to `to`, rescaled to ease from the value at
+/// `from` to the value at `to`. Used to start a transition partway through a segment, or to
+/// play it backwards.
+fn remaining_curve(curve: &Rc<EasingCurve>, from: f64, to: f64) -> EasingCurve {
+    let curve = Rc::clone(curve);
+    let (start, end) = (curve.evaluate(from), curve.evaluate(to));
+    EasingCurve::custom(move |t| {
+        if start == end {
+            t
+        } else {
+            (curve.evaluate(from + t * (to - from)) - start) / (end - start)
+        }
+    })
+}
+
+/// Playback of a timeline. Positions and times are on its clock.
+#[derive(Default)]
+struct TimelineState {
+    tracks: Vec<Box<dyn Track>>,
+    clock: Clock,
+    duration: f64,
+    looping: TimelineLoop,
+    delay: TransitionDuration,
+    /// Position of the playhead when playback last started or stopped
+    playhead: f64,
+    /// Time playback last started at
+    started_at: f64,
+    /// Time playback waits for after `started_at`
+    pending_delay: f64,
+    playing: bool,
+    backwards: bool,
+}
+
+impl TimelineState {
+    fn now(&self) -> f64 {
+        self.clock.now(current_time())
+    }
+
+    fn playhead(&self, now: f64) -> f64 {
+        if !self.playing {
+            return self.playhead;
+        }
+        let elapsed = (now - (self.started_at + self.pending_delay)).max(0.0);
+        if self.backwards {
+            (self.playhead - elapsed).max(0.0)
+        } else {
+            (self.playhead + elapsed).min(self.duration)
+        }
+    }
+
+    /// The position playback stops at in its current direction
+    fn end(&self) -> f64 {
+        if self.backwards {
+            0.0
+        } else {
+            self.duration
+        }
+    }
+
+    fn start(&mut self, now: f64, delay: f64) {
+        self.playing = true;
+        self.started_at = now;
+        self.pending_delay = delay;
+        for track in &self.tracks {
+            track.queue(self.playhead, !self.backwards, delay);
+        }
+    }
+
+    fn stop(&mut self, now: f64) {
+        self.playhead = self.playhead(now);
+        self.playing = false;
+        for track in &self.tracks {
+            track.hold(self.playhead);
+        }
+    }
+
+    fn tick(&mut self, now: f64) {
+        let remaining = (self.playhead - self.end()).abs();
+        if !self.playing || now < self.started_at + self.pending_delay + remaining {
+            return;
+        }
+        self.playhead = self.end();
+        match self.looping {
+            TimelineLoop::Once => self.playing = false,
+            TimelineLoop::Loop => {
+                self.playhead = if self.backwards { self.duration } else { 0.0 };
+                self.start(now, 0.0);
+            }
+            TimelineLoop::PingPong => {
+                self.backwards = !self.backwards;
+                self.start(now, 0.0);
+            }
+        }
+    }
+}
+
+/// A set of properties animated together through keyframes, e.g.
+/// ```ignore
+/// let pulse = Timeline::new()
+///     .with_track(&self.scale, vec![
+///         Keyframe::new(0, 1.0),
+///         Keyframe::new(30, 1.2).with_curve(EasingCurve::OutQuad),
+///     ])
+///     .with_loop(TimelineLoop::PingPong);
+/// pulse.play();
+/// ```
+/// A timeline runs on the clock of its first keyframe, frames in this case. Offsets on the
+/// other clock are converted at 60 frames per second.
+///
+/// Timelines are cheap to clone, and clones control the same playback, so they can be kept
+/// in a `Property<Timeline>` for handlers to play, pause, seek and reverse.
+#[derive(Clone, Default)]
+pub struct Timeline {
+    state: Rc<RefCell<TimelineState>>,
+}
+
+impl ImplToFromPaxAny for Timeline {}
+impl Interpolatable for Timeline {}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Animates `property` through `keyframes`, holding the first keyframe's value before
+    /// it and the last one's after it
+    pub fn with_track<T: PropertyValue>(
+        self,
+        property: &Property<T>,
+        keyframes: Vec<Keyframe<T>>,
+    ) -> Self {
+        let mut state = self.state.borrow_mut();
+        if let (true, Some(first)) = (state.tracks.is_empty(), keyframes.first()) {
+            state.clock = Clock::of(first.offset);
+        }
+        let clock = state.clock;
+        let mut keyframes: Vec<_> = keyframes
+            .into_iter()
+            .map(|keyframe| {
+                (
+                    clock.position(keyframe.offset),
+                    keyframe.value,
+                    Rc::new(keyframe.curve),
+                )
+            })
+            .collect();
+        keyframes.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+        keyframes.dedup_by(|(a, ..), (b, ..)| a == b);
+        if let Some((last, ..)) = keyframes.last() {
+            state.duration = state.duration.max(*last);
+            state.tracks.push(Box::new(PropertyTrack {
+                property: property.clone(),
+                clock,
+                keyframes,
+            }));
+        }
+        drop(state);
+        self
+    }
+
+    pub fn with_loop(self, looping: TimelineLoop) -> Self {
+        self.state.borrow_mut().looping = looping;
+        self
+    }
+
+    /// Waits for `delay` before playing from the start
+    pub fn with_delay(self, delay: impl Into<TransitionDuration>) -> Self {
+        self.state.borrow_mut().delay = delay.into();
+        self
+    }
+
+    /// Plays from the playhead, starting over if it's at the end. Playback from the start
+    /// waits for the delay first.
+    pub fn play(&self) {
+        let mut state = self.state.borrow_mut();
+        if state.playing {
+            return;
+        }
+        let now = state.now();
+        let start = if state.backwards { state.duration } else { 0.0 };
+        if state.playhead == state.end() {
+            state.playhead = start;
+        }
+        let delay = if state.playhead == start {
+            state.clock.position(state.delay)
+        } else {
+            0.0
+        };
+        state.start(now, delay);
+        drop(state);
+
+        PLAYING_TIMELINES.with_borrow_mut(|playing| {
+            if !playing
+                .iter()
+                .any(|timeline| Weak::as_ptr(timeline) == Rc::as_ptr(&self.state))
+            {
+                playing.push(Rc::downgrade(&self.state));
+            }
+        });
+    }
+
+    /// Stops playback, leaving the animated properties at their values at the playhead
+    pub fn pause(&self) {
+        let mut state = self.state.borrow_mut();
+        if state.playing {
+            let now = state.now();
+            state.stop(now);
+        }
+    }
+
+    /// Moves the playhead to `offset`, continuing playback from there if playing
+    pub fn seek(&self, offset: impl Into<TransitionDuration>) {
+        let mut state = self.state.borrow_mut();
+        let now = state.now();
+        state.playhead = state.clock.position(offset.into()).min(state.duration);
+        if state.playing {
+            state.start(now, 0.0);
+        } else {
+            for track in &state.tracks {
+                track.hold(state.playhead);
+            }
+        }
+    }
+
+    /// Flips the direction of playback, continuing from the playhead if playing
+    pub fn reverse(&self) {
+        let mut state = self.state.borrow_mut();
+        let now = state.now();
+        state.playhead = state.playhead(now);
+        state.backwards = !state.backwards;
+        if state.playing {
+            state.start(now, 0.0);
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state.borrow().playing
+    }
+
+    pub fn playhead(&self) -> TransitionDuration {
+        let state = self.state.borrow();
+        state.clock.duration(state.playhead(state.now()))
+    }
+
+    /// The offset of the last keyframe
+    pub fn duration(&self) -> TransitionDuration {
+        let state = self.state.borrow();
+        state.clock.duration(state.duration)
+    }
+}
+
+/// Loops, ping-pongs or stops the playing timelines that reached their end. Run by the
+/// engine every tick, after advancing the time.
+pub fn tick_timelines() {
+    let playing: Vec<_> = PLAYING_TIMELINES.with_borrow_mut(|playing| {
+        playing.retain(|timeline| {
+            timeline
+                .upgrade()
+                .is_some_and(|timeline| timeline.borrow().playing)
+        });
+        playing.iter().filter_map(Weak::upgrade).collect()
+    });
+    for timeline in playing {
+        let mut timeline = timeline.borrow_mut();
+        let now = timeline.now();
+        timeline.tick(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::properties::{register_time, set_elapsed_millis};
+
+    fn advance(time: &Property<u64>, frames: u64) {
+        for _ in 0..frames {
+            time.set(time.get() + 1);
+            tick_timelines();
+        }
+    }
+
+    #[test]
+    fn test_timeline_plays_keyframes() {
+        let time = Property::new(0);
+        register_time(&time);
+        let x = Property::new(0.0);
+        let y = Property::new(0.0);
+        let timeline = Timeline::new()
+            .with_track(&x, vec![Keyframe::new(0, 0.0), Keyframe::new(10, 100.0)])
+            .with_track(
+                &y,
+                vec![
+                    Keyframe::new(5, 10.0),
+                    Keyframe::new(10, 20.0).with_curve(EasingCurve::InQuad),
+                ],
+            );
+        timeline.play();
+        advance(&time, 5);
+        assert_eq!(x.get(), 50.0);
+        assert_eq!(y.get(), 10.0);
+        advance(&time, 4);
+        assert!((y.get() - 16.4).abs() < 1e-9);
+        advance(&time, 1);
+        assert_eq!((x.get(), y.get()), (100.0, 20.0));
+        assert!(!timeline.is_playing());
+    }
+
+    #[test]
+    fn test_timeline_delay_and_ping_pong() {
+        let time = Property::new(0);
+        register_time(&time);
+        let x = Property::new(0.0);
+        let timeline = Timeline::new()
+            .with_track(&x, vec![Keyframe::new(0, 0.0), Keyframe::new(10, 100.0)])
+            .with_loop(TimelineLoop::PingPong)
+            .with_delay(5);
+        timeline.play();
+        advance(&time, 5);
+        assert_eq!(x.get(), 0.0);
+        advance(&time, 10);
+        assert_eq!(x.get(), 100.0);
+        advance(&time, 3);
+        assert_eq!(x.get(), 70.0);
+        assert_eq!(timeline.playhead(), TransitionDuration::Frames(7));
+        advance(&time, 7);
+        assert_eq!(x.get(), 0.0);
+        advance(&time, 2);
+        assert_eq!(x.get(), 20.0);
+        assert!(timeline.is_playing());
+    }
+
+    #[test]
+    fn test_timeline_pause_seek_reverse() {
+        let time = Property::new(0);
+        register_time(&time);
+        let x = Property::new(0.0);
+        let timeline =
+            Timeline::new().with_track(&x, vec![Keyframe::new(0, 0.0), Keyframe::new(10, 100.0)]);
+        timeline.play();
+        advance(&time, 4);
+        timeline.pause();
+        advance(&time, 4);
+        assert_eq!(x.get(), 40.0);
+
+        timeline.seek(8);
+        assert_eq!(x.get(), 80.0);
+        timeline.reverse();
+        timeline.play();
+        advance(&time, 2);
+        assert_eq!(x.get(), 60.0);
+        timeline.reverse();
+        advance(&time, 1);
+        assert_eq!(x.get(), 70.0);
+    }
+
+    #[test]
+    fn test_timeline_in_millis() {
+        let time = Property::new(0);
+        register_time(&time);
+        set_elapsed_millis(0.0);
+        let tick = |millis: f64| {
+            set_elapsed_millis(millis);
+            time.set(time.get() + 1);
+            tick_timelines();
+        };
+        let x = Property::new(0.0);
+        let timeline = Timeline::new()
+            .with_track(
+                &x,
+                vec![
+                    Keyframe::new(Duration::ZERO, 0.0),
+                    Keyframe::new(Duration::from_millis(100), 100.0),
+                ],
+            )
+            .with_delay(Duration::from_millis(20));
+        assert_eq!(timeline.duration(), TransitionDuration::Millis(100.0));
+        timeline.play();
+        // however many frames it takes
+        tick(20.0);
+        assert_eq!(x.get(), 0.0);
+        tick(70.0);
+        assert_eq!(x.get(), 50.0);
+        assert_eq!(timeline.playhead(), TransitionDuration::Millis(50.0));
+        tick(80.0);
+        assert_eq!(x.get(), 60.0);
+        tick(150.0);
+        assert_eq!(x.get(), 100.0);
+        assert!(!timeline.is_playing());
+
+        // frames are converted at 60 per second
+        timeline.seek(3);
+        assert_eq!(x.get(), 50.0);
+    }
+}
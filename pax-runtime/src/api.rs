@@ -74,7 +74,7 @@ impl NodeContext {
     }
 
     pub fn subscribe(&self, dependencies: &[UntypedProperty], f: impl Fn() + 'static) {
-        let subscription_prop = Property::computed(f, dependencies);
+        let subscription_prop = Property::subscription(f, dependencies);
         match self.expanded_node.upgrade() {
             Some(expanded_node) => borrow_mut!(expanded_node.subscriptions).push(subscription_prop),
             None => log::warn!("couldn't add subscription: node doesn't exist anymore"),
@@ -126,6 +126,47 @@ impl NodeContext {
         Ok(())
     }
 
+    /// Moves keyboard focus to this node, if it's focusable. Keyboard events are delivered
+    /// to the focused node and bubble up from it.
+    pub fn focus(&self) {
+        if let Some(node) = self.expanded_node.upgrade() {
+            if node.is_focusable() {
+                self.runtime_context.set_focused_element(Some(&node));
+            }
+        }
+    }
+
+    /// Removes keyboard focus from this node, if it has it
+    pub fn blur(&self) {
+        if self.is_focused() {
+            self.runtime_context.set_focused_element(None);
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        let focused = self.runtime_context.get_focused_element();
+        let node = self.expanded_node.upgrade();
+        matches!((focused, node), (Some(focused), Some(node)) if focused.id == node.id)
+    }
+
+    /// Routes the events of pointer `pointer_id` to this node regardless of hit testing, e.g.
+    /// to keep dragging a slider thumb after the pointer leaves it. The capture is released
+    /// when the pointer is lifted or cancelled.
+    pub fn capture_pointer(&self, pointer_id: i64) {
+        if let Some(node) = self.expanded_node.upgrade() {
+            self.runtime_context.capture_pointer(pointer_id, &node);
+        }
+    }
+
+    /// Releases pointer `pointer_id`, if this node captures it
+    pub fn release_pointer_capture(&self, pointer_id: i64) {
+        let capturing = self.runtime_context.get_pointer_capture(pointer_id);
+        let node = self.expanded_node.upgrade();
+        if matches!((capturing, node), (Some(capturing), Some(node)) if capturing.id == node.id) {
+            self.runtime_context.release_pointer_capture(pointer_id);
+        }
+    }
+
     pub fn set_cursor(&self, cursor: CursorStyle) {
         self.runtime_context
             .enqueue_native_message(NativeMessage::SetCursor(SetCursorPatch {
@@ -1,10 +1,9 @@
 use_RefCell!();
 use crate::api::NodeContext;
 use crate::{
-    ConditionalProperties, ExpandedNode, HandlerRegistry, InstanceNode, InstantiationArgs,
-    ReusableInstanceNodeArgs, RuntimePropertiesStackFrame,
+    BoundExpression, ConditionalProperties, ExpandedNode, HandlerRegistry, InstanceNode,
+    InstantiationArgs, ReusableInstanceNodeArgs, RuntimePropertiesStackFrame,
 };
-use pax_lang::Computable;
 use pax_manifest::{TypeId, ValueDefinition};
 use pax_message::borrow;
 use pax_runtime_api::pax_value::{CoercionRules, PaxAny, ToFromPaxAny};
@@ -87,13 +86,20 @@ pub trait DefinitionToInstanceTraverser {
             let mut instances = Vec::new();
             for node_id in root {
                 let node = template.get_node(&node_id).unwrap();
+                if node.is_else_branch() {
+                    // instantiated together with the `if` that heads its chain
+                    continue;
+                }
                 match node.type_id.get_pax_type() {
                     pax_manifest::PaxType::If
                     | pax_manifest::PaxType::Slot
-                    | pax_manifest::PaxType::Repeat => {
+                    | pax_manifest::PaxType::Repeat
+                    | pax_manifest::PaxType::Match => {
                         instances.push(self.build_control_flow(type_id, &node_id, None));
                     }
                     pax_manifest::PaxType::Comment => continue,
+                    // instantiated together with the `match` owning it
+                    pax_manifest::PaxType::MatchArm => continue,
                     _ => {
                         instances.push(self.build_template_node(type_id, &node_id, None));
                     }
@@ -110,6 +116,7 @@ pub trait DefinitionToInstanceTraverser {
             children: None,
             template_node_identifier: None,
             properties_scope_factory: Some(factory.get_properties_scope_factory()),
+            state_variants: Vec::new(),
         }
     }
 
@@ -149,6 +156,21 @@ pub trait DefinitionToInstanceTraverser {
                     .as_ref()
                     .unwrap()
                     .clone();
+                let else_branch_ids = containing_template.get_else_branches(node_id);
+                // `None` marks a trailing `else`
+                let else_expr_infos: Vec<Option<pax_manifest::ExpressionInfo>> = else_branch_ids
+                    .iter()
+                    .map(|id| {
+                        containing_template
+                            .get_node(id)
+                            .and_then(|n| n.control_flow_settings.as_ref())
+                            .and_then(|cfsd| cfsd.condition_expression.clone())
+                    })
+                    .collect();
+                let else_branches: Vec<_> = else_branch_ids
+                    .iter()
+                    .map(|id| RefCell::new(self.build_children(containing_component_type_id, id)))
+                    .collect();
                 let prototypical_properties_factory: Box<
                     dyn Fn(
                         std::rc::Rc<crate::RuntimePropertiesStackFrame>,
@@ -156,17 +178,19 @@ pub trait DefinitionToInstanceTraverser {
                     )
                         -> Option<std::rc::Rc<RefCell<pax_runtime_api::pax_value::PaxAny>>>,
                 > = Box::new(move |stack_frame, expanded_node| {
-                    let cloned_stack = stack_frame.clone();
                     let expr_ast = expr_info.expression.clone();
+                    let else_expressions: Vec<Property<bool>> = else_expr_infos
+                        .iter()
+                        .map(|else_expr_info| match else_expr_info {
+                            Some(else_expr_info) => {
+                                build_condition_property(else_expr_info, &stack_frame)
+                            }
+                            None => Property::new(true),
+                        })
+                        .collect();
 
-                    let mut dependencies = Vec::new();
-                    for dependency in &expr_info.dependencies {
-                        if let Some(p) = stack_frame.resolve_symbol_as_erased_property(dependency) {
-                            dependencies.push(p);
-                        } else {
-                            log::warn!("Failed to resolve symbol {}", dependency);
-                        }
-                    }
+                    let expression = BoundExpression::new(&expr_info, &stack_frame);
+                    let dependencies = expression.dependencies().to_vec();
 
                     if let Some(expanded_node) = &expanded_node {
                         let expanded_node = borrow!(**expanded_node);
@@ -178,25 +202,25 @@ pub trait DefinitionToInstanceTraverser {
                         cp.boolean_expression
                             .replace_with(Property::computed_with_name(
                                 move || {
-                                    let new_value = expr_ast
-                                        .compute(cloned_stack.clone())
-                                        .unwrap_or_else(|err| {
-                                            log::warn!("Failed to compute expression: {:?}", err);
-                                            Default::default()
-                                        });
-                                    let coerced =
-                                        bool::try_coerce(new_value).unwrap_or_else(|_e| {
-                                            log::warn!(
-                                                "Failed to parse boolean expression: {}",
-                                                expr_ast
-                                            );
-                                            Default::default()
-                                        });
-                                    coerced
+                                    expression.compute_as::<bool>().unwrap_or_else(|err| {
+                                        log::warn!(
+                                            "Failed to compute boolean expression {}: {}",
+                                            expr_ast,
+                                            err
+                                        );
+                                        Default::default()
+                                    })
                                 },
                                 &dependencies,
                                 &name,
                             ));
+                        if cp.else_expressions.len() == else_expressions.len() {
+                            for (old, new) in cp.else_expressions.iter().zip(else_expressions) {
+                                old.replace_with(new);
+                            }
+                        } else {
+                            cp.else_expressions = else_expressions;
+                        }
                         return None;
                     }
 
@@ -205,28 +229,35 @@ pub trait DefinitionToInstanceTraverser {
                         let name = format!("conditional (if) expr ({})", expr_ast);
                         properties.boolean_expression = Property::computed_with_name(
                             move || {
-                                let new_value = expr_ast.compute(cloned_stack.clone()).unwrap();
-                                let coerced = bool::try_coerce(new_value).unwrap_or_else(|_e| {
-                                    log::warn!("Failed to parse boolean expression: {}", expr_ast);
+                                expression.compute_as::<bool>().unwrap_or_else(|err| {
+                                    log::warn!(
+                                        "Failed to compute boolean expression {}: {}",
+                                        expr_ast,
+                                        err
+                                    );
                                     Default::default()
-                                });
-                                coerced
+                                })
                             },
                             &dependencies,
                             &name,
                         );
+                        properties.else_expressions = else_expressions;
                         properties.to_pax_any()
                     })))
                 });
-                crate::ConditionalInstance::instantiate(crate::rendering::InstantiationArgs {
-                    prototypical_common_properties_factory,
-                    prototypical_properties_factory,
-                    handler_registry: None,
-                    component_template: None,
-                    children: Some(children),
-                    template_node_identifier: Some(unique_identifier),
-                    properties_scope_factory: None,
-                })
+                crate::ConditionalInstance::instantiate_with_else_branches(
+                    crate::rendering::InstantiationArgs {
+                        prototypical_common_properties_factory,
+                        prototypical_properties_factory,
+                        handler_registry: None,
+                        component_template: None,
+                        children: Some(children),
+                        template_node_identifier: Some(unique_identifier),
+                        properties_scope_factory: None,
+                        state_variants: Vec::new(),
+                    },
+                    else_branches,
+                )
             }
             pax_manifest::PaxType::Slot => {
                 let expr_info = tnd
@@ -245,17 +276,10 @@ pub trait DefinitionToInstanceTraverser {
                     )
                         -> Option<std::rc::Rc<RefCell<pax_runtime_api::pax_value::PaxAny>>>,
                 > = Box::new(move |stack_frame, expanded_node| {
-                    let cloned_stack = stack_frame.clone();
                     let expr_ast = expr_info.expression.clone();
 
-                    let mut dependencies = Vec::new();
-                    for dependency in &expr_info.dependencies {
-                        if let Some(p) = stack_frame.resolve_symbol_as_erased_property(dependency) {
-                            dependencies.push(p);
-                        } else {
-                            log::warn!("Failed to resolve symbol {}", dependency);
-                        }
-                    }
+                    let expression = BoundExpression::new(&expr_info, &stack_frame);
+                    let dependencies = expression.dependencies().to_vec();
 
                     if let Some(expanded_node) = &expanded_node {
                         let expanded_node = borrow!(**expanded_node);
@@ -268,24 +292,14 @@ pub trait DefinitionToInstanceTraverser {
                             .index
                             .replace_with(Property::computed_with_name(
                                 move || {
-                                    let new_value = expr_ast
-                                        .compute(cloned_stack.clone())
-                                        .unwrap_or_else(|op_err| {
-                                            log::warn!(
-                                                "Failed to compute expression: {:?}",
-                                                op_err
-                                            );
-                                            Default::default()
-                                        });
-                                    let coerced: Numeric = Numeric::try_coerce(new_value)
-                                        .unwrap_or_else(|_| {
-                                            log::warn!(
-                                                "Failed to parse slot index expression: {}",
-                                                expr_ast
-                                            );
-                                            Default::default()
-                                        });
-                                    coerced
+                                    expression.compute_as::<Numeric>().unwrap_or_else(|err| {
+                                        log::warn!(
+                                            "Failed to compute slot index expression {}: {}",
+                                            expr_ast,
+                                            err
+                                        );
+                                        Default::default()
+                                    })
                                 },
                                 &dependencies,
                                 "slot index",
@@ -298,21 +312,14 @@ pub trait DefinitionToInstanceTraverser {
 
                         properties.index = Property::computed_with_name(
                             move || {
-                                let new_value = expr_ast
-                                    .compute(cloned_stack.clone())
-                                    .unwrap_or_else(|op_err| {
-                                        log::warn!("Failed to compute expression: {:?}", op_err);
-                                        Default::default()
-                                    });
-                                let coerced: Numeric = Numeric::try_coerce(new_value)
-                                    .unwrap_or_else(|_| {
-                                        log::warn!(
-                                            "Failed to parse slot index expression: {}",
-                                            expr_ast
-                                        );
-                                        Default::default()
-                                    });
-                                coerced
+                                expression.compute_as::<Numeric>().unwrap_or_else(|err| {
+                                    log::warn!(
+                                        "Failed to compute slot index expression {}: {}",
+                                        expr_ast,
+                                        err
+                                    );
+                                    Default::default()
+                                })
                             },
                             &dependencies,
                             "slot index",
@@ -328,8 +335,92 @@ pub trait DefinitionToInstanceTraverser {
                     children: Some(children),
                     template_node_identifier: Some(unique_identifier),
                     properties_scope_factory: None,
+                    state_variants: Vec::new(),
                 })
             }
+            pax_manifest::PaxType::Match => {
+                let expr_info = tnd
+                    .control_flow_settings
+                    .as_ref()
+                    .unwrap()
+                    .match_expression
+                    .as_ref()
+                    .unwrap()
+                    .clone();
+                let arms: Vec<_> = containing_template
+                    .get_children(node_id)
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|arm_id| {
+                        let pattern = containing_template
+                            .get_node(arm_id)?
+                            .control_flow_settings
+                            .as_ref()?
+                            .match_arm_pattern
+                            .clone()?;
+                        Some(crate::MatchArm {
+                            variant: pattern.get_variant_name(),
+                            enum_name: pattern.get_enum_name(),
+                            bindings: pattern.bindings,
+                            guard: pattern.guard,
+                            children: RefCell::new(
+                                self.build_children(containing_component_type_id, arm_id),
+                            ),
+                        })
+                    })
+                    .collect();
+
+                let prototypical_properties_factory: Box<
+                    dyn Fn(
+                        std::rc::Rc<crate::RuntimePropertiesStackFrame>,
+                        Option<std::rc::Rc<ExpandedNode>>,
+                    )
+                        -> Option<std::rc::Rc<RefCell<pax_runtime_api::pax_value::PaxAny>>>,
+                > = Box::new(move |stack_frame, expanded_node| {
+                    let expression = BoundExpression::new(&expr_info, &stack_frame);
+                    let dependencies = expression.dependencies().to_vec();
+                    let subject = Property::computed_with_name(
+                        move || {
+                            expression.compute().unwrap_or_else(|op_err| {
+                                log::warn!("Failed to compute expression: {:?}", op_err);
+                                Default::default()
+                            })
+                        },
+                        &dependencies,
+                        "match subject",
+                    );
+
+                    if let Some(expanded_node) = &expanded_node {
+                        let expanded_node = borrow!(**expanded_node);
+                        let outer_ref = expanded_node.properties.borrow();
+                        let rc = Rc::clone(&outer_ref);
+                        let mut inner_ref = (*rc).borrow_mut();
+                        let match_properties =
+                            crate::MatchProperties::mut_from_pax_any(&mut inner_ref).unwrap();
+                        match_properties.subject.replace_with(subject);
+                        return None;
+                    }
+
+                    Some(std::rc::Rc::new(RefCell::new({
+                        let mut properties = crate::MatchProperties::default();
+                        properties.subject = subject;
+                        properties.to_pax_any()
+                    })))
+                });
+                crate::MatchInstance::instantiate_with_arms(
+                    crate::rendering::InstantiationArgs {
+                        prototypical_common_properties_factory,
+                        prototypical_properties_factory,
+                        handler_registry: None,
+                        component_template: None,
+                        children: Some(children),
+                        template_node_identifier: Some(unique_identifier),
+                        properties_scope_factory: None,
+                        state_variants: Vec::new(),
+                    },
+                    arms,
+                )
+            }
             pax_manifest::PaxType::Repeat => {
                 let source_expression_info = tnd
                     .control_flow_settings
@@ -345,6 +436,13 @@ pub trait DefinitionToInstanceTraverser {
                     .repeat_predicate_definition
                     .clone()
                     .unwrap();
+                let key_expression = tnd
+                    .control_flow_settings
+                    .as_ref()
+                    .unwrap()
+                    .repeat_key_expression
+                    .clone()
+                    .map(Rc::new);
                 let prototypical_properties_factory: Box<
                     dyn Fn(
                         std::rc::Rc<crate::RuntimePropertiesStackFrame>,
@@ -352,18 +450,8 @@ pub trait DefinitionToInstanceTraverser {
                     )
                         -> Option<std::rc::Rc<RefCell<pax_runtime_api::pax_value::PaxAny>>>,
                 > = Box::new(move |stack_frame, expanded_node| {
-                    let cloned_stack = stack_frame.clone();
-                    let expr = source_expression_info.expression.clone();
-                    let deps = source_expression_info.dependencies.clone();
-
-                    let mut dependencies = Vec::new();
-                    for dependency in &deps {
-                        if let Some(p) = stack_frame.resolve_symbol_as_erased_property(dependency) {
-                            dependencies.push(p);
-                        } else {
-                            log::warn!("Failed to resolve symbol {}", dependency);
-                        }
-                    }
+                    let expression = BoundExpression::new(&source_expression_info, &stack_frame);
+                    let dependencies = expression.dependencies().to_vec();
 
                     let (elem, index) = match &predictate_definition {
                         pax_manifest::ControlFlowRepeatPredicateDefinition::ElemId(id) => {
@@ -386,7 +474,7 @@ pub trait DefinitionToInstanceTraverser {
                             .source_expression
                             .replace_with(Property::computed_with_name(
                                 move || {
-                                    expr.compute(cloned_stack.clone()).unwrap_or_else(|op_err| {
+                                    expression.compute().unwrap_or_else(|op_err| {
                                         log::warn!("Failed to compute expression: {:?}", op_err);
                                         Default::default()
                                     })
@@ -398,6 +486,9 @@ pub trait DefinitionToInstanceTraverser {
                         repeat
                             .iterator_elem_symbol
                             .replace_with(Property::new(elem));
+                        repeat
+                            .key_expression
+                            .replace_with(Property::new(key_expression.clone()));
                         return None;
                     }
 
@@ -406,7 +497,7 @@ pub trait DefinitionToInstanceTraverser {
 
                         properties.source_expression = Property::computed_with_name(
                             move || {
-                                expr.compute(cloned_stack.clone()).unwrap_or_else(|op_err| {
+                                expression.compute().unwrap_or_else(|op_err| {
                                     log::warn!("Failed to compute expression: {:?}", op_err);
                                     Default::default()
                                 })
@@ -421,6 +512,9 @@ pub trait DefinitionToInstanceTraverser {
                         properties
                             .iterator_elem_symbol
                             .replace_with(Property::new(elem));
+                        properties
+                            .key_expression
+                            .replace_with(Property::new(key_expression.clone()));
                         properties.to_pax_any()
                     })))
                 });
@@ -432,6 +526,7 @@ pub trait DefinitionToInstanceTraverser {
                     children: Some(children),
                     template_node_identifier: Some(unique_identifier),
                     properties_scope_factory: None,
+                    state_variants: Vec::new(),
                 })
             }
             _ => {
@@ -456,10 +551,15 @@ pub trait DefinitionToInstanceTraverser {
         let mut children_instances = Vec::new();
         for child_id in &children.unwrap_or_default() {
             let child = containing_template.get_node(&child_id).unwrap();
+            if child.is_else_branch() {
+                // instantiated together with the `if` that heads its chain
+                continue;
+            }
             match child.type_id.get_pax_type() {
                 pax_manifest::PaxType::If
                 | pax_manifest::PaxType::Slot
-                | pax_manifest::PaxType::Repeat => {
+                | pax_manifest::PaxType::Repeat
+                | pax_manifest::PaxType::Match => {
                     children_instances.push(self.build_control_flow(
                         containing_component_type_id,
                         &child_id,
@@ -467,6 +567,8 @@ pub trait DefinitionToInstanceTraverser {
                     ));
                 }
                 pax_manifest::PaxType::Comment => continue,
+                // instantiated together with the `match` owning it
+                pax_manifest::PaxType::MatchArm => continue,
                 _ => {
                     children_instances.push(self.build_template_node(
                         containing_component_type_id,
@@ -531,7 +633,8 @@ pub trait DefinitionToInstanceTraverser {
         }
 
         // update properties from tnd
-        let inline_properties = manifest.get_inline_properties(containing_component_type_id, node);
+        let inline_properties =
+            manifest.get_inline_properties(containing_component_type_id, node_id);
         let updated_properties =
             node_component_factory.build_inline_properties(inline_properties.clone());
         args.prototypical_properties_factory = updated_properties;
@@ -541,6 +644,13 @@ pub trait DefinitionToInstanceTraverser {
             node_component_factory.build_inline_common_properties(inline_properties);
         args.prototypical_common_properties_factory = updated_common_properties;
 
+        // update state variants from the pseudo-class selectors matching the tnd
+        args.state_variants = manifest
+            .get_inline_state_variants(containing_component_type_id, node_id)
+            .into_iter()
+            .map(|variant| crate::StateVariant::new(variant, &*node_component_factory))
+            .collect();
+
         node_component_factory.build_component(args)
     }
 
@@ -616,6 +726,27 @@ pub trait DefinitionToInstanceTraverser {
     }
 }
 
+/// Builds the computed condition of an `else if` branch of a conditional chain
+fn build_condition_property(
+    expr_info: &pax_manifest::ExpressionInfo,
+    stack: &Rc<RuntimePropertiesStackFrame>,
+) -> Property<bool> {
+    let expression = BoundExpression::new(expr_info, stack);
+    let dependencies = expression.dependencies().to_vec();
+    let expr_ast = expr_info.expression.clone();
+    let name = format!("conditional (else if) expr ({})", expr_ast);
+    Property::computed_with_name(
+        move || {
+            expression.compute_as::<bool>().unwrap_or_else(|err| {
+                log::warn!("Failed to compute boolean expression {}: {}", expr_ast, err);
+                Default::default()
+            })
+        },
+        &dependencies,
+        &name,
+    )
+}
+
 fn resolve_property<T: CoercionRules + PropertyValue + DeserializeOwned>(
     name: &str,
     defined_properties: &BTreeMap<String, ValueDefinition>,
@@ -624,7 +755,6 @@ fn resolve_property<T: CoercionRules + PropertyValue + DeserializeOwned>(
     let Some(value_def) = defined_properties.get(name) else {
         return Property::default();
     };
-    let cloned_stack = stack.clone();
     let resolved_property: Property<Option<T>> = match value_def.clone() {
         pax_manifest::ValueDefinition::LiteralValue(lv) => {
             let val = T::try_coerce(lv).unwrap_or_else(|err| {
@@ -644,29 +774,16 @@ fn resolve_property<T: CoercionRules + PropertyValue + DeserializeOwned>(
             Property::new_from_untyped(untyped_property.clone())
         }
         pax_manifest::ValueDefinition::Expression(info) => {
-            let mut dependents = vec![];
-            for dependency in &info.dependencies {
-                if let Some(p) = stack.resolve_symbol_as_erased_property(dependency) {
-                    dependents.push(p);
-                } else {
-                    log::warn!("Failed to resolve symbol {}", dependency);
-                }
-            }
+            let expression = BoundExpression::new(&info, stack);
+            let dependents = expression.dependencies().to_vec();
             let name = &info.expression.to_string();
             Property::computed_with_name(
                 move || {
-                    let new_value = info
-                        .expression
-                        .compute(cloned_stack.clone())
-                        .unwrap_or_else(|err| {
-                            log::warn!("Failed to compute expression: {:?}", err);
-                            Default::default()
-                        });
-                    let coerced = T::try_coerce(new_value.clone()).unwrap_or_else(|err| {
-                        log::warn!("Failed to coerce new value for property. Error: {:?}", err);
+                    let value = expression.compute_as::<T>().unwrap_or_else(|err| {
+                        log::warn!("Failed to compute expression: {:?}", err);
                         Default::default()
                     });
-                    Some(coerced)
+                    Some(value)
                 },
                 &dependents,
                 name,
@@ -768,6 +885,20 @@ pub trait ComponentFactory {
         registry: Rc<RefCell<HandlerRegistry>>,
     ) -> Rc<RefCell<HandlerRegistry>>;
 
+    /// Returns a function overriding the named properties of this component with those of a
+    /// state variant, weighted by the given property (see [`crate::override_with_state_variant`]).
+    /// Fails if either properties aren't this component's.
+    fn build_state_variant_override(
+        &self,
+    ) -> Box<
+        dyn Fn(
+            &Rc<RefCell<PaxAny>>,
+            &Rc<RefCell<PaxAny>>,
+            &[String],
+            &Property<f64>,
+        ) -> Result<(), String>,
+    >;
+
     // Calls the instantiation function for the component
     fn build_component(&self, args: InstantiationArgs) -> Rc<dyn InstanceNode>;
 
@@ -827,6 +958,11 @@ fn create_new_common_properties(
         unclippable: resolve_property("unclippable", defined_properties, stack_frame),
         _raycastable: resolve_property("_raycastable", defined_properties, stack_frame),
         _suspended: resolve_property("_suspended", defined_properties, stack_frame),
+        disabled: resolve_property("disabled", defined_properties, stack_frame),
+        focusable: resolve_property("focusable", defined_properties, stack_frame),
+        tab_index: resolve_property("tab_index", defined_properties, stack_frame),
+        opacity: resolve_property("opacity", defined_properties, stack_frame),
+        blend_mode: resolve_property("blend_mode", defined_properties, stack_frame),
     }))
 }
 
@@ -882,4 +1018,26 @@ fn update_common_properties(
         defined_properties,
         stack_frame,
     ));
+    cp.disabled.replace_with(resolve_property(
+        "disabled",
+        defined_properties,
+        stack_frame,
+    ));
+    cp.focusable.replace_with(resolve_property(
+        "focusable",
+        defined_properties,
+        stack_frame,
+    ));
+    cp.tab_index.replace_with(resolve_property(
+        "tab_index",
+        defined_properties,
+        stack_frame,
+    ));
+    cp.opacity
+        .replace_with(resolve_property("opacity", defined_properties, stack_frame));
+    cp.blend_mode.replace_with(resolve_property(
+        "blend_mode",
+        defined_properties,
+        stack_frame,
+    ));
 }
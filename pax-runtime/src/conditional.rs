@@ -6,16 +6,21 @@ use pax_runtime_api::{borrow, borrow_mut, use_RefCell, PaxValue, Property, ToPax
 
 use crate::api::Layer;
 use crate::{
-    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstanceNodePtrList,
+    InstantiationArgs, RuntimeContext,
 };
 
 /// A special "control-flow" primitive, Conditional (`if`) allows for a
 /// subtree of a component template to be rendered conditionally,
 /// based on the value of the property `boolean_expression`.
+/// Any `else if` / `else` branches chained to the `if` are owned by the same
+/// ConditionalInstance, which mounts at most one branch at a time.
 /// The Pax compiler handles ConditionalInstance specially
 /// with the `if` syntax in templates.
 pub struct ConditionalInstance {
     base: BaseInstance,
+    /// Template children of each chained `else if` / `else` branch, in order
+    else_branches: Vec<InstanceNodePtrList>,
 }
 
 impl ImplToFromPaxAny for ConditionalProperties {}
@@ -24,15 +29,29 @@ impl ImplToFromPaxAny for ConditionalProperties {}
 #[derive(Default)]
 pub struct ConditionalProperties {
     pub boolean_expression: Property<bool>,
+    /// Conditions of the chained `else if` branches, in order. A trailing `else`
+    /// is represented by a condition that is always `true`.
+    pub else_expressions: Vec<Property<bool>>,
 }
 
 impl ToPaxValue for ConditionalProperties {
     fn to_pax_value(self) -> PaxValue {
         PaxValue::Object(
-            vec![(
-                "boolean_expression".to_string(),
-                self.boolean_expression.to_pax_value(),
-            )]
+            vec![
+                (
+                    "boolean_expression".to_string(),
+                    self.boolean_expression.to_pax_value(),
+                ),
+                (
+                    "else_expressions".to_string(),
+                    PaxValue::Vec(
+                        self.else_expressions
+                            .into_iter()
+                            .map(|e| e.to_pax_value())
+                            .collect(),
+                    ),
+                ),
+            ]
             .into_iter()
             .collect(),
         )
@@ -44,18 +63,7 @@ impl InstanceNode for ConditionalInstance {
     where
         Self: Sized,
     {
-        Rc::new(Self {
-            base: BaseInstance::new(
-                args,
-                InstanceFlags {
-                    invisible_to_slot: true,
-                    invisible_to_raycasting: true,
-                    layer: Layer::DontCare,
-                    is_component: false,
-                    is_slot: false,
-                },
-            ),
-        })
+        Self::instantiate_with_else_branches(args, vec![])
     }
 
     fn handle_mount(
@@ -88,6 +96,28 @@ impl InstanceNode for ConditionalInstance {
 }
 
 impl ConditionalInstance {
+    /// Instantiates an `if` together with the template children of its chained
+    /// `else if` / `else` branches. `else_branches` must line up with the
+    /// `else_expressions` produced by the properties factory in `args`.
+    pub fn instantiate_with_else_branches(
+        args: InstantiationArgs,
+        else_branches: Vec<InstanceNodePtrList>,
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: true,
+                    invisible_to_raycasting: true,
+                    layer: Layer::DontCare,
+                    is_component: false,
+                    is_slot: false,
+                },
+            ),
+            else_branches,
+        })
+    }
+
     fn handle_setup(
         self: Rc<Self>,
         expanded_node: &Rc<ExpandedNode>,
@@ -98,14 +128,19 @@ impl ConditionalInstance {
         let cloned_self = Rc::clone(&self);
         let cloned_context = Rc::clone(context);
 
-        let cond_expr =
+        let (cond_expr, else_exprs) =
             expanded_node.with_properties_unwrapped(|properties: &mut ConditionalProperties| {
-                properties.boolean_expression.clone()
+                (
+                    properties.boolean_expression.clone(),
+                    properties.else_expressions.clone(),
+                )
             });
 
-        let dep = cond_expr.untyped();
+        let mut deps = vec![cond_expr.untyped()];
+        deps.extend(else_exprs.iter().map(|e| e.untyped()));
 
-        let old_val = RefCell::new(false);
+        // index of the mounted branch: 0 for the `if`, n for the n-th `else if` / `else`
+        let old_val: RefCell<Option<usize>> = RefCell::new(None);
         expanded_node
             .children
             .replace_with(Property::computed_with_name(
@@ -113,14 +148,23 @@ impl ConditionalInstance {
                     let Some(cloned_expanded_node) = weak_ref_self.upgrade() else {
                         panic!("ran evaluator after expanded node dropped (conditional elem)")
                     };
-                    let val = cond_expr.get();
+                    let val = if cond_expr.get() {
+                        Some(0)
+                    } else {
+                        else_exprs.iter().position(|e| e.get()).map(|i| i + 1)
+                    };
                     if val == *borrow!(old_val) {
                         return cloned_expanded_node.children.get();
                     }
                     *borrow_mut!(old_val) = val;
-                    if val {
+                    let branch_children = match val {
+                        Some(0) => Some(cloned_self.base().get_instance_children()),
+                        Some(i) => cloned_self.else_branches.get(i - 1),
+                        None => None,
+                    };
+                    if let Some(branch_children) = branch_children {
                         let env = Rc::clone(&cloned_expanded_node.stack);
-                        let children = borrow!(cloned_self.base().get_instance_children());
+                        let children = borrow!(branch_children);
                         let children_with_envs = children.iter().cloned().zip(iter::repeat(env));
                         let res = cloned_expanded_node.generate_children(
                             children_with_envs,
@@ -138,7 +182,7 @@ impl ConditionalInstance {
                         )
                     }
                 },
-                &[dep],
+                &deps,
                 &format!("conditional_children (node id: {})", expanded_node.id.0),
             ));
     }
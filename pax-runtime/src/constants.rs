@@ -6,6 +6,8 @@ pub const TOUCH_END_HANDLERS: &str = "touch_end";
 pub const KEY_DOWN_HANDLERS: &str = "key_down";
 pub const SELECT_START_HANDLERS: &str = "select_start";
 pub const FOCUSED_HANDLERS: &str = "focused";
+pub const FOCUS_HANDLERS: &str = "focus";
+pub const BLUR_HANDLERS: &str = "blur";
 pub const KEY_UP_HANDLERS: &str = "key_up";
 pub const KEY_PRESS_HANDLERS: &str = "key_press";
 pub const CHECKBOX_CHANGE_HANDLERS: &str = "checkbox_change";
@@ -22,6 +24,19 @@ pub const MOUSE_OVER_HANDLERS: &str = "mouse_over";
 pub const MOUSE_OUT_HANDLERS: &str = "mouse_out";
 pub const DOUBLE_CLICK_HANDLERS: &str = "double_click";
 pub const CONTEXT_MENU_HANDLERS: &str = "context_menu";
+pub const POINTER_DOWN_HANDLERS: &str = "pointer_down";
+pub const POINTER_MOVE_HANDLERS: &str = "pointer_move";
+pub const POINTER_UP_HANDLERS: &str = "pointer_up";
+pub const POINTER_CANCEL_HANDLERS: &str = "pointer_cancel";
+pub const DRAG_START_HANDLERS: &str = "drag_start";
+pub const DRAG_HANDLERS: &str = "drag";
+pub const DRAG_END_HANDLERS: &str = "drag_end";
+pub const PINCH_HANDLERS: &str = "pinch";
+pub const LONG_PRESS_HANDLERS: &str = "long_press";
+pub const SWIPE_HANDLERS: &str = "swipe";
 pub const WHEEL_HANDLERS: &str = "wheel";
 pub const PRE_RENDER_HANDLERS: &str = "pre_render";
 pub const MOUNT_HANDLERS: &str = "mount";
+
+/// Suffix of the keys of capture-phase handlers, e.g. `click.capture` for `@click.capture`
+pub const CAPTURE_PHASE_SUFFIX: &str = ".capture";
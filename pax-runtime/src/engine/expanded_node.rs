@@ -2,19 +2,21 @@ use crate::api::TextInput;
 use crate::node_interface::NodeLocal;
 use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny, ToFromPaxAny};
 use pax_runtime_api::{
-    borrow, borrow_mut, use_RefCell, Focus, Interpolatable, Layer, Percent, Property, SelectStart,
-    Variable,
+    borrow, borrow_mut, use_RefCell, BlendMode, Focus, FocusIn, FocusOut, Interpolatable, Layer,
+    Percent, Property, SelectStart, Variable,
 };
 
 use crate::api::math::Point2;
 use crate::constants::{
-    BUTTON_CLICK_HANDLERS, CHECKBOX_CHANGE_HANDLERS, CLAP_HANDLERS, CLICK_HANDLERS,
-    CONTEXT_MENU_HANDLERS, DOUBLE_CLICK_HANDLERS, DROP_HANDLERS, FOCUSED_HANDLERS,
-    KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS, MOUSE_DOWN_HANDLERS,
-    MOUSE_MOVE_HANDLERS, MOUSE_OUT_HANDLERS, MOUSE_OVER_HANDLERS, MOUSE_UP_HANDLERS,
-    SCROLL_HANDLERS, SELECT_START_HANDLERS, TEXTBOX_CHANGE_HANDLERS, TEXTBOX_INPUT_HANDLERS,
-    TEXT_INPUT_HANDLERS, TOUCH_END_HANDLERS, TOUCH_MOVE_HANDLERS, TOUCH_START_HANDLERS,
-    WHEEL_HANDLERS,
+    BLUR_HANDLERS, BUTTON_CLICK_HANDLERS, CAPTURE_PHASE_SUFFIX, CHECKBOX_CHANGE_HANDLERS,
+    CLAP_HANDLERS, CLICK_HANDLERS, CONTEXT_MENU_HANDLERS, DOUBLE_CLICK_HANDLERS, DRAG_END_HANDLERS,
+    DRAG_HANDLERS, DRAG_START_HANDLERS, DROP_HANDLERS, FOCUSED_HANDLERS, FOCUS_HANDLERS,
+    KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS, LONG_PRESS_HANDLERS,
+    MOUSE_DOWN_HANDLERS, MOUSE_MOVE_HANDLERS, MOUSE_OUT_HANDLERS, MOUSE_OVER_HANDLERS,
+    MOUSE_UP_HANDLERS, PINCH_HANDLERS, POINTER_CANCEL_HANDLERS, POINTER_DOWN_HANDLERS,
+    POINTER_MOVE_HANDLERS, POINTER_UP_HANDLERS, SCROLL_HANDLERS, SELECT_START_HANDLERS,
+    SWIPE_HANDLERS, TEXTBOX_CHANGE_HANDLERS, TEXTBOX_INPUT_HANDLERS, TEXT_INPUT_HANDLERS,
+    TOUCH_END_HANDLERS, TOUCH_MOVE_HANDLERS, TOUCH_START_HANDLERS, WHEEL_HANDLERS,
 };
 use_RefCell!();
 use crate::{ExpandedNodeIdentifier, Globals, LayoutProperties, TransformAndBounds};
@@ -24,15 +26,17 @@ use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 
 use crate::api::{
-    ButtonClick, CheckboxChange, Clap, Click, CommonProperties, ContextMenu, DoubleClick, Drop,
-    Event, KeyDown, KeyPress, KeyUp, MouseDown, MouseMove, MouseOut, MouseOver, MouseUp,
-    NodeContext, RenderContext, Scroll, Size, TextboxChange, TextboxInput, TouchEnd, TouchMove,
-    TouchStart, Wheel, Window,
+    ButtonClick, CheckboxChange, Clap, Click, CommonProperties, Compositing, ContextMenu,
+    DoubleClick, Drag, DragEnd, DragStart, Drop, Event, KeyDown, KeyPress, KeyUp, LongPress,
+    MouseDown, MouseMove, MouseOut, MouseOver, MouseUp, NodeContext, Pinch, PointerCancel,
+    PointerDown, PointerMove, PointerUp, RenderContext, Scroll, Size, Swipe, TextboxChange,
+    TextboxInput, TouchEnd, TouchMove, TouchStart, Wheel, Window,
 };
 
 use crate::{
-    compute_tab, ComponentInstance, HandlerLocation, InstanceNode, InstanceNodePtr, RuntimeContext,
-    RuntimePropertiesStackFrame,
+    apply_state_variants, compute_tab, ComponentInstance, HandlerLocation, InstanceNode,
+    InstanceNodePtr, InteractionState, RuntimeContext, RuntimePropertiesStackFrame,
+    StateVariantTransition,
 };
 
 #[derive(Clone)]
@@ -89,6 +93,15 @@ pub struct ExpandedNode {
     /// computed property based on parent bounds + common properties
     pub transform_and_bounds: Property<TransformAndBounds<NodeLocal, Window>>,
 
+    /// The opacity this node ends up rendered with: its own `opacity` multiplied
+    /// by that of all its render ancestors. Canvas nodes are composited as groups
+    /// instead, but native elements have to be sent this value.
+    pub opacity: Property<f64>,
+
+    /// The blend modes of this node and its render ancestors that aren't `Normal`,
+    /// outermost first. Canvas nodes redraw when these change.
+    pub blend_modes: Property<Vec<BlendMode>>,
+
     /// For component instances only, tracks the expanded slot_children in its
     /// non-collapsed form (repeat and conditionals still present). This allows
     /// repeat/conditionals to update their children (handled in component.rs
@@ -135,6 +148,13 @@ pub struct ExpandedNode {
 
     /// subscription properties: added to this expanded node by calling ctx.subscribe in a node event handler
     pub subscriptions: RefCell<Vec<Property<()>>>,
+
+    /// Pointer interaction state, matched by the pseudo-classes of `@settings` selectors
+    pub interaction_state: InteractionState,
+
+    /// Transition the state variants of this node's properties in and out as its
+    /// interaction state changes, see [`crate::apply_state_variants`]
+    pub state_variant_transitions: RefCell<Vec<StateVariantTransition>>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
@@ -154,43 +174,34 @@ impl Interpolatable for ExpandedNode {}
 
 macro_rules! dispatch_event_handler {
     ($fn_name:ident, $arg_type:ty, $handler_key:ident, $recurse:expr) => {
+        /// Runs the handlers for this event on this node. Recursing events are first
+        /// dispatched to the capture-phase handlers from the root down to this node, then
+        /// bubble from this node up through its template parents, until a handler calls
+        /// [`Event::stop_propagation`]. Returns whether a handler called
+        /// [`Event::prevent_default`].
         pub fn $fn_name(
             self: &Rc<Self>,
             event: Event<$arg_type>,
-            globals: &Globals,
+            _globals: &Globals,
             ctx: &Rc<RuntimeContext>,
         ) -> bool {
-            if let Some(registry) = borrow!(self.instance_node).base().get_handler_registry() {
-                let borrowed_registry = &borrow!(*registry);
-                if let Some(handlers) = borrowed_registry.handlers.get($handler_key) {
-                    if handlers.len() > 0 {
-                        let component_properties =
-                            if let Some(cc) = self.containing_component.upgrade() {
-                                Rc::clone(&*borrow!(cc.properties))
-                            } else {
-                                Rc::clone(&*borrow!(self.properties))
-                            };
-
-                        let context = self.get_node_context(ctx);
-                        handlers.iter().for_each(|handler| {
-                            let properties = if let HandlerLocation::Component = &handler.location {
-                                Rc::clone(&*borrow!(self.properties))
-                            } else {
-                                Rc::clone(&component_properties)
-                            };
-                            (handler.function)(
-                                Rc::clone(&properties),
-                                &context,
-                                Some(event.clone().to_pax_any()),
-                            );
-                        });
-                    }
-                };
+            if !$recurse {
+                self.run_event_handlers($handler_key, &event, ctx);
+                return event.cancelled();
+            }
+
+            let mut path = vec![Rc::clone(self)];
+            while let Some(parent) = path.last().unwrap().template_parent.upgrade() {
+                path.push(parent);
             }
 
-            if $recurse {
-                if let Some(parent) = self.template_parent.upgrade() {
-                    return parent.$fn_name(event, globals, ctx);
+            let capture_key = format!("{}{}", $handler_key, CAPTURE_PHASE_SUFFIX);
+            let capture_phase = path.iter().rev().map(|node| (node, capture_key.as_str()));
+            let bubble_phase = path.iter().map(|node| (node, $handler_key));
+            for (node, key) in capture_phase.chain(bubble_phase) {
+                node.run_event_handlers(key, &event, ctx);
+                if event.propagation_stopped() {
+                    break;
                 }
             }
             event.cancelled()
@@ -208,6 +219,7 @@ impl ExpandedNode {
             Weak::new(),
         );
         root_node.bind_to_parent_bounds(ctx);
+        root_node.bind_to_parent_compositing();
         Rc::clone(&root_node).recurse_mount(ctx);
         root_node
     }
@@ -228,6 +240,15 @@ impl ExpandedNode {
                 .instance_prototypical_common_properties_factory)(env.clone(), None)
             .unwrap();
 
+        let interaction_state = InteractionState::default();
+        let state_variant_transitions = apply_state_variants(
+            &template.base().state_variants,
+            &properties,
+            &common_properties,
+            &interaction_state,
+            &env,
+        );
+
         let mut property_scope = borrow!(*common_properties).retrieve_property_scope();
 
         if let Some(scope) = &template.base().properties_scope_factory {
@@ -257,6 +278,8 @@ impl ExpandedNode {
             ),
             mounted_children: RefCell::new(Vec::new()),
             transform_and_bounds: Property::new(TransformAndBounds::default()),
+            opacity: Property::new(1.0),
+            blend_modes: Property::default(),
             expanded_slot_children: Default::default(),
             expanded_and_flattened_slot_children: Default::default(),
             flattened_slot_children_count: Property::new(0),
@@ -267,6 +290,8 @@ impl ExpandedNode {
             changed_listener: Property::default(),
             slot_child_attached_listener: Property::default(),
             subscriptions: Default::default(),
+            interaction_state,
+            state_variant_transitions: RefCell::new(state_variant_transitions),
         });
         res
     }
@@ -287,7 +312,15 @@ impl ExpandedNode {
             Rc::clone(&self.stack),
             Some(Rc::clone(&self)),
         );
+        *borrow_mut!(self.state_variant_transitions) = apply_state_variants(
+            &template.base().state_variants,
+            &borrow!(self.properties),
+            &borrow!(self.common_properties),
+            &self.interaction_state,
+            &self.stack,
+        );
         self.bind_to_parent_bounds(context);
+        self.bind_to_parent_compositing();
         context.set_canvas_dirty(self.occlusion.get().occlusion_layer_id);
     }
 
@@ -309,9 +342,16 @@ impl ExpandedNode {
         *borrow_mut!(self.properties_scope) = borrow!(new_expanded_node.properties_scope).clone();
         *borrow_mut!(self.common_properties) =
             Rc::clone(&*borrow!(new_expanded_node.common_properties));
+        new_expanded_node
+            .interaction_state
+            .mirror(&self.interaction_state);
+        *borrow_mut!(self.state_variant_transitions) = std::mem::take(&mut *borrow_mut!(
+            new_expanded_node.state_variant_transitions
+        ));
         self.occlusion.set(Default::default());
 
         self.bind_to_parent_bounds(context);
+        self.bind_to_parent_compositing();
         Rc::clone(self).recurse_mount(context);
         Rc::clone(self).recurse_update(context);
     }
@@ -368,18 +408,7 @@ impl ExpandedNode {
         //TODO here we could probably check intersection between old and new children (to avoid unmount + mount)
 
         for child in new_children.iter() {
-            // set parent and connect up viewport bounds to new parent
-            *borrow_mut!(child.render_parent) = Rc::downgrade(self);
-            // set frame clipping reference
-            let parent_frame = parent_frame.clone();
-            let deps = [parent_frame.untyped()];
-            child
-                .parent_frame
-                .replace_with(Property::computed(move || parent_frame.get(), &deps));
-
-            // suspension is used in the designer to turn of/on tick/update
-            child.inherit_suspend(self);
-            child.bind_to_parent_bounds(context);
+            self.bind_child(child, context, parent_frame);
         }
         if self.attached.get() > 0 {
             for child in curr_children.iter() {
@@ -393,6 +422,54 @@ impl ExpandedNode {
         new_children
     }
 
+    /// Like `attach_children`, but children that are part of both the currently mounted
+    /// and the new set stay mounted (keeping their state) instead of being remounted
+    pub fn reattach_children(
+        self: &Rc<Self>,
+        new_children: Vec<Rc<ExpandedNode>>,
+        context: &Rc<RuntimeContext>,
+        parent_frame: &Property<Option<ExpandedNodeIdentifier>>,
+    ) -> Vec<Rc<ExpandedNode>> {
+        let mut curr_children = borrow_mut!(self.mounted_children);
+        for child in new_children.iter() {
+            self.bind_child(child, context, parent_frame);
+        }
+        if self.attached.get() > 0 {
+            for child in curr_children.iter() {
+                if !new_children.iter().any(|c| Rc::ptr_eq(c, child)) {
+                    Rc::clone(child).recurse_unmount(context);
+                }
+            }
+            for child in new_children.iter() {
+                // no-op for children that are already mounted
+                Rc::clone(child).recurse_mount(context);
+            }
+        }
+        *curr_children = new_children.clone();
+        new_children
+    }
+
+    fn bind_child(
+        self: &Rc<Self>,
+        child: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+        parent_frame: &Property<Option<ExpandedNodeIdentifier>>,
+    ) {
+        // set parent and connect up viewport bounds to new parent
+        *borrow_mut!(child.render_parent) = Rc::downgrade(self);
+        // set frame clipping reference
+        let parent_frame = parent_frame.clone();
+        let deps = [parent_frame.untyped()];
+        child
+            .parent_frame
+            .replace_with(Property::computed(move || parent_frame.get(), &deps));
+
+        // suspension is used in the designer to turn of/on tick/update
+        child.inherit_suspend(self);
+        child.bind_to_parent_bounds(context);
+        child.bind_to_parent_compositing();
+    }
+
     fn bind_to_parent_bounds(self: &Rc<Self>, ctx: &Rc<RuntimeContext>) {
         let parent_transform_and_bounds = borrow!(self.render_parent)
             .upgrade()
@@ -409,6 +486,40 @@ impl ExpandedNode {
         self.transform_and_bounds.replace_with(transform_and_bounds);
     }
 
+    fn bind_to_parent_compositing(self: &Rc<Self>) {
+        let parent = borrow!(self.render_parent).upgrade();
+        let cp = self.get_common_properties();
+
+        let parent_opacity = parent
+            .as_ref()
+            .map(|n| n.opacity.clone())
+            .unwrap_or_else(|| Property::new(1.0));
+        let self_opacity = borrow!(cp).opacity.clone();
+        let deps = [parent_opacity.untyped(), self_opacity.untyped()];
+        self.opacity.replace_with(Property::computed(
+            move || parent_opacity.get() * self_opacity.get().unwrap_or(1.0).clamp(0.0, 1.0),
+            &deps,
+        ));
+
+        let parent_blend_modes = parent
+            .as_ref()
+            .map(|n| n.blend_modes.clone())
+            .unwrap_or_default();
+        let self_blend_mode = borrow!(cp).blend_mode.clone();
+        let deps = [parent_blend_modes.untyped(), self_blend_mode.untyped()];
+        self.blend_modes.replace_with(Property::computed(
+            move || {
+                let mut blend_modes = parent_blend_modes.get();
+                match self_blend_mode.get() {
+                    Some(BlendMode::Normal) | None => (),
+                    Some(blend_mode) => blend_modes.push(blend_mode),
+                }
+                blend_modes
+            },
+            &deps,
+        ));
+    }
+
     pub fn inherit_suspend(self: &Rc<Self>, node: &Rc<Self>) {
         let cp = self.get_common_properties();
         let self_suspended = borrow!(cp)._suspended.clone();
@@ -485,6 +596,9 @@ impl ExpandedNode {
             // fire dirty bit if present
             subscription.get();
         }
+        for transition in &*borrow!(self.state_variant_transitions) {
+            transition.update();
+        }
         if borrow!(self.instance_node).base().flags().is_component {
             self.compute_flattened_slot_children();
         }
@@ -543,6 +657,7 @@ impl ExpandedNode {
         if self.attached.get() == 1 {
             self.attached.set(self.attached.get() - 1);
             context.remove_from_cache(&self);
+            context.release_unmounted(&self);
             for child in borrow!(self.mounted_children).iter() {
                 Rc::clone(child).recurse_unmount(context);
             }
@@ -585,12 +700,44 @@ impl ExpandedNode {
     }
 
     pub fn recurse_render(self: &Rc<Self>, ctx: &Rc<RuntimeContext>, rcs: &mut dyn RenderContext) {
+        // nodes that aren't fully opaque or that blend with what's underneath are drawn
+        // as a group, on every layer their descendants might be drawing to
+        let (opacity, blend_mode) = {
+            let cp = self.get_common_properties();
+            let cp = borrow!(cp);
+            (
+                cp.opacity.get().unwrap_or(1.0).clamp(0.0, 1.0),
+                cp.blend_mode.get().unwrap_or_default(),
+            )
+        };
+        let grouped_layers: Vec<usize> = if opacity < 1.0 || blend_mode != BlendMode::Normal {
+            (0..rcs.layers())
+                .filter(|layer| ctx.is_canvas_dirty(layer))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        for &layer in &grouped_layers {
+            rcs.save(layer);
+            rcs.set_blend_mode(layer, blend_mode);
+            rcs.push_group(
+                layer,
+                Compositing {
+                    opacity,
+                    ..Default::default()
+                },
+            );
+        }
         borrow!(self.instance_node).handle_pre_render(&self, ctx, rcs);
         for child in self.children.get().iter().rev() {
             child.recurse_render_queue(ctx, rcs);
         }
         borrow!(self.instance_node).render(&self, ctx, rcs);
         borrow!(self.instance_node).handle_post_render(&self, ctx, rcs);
+        for &layer in &grouped_layers {
+            rcs.pop_group(layer);
+            rcs.restore(layer);
+        }
     }
 
     /// Manages unpacking an Rc<RefCell<PaxValue>>, downcasting into
@@ -630,6 +777,29 @@ impl ExpandedNode {
         func(self);
     }
 
+    /// Visits this node and its descendants in tree order, parents before their children
+    pub fn recurse_visit_preorder(self: &Rc<Self>, func: &mut impl FnMut(&Rc<Self>)) {
+        self.compute_flattened_slot_children();
+        func(self);
+        for child in self.children.get().iter() {
+            child.recurse_visit_preorder(func)
+        }
+    }
+
+    /// Whether this node can receive keyboard focus: it's `focusable` and not `disabled`
+    pub fn is_focusable(&self) -> bool {
+        let common_properties = borrow!(self.common_properties);
+        let common_properties = borrow!(common_properties);
+        common_properties.focusable.get().unwrap_or(false)
+            && !common_properties.disabled.get().unwrap_or(false)
+    }
+
+    pub fn tab_index(&self) -> isize {
+        let common_properties = borrow!(self.common_properties);
+        let tab_index = borrow!(common_properties).tab_index.get();
+        tab_index.unwrap_or(0)
+    }
+
     pub fn get_node_context(self: &Rc<Self>, ctx: &Rc<RuntimeContext>) -> NodeContext {
         let globals = ctx.globals();
         let t_and_b = self.transform_and_bounds.clone();
@@ -772,15 +942,54 @@ impl ExpandedNode {
         }
     }
 
+    /// Runs the handlers registered on this node under `handler_key`, with the properties of
+    /// the component they were bound in
+    pub(crate) fn run_event_handlers<T: Clone + 'static>(
+        self: &Rc<Self>,
+        handler_key: &str,
+        event: &Event<T>,
+        ctx: &Rc<RuntimeContext>,
+    ) {
+        let Some(registry) = borrow!(self.instance_node).base().get_handler_registry() else {
+            return;
+        };
+        let borrowed_registry = &borrow!(*registry);
+        let Some(handlers) = borrowed_registry.handlers.get(handler_key) else {
+            return;
+        };
+        if handlers.is_empty() {
+            return;
+        }
+        let component_properties = if let Some(cc) = self.containing_component.upgrade() {
+            Rc::clone(&*borrow!(cc.properties))
+        } else {
+            Rc::clone(&*borrow!(self.properties))
+        };
+
+        let context = self.get_node_context(ctx);
+        handlers.iter().for_each(|handler| {
+            let properties = if let HandlerLocation::Component = &handler.location {
+                Rc::clone(&*borrow!(self.properties))
+            } else {
+                Rc::clone(&component_properties)
+            };
+            (handler.function)(
+                Rc::clone(&properties),
+                &context,
+                Some(event.clone().to_pax_any()),
+            );
+        });
+    }
+
     dispatch_event_handler!(dispatch_scroll, Scroll, SCROLL_HANDLERS, true);
     dispatch_event_handler!(dispatch_clap, Clap, CLAP_HANDLERS, true);
     dispatch_event_handler!(dispatch_touch_start, TouchStart, TOUCH_START_HANDLERS, true);
 
     dispatch_event_handler!(dispatch_touch_move, TouchMove, TOUCH_MOVE_HANDLERS, true);
     dispatch_event_handler!(dispatch_touch_end, TouchEnd, TOUCH_END_HANDLERS, true);
-    dispatch_event_handler!(dispatch_key_down, KeyDown, KEY_DOWN_HANDLERS, false);
-    dispatch_event_handler!(dispatch_key_up, KeyUp, KEY_UP_HANDLERS, false);
-    dispatch_event_handler!(dispatch_key_press, KeyPress, KEY_PRESS_HANDLERS, false);
+    dispatch_event_handler!(dispatch_key_down, KeyDown, KEY_DOWN_HANDLERS, true);
+    dispatch_event_handler!(dispatch_key_up, KeyUp, KEY_UP_HANDLERS, true);
+    dispatch_event_handler!(dispatch_key_press, KeyPress, KEY_PRESS_HANDLERS, true);
     dispatch_event_handler!(
         dispatch_checkbox_change,
         CheckboxChange,
@@ -824,9 +1033,36 @@ impl ExpandedNode {
         true
     );
     dispatch_event_handler!(dispatch_click, Click, CLICK_HANDLERS, true);
+    dispatch_event_handler!(
+        dispatch_pointer_down,
+        PointerDown,
+        POINTER_DOWN_HANDLERS,
+        true
+    );
+    dispatch_event_handler!(
+        dispatch_pointer_move,
+        PointerMove,
+        POINTER_MOVE_HANDLERS,
+        true
+    );
+    dispatch_event_handler!(dispatch_pointer_up, PointerUp, POINTER_UP_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag_start, DragStart, DRAG_START_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag, Drag, DRAG_HANDLERS, true);
+    dispatch_event_handler!(dispatch_drag_end, DragEnd, DRAG_END_HANDLERS, true);
+    dispatch_event_handler!(dispatch_pinch, Pinch, PINCH_HANDLERS, true);
+    dispatch_event_handler!(dispatch_long_press, LongPress, LONG_PRESS_HANDLERS, true);
+    dispatch_event_handler!(dispatch_swipe, Swipe, SWIPE_HANDLERS, true);
+    dispatch_event_handler!(
+        dispatch_pointer_cancel,
+        PointerCancel,
+        POINTER_CANCEL_HANDLERS,
+        true
+    );
     dispatch_event_handler!(dispatch_wheel, Wheel, WHEEL_HANDLERS, true);
     dispatch_event_handler!(dispatch_drop, Drop, DROP_HANDLERS, true);
     dispatch_event_handler!(dispatch_focus, Focus, FOCUSED_HANDLERS, false);
+    dispatch_event_handler!(dispatch_focus_in, FocusIn, FOCUS_HANDLERS, false);
+    dispatch_event_handler!(dispatch_focus_out, FocusOut, BLUR_HANDLERS, false);
     dispatch_event_handler!(
         dispatch_select_start,
         SelectStart,
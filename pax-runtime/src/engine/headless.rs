@@ -0,0 +1,335 @@
+use std::fmt;
+use std::path::Path;
+
+#[cfg(not(feature = "designtime"))]
+use {
+    super::tiny_skia_render_context::TinySkiaRenderer,
+    crate::{api::RenderContext, ComponentInstance, DefinitionToInstanceTraverser, PaxEngine},
+    pax_message::NativeMessage,
+    pax_runtime_api::{Platform, OS},
+    std::{cell::Cell, rc::Rc},
+};
+
+/// Set to write rendered snapshots over golden images instead of comparing against them
+pub const UPDATE_GOLDENS_ENV_VAR: &str = "PAX_UPDATE_GOLDENS";
+
+/// Runs a [`PaxEngine`] without a chassis, drawing to a [`TinySkiaRenderer`] at a fixed
+/// viewport size. Time advances by a fixed step per tick, so frames are reproducible.
+/// Native elements such as text and form controls are only sent as messages, and don't
+/// show up in snapshots.
+#[cfg(not(feature = "designtime"))]
+pub struct HeadlessEngine {
+    pub engine: PaxEngine,
+    pub renderer: TinySkiaRenderer,
+    ticks: Rc<Cell<u64>>,
+}
+
+#[cfg(not(feature = "designtime"))]
+impl HeadlessEngine {
+    pub const TICKS_PER_SECOND: u64 = 60;
+
+    pub fn new(main_component_instance: Rc<ComponentInstance>, width: u32, height: u32) -> Self {
+        let ticks = Rc::new(Cell::new(0));
+        let elapsed_ticks = Rc::clone(&ticks);
+        let engine = PaxEngine::new(
+            main_component_instance,
+            (width as f64, height as f64),
+            Platform::Unknown,
+            OS::Unknown,
+            Box::new(move || (elapsed_ticks.get() * 1000 / Self::TICKS_PER_SECOND) as u128),
+        );
+        Self {
+            engine,
+            renderer: TinySkiaRenderer::new(width, height),
+            ticks,
+        }
+    }
+
+    /// Instantiates the main component of a cartridge
+    pub fn from_cartridge(
+        definition_to_instance_traverser: &dyn DefinitionToInstanceTraverser,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let main_component_instance =
+            definition_to_instance_traverser.get_main_component("USERLAND_COMPONENT_ROOT");
+        Self::new(main_component_instance, width, height)
+    }
+
+    pub fn tick(&mut self) -> Vec<NativeMessage> {
+        let native_messages = self.engine.tick();
+        self.ticks.set(self.ticks.get() + 1);
+        native_messages
+    }
+
+    pub fn render(&mut self) {
+        self.engine.render(&mut self.renderer);
+    }
+
+    /// Ticks and renders `frames` times, returning the native messages sent along the way
+    pub fn run_frames(&mut self, frames: usize) -> Vec<NativeMessage> {
+        let mut native_messages = Vec::new();
+        for _ in 0..frames {
+            native_messages.extend(self.tick());
+            self.render();
+        }
+        native_messages
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.engine.runtime_context.set_all_canvases_dirty();
+        self.renderer.resize(width as usize, height as usize);
+        self.engine.set_viewport_size((width as f64, height as f64));
+    }
+
+    /// What's been rendered so far
+    pub fn snapshot(&self) -> Snapshot {
+        self.renderer.snapshot()
+    }
+}
+
+/// Unpremultiplied RGBA pixels, row by row from the top left
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn new(width: u32, height: u32, rgba: Vec<u8>) -> Self {
+        assert_eq!(
+            rgba.len(),
+            width as usize * height as usize * 4,
+            "snapshot should have four bytes per pixel"
+        );
+        Self {
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [
+            self.rgba[i],
+            self.rgba[i + 1],
+            self.rgba[i + 2],
+            self.rgba[i + 3],
+        ]
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgba))
+            .expect("encoding to memory shouldn't fail");
+        png
+    }
+
+    pub fn from_png(png: &[u8]) -> Result<Self, String> {
+        let mut decoder = png::Decoder::new(png);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+        let pixels = &buf[..info.buffer_size()];
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => pixels.to_vec(),
+            png::ColorType::Rgb => pixels
+                .chunks_exact(3)
+                .flat_map(|px| [px[0], px[1], px[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => pixels
+                .chunks_exact(2)
+                .flat_map(|px| [px[0], px[0], px[0], px[1]])
+                .collect(),
+            png::ColorType::Grayscale => pixels.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => return Err("indexed png wasn't expanded".to_owned()),
+        };
+        Ok(Self::new(info.width, info.height, rgba))
+    }
+
+    /// Compares against `expected`, allowing each channel of each pixel to be off by up
+    /// to `tolerance`. Colors are compared premultiplied, since how transparent pixels
+    /// are colored doesn't matter once they're composited.
+    pub fn compare(&self, expected: &Snapshot, tolerance: u8) -> Result<(), SnapshotMismatch> {
+        if (self.width, self.height) != (expected.width, expected.height) {
+            return Err(SnapshotMismatch::Size {
+                actual: (self.width, self.height),
+                expected: (expected.width, expected.height),
+            });
+        }
+        let mut mismatched_pixels = 0;
+        let mut max_difference = 0;
+        let mut diff = Vec::with_capacity(self.rgba.len());
+        for (actual, expected) in self.rgba.chunks_exact(4).zip(expected.rgba.chunks_exact(4)) {
+            let difference = premultiplied(actual)
+                .into_iter()
+                .zip(premultiplied(expected))
+                .map(|(a, e)| a.abs_diff(e))
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+            if difference > tolerance {
+                mismatched_pixels += 1;
+                diff.extend([255, 0, 0, 255]);
+            } else {
+                diff.extend([expected[0], expected[1], expected[2], expected[3] / 4]);
+            }
+        }
+        if mismatched_pixels == 0 {
+            return Ok(());
+        }
+        Err(SnapshotMismatch::Pixels {
+            mismatched_pixels,
+            max_difference,
+            diff: Snapshot::new(self.width, self.height, diff),
+        })
+    }
+
+    /// Panics unless this matches the golden image at `golden_path` within `tolerance`,
+    /// as in [`Snapshot::compare`]. On a mismatch, what was rendered is written next to
+    /// the golden image as `<name>.actual.png`, along with `<name>.diff.png` marking the
+    /// pixels that differ in red. With [`UPDATE_GOLDENS_ENV_VAR`] set, the golden image is
+    /// written instead.
+    pub fn assert_matches_golden(&self, golden_path: impl AsRef<Path>, tolerance: u8) {
+        let golden_path = golden_path.as_ref();
+        if std::env::var_os(UPDATE_GOLDENS_ENV_VAR).is_some() {
+            if let Some(dir) = golden_path.parent() {
+                std::fs::create_dir_all(dir).expect("couldn't create golden image directory");
+            }
+            std::fs::write(golden_path, self.to_png()).expect("couldn't write golden image");
+            return;
+        }
+        let golden = std::fs::read(golden_path).unwrap_or_else(|e| {
+            panic!(
+                "couldn't read golden image {}: {e} (run with {UPDATE_GOLDENS_ENV_VAR}=1 to create it)",
+                golden_path.display()
+            )
+        });
+        let golden = Snapshot::from_png(&golden).unwrap_or_else(|e| {
+            panic!(
+                "couldn't decode golden image {}: {e}",
+                golden_path.display()
+            )
+        });
+        if let Err(mismatch) = self.compare(&golden, tolerance) {
+            let actual_path = golden_path.with_extension("actual.png");
+            let _ = std::fs::write(&actual_path, self.to_png());
+            if let SnapshotMismatch::Pixels { diff, .. } = &mismatch {
+                let _ = std::fs::write(golden_path.with_extension("diff.png"), diff.to_png());
+            }
+            panic!(
+                "snapshot doesn't match golden image {}: {mismatch} (rendered to {})",
+                golden_path.display(),
+                actual_path.display()
+            );
+        }
+    }
+}
+
+fn premultiplied(rgba: &[u8]) -> [u8; 4] {
+    let alpha = rgba[3] as u16;
+    let premultiply = |channel: u8| ((channel as u16 * alpha + 127) / 255) as u8;
+    [
+        premultiply(rgba[0]),
+        premultiply(rgba[1]),
+        premultiply(rgba[2]),
+        rgba[3],
+    ]
+}
+
+#[derive(Debug, Clone)]
+pub enum SnapshotMismatch {
+    Size {
+        actual: (u32, u32),
+        expected: (u32, u32),
+    },
+    Pixels {
+        mismatched_pixels: usize,
+        max_difference: u8,
+        /// The expected image faded out, with the pixels that differ in red
+        diff: Snapshot,
+    },
+}
+
+impl fmt::Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotMismatch::Size { actual, expected } => write!(
+                f,
+                "size is {}x{}, expected {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            SnapshotMismatch::Pixels {
+                mismatched_pixels,
+                max_difference,
+                ..
+            } => write!(
+                f,
+                "{mismatched_pixels} pixels differ, by up to {max_difference} in a channel"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Snapshot, SnapshotMismatch};
+
+    fn gradient(width: u32, height: u32) -> Snapshot {
+        let rgba = (0..width * height)
+            .flat_map(|i| [(i % 256) as u8, (i / 256) as u8, 128, (255 - i % 256) as u8])
+            .collect();
+        Snapshot::new(width, height, rgba)
+    }
+
+    #[test]
+    fn snapshot_survives_png_round_trip() {
+        let snapshot = gradient(40, 30);
+        assert_eq!(Snapshot::from_png(&snapshot.to_png()), Ok(snapshot));
+    }
+
+    #[test]
+    fn compare_allows_differences_within_tolerance() {
+        let expected = Snapshot::new(2, 1, vec![100, 100, 100, 255, 0, 0, 0, 0]);
+        let off_by_three = Snapshot::new(2, 1, vec![103, 100, 98, 255, 0, 0, 0, 0]);
+        assert!(off_by_three.compare(&expected, 3).is_ok());
+        match off_by_three.compare(&expected, 2) {
+            Err(SnapshotMismatch::Pixels {
+                mismatched_pixels,
+                max_difference,
+                diff,
+            }) => {
+                assert_eq!((mismatched_pixels, max_difference), (1, 3));
+                assert_eq!(diff.pixel(0, 0), [255, 0, 0, 255]);
+            }
+            other => panic!("expected pixels to differ, got {:?}", other),
+        }
+        // the color of fully transparent pixels doesn't matter
+        let recolored_transparent = Snapshot::new(2, 1, vec![100, 100, 100, 255, 9, 9, 9, 0]);
+        assert!(recolored_transparent.compare(&expected, 0).is_ok());
+        assert!(matches!(
+            gradient(2, 2).compare(&expected, 255),
+            Err(SnapshotMismatch::Size { .. })
+        ));
+    }
+}
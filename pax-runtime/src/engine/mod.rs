@@ -5,23 +5,27 @@ use_RefCell!();
 use std::collections::HashMap;
 use std::ops::Range;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicUsize, Ordering};
 
 use pax_message::NativeMessage;
 use pax_runtime_api::{
     pax_value::PaxAny, use_RefCell, Event, Focus, SelectStart, Variable, Window, OS,
 };
 
-use crate::api::{KeyDown, KeyPress, KeyUp, NodeContext, RenderContext};
+use crate::api::{KeyDown, KeyPress, KeyUp, ModifierKey, NodeContext, RenderContext};
+use crate::constants::{KEY_DOWN_HANDLERS, KEY_PRESS_HANDLERS, KEY_UP_HANDLERS};
 
 use crate::{ComponentInstance, RuntimeContext};
 use pax_runtime_api::Platform;
 
+#[cfg(feature = "headless")]
+pub mod headless;
 pub mod node_interface;
 pub mod occlusion;
 // TODO move these to not be in engine - make separate crates?
 pub mod pax_pixels_render_context;
 pub mod piet_render_context;
+#[cfg(feature = "headless")]
+pub mod tiny_skia_render_context;
 
 /// The atomic unit of rendering; also the container for each unique tuple of computed properties.
 /// Represents an expanded node, that is "expanded" in the context of computed properties and repeat expansion.
@@ -279,9 +283,13 @@ impl PaxEngine {
 
         let ctx = &self.runtime_context;
         occlusion::update_node_occlusion(&self.root_expanded_node, ctx);
-        let time = &ctx.globals().frames_elapsed;
+        let globals = ctx.globals();
+        pax_runtime_api::properties::set_elapsed_millis((globals.get_elapsed_millis)() as f64);
+        let time = &globals.frames_elapsed;
         time.set(time.get() + 1);
 
+        pax_runtime_api::timeline::tick_timelines();
+        crate::recognize_long_presses(ctx);
         ctx.flush_custom_events().unwrap();
         let native_messages = ctx.take_native_messages();
         native_messages
@@ -335,9 +343,10 @@ impl PaxEngine {
     }
 
     pub fn update_layer_count(&self, rcs: &mut dyn RenderContext) -> Range<usize> {
-        static LAST_LAYER_COUNT: AtomicUsize = AtomicUsize::new(0); // last-patch layer_count
         let curr_layer_count = self.runtime_context.layer_count.get();
-        let old_layer_count = LAST_LAYER_COUNT.load(Ordering::Relaxed);
+        // the render context keeps the layer count of the last patch, rather than a
+        // global, so that several engines can render in the same process
+        let old_layer_count = rcs.layers();
         if old_layer_count != curr_layer_count {
             rcs.resize_layers_to(
                 curr_layer_count,
@@ -345,7 +354,6 @@ impl PaxEngine {
             );
             self.runtime_context
                 .resize_canvas_layers_to(curr_layer_count);
-            LAST_LAYER_COUNT.store(curr_layer_count, Ordering::Relaxed)
         }
         old_layer_count..curr_layer_count
     }
@@ -376,42 +384,51 @@ impl PaxEngine {
         prevent_default
     }
 
+    /// Dispatches a key down to the focused node, see [`PaxEngine::dispatch_key_event`].
+    /// Unless a handler prevents its default, Tab and Shift-Tab then move focus through the
+    /// focusable nodes.
     pub fn global_dispatch_key_down(&self, args: KeyDown) -> bool {
-        let mut prevent_default = false;
-        self.root_expanded_node
-            .recurse_visit_postorder(&mut |expanded_node| {
-                prevent_default |= expanded_node.dispatch_key_down(
-                    Event::new(args.clone()),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
-            });
+        let is_tab = args.keyboard.key == "Tab";
+        let backwards = args
+            .keyboard
+            .modifiers
+            .iter()
+            .any(|m| matches!(m, ModifierKey::Shift));
+        let prevent_default =
+            self.dispatch_key_event(args, KEY_DOWN_HANDLERS, ExpandedNode::dispatch_key_down);
+        if is_tab && !prevent_default {
+            return self.runtime_context.focus_next(backwards);
+        }
         prevent_default
     }
 
     pub fn global_dispatch_key_up(&self, args: KeyUp) -> bool {
-        let mut prevent_default = false;
-        self.root_expanded_node
-            .recurse_visit_postorder(&mut |expanded_node| {
-                prevent_default |= expanded_node.dispatch_key_up(
-                    Event::new(args.clone()),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
-            });
-        prevent_default
+        self.dispatch_key_event(args, KEY_UP_HANDLERS, ExpandedNode::dispatch_key_up)
     }
 
     pub fn global_dispatch_key_press(&self, args: KeyPress) -> bool {
-        let mut prevent_default = false;
+        self.dispatch_key_event(args, KEY_PRESS_HANDLERS, ExpandedNode::dispatch_key_press)
+    }
+
+    /// Dispatches a keyboard event to the focused node, bubbling up from it. While no node
+    /// is focused, the event is broadcast to every node instead.
+    fn dispatch_key_event<T: Clone + 'static>(
+        &self,
+        args: T,
+        handler_key: &str,
+        dispatch: fn(&Rc<ExpandedNode>, Event<T>, &Globals, &Rc<RuntimeContext>) -> bool,
+    ) -> bool {
+        let event = Event::new(args);
+        let globals = self.runtime_context.globals();
+        if let Some(focused) = self.runtime_context.get_focused_element() {
+            return dispatch(&focused, event, &globals, &self.runtime_context);
+        }
         self.root_expanded_node
             .recurse_visit_postorder(&mut |expanded_node| {
-                prevent_default |= expanded_node.dispatch_key_press(
-                    Event::new(args.clone()),
-                    &self.runtime_context.globals(),
-                    &self.runtime_context,
-                );
+                if !event.propagation_stopped() {
+                    expanded_node.run_event_handlers(handler_key, &event, &self.runtime_context);
+                }
             });
-        prevent_default
+        event.cancelled()
     }
 }
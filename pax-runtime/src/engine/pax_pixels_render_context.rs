@@ -1,6 +1,6 @@
 use kurbo::{BezPath, PathEl, Shape};
 use pax_pixels::{point, Box2D, Image, Path, Transform2D, WgpuRenderer};
-use pax_runtime_api::{Axis, RenderContext};
+use pax_runtime_api::{Axis, BlendMode, Compositing, RenderContext};
 use std::{cell::RefCell, collections::HashMap, future::Future, pin::Pin, rc::Rc};
 
 type LayerDef = (WgpuRenderer<'static>, Pin<Box<dyn Fn()>>);
@@ -73,14 +73,14 @@ impl RenderContext for PaxPixelsRenderer {
         layer: usize,
         path: kurbo::BezPath,
         fill: &pax_runtime_api::Fill,
-        width: f64,
+        style: &kurbo::Stroke,
     ) {
         self.with_layer_context(layer, |context| {
             let bounds = path.bounding_box();
             context.stroke_path(
                 convert_kurbo_to_lyon_path(&path),
                 to_pax_pixels_fill(fill, bounds),
-                width as f32,
+                &to_pax_pixels_stroke(style),
             );
         });
     }
@@ -109,6 +109,29 @@ impl RenderContext for PaxPixelsRenderer {
         });
     }
 
+    fn push_group(&mut self, layer: usize, compositing: Compositing) {
+        self.with_layer_context(layer, |context| {
+            context.push_group(compositing.opacity as f32, compositing.blur as f32);
+        });
+    }
+    fn pop_group(&mut self, layer: usize) {
+        self.with_layer_context(layer, |context| {
+            context.pop_group();
+        });
+    }
+
+    fn set_blend_mode(&mut self, layer: usize, blend_mode: BlendMode) {
+        self.with_layer_context(layer, |context| {
+            context.set_blend_mode(to_pax_pixels_blend_mode(blend_mode));
+        });
+    }
+
+    fn blur_backdrop(&mut self, layer: usize, path: kurbo::BezPath, sigma: f64) {
+        self.with_layer_context(layer, |context| {
+            context.blur_backdrop(convert_kurbo_to_lyon_path(&path), sigma as f32);
+        });
+    }
+
     fn load_image(&mut self, identifier: &str, image: &[u8], width: usize, height: usize) {
         self.image_map.insert(
             identifier.to_string(),
@@ -293,6 +316,50 @@ fn to_pax_pixels_fill(fill: &pax_runtime_api::Fill, rect: kurbo::Rect) -> pax_pi
     }
 }
 
+fn to_pax_pixels_stroke(style: &kurbo::Stroke) -> pax_pixels::Stroke {
+    pax_pixels::Stroke {
+        weight: style.width as f32,
+        cap: match style.start_cap {
+            kurbo::Cap::Butt => pax_pixels::LineCap::Butt,
+            kurbo::Cap::Square => pax_pixels::LineCap::Square,
+            kurbo::Cap::Round => pax_pixels::LineCap::Round,
+        },
+        join: match style.join {
+            kurbo::Join::Bevel => pax_pixels::LineJoin::Bevel,
+            kurbo::Join::Miter => pax_pixels::LineJoin::Miter,
+            kurbo::Join::Round => pax_pixels::LineJoin::Round,
+        },
+        miter_limit: style.miter_limit as f32,
+        dash: style
+            .dash_pattern
+            .iter()
+            .map(|&length| length as f32)
+            .collect(),
+        dash_offset: style.dash_offset as f32,
+    }
+}
+
+fn to_pax_pixels_blend_mode(blend_mode: BlendMode) -> pax_pixels::BlendMode {
+    match blend_mode {
+        BlendMode::Normal => pax_pixels::BlendMode::Normal,
+        BlendMode::Multiply => pax_pixels::BlendMode::Multiply,
+        BlendMode::Screen => pax_pixels::BlendMode::Screen,
+        BlendMode::Overlay => pax_pixels::BlendMode::Overlay,
+        BlendMode::Darken => pax_pixels::BlendMode::Darken,
+        BlendMode::Lighten => pax_pixels::BlendMode::Lighten,
+        BlendMode::ColorDodge => pax_pixels::BlendMode::ColorDodge,
+        BlendMode::ColorBurn => pax_pixels::BlendMode::ColorBurn,
+        BlendMode::HardLight => pax_pixels::BlendMode::HardLight,
+        BlendMode::SoftLight => pax_pixels::BlendMode::SoftLight,
+        BlendMode::Difference => pax_pixels::BlendMode::Difference,
+        BlendMode::Exclusion => pax_pixels::BlendMode::Exclusion,
+        BlendMode::Hue => pax_pixels::BlendMode::Hue,
+        BlendMode::Saturation => pax_pixels::BlendMode::Saturation,
+        BlendMode::Color => pax_pixels::BlendMode::Color,
+        BlendMode::Luminosity => pax_pixels::BlendMode::Luminosity,
+    }
+}
+
 pub fn to_pax_pixels_color(color: &pax_runtime_api::Color) -> pax_pixels::Color {
     let [r, g, b, a] = color.to_rgba_0_1();
     pax_pixels::Color::rgba(r as f32, g as f32, b as f32, a as f32)
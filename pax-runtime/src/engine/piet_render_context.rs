@@ -1,7 +1,7 @@
-use pax_runtime_api::Fill;
+use pax_runtime_api::{BlendMode, Compositing, Fill};
 use piet::{
-    kurbo::{self, Affine, Shape},
-    InterpolationMode, LinearGradient, RadialGradient,
+    kurbo::{self, Affine, Rect, Shape},
+    ImageFormat, InterpolationMode, LineCap, LineJoin, LinearGradient, RadialGradient, StrokeStyle,
 };
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
@@ -10,15 +10,43 @@ use crate::api;
 struct ImgData<R: piet::RenderContext> {
     img: R::Image,
     size: (usize, usize),
+    // the unpremultiplied RGBA pixels, kept without a group factory to fade the image
+    // by the opacity of the groups it's drawn in
+    pixels: Option<Vec<u8>>,
 }
 
 type ClearFn = Box<dyn Fn()>;
 type ResizeFn = Box<dyn Fn()>;
+type CompositeFn = Box<dyn FnOnce(&Compositing, BlendMode, Option<Rect>)>;
+type GroupFactory<R> = Box<dyn Fn(usize) -> (R, CompositeFn)>;
+type PixelReader = Box<dyn Fn(usize, Rect) -> Option<(Vec<u8>, usize, usize)>>;
+
+struct Group<R: piet::RenderContext> {
+    // None if the backend can't draw offscreen, in which case the opacity is
+    // applied to each fill and stroke instead, and blur is ignored
+    offscreen: Option<(R, CompositeFn)>,
+    compositing: Compositing,
+    // the blend mode in effect when the group was pushed, which it's composited with
+    blend_mode: BlendMode,
+    // the area drawn to so far, in the coordinates of the layer
+    bounds: Option<Rect>,
+}
+
+/// The blend mode of a layer, along with the ones to go back to on restore
+#[derive(Default)]
+struct BlendState {
+    current: BlendMode,
+    saved: Vec<BlendMode>,
+}
 
 pub struct PietRenderer<R: piet::RenderContext> {
     backends: Vec<(R, ClearFn, ResizeFn)>,
     image_map: HashMap<String, ImgData<R>>,
     layer_factory: Box<dyn Fn(usize) -> (R, ClearFn, ResizeFn)>,
+    group_factory: Option<GroupFactory<R>>,
+    groups: HashMap<usize, Vec<Group<R>>>,
+    blend_states: HashMap<usize, BlendState>,
+    pixel_reader: Option<PixelReader>,
 }
 
 impl<R: piet::RenderContext> PietRenderer<R> {
@@ -27,51 +55,245 @@ impl<R: piet::RenderContext> PietRenderer<R> {
             layer_factory: Box::new(layer_factory),
             backends: Vec::new(),
             image_map: HashMap::new(),
+            group_factory: None,
+            groups: HashMap::new(),
+            blend_states: HashMap::new(),
+            pixel_reader: None,
+        }
+    }
+
+    /// Draws groups offscreen. `group_factory` creates a render context for a group
+    /// on the given layer, along with a function compositing what was drawn into it
+    /// back onto whatever the layer was drawing to before. The function is given the
+    /// area that was drawn to in layer coordinates, or None if nothing was, and is
+    /// expected to blur the group with [`blur_rgba`] if asked to. Blend modes are only
+    /// supported with a group factory. Without one, the opacity of a group is applied
+    /// to each thing drawn in it, so overlapping children show through each other, and
+    /// blur is ignored.
+    pub fn with_group_factory(
+        mut self,
+        group_factory: impl Fn(usize) -> (R, CompositeFn) + 'static,
+    ) -> Self {
+        self.group_factory = Some(Box::new(group_factory));
+        self
+    }
+
+    /// Enables backdrop blurs. `pixel_reader` returns the unpremultiplied RGBA pixels
+    /// of what the given layer is currently drawing to within a rect in layer
+    /// coordinates, along with their width and height in pixels.
+    pub fn with_pixel_reader(
+        mut self,
+        pixel_reader: impl Fn(usize, Rect) -> Option<(Vec<u8>, usize, usize)> + 'static,
+    ) -> Self {
+        self.pixel_reader = Some(Box::new(pixel_reader));
+        self
+    }
+
+    /// The render context currently drawn to on `layer`: the innermost offscreen
+    /// group if there is one, otherwise the layer itself
+    fn target(&mut self, layer: usize) -> Option<&mut R> {
+        let has_offscreen = self
+            .groups
+            .get(&layer)
+            .is_some_and(|groups| groups.iter().any(|group| group.offscreen.is_some()));
+        if has_offscreen {
+            self.groups
+                .get_mut(&layer)?
+                .iter_mut()
+                .rev()
+                .find_map(|group| group.offscreen.as_mut().map(|(target, _)| target))
+        } else {
+            self.backends.get_mut(layer).map(|(target, _, _)| target)
+        }
+    }
+
+    /// The opacity of the groups on `layer` that couldn't be drawn offscreen
+    fn fallback_opacity(&self, layer: usize) -> f64 {
+        self.groups.get(&layer).map_or(1.0, |groups| {
+            groups
+                .iter()
+                .filter(|group| group.offscreen.is_none())
+                .map(|group| group.compositing.opacity)
+                .product()
+        })
+    }
+
+    fn blend_mode(&self, layer: usize) -> BlendMode {
+        self.blend_states
+            .get(&layer)
+            .map_or(BlendMode::Normal, |state| state.current)
+    }
+
+    /// Draws with `draw`, in a group of its own if it needs to be blended with
+    /// something other than the normal blend mode, since piet can't blend by itself
+    fn blended(&mut self, layer: usize, draw: impl FnOnce(&mut Self)) {
+        let needs_group =
+            self.blend_mode(layer) != BlendMode::Normal && self.group_factory.is_some();
+        if needs_group {
+            api::RenderContext::push_group(self, layer, Compositing::default());
+        }
+        draw(self);
+        if needs_group {
+            api::RenderContext::pop_group(self, layer);
+        }
+    }
+
+    /// Records that `rect`, in the current coordinates of `layer`, is being drawn to
+    fn mark_drawn(&mut self, layer: usize, rect: Rect) {
+        let Some(transform) = self.target(layer).map(|target| target.current_transform()) else {
+            return;
+        };
+        if let Some(group) = self
+            .groups
+            .get_mut(&layer)
+            .and_then(|groups| groups.last_mut())
+        {
+            let rect = transform.transform_rect_bbox(rect);
+            group.bounds = Some(group.bounds.map_or(rect, |bounds| bounds.union(rect)));
         }
     }
 }
 
 impl<R: piet::RenderContext> api::RenderContext for PietRenderer<R> {
     fn fill(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill) {
-        let rect = path.bounding_box();
-        let brush = fill_to_piet_brush(fill, rect);
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
-            layer.fill(path, &brush);
-        }
+        self.blended(layer, |this| {
+            let rect = path.bounding_box();
+            this.mark_drawn(layer, rect);
+            let brush = fill_to_piet_brush(fill, rect, this.fallback_opacity(layer));
+            if let Some(layer) = this.target(layer) {
+                layer.fill(path, &brush);
+            }
+        });
     }
 
-    fn stroke(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill, width: f64) {
-        let rect = path.bounding_box();
-        let brush = fill_to_piet_brush(fill, rect);
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
-            layer.stroke(path, &brush, width);
-        }
+    fn stroke(&mut self, layer: usize, path: kurbo::BezPath, fill: &Fill, style: &kurbo::Stroke) {
+        self.blended(layer, |this| {
+            let rect = path.bounding_box();
+            // miters reach the furthest past the path, square caps and bevels only as far
+            // as the corners of a square around each point
+            let reach = style.width / 2.0 * style.miter_limit.max(std::f64::consts::SQRT_2);
+            this.mark_drawn(layer, rect.inflate(reach, reach));
+            let brush = fill_to_piet_brush(fill, rect, this.fallback_opacity(layer));
+            if let Some(layer) = this.target(layer) {
+                layer.stroke_styled(path, &brush, style.width, &to_piet_stroke_style(style));
+            }
+        });
     }
 
     fn save(&mut self, layer: usize) {
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
+        let state = self.blend_states.entry(layer).or_default();
+        state.saved.push(state.current);
+        if let Some(layer) = self.target(layer) {
             let _ = layer.save();
         }
     }
 
     fn transform(&mut self, layer: usize, affine: Affine) {
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.target(layer) {
             layer.transform(affine);
         }
     }
 
     fn clip(&mut self, layer: usize, path: kurbo::BezPath) {
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
+        if let Some(layer) = self.target(layer) {
             layer.clip(path);
         }
     }
 
     fn restore(&mut self, layer: usize) {
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
+        if let Some(state) = self.blend_states.get_mut(&layer) {
+            state.current = state.saved.pop().unwrap_or_default();
+        }
+        if let Some(layer) = self.target(layer) {
             let _ = layer.restore();
         }
     }
 
+    fn push_group(&mut self, layer: usize, compositing: Compositing) {
+        let Some(transform) = self.target(layer).map(|target| target.current_transform()) else {
+            return;
+        };
+        let offscreen = self.group_factory.as_ref().map(|group_factory| {
+            let (mut offscreen, composite) = (group_factory)(layer);
+            offscreen.transform(transform);
+            (offscreen, composite)
+        });
+        let blend_mode = std::mem::take(&mut self.blend_states.entry(layer).or_default().current);
+        self.groups.entry(layer).or_default().push(Group {
+            offscreen,
+            compositing,
+            blend_mode,
+            bounds: None,
+        });
+    }
+
+    fn pop_group(&mut self, layer: usize) {
+        let Some(groups) = self.groups.get_mut(&layer) else {
+            return;
+        };
+        let Some(group) = groups.pop() else {
+            return;
+        };
+        // blurring spreads the group by about three standard deviations
+        let spread = 3.0 * group.compositing.blur;
+        let bounds = group.bounds.map(|bounds| bounds.inflate(spread, spread));
+        if let (Some(parent), Some(bounds)) = (groups.last_mut(), bounds) {
+            parent.bounds = Some(parent.bounds.map_or(bounds, |b| b.union(bounds)));
+        }
+        if let Some(state) = self.blend_states.get_mut(&layer) {
+            state.current = group.blend_mode;
+        }
+        if let Some((mut offscreen, composite)) = group.offscreen {
+            let _ = offscreen.finish();
+            (composite)(&group.compositing, group.blend_mode, bounds);
+        }
+    }
+
+    fn set_blend_mode(&mut self, layer: usize, blend_mode: BlendMode) {
+        self.blend_states.entry(layer).or_default().current = blend_mode;
+    }
+
+    fn blur_backdrop(&mut self, layer: usize, path: kurbo::BezPath, sigma: f64) {
+        if sigma <= 0.0 {
+            return;
+        }
+        let Some(transform) = self.target(layer).map(|target| target.current_transform()) else {
+            return;
+        };
+        let rect = transform
+            .transform_rect_bbox(path.bounding_box())
+            .inflate(3.0 * sigma, 3.0 * sigma)
+            .expand();
+        let Some((mut pixels, width, height)) = self
+            .pixel_reader
+            .as_ref()
+            .and_then(|pixel_reader| (pixel_reader)(layer, rect))
+        else {
+            return;
+        };
+        if width == 0 || height == 0 {
+            return;
+        }
+        // the pixels can be denser than layer coordinates, such as on high dpi screens
+        blur_rgba(
+            &mut pixels,
+            width,
+            height,
+            sigma * width as f64 / rect.width(),
+        );
+        let Some(target) = self.target(layer) else {
+            return;
+        };
+        let Ok(image) = target.make_image(width, height, &pixels, ImageFormat::RgbaSeparate) else {
+            return;
+        };
+        let _ = target.save();
+        target.clip(path);
+        target.transform(transform.inverse());
+        target.draw_image(&image, rect, InterpolationMode::Bilinear);
+        let _ = target.restore();
+    }
+
     fn load_image(&mut self, path: &str, buf: &[u8], width: usize, height: usize) {
         //is this okay!? we know it's the same kind of backend no matter what layer, but it might be storing data?
         let (render_context, _, _) = self.backends.first_mut().unwrap();
@@ -83,6 +305,7 @@ impl<R: piet::RenderContext> api::RenderContext for PietRenderer<R> {
             ImgData {
                 img,
                 size: (width, height),
+                pixels: self.group_factory.is_none().then(|| buf.to_vec()),
             },
         );
     }
@@ -95,9 +318,31 @@ impl<R: piet::RenderContext> api::RenderContext for PietRenderer<R> {
         let Some(data) = self.image_map.get(image_path) else {
             return;
         };
-        if let Some((layer, _, _)) = self.backends.get_mut(layer) {
-            layer.draw_image(&data.img, rect, InterpolationMode::Bilinear);
-        }
+        let img = data.img.clone();
+        let (width, height) = data.size;
+        // piet can't draw images with an opacity, so groups that couldn't be drawn
+        // offscreen fade a copy of the image instead
+        let opacity = self.fallback_opacity(layer);
+        let pixels = data.pixels.as_ref().filter(|_| opacity < 1.0).cloned();
+        self.blended(layer, |this| {
+            this.mark_drawn(layer, rect);
+            let Some(target) = this.target(layer) else {
+                return;
+            };
+            let faded = pixels.and_then(|mut pixels| {
+                for alpha in pixels.iter_mut().skip(3).step_by(4) {
+                    *alpha = (*alpha as f64 * opacity).round() as u8;
+                }
+                target
+                    .make_image(width, height, &pixels, ImageFormat::RgbaSeparate)
+                    .ok()
+            });
+            target.draw_image(
+                faded.as_ref().unwrap_or(&img),
+                rect,
+                InterpolationMode::Bilinear,
+            );
+        });
     }
 
     fn layers(&self) -> usize {
@@ -109,6 +354,8 @@ impl<R: piet::RenderContext> api::RenderContext for PietRenderer<R> {
         match layer_count.cmp(&current_len) {
             std::cmp::Ordering::Less => {
                 self.backends.truncate(layer_count);
+                self.groups.retain(|layer, _| *layer < layer_count);
+                self.blend_states.retain(|layer, _| *layer < layer_count);
             }
             std::cmp::Ordering::Equal => return,
             std::cmp::Ordering::Greater => {
@@ -144,21 +391,52 @@ impl<R: piet::RenderContext> api::RenderContext for PietRenderer<R> {
     }
 }
 
-fn fill_to_piet_brush(fill: &Fill, rect: kurbo::Rect) -> piet::PaintBrush {
+fn to_piet_stroke_style(style: &kurbo::Stroke) -> StrokeStyle {
+    let mut piet_style = StrokeStyle::new()
+        .line_join(match style.join {
+            kurbo::Join::Bevel => LineJoin::Bevel,
+            kurbo::Join::Miter => LineJoin::Miter {
+                limit: style.miter_limit,
+            },
+            kurbo::Join::Round => LineJoin::Round,
+        })
+        .line_cap(match style.start_cap {
+            kurbo::Cap::Butt => LineCap::Butt,
+            kurbo::Cap::Square => LineCap::Square,
+            kurbo::Cap::Round => LineCap::Round,
+        })
+        .dash_offset(style.dash_offset);
+    if !style.dash_pattern.is_empty() {
+        piet_style.set_dash_pattern(&style.dash_pattern[..]);
+    }
+    piet_style
+}
+
+fn fill_to_piet_brush(fill: &Fill, rect: kurbo::Rect, opacity: f64) -> piet::PaintBrush {
+    let fade = |color: piet::Color| color.with_alpha(color.as_rgba().3 * opacity);
+    let fade_stops = |stops: Vec<piet::GradientStop>| -> Vec<piet::GradientStop> {
+        stops
+            .into_iter()
+            .map(|stop| piet::GradientStop {
+                pos: stop.pos,
+                color: fade(stop.color),
+            })
+            .collect()
+    };
     match fill {
-        Fill::Solid(color) => color.to_piet_color().into(),
+        Fill::Solid(color) => fade(color.to_piet_color()).into(),
         Fill::LinearGradient(linear) => {
             let linear_gradient = LinearGradient::new(
                 Fill::to_unit_point(linear.start, (rect.width(), rect.height())),
                 Fill::to_unit_point(linear.end, (rect.width(), rect.height())),
-                Fill::to_piet_gradient_stops(linear.stops.clone()),
+                fade_stops(Fill::to_piet_gradient_stops(linear.stops.clone())),
             );
             linear_gradient.into()
         }
         Fill::RadialGradient(radial) => {
             let origin = Fill::to_unit_point(radial.start, (rect.width(), rect.height()));
             let center = Fill::to_unit_point(radial.end, (rect.width(), rect.height()));
-            let gradient_stops = Fill::to_piet_gradient_stops(radial.stops.clone());
+            let gradient_stops = fade_stops(Fill::to_piet_gradient_stops(radial.stops.clone()));
             let radial_gradient = RadialGradient::new(radial.radius, gradient_stops)
                 .with_center(center)
                 .with_origin(origin);
@@ -166,3 +444,121 @@ fn fill_to_piet_brush(fill: &Fill, rect: kurbo::Rect) -> piet::PaintBrush {
         }
     }
 }
+
+/// Blurs unpremultiplied RGBA pixels in place with a gaussian of standard deviation
+/// `sigma` in pixels, approximated by three successive box blurs. Pixels past the
+/// edges are taken to be copies of the edge.
+pub fn blur_rgba(rgba: &mut [u8], width: usize, height: usize, sigma: f64) {
+    if sigma <= 0.0 || width == 0 || height == 0 {
+        return;
+    }
+    let mut pixels: Vec<f32> = rgba
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let alpha = px[3] as f32 / 255.0;
+            [
+                px[0] as f32 * alpha,
+                px[1] as f32 * alpha,
+                px[2] as f32 * alpha,
+                px[3] as f32,
+            ]
+        })
+        .collect();
+    let mut scratch = vec![0.0; pixels.len()];
+    for radius in box_blur_radii(sigma) {
+        // horizontally, then vertically
+        box_blur(&pixels, &mut scratch, height, width, 4 * width, 4, radius);
+        box_blur(&scratch, &mut pixels, width, height, 4, 4 * width, radius);
+    }
+    for (px, blurred) in rgba.chunks_exact_mut(4).zip(pixels.chunks_exact(4)) {
+        let alpha = blurred[3] / 255.0;
+        for channel in 0..3 {
+            px[channel] = if alpha > 0.0 {
+                (blurred[channel] / alpha).round().clamp(0.0, 255.0) as u8
+            } else {
+                0
+            };
+        }
+        px[3] = blurred[3].round().clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Radii of three box blurs that together approximate a gaussian of deviation `sigma`
+fn box_blur_radii(sigma: f64) -> [usize; 3] {
+    let ideal_width = (4.0 * sigma * sigma + 1.0).sqrt();
+    let mut lower = ideal_width.floor() as usize;
+    if lower.is_multiple_of(2) {
+        lower -= 1;
+    }
+    let lower_f = lower as f64;
+    let lower_count = ((12.0 * sigma * sigma - 3.0 * lower_f * lower_f - 12.0 * lower_f - 9.0)
+        / (-4.0 * lower_f - 4.0))
+        .round()
+        .clamp(0.0, 3.0) as usize;
+    std::array::from_fn(|i| {
+        if i < lower_count {
+            lower / 2
+        } else {
+            lower / 2 + 1
+        }
+    })
+}
+
+/// Averages every value of `src` with the `radius` values on each side of it along its
+/// line, writing the results to `dst`. Lines start `line_stride` apart and hold `len`
+/// pixels each `step` apart.
+fn box_blur(
+    src: &[f32],
+    dst: &mut [f32],
+    lines: usize,
+    len: usize,
+    line_stride: usize,
+    step: usize,
+    radius: usize,
+) {
+    let window = (2 * radius + 1) as f32;
+    for line in 0..lines {
+        let start = line * line_stride;
+        let at = |i: isize, channel: usize| {
+            src[start + i.clamp(0, len as isize - 1) as usize * step + channel]
+        };
+        for channel in 0..4 {
+            let r = radius as isize;
+            let mut sum: f32 = (-r..=r).map(|i| at(i, channel)).sum();
+            for i in 0..len as isize {
+                dst[start + i as usize * step + channel] = sum / window;
+                sum += at(i + r + 1, channel) - at(i - r, channel);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blur_rgba;
+
+    #[test]
+    fn blur_keeps_uniform_color() {
+        let mut pixels = [40, 80, 120, 200].repeat(16 * 8);
+        let expected = pixels.clone();
+        blur_rgba(&mut pixels, 16, 8, 3.0);
+        assert_eq!(pixels, expected);
+    }
+
+    #[test]
+    fn blur_spreads_a_point_symmetrically() {
+        let (width, height) = (31, 31);
+        let mut pixels = vec![0; width * height * 4];
+        let center = (15 * width + 15) * 4;
+        pixels[center..center + 4].copy_from_slice(&[255, 0, 0, 255]);
+        blur_rgba(&mut pixels, width, height, 2.0);
+        let alpha = |x: usize, y: usize| pixels[(y * width + x) * 4 + 3];
+        assert!(alpha(15, 15) < 255);
+        assert!(alpha(15, 15) > alpha(17, 15));
+        assert_eq!(alpha(13, 15), alpha(17, 15));
+        assert_eq!(alpha(15, 13), alpha(15, 17));
+        assert_eq!(alpha(0, 0), 0);
+        // the color of what was blurred is kept, only its coverage spreads
+        assert_eq!(pixels[((15 * width) + 17) * 4], 255);
+    }
+}
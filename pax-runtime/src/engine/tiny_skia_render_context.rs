@@ -0,0 +1,579 @@
+use kurbo::{Affine, BezPath, PathEl, Rect, Shape};
+use pax_runtime_api::{BlendMode, Compositing, Fill};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use tiny_skia::{
+    ClipMask, ColorU8, FillRule, FilterQuality, IntRect, Paint, Pixmap, PixmapPaint, Transform,
+};
+
+use super::headless::Snapshot;
+use super::piet_render_context::blur_rgba;
+use crate::api;
+
+/// What a layer is drawing to: the layer itself, or an offscreen group pushed onto it
+struct Surface {
+    pixmap: Pixmap,
+    state: DrawState,
+    saved: Vec<DrawState>,
+    // how the surface is drawn back onto the one below it, unused for the layer itself
+    compositing: Compositing,
+}
+
+#[derive(Clone, Default)]
+struct DrawState {
+    transform: Affine,
+    // in pixels, None when nothing is clipped
+    clip: Option<ClipMask>,
+    blend_mode: BlendMode,
+}
+
+impl Surface {
+    fn new(width: u32, height: u32, transform: Affine, compositing: Compositing) -> Self {
+        Self {
+            // tiny-skia can't make empty pixmaps, so a zero sized viewport gets a pixel
+            pixmap: Pixmap::new(width.max(1), height.max(1)).unwrap(),
+            state: DrawState {
+                transform,
+                ..Default::default()
+            },
+            saved: Vec::new(),
+            compositing,
+        }
+    }
+}
+
+/// Renders on the CPU with tiny-skia, one pixel per unit of the viewport, without a
+/// window or browser. Meant for rendering headlessly, e.g. to compare what components
+/// draw against golden images in tests.
+pub struct TinySkiaRenderer {
+    width: u32,
+    height: u32,
+    // the layer itself first, followed by the groups pushed onto it
+    layers: Vec<Vec<Surface>>,
+    images: HashMap<String, Pixmap>,
+}
+
+impl TinySkiaRenderer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            layers: Vec::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Every layer composited bottom to top onto a transparent background
+    pub fn snapshot(&self) -> Snapshot {
+        let mut composited = Pixmap::new(self.width.max(1), self.height.max(1)).unwrap();
+        for surfaces in &self.layers {
+            if let Some(surface) = surfaces.first() {
+                composited.draw_pixmap(
+                    0,
+                    0,
+                    surface.pixmap.as_ref(),
+                    &PixmapPaint::default(),
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+        Snapshot::new(
+            composited.width(),
+            composited.height(),
+            to_rgba(&composited),
+        )
+    }
+
+    fn target(&mut self, layer: usize) -> Option<&mut Surface> {
+        self.layers.get_mut(layer)?.last_mut()
+    }
+}
+
+impl api::RenderContext for TinySkiaRenderer {
+    fn fill(&mut self, layer: usize, path: BezPath, fill: &Fill) {
+        let Some(surface) = self.target(layer) else {
+            return;
+        };
+        let Some(skia_path) = to_path(&path) else {
+            return;
+        };
+        let mut paint = to_paint(fill, path.bounding_box());
+        paint.blend_mode = to_skia_blend_mode(surface.state.blend_mode);
+        surface.pixmap.fill_path(
+            &skia_path,
+            &paint,
+            FillRule::Winding,
+            to_transform(surface.state.transform),
+            surface.state.clip.as_ref(),
+        );
+    }
+
+    fn stroke(&mut self, layer: usize, path: BezPath, fill: &Fill, style: &kurbo::Stroke) {
+        // tiny-skia draws zero width strokes as hairlines, other renderers don't draw them
+        if style.width <= 0.0 {
+            return;
+        }
+        let Some(surface) = self.target(layer) else {
+            return;
+        };
+        let Some(skia_path) = to_path(&path) else {
+            return;
+        };
+        let mut paint = to_paint(fill, path.bounding_box());
+        paint.blend_mode = to_skia_blend_mode(surface.state.blend_mode);
+        surface.pixmap.stroke_path(
+            &skia_path,
+            &paint,
+            &to_skia_stroke(style),
+            to_transform(surface.state.transform),
+            surface.state.clip.as_ref(),
+        );
+    }
+
+    fn save(&mut self, layer: usize) {
+        if let Some(surface) = self.target(layer) {
+            surface.saved.push(surface.state.clone());
+        }
+    }
+
+    fn restore(&mut self, layer: usize) {
+        if let Some(surface) = self.target(layer) {
+            if let Some(state) = surface.saved.pop() {
+                surface.state = state;
+            }
+        }
+    }
+
+    fn clip(&mut self, layer: usize, path: BezPath) {
+        let (width, height) = (self.width.max(1), self.height.max(1));
+        let Some(surface) = self.target(layer) else {
+            return;
+        };
+        let path = to_path(&(surface.state.transform * path));
+        match (surface.state.clip.as_mut(), path) {
+            (Some(clip), Some(path)) => {
+                clip.intersect_path(&path, FillRule::Winding, true);
+            }
+            (None, Some(path)) => {
+                let mut clip = ClipMask::new();
+                clip.set_path(width, height, &path, FillRule::Winding, true);
+                surface.state.clip = Some(clip);
+            }
+            // nothing is left when clipping to an empty path, so clip to a rect that's
+            // entirely off the pixmap
+            (_, None) => {
+                let outside = tiny_skia::Rect::from_xywh(-2.0, -2.0, 1.0, 1.0).unwrap();
+                let mut clip = ClipMask::new();
+                clip.set_path(
+                    width,
+                    height,
+                    &tiny_skia::PathBuilder::from_rect(outside),
+                    FillRule::Winding,
+                    false,
+                );
+                surface.state.clip = Some(clip);
+            }
+        }
+    }
+
+    fn transform(&mut self, layer: usize, affine: Affine) {
+        if let Some(surface) = self.target(layer) {
+            surface.state.transform *= affine;
+        }
+    }
+
+    fn push_group(&mut self, layer: usize, compositing: Compositing) {
+        let (width, height) = (self.width, self.height);
+        let Some(surfaces) = self.layers.get_mut(layer) else {
+            return;
+        };
+        let transform = surfaces
+            .last()
+            .map_or(Affine::IDENTITY, |surface| surface.state.transform);
+        surfaces.push(Surface::new(width, height, transform, compositing));
+    }
+
+    fn pop_group(&mut self, layer: usize) {
+        let Some(surfaces) = self.layers.get_mut(layer) else {
+            return;
+        };
+        if surfaces.len() < 2 {
+            return;
+        }
+        let Some(mut group) = surfaces.pop() else {
+            return;
+        };
+        let Some(parent) = surfaces.last_mut() else {
+            return;
+        };
+        if group.compositing.blur > 0.0 {
+            blur_pixmap(&mut group.pixmap, group.compositing.blur);
+        }
+        parent.pixmap.draw_pixmap(
+            0,
+            0,
+            group.pixmap.as_ref(),
+            &PixmapPaint {
+                opacity: group.compositing.opacity.clamp(0.0, 1.0) as f32,
+                blend_mode: to_skia_blend_mode(parent.state.blend_mode),
+                quality: FilterQuality::Nearest,
+            },
+            Transform::identity(),
+            parent.state.clip.as_ref(),
+        );
+    }
+
+    fn set_blend_mode(&mut self, layer: usize, blend_mode: BlendMode) {
+        if let Some(surface) = self.target(layer) {
+            surface.state.blend_mode = blend_mode;
+        }
+    }
+
+    fn blur_backdrop(&mut self, layer: usize, path: BezPath, sigma: f64) {
+        if sigma <= 0.0 {
+            return;
+        }
+        let (width, height) = (self.width.max(1), self.height.max(1));
+        let Some(surface) = self.target(layer) else {
+            return;
+        };
+        let path = surface.state.transform * path;
+        let Some(skia_path) = to_path(&path) else {
+            return;
+        };
+        // blurring pulls in what's up to about three standard deviations away
+        let bounds = path
+            .bounding_box()
+            .inflate(3.0 * sigma, 3.0 * sigma)
+            .expand()
+            .intersect(Rect::new(0.0, 0.0, width as f64, height as f64));
+        let Some(region) = IntRect::from_ltrb(
+            bounds.x0 as i32,
+            bounds.y0 as i32,
+            bounds.x1 as i32,
+            bounds.y1 as i32,
+        ) else {
+            return;
+        };
+        let Some(mut blurred) = surface.pixmap.clone_rect(region) else {
+            return;
+        };
+        blur_pixmap(&mut blurred, sigma);
+        let mut mask = surface.state.clip.clone().unwrap_or_default();
+        if mask.is_empty() {
+            mask.set_path(width, height, &skia_path, FillRule::Winding, true);
+        } else {
+            mask.intersect_path(&skia_path, FillRule::Winding, true);
+        }
+        surface.pixmap.draw_pixmap(
+            region.x(),
+            region.y(),
+            blurred.as_ref(),
+            &PixmapPaint {
+                blend_mode: tiny_skia::BlendMode::Source,
+                ..Default::default()
+            },
+            Transform::identity(),
+            Some(&mask),
+        );
+    }
+
+    fn load_image(&mut self, identifier: &str, image: &[u8], width: usize, height: usize) {
+        if let Some(pixmap) = from_rgba(image, width as u32, height as u32) {
+            self.images.insert(identifier.to_owned(), pixmap);
+        }
+    }
+
+    fn draw_image(&mut self, layer: usize, image_path: &str, rect: Rect) {
+        let Self { layers, images, .. } = self;
+        let (Some(image), Some(surface)) = (
+            images.get(image_path),
+            layers
+                .get_mut(layer)
+                .and_then(|surfaces| surfaces.last_mut()),
+        ) else {
+            return;
+        };
+        let placement = Affine::translate(rect.origin().to_vec2())
+            * Affine::scale_non_uniform(
+                rect.width() / image.width() as f64,
+                rect.height() / image.height() as f64,
+            );
+        surface.pixmap.draw_pixmap(
+            0,
+            0,
+            image.as_ref(),
+            &PixmapPaint {
+                blend_mode: to_skia_blend_mode(surface.state.blend_mode),
+                quality: FilterQuality::Bilinear,
+                ..Default::default()
+            },
+            to_transform(surface.state.transform * placement),
+            surface.state.clip.as_ref(),
+        );
+    }
+
+    fn get_image_size(&mut self, image_path: &str) -> Option<(usize, usize)> {
+        self.images
+            .get(image_path)
+            .map(|image| (image.width() as usize, image.height() as usize))
+    }
+
+    fn image_loaded(&self, image_path: &str) -> bool {
+        self.images.contains_key(image_path)
+    }
+
+    fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    fn resize_layers_to(&mut self, layer_count: usize, dirty_canvases: Rc<RefCell<Vec<bool>>>) {
+        let current_len = self.layers.len();
+        self.layers.truncate(layer_count);
+        for i in current_len..layer_count {
+            self.layers.push(vec![Surface::new(
+                self.width,
+                self.height,
+                Affine::IDENTITY,
+                Compositing::default(),
+            )]);
+            if let Some(dirty_bit) = dirty_canvases.borrow_mut().get_mut(i) {
+                *dirty_bit = true;
+            }
+        }
+    }
+
+    fn clear(&mut self, layer: usize) {
+        if let Some(surface) = self.layers.get_mut(layer).and_then(|s| s.first_mut()) {
+            surface.pixmap.fill(tiny_skia::Color::TRANSPARENT);
+        }
+    }
+
+    fn flush(&mut self, _layer: usize, _dirty_canvases: Rc<RefCell<Vec<bool>>>) {
+        // NOTE: everything is drawn to the pixmaps right away
+    }
+
+    fn resize(&mut self, width: usize, height: usize) {
+        self.width = width as u32;
+        self.height = height as u32;
+        for surfaces in &mut self.layers {
+            *surfaces = vec![Surface::new(
+                self.width,
+                self.height,
+                Affine::IDENTITY,
+                Compositing::default(),
+            )];
+        }
+    }
+}
+
+/// None for paths without any segments, which tiny-skia can't represent
+fn to_path(path: &BezPath) -> Option<tiny_skia::Path> {
+    let mut builder = tiny_skia::PathBuilder::new();
+    for el in path.elements() {
+        match *el {
+            PathEl::MoveTo(p) => builder.move_to(p.x as f32, p.y as f32),
+            PathEl::LineTo(p) => builder.line_to(p.x as f32, p.y as f32),
+            PathEl::QuadTo(p1, p2) => {
+                builder.quad_to(p1.x as f32, p1.y as f32, p2.x as f32, p2.y as f32)
+            }
+            PathEl::CurveTo(p1, p2, p3) => builder.cubic_to(
+                p1.x as f32,
+                p1.y as f32,
+                p2.x as f32,
+                p2.y as f32,
+                p3.x as f32,
+                p3.y as f32,
+            ),
+            PathEl::ClosePath => builder.close(),
+        }
+    }
+    builder.finish()
+}
+
+fn to_transform(affine: Affine) -> Transform {
+    let [a, b, c, d, e, f] = affine.as_coeffs();
+    Transform::from_row(a as f32, b as f32, c as f32, d as f32, e as f32, f as f32)
+}
+
+fn to_skia_color(color: &pax_runtime_api::Color) -> tiny_skia::Color {
+    let [r, g, b, a] = color
+        .to_rgba_0_1()
+        .map(|channel| channel.clamp(0.0, 1.0) as f32);
+    tiny_skia::Color::from_rgba(r, g, b, a).unwrap_or(tiny_skia::Color::TRANSPARENT)
+}
+
+/// Gradients are laid out within `rect` the same way piet lays them out
+fn to_paint(fill: &Fill, rect: Rect) -> Paint<'static> {
+    let size = (rect.width(), rect.height());
+    let to_stops = |stops: &Vec<pax_runtime_api::GradientStop>| {
+        stops
+            .iter()
+            .map(|stop| {
+                let pos = match stop.position {
+                    pax_runtime_api::Size::Percent(percent) => percent.to_float() / 100.0,
+                    _ => panic!("Gradient stops must be specified in percentages"),
+                };
+                tiny_skia::GradientStop::new(pos as f32, to_skia_color(&stop.color))
+            })
+            .collect()
+    };
+    let to_point = |point: kurbo::Point| tiny_skia::Point::from_xy(point.x as f32, point.y as f32);
+    let mut paint = Paint::default();
+    let shader = match fill {
+        Fill::Solid(color) => {
+            paint.set_color(to_skia_color(color));
+            return paint;
+        }
+        Fill::LinearGradient(linear) => tiny_skia::LinearGradient::new(
+            to_point(Fill::to_unit_point(linear.start, size).resolve(rect)),
+            to_point(Fill::to_unit_point(linear.end, size).resolve(rect)),
+            to_stops(&linear.stops),
+            tiny_skia::SpreadMode::Pad,
+            Transform::identity(),
+        ),
+        Fill::RadialGradient(radial) => {
+            // the unit points are spread over a square around the rect, as big as its
+            // longest side
+            let side = rect.width().max(rect.height());
+            let square = Rect::from_center_size(rect.center(), (side, side));
+            tiny_skia::RadialGradient::new(
+                to_point(Fill::to_unit_point(radial.start, size).resolve(square)),
+                to_point(Fill::to_unit_point(radial.end, size).resolve(square)),
+                (radial.radius * side) as f32,
+                to_stops(&radial.stops),
+                tiny_skia::SpreadMode::Pad,
+                Transform::identity(),
+            )
+        }
+    };
+    // degenerate gradients don't draw anything
+    paint.shader = shader.unwrap_or(tiny_skia::Shader::SolidColor(tiny_skia::Color::TRANSPARENT));
+    paint
+}
+
+fn to_skia_stroke(style: &kurbo::Stroke) -> tiny_skia::Stroke {
+    let dash_pattern: Vec<f32> = style.dash_pattern.iter().map(|len| *len as f32).collect();
+    tiny_skia::Stroke {
+        width: style.width as f32,
+        miter_limit: style.miter_limit as f32,
+        line_cap: match style.start_cap {
+            kurbo::Cap::Butt => tiny_skia::LineCap::Butt,
+            kurbo::Cap::Square => tiny_skia::LineCap::Square,
+            kurbo::Cap::Round => tiny_skia::LineCap::Round,
+        },
+        line_join: match style.join {
+            kurbo::Join::Bevel => tiny_skia::LineJoin::Bevel,
+            kurbo::Join::Miter => tiny_skia::LineJoin::Miter,
+            kurbo::Join::Round => tiny_skia::LineJoin::Round,
+        },
+        // tiny-skia only takes patterns of even length
+        dash: match dash_pattern.len() % 2 {
+            0 => tiny_skia::StrokeDash::new(dash_pattern, style.dash_offset as f32),
+            _ => tiny_skia::StrokeDash::new(dash_pattern.repeat(2), style.dash_offset as f32),
+        },
+    }
+}
+
+fn to_skia_blend_mode(blend_mode: BlendMode) -> tiny_skia::BlendMode {
+    match blend_mode {
+        BlendMode::Normal => tiny_skia::BlendMode::SourceOver,
+        BlendMode::Multiply => tiny_skia::BlendMode::Multiply,
+        BlendMode::Screen => tiny_skia::BlendMode::Screen,
+        BlendMode::Overlay => tiny_skia::BlendMode::Overlay,
+        BlendMode::Darken => tiny_skia::BlendMode::Darken,
+        BlendMode::Lighten => tiny_skia::BlendMode::Lighten,
+        BlendMode::ColorDodge => tiny_skia::BlendMode::ColorDodge,
+        BlendMode::ColorBurn => tiny_skia::BlendMode::ColorBurn,
+        BlendMode::HardLight => tiny_skia::BlendMode::HardLight,
+        BlendMode::SoftLight => tiny_skia::BlendMode::SoftLight,
+        BlendMode::Difference => tiny_skia::BlendMode::Difference,
+        BlendMode::Exclusion => tiny_skia::BlendMode::Exclusion,
+        BlendMode::Hue => tiny_skia::BlendMode::Hue,
+        BlendMode::Saturation => tiny_skia::BlendMode::Saturation,
+        BlendMode::Color => tiny_skia::BlendMode::Color,
+        BlendMode::Luminosity => tiny_skia::BlendMode::Luminosity,
+    }
+}
+
+fn blur_pixmap(pixmap: &mut Pixmap, sigma: f64) {
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let mut rgba = to_rgba(pixmap);
+    blur_rgba(&mut rgba, width as usize, height as usize, sigma);
+    if let Some(blurred) = from_rgba(&rgba, width, height) {
+        *pixmap = blurred;
+    }
+}
+
+/// The unpremultiplied RGBA pixels of `pixmap`
+fn to_rgba(pixmap: &Pixmap) -> Vec<u8> {
+    pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect()
+}
+
+fn from_rgba(rgba: &[u8], width: u32, height: u32) -> Option<Pixmap> {
+    let mut pixmap = Pixmap::new(width, height)?;
+    if rgba.len() != pixmap.data().len() {
+        return None;
+    }
+    for (pixel, rgba) in pixmap.pixels_mut().iter_mut().zip(rgba.chunks_exact(4)) {
+        *pixel = ColorU8::from_rgba(rgba[0], rgba[1], rgba[2], rgba[3]).premultiply();
+    }
+    Some(pixmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TinySkiaRenderer;
+    use crate::api::{Color, Compositing, Fill, RenderContext};
+    use kurbo::{Rect, Shape};
+    use std::{cell::RefCell, rc::Rc};
+
+    fn renderer(width: u32, height: u32) -> TinySkiaRenderer {
+        let mut renderer = TinySkiaRenderer::new(width, height);
+        renderer.resize_layers_to(1, Rc::new(RefCell::new(Vec::new())));
+        renderer
+    }
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> kurbo::BezPath {
+        Rect::new(x0, y0, x1, y1).to_path(0.1)
+    }
+
+    #[test]
+    fn clips_and_transforms_fills() {
+        let mut renderer = renderer(20, 10);
+        renderer.save(0);
+        renderer.clip(0, rect(0.0, 0.0, 10.0, 10.0));
+        renderer.transform(0, kurbo::Affine::translate((5.0, 0.0)));
+        renderer.fill(0, rect(0.0, 0.0, 10.0, 10.0), &Fill::Solid(Color::BLACK));
+        renderer.restore(0);
+        let snapshot = renderer.snapshot();
+        assert_eq!(snapshot.pixel(2, 5), [0, 0, 0, 0]);
+        assert_eq!(snapshot.pixel(7, 5), [0, 0, 0, 255]);
+        assert_eq!(snapshot.pixel(12, 5), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn composites_groups_with_their_opacity() {
+        let mut renderer = renderer(10, 10);
+        renderer.push_group(
+            0,
+            Compositing {
+                opacity: 0.5,
+                ..Default::default()
+            },
+        );
+        // overlapping fills in a group don't show through each other
+        renderer.fill(0, rect(0.0, 0.0, 10.0, 10.0), &Fill::Solid(Color::BLACK));
+        renderer.fill(0, rect(0.0, 0.0, 10.0, 10.0), &Fill::Solid(Color::BLACK));
+        renderer.pop_group(0);
+        let [_, _, _, alpha] = renderer.snapshot().pixel(5, 5);
+        assert!(alpha.abs_diff(128) <= 1, "alpha was {alpha}");
+    }
+}
@@ -0,0 +1,118 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use pax_lang::{interpreter::PaxExpression, Computable};
+use pax_manifest::ExpressionInfo;
+use pax_runtime_api::properties::UntypedProperty;
+use pax_runtime_api::{CoercionRules, PaxValue, Variable};
+
+use crate::RuntimePropertiesStackFrame;
+
+/// Evaluates an expression given the variables of its dependencies, in the
+/// order of [`CompiledExpression::dependencies`]
+pub type CompiledExpressionFn = fn(&[Variable]) -> Result<PaxValue, String>;
+
+/// Evaluates an expression straight to the type of the property it's assigned to
+pub type TypedExpressionFn<T> = fn(&[Variable]) -> Result<T, String>;
+
+/// An expression compiled to Rust by `pax-compiler`, registered by release
+/// cartridges before the engine is built
+#[derive(Clone, Copy)]
+pub struct CompiledExpression {
+    pub dependencies: &'static [&'static str],
+    pub evaluate: CompiledExpressionFn,
+    /// A [`TypedExpressionFn`] of the type of the property the expression is assigned to,
+    /// or of the expression's own type where the compiler doesn't know the property
+    pub typed: &'static (dyn Any + Send + Sync),
+}
+
+/// Compiled expressions, indexed by the ids the compiler assigned to their `ExpressionInfo`s
+static COMPILED_EXPRESSIONS: OnceLock<&'static [Option<CompiledExpression>]> = OnceLock::new();
+
+/// Registers the expressions compiled for the cartridge. A process only runs one
+/// cartridge, so registrations after the first are ignored
+pub fn register_compiled_expressions(expressions: &'static [Option<CompiledExpression>]) {
+    let _ = COMPILED_EXPRESSIONS.set(expressions);
+}
+
+fn get_compiled_expression(info: &ExpressionInfo) -> Option<CompiledExpression> {
+    let id = info.id?;
+    COMPILED_EXPRESSIONS.get()?.get(id).copied().flatten()
+}
+
+enum Evaluator {
+    Compiled(CompiledExpression, Vec<Variable>),
+    Interpreted(PaxExpression, Rc<RuntimePropertiesStackFrame>),
+}
+
+/// An expression bound to the stack frame it's evaluated in. Uses the compiled
+/// version of the expression when one was registered, and the interpreter
+/// otherwise (designtime, or expressions edited after compilation).
+pub struct BoundExpression {
+    evaluator: Evaluator,
+    dependencies: Vec<UntypedProperty>,
+}
+
+impl BoundExpression {
+    pub fn new(info: &ExpressionInfo, stack: &Rc<RuntimePropertiesStackFrame>) -> Self {
+        if let Some(compiled) = get_compiled_expression(info) {
+            let variables: Option<Vec<Variable>> = compiled
+                .dependencies
+                .iter()
+                .map(|dependency| stack.resolve_symbol_as_variable(dependency))
+                .collect();
+            if let Some(variables) = variables {
+                let dependencies = variables
+                    .iter()
+                    .map(|v| v.get_untyped_property().clone())
+                    .collect();
+                return Self {
+                    evaluator: Evaluator::Compiled(compiled, variables),
+                    dependencies,
+                };
+            }
+        }
+
+        let mut dependencies = Vec::new();
+        for dependency in &info.dependencies {
+            if let Some(p) = stack.resolve_symbol_as_erased_property(dependency) {
+                dependencies.push(p);
+            } else {
+                log::warn!("Failed to resolve symbol {}", dependency);
+            }
+        }
+        Self {
+            evaluator: Evaluator::Interpreted(info.expression.clone(), stack.clone()),
+            dependencies,
+        }
+    }
+
+    /// Whether this evaluates the compiled version of the expression
+    pub fn is_compiled(&self) -> bool {
+        matches!(self.evaluator, Evaluator::Compiled(..))
+    }
+
+    /// The properties this expression needs to be recomputed on changes of
+    pub fn dependencies(&self) -> &[UntypedProperty] {
+        &self.dependencies
+    }
+
+    pub fn compute(&self) -> Result<PaxValue, String> {
+        match &self.evaluator {
+            Evaluator::Compiled(compiled, variables) => (compiled.evaluate)(variables),
+            Evaluator::Interpreted(expression, stack) => expression.compute(stack.clone()),
+        }
+    }
+
+    /// Computes the value of the expression as a `T`, directly when it was compiled to
+    /// evaluate to `T`, and by coercing the `PaxValue` it evaluates to otherwise
+    pub fn compute_as<T: CoercionRules>(&self) -> Result<T, String> {
+        if let Evaluator::Compiled(compiled, variables) = &self.evaluator {
+            if let Some(typed) = compiled.typed.downcast_ref::<TypedExpressionFn<T>>() {
+                return typed(variables);
+            }
+        }
+        T::try_coerce(self.compute()?)
+    }
+}
@@ -0,0 +1,324 @@
+//! Recognizes drags, pinches, long presses and swipes from the pointer events dispatched by
+//! the chassis. Gestures are dispatched to the node their pointer was pressed on, and
+//! pinches to the closest node both pointers were pressed within, wherever the pointers move
+//! afterwards, and bubble up from there.
+
+use std::collections::BTreeMap;
+use std::rc::{Rc, Weak};
+
+use pax_runtime_api::{
+    borrow_mut, use_RefCell, Drag, DragEnd, DragEventArgs, DragStart, Event, LongPress, Numeric,
+    Pinch, PointerEventArgs, Rotation, Swipe, SwipeDirection,
+};
+
+use crate::{ExpandedNode, RuntimeContext};
+use_RefCell!();
+
+/// Distance in px a pressed pointer moves before it starts a drag
+const DRAG_THRESHOLD: f64 = 4.0;
+/// Time in milliseconds a pointer is held still before it's a long press
+const LONG_PRESS_MILLIS: u128 = 500;
+/// Speed in px per millisecond a dragging pointer is released at to swipe
+const SWIPE_MIN_VELOCITY: f64 = 0.5;
+/// Time in milliseconds after which a pointer held still is considered at rest
+const VELOCITY_TIMEOUT_MILLIS: u128 = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PointerPhase {
+    Down,
+    Move,
+    Up,
+    Cancel,
+}
+
+struct TrackedPointer {
+    target: Weak<ExpandedNode>,
+    start: (f64, f64),
+    last: PointerEventArgs,
+    pressed_at: u128,
+    moved_at: u128,
+    velocity: (f64, f64),
+    dragging: bool,
+    long_pressed: bool,
+}
+
+impl TrackedPointer {
+    fn drag_args(&self, delta: (f64, f64)) -> DragEventArgs {
+        DragEventArgs {
+            pointer_id: self.last.pointer_id,
+            pointer_type: self.last.pointer_type.clone(),
+            start_x: self.start.0,
+            start_y: self.start.1,
+            x: self.last.x,
+            y: self.last.y,
+            delta_x: delta.0,
+            delta_y: delta.1,
+            velocity_x: self.velocity.0,
+            velocity_y: self.velocity.1,
+        }
+    }
+}
+
+struct PinchState {
+    pointers: (i64, i64),
+    target: Weak<ExpandedNode>,
+    start_distance: f64,
+    start_angle: f64,
+}
+
+enum Gesture {
+    DragStart(DragStart),
+    Drag(Drag),
+    DragEnd(DragEnd),
+    Pinch(Pinch),
+    LongPress(LongPress),
+    Swipe(Swipe),
+}
+
+/// Pointers currently pressed, and the gestures they're performing
+#[derive(Default)]
+pub struct GestureRecognizer {
+    pointers: BTreeMap<i64, TrackedPointer>,
+    pinch: Option<PinchState>,
+}
+
+impl GestureRecognizer {
+    fn pointer_event(
+        &mut self,
+        target: &Rc<ExpandedNode>,
+        phase: PointerPhase,
+        args: &PointerEventArgs,
+        now: u128,
+    ) -> Vec<(Weak<ExpandedNode>, Gesture)> {
+        let mut gestures = Vec::new();
+        let id = args.pointer_id;
+        match phase {
+            PointerPhase::Down => {
+                self.pointers.insert(
+                    id,
+                    TrackedPointer {
+                        target: Rc::downgrade(target),
+                        start: (args.x, args.y),
+                        last: args.clone(),
+                        pressed_at: now,
+                        moved_at: now,
+                        velocity: (0.0, 0.0),
+                        dragging: false,
+                        long_pressed: false,
+                    },
+                );
+                if self.pointers.len() == 2 && self.pinch.is_none() {
+                    // a second pointer turns drags into a pinch
+                    for pointer in self.pointers.values_mut() {
+                        if pointer.dragging {
+                            pointer.dragging = false;
+                            let drag = pointer.drag_args((0.0, 0.0));
+                            gestures
+                                .push((pointer.target.clone(), Gesture::DragEnd(DragEnd { drag })));
+                        }
+                    }
+                    let mut ids = self.pointers.keys().copied();
+                    let pointers = (ids.next().unwrap(), ids.next().unwrap());
+                    let (distance, angle) = self.span(pointers);
+                    let target = common_ancestor(
+                        &self.pointers[&pointers.0].target,
+                        &self.pointers[&pointers.1].target,
+                    );
+                    self.pinch = Some(PinchState {
+                        pointers,
+                        target,
+                        start_distance: distance,
+                        start_angle: angle,
+                    });
+                }
+            }
+            PointerPhase::Move => {
+                let Some(pointer) = self.pointers.get_mut(&id) else {
+                    return gestures;
+                };
+                let delta = (args.x - pointer.last.x, args.y - pointer.last.y);
+                let elapsed = now.saturating_sub(pointer.moved_at) as f64;
+                if elapsed > 0.0 {
+                    // smooth out the jitter of individual move events
+                    pointer.velocity = (
+                        0.6 * delta.0 / elapsed + 0.4 * pointer.velocity.0,
+                        0.6 * delta.1 / elapsed + 0.4 * pointer.velocity.1,
+                    );
+                }
+                pointer.last = args.clone();
+                pointer.moved_at = now;
+
+                if let Some(pinch) = &self.pinch {
+                    if pinch.pointers.0 == id || pinch.pointers.1 == id {
+                        let (distance, angle) = self.span(pinch.pointers);
+                        let first = &self.pointers[&pinch.pointers.0];
+                        let second = &self.pointers[&pinch.pointers.1];
+                        let gesture = Pinch {
+                            center_x: (first.last.x + second.last.x) / 2.0,
+                            center_y: (first.last.y + second.last.y) / 2.0,
+                            scale: distance / pinch.start_distance.max(f64::EPSILON),
+                            rotation: Rotation::Radians(Numeric::F64(angle - pinch.start_angle)),
+                        };
+                        gestures.push((pinch.target.clone(), Gesture::Pinch(gesture)));
+                    }
+                    return gestures;
+                }
+
+                if pointer.dragging {
+                    let drag = pointer.drag_args(delta);
+                    gestures.push((pointer.target.clone(), Gesture::Drag(Drag { drag })));
+                } else if distance(pointer.start, (args.x, args.y)) > DRAG_THRESHOLD {
+                    pointer.dragging = true;
+                    let drag =
+                        pointer.drag_args((args.x - pointer.start.0, args.y - pointer.start.1));
+                    gestures.push((
+                        pointer.target.clone(),
+                        Gesture::DragStart(DragStart { drag }),
+                    ));
+                }
+            }
+            PointerPhase::Up | PointerPhase::Cancel => {
+                let Some(mut pointer) = self.pointers.remove(&id) else {
+                    return gestures;
+                };
+                if let Some(pinch) = &self.pinch {
+                    if pinch.pointers.0 == id || pinch.pointers.1 == id {
+                        self.pinch = None;
+                        // the remaining pointer can start a new drag from where it is now
+                        for remaining in self.pointers.values_mut() {
+                            remaining.start = (remaining.last.x, remaining.last.y);
+                        }
+                    }
+                }
+                if now.saturating_sub(pointer.moved_at) > VELOCITY_TIMEOUT_MILLIS {
+                    pointer.velocity = (0.0, 0.0);
+                }
+                if !pointer.dragging {
+                    return gestures;
+                }
+                let drag = pointer.drag_args((0.0, 0.0));
+                gestures.push((pointer.target.clone(), Gesture::DragEnd(DragEnd { drag })));
+
+                let (velocity_x, velocity_y) = pointer.velocity;
+                if phase == PointerPhase::Up && velocity_x.hypot(velocity_y) >= SWIPE_MIN_VELOCITY {
+                    let direction = if velocity_x.abs() > velocity_y.abs() {
+                        if velocity_x > 0.0 {
+                            SwipeDirection::Right
+                        } else {
+                            SwipeDirection::Left
+                        }
+                    } else if velocity_y > 0.0 {
+                        SwipeDirection::Down
+                    } else {
+                        SwipeDirection::Up
+                    };
+                    let swipe = Swipe {
+                        direction,
+                        x: pointer.last.x,
+                        y: pointer.last.y,
+                        velocity_x,
+                        velocity_y,
+                    };
+                    gestures.push((pointer.target, Gesture::Swipe(swipe)));
+                }
+            }
+        }
+        gestures
+    }
+
+    fn long_presses(&mut self, now: u128) -> Vec<(Weak<ExpandedNode>, Gesture)> {
+        let pinching = self.pinch.is_some();
+        self.pointers
+            .values_mut()
+            .filter(|pointer| {
+                !pinching
+                    && !pointer.dragging
+                    && !pointer.long_pressed
+                    && now.saturating_sub(pointer.pressed_at) >= LONG_PRESS_MILLIS
+            })
+            .map(|pointer| {
+                pointer.long_pressed = true;
+                let long_press = LongPress {
+                    pointer_id: pointer.last.pointer_id,
+                    pointer_type: pointer.last.pointer_type.clone(),
+                    x: pointer.last.x,
+                    y: pointer.last.y,
+                };
+                (pointer.target.clone(), Gesture::LongPress(long_press))
+            })
+            .collect()
+    }
+
+    /// Distance between two pointers, and the angle of the line from the first to the second
+    fn span(&self, (first, second): (i64, i64)) -> (f64, f64) {
+        let first = &self.pointers[&first].last;
+        let second = &self.pointers[&second].last;
+        let (dx, dy) = (second.x - first.x, second.y - first.y);
+        (dx.hypot(dy), dy.atan2(dx))
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// The closest node that both `first` and `second` are, or are template descendants of
+fn common_ancestor(first: &Weak<ExpandedNode>, second: &Weak<ExpandedNode>) -> Weak<ExpandedNode> {
+    let (Some(first), Some(second)) = (first.upgrade(), second.upgrade()) else {
+        return Weak::new();
+    };
+    let mut ancestors = vec![first];
+    while let Some(parent) = ancestors.last().unwrap().template_parent.upgrade() {
+        ancestors.push(parent);
+    }
+    let mut node = Some(second);
+    while let Some(current) = node {
+        if ancestors.iter().any(|ancestor| ancestor.id == current.id) {
+            return Rc::downgrade(&current);
+        }
+        node = current.template_parent.upgrade();
+    }
+    Weak::new()
+}
+
+/// Feeds a pointer event dispatched to `target` to the gesture recognizer, dispatching the
+/// gestures it continues. Returns whether a gesture handler prevented the default.
+pub fn recognize_gestures(
+    ctx: &Rc<RuntimeContext>,
+    target: &Rc<ExpandedNode>,
+    phase: PointerPhase,
+    args: &PointerEventArgs,
+) -> bool {
+    let now = (ctx.globals().get_elapsed_millis)();
+    let gestures = borrow_mut!(ctx.gestures).pointer_event(target, phase, args, now);
+    dispatch_gestures(ctx, gestures)
+}
+
+/// Dispatches a long press for the pointers held still for long enough, once a tick
+pub fn recognize_long_presses(ctx: &Rc<RuntimeContext>) {
+    let now = (ctx.globals().get_elapsed_millis)();
+    let gestures = borrow_mut!(ctx.gestures).long_presses(now);
+    dispatch_gestures(ctx, gestures);
+}
+
+fn dispatch_gestures(
+    ctx: &Rc<RuntimeContext>,
+    gestures: Vec<(Weak<ExpandedNode>, Gesture)>,
+) -> bool {
+    let globals = ctx.globals();
+    let mut prevent_default = false;
+    for (target, gesture) in gestures {
+        let Some(target) = target.upgrade() else {
+            continue;
+        };
+        prevent_default |= match gesture {
+            Gesture::DragStart(args) => target.dispatch_drag_start(Event::new(args), &globals, ctx),
+            Gesture::Drag(args) => target.dispatch_drag(Event::new(args), &globals, ctx),
+            Gesture::DragEnd(args) => target.dispatch_drag_end(Event::new(args), &globals, ctx),
+            Gesture::Pinch(args) => target.dispatch_pinch(Event::new(args), &globals, ctx),
+            Gesture::LongPress(args) => target.dispatch_long_press(Event::new(args), &globals, ctx),
+            Gesture::Swipe(args) => target.dispatch_swipe(Event::new(args), &globals, ctx),
+        };
+    }
+    prevent_default
+}
@@ -4,22 +4,30 @@ pub mod component;
 pub mod conditional;
 pub mod constants;
 pub mod engine;
+pub mod expressions;
 pub mod form_event;
+pub mod gestures;
 pub mod layout;
+pub mod matching;
 pub mod properties;
 pub mod rendering;
 pub mod repeat;
 pub mod slot;
+pub mod state_variants;
 
 pub use crate::cartridge::*;
 pub use crate::component::*;
 pub use crate::conditional::*;
 pub use crate::engine::*;
+pub use crate::expressions::*;
+pub use crate::gestures::*;
 pub use crate::layout::*;
+pub use crate::matching::*;
 pub use crate::properties::*;
 pub use crate::rendering::*;
 pub use crate::repeat::*;
 pub use crate::slot::*;
+pub use crate::state_variants::*;
 
 #[allow(unused)]
 pub static DEBUG_TEXT_GREEN_BACKGROUND: bool = false;
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+use std::{iter, rc::Rc};
+use_RefCell!();
+
+use pax_manifest::ExpressionInfo;
+use pax_runtime_api::pax_value::ImplToFromPaxAny;
+use pax_runtime_api::{
+    borrow, borrow_mut, use_RefCell, CoercionRules, PaxValue, Property, ToPaxValue, Variable,
+};
+
+use crate::api::Layer;
+use crate::{
+    BaseInstance, BoundExpression, ExpandedNode, InstanceFlags, InstanceNode, InstanceNodePtrList,
+    InstantiationArgs, RuntimeContext, RuntimePropertiesStackFrame,
+};
+
+/// A special "control-flow" primitive associated with the `match` statement.
+/// Match mounts the template children of exactly one of its arms: the first
+/// whose pattern matches the enum variant of `subject`, and whose guard holds.
+/// Payload bindings of the mounted arm's pattern are added to its children's
+/// stack frame.
+pub struct MatchInstance {
+    base: BaseInstance,
+    arms: Vec<MatchArm>,
+}
+
+/// One arm of a `match` statement, like `State::Error(message) => { ... }`
+pub struct MatchArm {
+    /// Variant name selecting this arm, `None` for the `_` wildcard
+    pub variant: Option<String>,
+    /// Enum the variant is qualified with, `None` to match the variant of any enum
+    pub enum_name: Option<String>,
+    /// Symbols bound to the variant's payload, in order
+    pub bindings: Vec<String>,
+    /// Condition evaluated with the bindings in scope, that must also hold for this arm
+    /// to be selected
+    pub guard: Option<ExpressionInfo>,
+    pub children: InstanceNodePtrList,
+}
+
+impl MatchArm {
+    fn matches(&self, subject: &PaxValue) -> bool {
+        let Some(variant) = &self.variant else {
+            return true;
+        };
+        variant_of(subject).is_some_and(|(enum_name, name, _)| {
+            &name == variant
+                && self
+                    .enum_name
+                    .as_ref()
+                    .is_none_or(|expected| expected == &enum_name)
+        })
+    }
+
+    /// The stack frame of this arm's children, binding the payload of `subject`
+    fn bind_payload(
+        &self,
+        stack: &Rc<RuntimePropertiesStackFrame>,
+        subject: &Property<PaxValue>,
+    ) -> Rc<RuntimePropertiesStackFrame> {
+        let mut scope: HashMap<String, Variable> = HashMap::new();
+        for (i, binding) in self.bindings.iter().enumerate() {
+            let cp_subject = subject.clone();
+            let property_binding = Property::computed_with_name(
+                move || {
+                    cp_subject.read(|subject| {
+                        variant_of(subject)
+                            .and_then(|(_, _, payload)| payload.into_iter().nth(i))
+                            .unwrap_or_default()
+                    })
+                },
+                &[subject.untyped()],
+                "match arm binding",
+            );
+            scope.insert(
+                binding.clone(),
+                Variable::new_from_typed_property(property_binding),
+            );
+        }
+        stack.push(scope)
+    }
+
+    /// Whether this arm's guard holds, `None` if it has no guard. Only read once the
+    /// pattern matched, as the guard may rely on the bindings
+    fn guard(&self, env: &Rc<RuntimePropertiesStackFrame>) -> Option<Property<bool>> {
+        let expression = BoundExpression::new(self.guard.as_ref()?, env);
+        let dependencies = expression.dependencies().to_vec();
+        Some(Property::computed_with_name(
+            move || {
+                expression
+                    .compute()
+                    .and_then(bool::try_coerce)
+                    .unwrap_or_else(|err| {
+                        log::warn!("Failed to compute match guard: {:?}", err);
+                        false
+                    })
+            },
+            &dependencies,
+            "match arm guard",
+        ))
+    }
+}
+
+impl ImplToFromPaxAny for MatchProperties {}
+
+///Contains the value matched against the arms of a `match` statement.
+#[derive(Default)]
+pub struct MatchProperties {
+    pub subject: Property<PaxValue>,
+}
+
+impl ToPaxValue for MatchProperties {
+    fn to_pax_value(self) -> PaxValue {
+        PaxValue::Object(
+            vec![("subject".to_string(), self.subject.to_pax_value())]
+                .into_iter()
+                .collect(),
+        )
+    }
+}
+
+impl InstanceNode for MatchInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self>
+    where
+        Self: Sized,
+    {
+        Self::instantiate_with_arms(args, vec![])
+    }
+
+    fn handle_mount(
+        self: Rc<Self>,
+        expanded_node: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+    ) {
+        self.handle_setup(expanded_node, context, true);
+    }
+
+    fn handle_control_flow_node_expansion(
+        self: Rc<Self>,
+        expanded_node: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+    ) {
+        self.handle_setup(expanded_node, context, false);
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Match").finish()
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+}
+
+impl MatchInstance {
+    pub fn instantiate_with_arms(args: InstantiationArgs, arms: Vec<MatchArm>) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: true,
+                    invisible_to_raycasting: true,
+                    layer: Layer::DontCare,
+                    is_component: false,
+                    is_slot: false,
+                },
+            ),
+            arms,
+        })
+    }
+
+    fn handle_setup(
+        self: Rc<Self>,
+        expanded_node: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+        is_mount: bool,
+    ) {
+        let weak_ref_self = Rc::downgrade(expanded_node);
+        let cloned_self = Rc::clone(&self);
+        let cloned_context = Rc::clone(context);
+
+        let subject =
+            expanded_node.with_properties_unwrapped(|properties: &mut MatchProperties| {
+                properties.subject.clone()
+            });
+        // payload bindings and guards stay live while this match is expanded
+        let arm_envs: Vec<_> = self
+            .arms
+            .iter()
+            .map(|arm| arm.bind_payload(&expanded_node.stack, &subject))
+            .collect();
+        let guards: Vec<_> = self
+            .arms
+            .iter()
+            .zip(arm_envs.iter())
+            .map(|(arm, env)| arm.guard(env))
+            .collect();
+        let deps: Vec<_> = iter::once(subject.untyped())
+            .chain(guards.iter().flatten().map(Property::untyped))
+            .collect();
+
+        // index of the mounted arm, if any
+        let old_val: RefCell<Option<usize>> = RefCell::new(None);
+        expanded_node
+            .children
+            .replace_with(Property::computed_with_name(
+                move || {
+                    let Some(cloned_expanded_node) = weak_ref_self.upgrade() else {
+                        panic!("ran evaluator after expanded node dropped (match elem)")
+                    };
+                    let subject = subject.get();
+                    let val =
+                        cloned_self
+                            .arms
+                            .iter()
+                            .zip(guards.iter())
+                            .position(|(arm, guard)| {
+                                arm.matches(&subject) && guard.as_ref().is_none_or(Property::get)
+                            });
+                    if val == *borrow!(old_val) {
+                        return cloned_expanded_node.children.get();
+                    }
+                    *borrow_mut!(old_val) = val;
+                    let Some((arm, env)) = val.map(|i| (&cloned_self.arms[i], &arm_envs[i])) else {
+                        return cloned_expanded_node.generate_children(
+                            vec![],
+                            &cloned_context,
+                            &cloned_expanded_node.parent_frame,
+                            is_mount,
+                        );
+                    };
+
+                    let children = borrow!(arm.children);
+                    let children_with_envs =
+                        children.iter().cloned().zip(iter::repeat(Rc::clone(env)));
+                    cloned_expanded_node.generate_children(
+                        children_with_envs,
+                        &cloned_context,
+                        &cloned_expanded_node.parent_frame,
+                        is_mount,
+                    )
+                },
+                &deps,
+                &format!("match_children (node id: {})", expanded_node.id.0),
+            ));
+    }
+}
+
+/// The enum name, variant name and payload of an enum value. `Option`s match as
+/// `Option::Some(value)` / `Option::None`
+fn variant_of(value: &PaxValue) -> Option<(String, String, Vec<PaxValue>)> {
+    match value {
+        PaxValue::Enum(contents) => {
+            let (enum_name, variant, payload) = contents.as_ref();
+            Some((enum_name.clone(), variant.clone(), payload.clone()))
+        }
+        PaxValue::Option(opt) => Some(match opt.as_ref() {
+            Some(value) => (
+                "Option".to_string(),
+                "Some".to_string(),
+                vec![value.clone()],
+            ),
+            None => ("Option".to_string(), "None".to_string(), vec![]),
+        }),
+        _ => None,
+    }
+}
@@ -5,8 +5,8 @@ use pax_manifest::UniqueTemplateNodeIdentifier;
 use pax_message::{NativeMessage, ScreenshotData};
 use pax_runtime_api::properties::UntypedProperty;
 use pax_runtime_api::{
-    borrow, borrow_mut, use_RefCell, Event, Interpolatable, MouseOut, MouseOver, RenderContext,
-    Store, Variable,
+    borrow, borrow_mut, use_RefCell, Event, FocusIn, FocusOut, Interpolatable, MouseOut, MouseOver,
+    Property, RenderContext, Store, Variable,
 };
 use_RefCell!();
 use std::any::{Any, TypeId};
@@ -15,7 +15,7 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::rc::{Rc, Weak};
 
-use crate::{ExpandedNode, Globals};
+use crate::{ExpandedNode, GestureRecognizer, Globals, InteractionState};
 
 #[cfg(feature = "designtime")]
 use crate::{ComponentInstance, InstanceNode};
@@ -44,6 +44,11 @@ pub struct RuntimeContext {
     pub userland_root_expanded_node: RefCell<Option<Rc<ExpandedNode>>>,
     node_cache: RefCell<NodeCache>,
     last_topmost_element: RefCell<Weak<ExpandedNode>>,
+    hovered_element: RefCell<Weak<ExpandedNode>>,
+    pressed_element: RefCell<Weak<ExpandedNode>>,
+    focused_element: RefCell<Weak<ExpandedNode>>,
+    pointer_captures: RefCell<HashMap<i64, Weak<ExpandedNode>>>,
+    pub(crate) gestures: RefCell<GestureRecognizer>,
     queued_custom_events: RefCell<Vec<(Rc<ExpandedNode>, &'static str)>>,
     queued_renders: RefCell<Vec<Rc<ExpandedNode>>>,
     pub layer_count: Cell<usize>,
@@ -101,6 +106,11 @@ impl RuntimeContext {
             queued_renders: Default::default(),
             layer_count: Cell::default(),
             last_topmost_element: Default::default(),
+            hovered_element: Default::default(),
+            pressed_element: Default::default(),
+            focused_element: Default::default(),
+            pointer_captures: Default::default(),
+            gestures: Default::default(),
             dirty_canvases: Default::default(),
             screenshot_map: Default::default(),
         }
@@ -120,6 +130,11 @@ impl RuntimeContext {
             queued_renders: Default::default(),
             layer_count: Cell::default(),
             last_topmost_element: Default::default(),
+            hovered_element: Default::default(),
+            pressed_element: Default::default(),
+            focused_element: Default::default(),
+            pointer_captures: Default::default(),
+            gestures: Default::default(),
             dirty_canvases: Default::default(),
             screenshot_map: Default::default(),
         }
@@ -276,16 +291,23 @@ impl RuntimeContext {
         }
         accum
     }
-    /// Alias for `get_elements_beneath_ray` with `limit_one = true`
+    /// The topmost node beneath `ray`, or the root if there's none. Unlike
+    /// [`RuntimeContext::get_topmost_element_beneath_ray`], has no side effects on hover state.
+    pub fn find_topmost_element_beneath_ray(&self, ray: Point2<Window>) -> Rc<ExpandedNode> {
+        let res = self.get_elements_beneath_ray(None, ray, true, vec![], false);
+        res.into_iter()
+            .next()
+            .unwrap_or(borrow!(self.root_expanded_node).upgrade().unwrap())
+    }
+
+    /// Alias for `get_elements_beneath_ray` with `limit_one = true`, that also moves hover
+    /// to the node found, dispatching `@mouse_out` and `@mouse_over` to the components
+    /// left and entered
     pub fn get_topmost_element_beneath_ray(
         self: &Rc<Self>,
         ray: Point2<Window>,
     ) -> Rc<ExpandedNode> {
-        let res = self.get_elements_beneath_ray(None, ray, true, vec![], false);
-        let new_topmost = res
-            .into_iter()
-            .next()
-            .unwrap_or(borrow!(self.root_expanded_node).upgrade().unwrap());
+        let new_topmost = self.find_topmost_element_beneath_ray(ray);
 
         //send mouse over/out events if the hit element is different than last
         let last_topmost = borrow!(self.last_topmost_element).upgrade();
@@ -304,9 +326,119 @@ impl RuntimeContext {
                 .map(Rc::downgrade)
                 .unwrap_or_default();
         }
+        move_interaction_state(&self.hovered_element, Some(&new_topmost), |s| &s.hovered);
         new_topmost
     }
 
+    /// Marks `node` and its template ancestors as pressed, e.g. on mouse down, and
+    /// the previously pressed ones as not
+    pub fn set_pressed_element(&self, node: Option<&Rc<ExpandedNode>>) {
+        move_interaction_state(&self.pressed_element, node, |s| &s.pressed);
+    }
+
+    /// The node keyboard events are routed to, if any
+    pub fn get_focused_element(&self) -> Option<Rc<ExpandedNode>> {
+        borrow!(self.focused_element).upgrade()
+    }
+
+    /// Moves keyboard focus to `node`, dispatching `@blur` to the previously focused node
+    /// and `@focus` to the new one. `node` and its template ancestors are marked as focused,
+    /// and the previously focused ones as not.
+    pub fn set_focused_element(self: &Rc<Self>, node: Option<&Rc<ExpandedNode>>) {
+        let previous = self.get_focused_element();
+        if previous.as_ref().map(|n| n.id) == node.map(|n| n.id) {
+            return;
+        }
+        move_interaction_state(&self.focused_element, node, |s| &s.focused);
+        let globals = self.globals();
+        if let Some(previous) = previous {
+            previous.dispatch_focus_out(Event::new(FocusOut {}), &globals, self);
+        }
+        if let Some(node) = node {
+            node.dispatch_focus_in(Event::new(FocusIn {}), &globals, self);
+        }
+    }
+
+    /// Clears what refers to `node` as it's unmounted: if it's focused, focus is cleared,
+    /// without dispatching `@blur` to the node being removed, and the pointers it captures
+    /// are released
+    pub fn release_unmounted(&self, node: &ExpandedNode) {
+        if self.get_focused_element().is_some_and(|n| n.id == node.id) {
+            move_interaction_state(&self.focused_element, None, |s| &s.focused);
+        }
+        borrow_mut!(self.pointer_captures)
+            .retain(|_, captor| captor.upgrade().is_some_and(|n| n.id != node.id));
+    }
+
+    /// Focuses `node` or its closest focusable template ancestor, e.g. on mouse down, blurring
+    /// the focused node if there's none
+    pub fn focus_closest_focusable(self: &Rc<Self>, node: &Rc<ExpandedNode>) {
+        let focusable =
+            std::iter::successors(Some(Rc::clone(node)), |n| n.template_parent.upgrade())
+                .find(|n| n.is_focusable());
+        self.set_focused_element(focusable.as_ref());
+    }
+
+    /// Moves focus to the next focusable node in Tab order, or the previous one if
+    /// `backwards`, wrapping around. Returns whether there was a node to focus.
+    pub fn focus_next(self: &Rc<Self>, backwards: bool) -> bool {
+        let Some(root) = borrow!(self.root_expanded_node).upgrade() else {
+            return false;
+        };
+        let mut tab_order = Vec::new();
+        root.recurse_visit_preorder(&mut |node| {
+            if node.is_focusable() && node.tab_index() >= 0 {
+                tab_order.push((node.tab_index(), Rc::clone(node)));
+            }
+        });
+        if tab_order.is_empty() {
+            return false;
+        }
+        // positive indices first, then the nodes without one, keeping tree order otherwise
+        tab_order.sort_by_key(|(tab_index, _)| match *tab_index {
+            0 => isize::MAX,
+            tab_index => tab_index,
+        });
+
+        let len = tab_order.len();
+        let current = self
+            .get_focused_element()
+            .and_then(|focused| tab_order.iter().position(|(_, n)| n.id == focused.id));
+        let next = match (current, backwards) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        self.set_focused_element(Some(&tab_order[next].1));
+        true
+    }
+
+    /// Routes the events of pointer `pointer_id` to `node` regardless of hit testing, until
+    /// the capture is released or the pointer is lifted
+    pub fn capture_pointer(&self, pointer_id: i64, node: &Rc<ExpandedNode>) {
+        borrow_mut!(self.pointer_captures).insert(pointer_id, Rc::downgrade(node));
+    }
+
+    pub fn release_pointer_capture(&self, pointer_id: i64) {
+        borrow_mut!(self.pointer_captures).remove(&pointer_id);
+    }
+
+    /// The node capturing pointer `pointer_id`, if any
+    pub fn get_pointer_capture(&self, pointer_id: i64) -> Option<Rc<ExpandedNode>> {
+        borrow!(self.pointer_captures)
+            .get(&pointer_id)
+            .and_then(Weak::upgrade)
+    }
+
+    /// The node to dispatch the events of pointer `pointer_id` at `ray` to: the node capturing
+    /// it if any, otherwise the topmost node beneath the pointer. Hover is left to the mouse
+    /// events, see [`RuntimeContext::get_topmost_element_beneath_ray`].
+    pub fn get_pointer_target(&self, pointer_id: i64, ray: Point2<Window>) -> Rc<ExpandedNode> {
+        self.get_pointer_capture(pointer_id)
+            .unwrap_or_else(|| self.find_topmost_element_beneath_ray(ray))
+    }
+
     pub fn gen_uid(&self) -> ExpandedNodeIdentifier {
         let val = self.next_uid.get();
         let next_val = ExpandedNodeIdentifier(val.0 + 1);
@@ -373,6 +505,31 @@ impl RuntimeContext {
     }
 }
 
+/// Moves an interaction state from the nodes along the template path of the element last
+/// holding it to those along the path of `node`
+fn move_interaction_state(
+    last: &RefCell<Weak<ExpandedNode>>,
+    node: Option<&Rc<ExpandedNode>>,
+    state: impl Fn(&InteractionState) -> &Property<bool>,
+) {
+    let path = |node: Option<Rc<ExpandedNode>>| -> Vec<Rc<ExpandedNode>> {
+        std::iter::successors(node, |n| n.template_parent.upgrade()).collect()
+    };
+    let last_path = path(borrow!(last).upgrade());
+    let new_path = path(node.cloned());
+    for leaving in &last_path {
+        if !new_path.iter().any(|n| n.id == leaving.id) {
+            state(&leaving.interaction_state).set(false);
+        }
+    }
+    for entering in &new_path {
+        if !last_path.iter().any(|n| n.id == entering.id) {
+            state(&entering.interaction_state).set(true);
+        }
+    }
+    *borrow_mut!(last) = node.map(Rc::downgrade).unwrap_or_default();
+}
+
 fn find_paths_to_common_ancestor(
     last_topmost: &Option<Rc<ExpandedNode>>,
     new_topmost_comp: &Option<Rc<ExpandedNode>>,
@@ -12,7 +12,9 @@ use piet::{Color, StrokeStyle};
 
 use crate::api::{Layer, Scroll};
 
-use crate::{ExpandedNode, HandlerRegistry, RuntimeContext, RuntimePropertiesStackFrame};
+use crate::{
+    ExpandedNode, HandlerRegistry, RuntimeContext, RuntimePropertiesStackFrame, StateVariant,
+};
 
 /// Type aliases to make it easier to work with nested Rcs and
 /// RefCells for instance nodes.
@@ -40,6 +42,8 @@ pub struct InstantiationArgs {
     // Used by RuntimePropertyStackFrame to pull out struct's properties based on their names
     pub properties_scope_factory:
         Option<Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, Variable>>>,
+    /// Overrides of the properties above while in interaction states, e.g. `:hover`
+    pub state_variants: Vec<StateVariant>,
 }
 
 pub struct ReusableInstanceNodeArgs {
@@ -231,6 +235,7 @@ pub struct BaseInstance {
     pub template_node_identifier: Option<UniqueTemplateNodeIdentifier>,
     pub properties_scope_factory:
         Option<Box<dyn Fn(Rc<RefCell<PaxAny>>) -> HashMap<String, Variable>>>,
+    pub state_variants: Vec<StateVariant>,
     instance_children: InstanceNodePtrList,
     flags: InstanceFlags,
 }
@@ -268,6 +273,7 @@ impl BaseInstance {
             flags,
             template_node_identifier: args.template_node_identifier,
             properties_scope_factory: args.properties_scope_factory,
+            state_variants: args.state_variants,
         }
     }
 
@@ -3,14 +3,15 @@ use std::iter;
 use std::rc::Rc;
 use_RefCell!();
 
-use pax_runtime_api::CoercionRules;
+use pax_manifest::ExpressionInfo;
 use pax_runtime_api::{
     borrow, borrow_mut, use_RefCell, ImplToFromPaxAny, PaxValue, Property, ToPaxValue, Variable,
 };
 
 use crate::api::Layer;
 use crate::{
-    BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
+    BaseInstance, BoundExpression, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs,
+    RuntimeContext,
 };
 
 /// A special "control-flow" primitive associated with the `for` statement.
@@ -30,6 +31,9 @@ pub struct RepeatProperties {
     pub source_expression: Property<PaxValue>,
     pub iterator_i_symbol: Property<Option<String>>,
     pub iterator_elem_symbol: Property<Option<String>>,
+    /// Optional `key` expression identifying each element of the source. When present,
+    /// expanded nodes follow their element across reorders instead of being rebuilt
+    pub key_expression: Property<Option<Rc<ExpressionInfo>>>,
 }
 
 impl ToPaxValue for RepeatProperties {
@@ -48,6 +52,13 @@ impl ToPaxValue for RepeatProperties {
                     "iterator_elem_symbol".to_string(),
                     self.iterator_elem_symbol.to_pax_value(),
                 ),
+                (
+                    "key_expression".to_string(),
+                    self.key_expression
+                        .get()
+                        .map(|info| info.expression.to_string())
+                        .to_pax_value(),
+                ),
             ]
             .into_iter()
             .collect(),
@@ -149,43 +160,52 @@ impl RepeatInstance {
                 properties.iterator_elem_symbol.clone()
             });
 
+        let key_expression =
+            expanded_node.with_properties_unwrapped(|properties: &mut RepeatProperties| {
+                properties.key_expression.clone()
+            });
+
+        let key_order = key_order(expanded_node, &source_expression, &key_expression);
         let deps = [
             source_expression.untyped(),
             i_symbol.untyped(),
             elem_symbol.untyped(),
+            key_expression.untyped(),
+            key_order.untyped(),
         ];
 
-        let last_length = Rc::new(RefCell::new(0));
+        let last_length = Rc::new(RefCell::new(Some(0)));
         let last_elem_sym = Rc::new(RefCell::new(None));
         let last_i_sym = Rc::new(RefCell::new(None));
+        let keyed_rows: Rc<RefCell<KeyedRows>> = Default::default();
 
         let children = Property::computed_with_name(
             move || {
                 let Some(cloned_expanded_node) = weak_ref_self.upgrade() else {
                     panic!("ran evaluator after expanded node dropped (repeat elem)")
                 };
-                let source_len = source_expression.read(|source| {
-                    let source_len = if let PaxValue::Range(start, end) = source {
-                        (isize::try_coerce(*end.clone()).unwrap()
-                            - isize::try_coerce(*start.clone()).unwrap())
-                            as usize
-                    } else if let PaxValue::Vec(v) = source {
-                        v.len()
-                    } else {
-                        log::warn!("source is not a vec");
-                        0
-                    };
-                    source_len
-                });
-                if source_len == *borrow!(last_length)
+                if key_expression.read(Option::is_some) {
+                    // force a full rebuild if the key is ever removed again
+                    *borrow_mut!(last_length) = None;
+                    return cloned_self.keyed_children(
+                        &cloned_expanded_node,
+                        &cloned_context,
+                        &key_order,
+                        &keyed_rows,
+                        is_mount,
+                    );
+                }
+                let source_len = source_expression.read(source_len);
+                if Some(source_len) == *borrow!(last_length)
                     && i_symbol.read(|i| i == &*borrow!(last_i_sym))
                     && elem_symbol.read(|e| e == &*borrow!(last_elem_sym))
                 {
                     return cloned_expanded_node.children.get();
                 }
-                *borrow_mut!(last_length) = source_len;
+                *borrow_mut!(last_length) = Some(source_len);
                 *borrow_mut!(last_i_sym) = i_symbol.get();
                 *borrow_mut!(last_elem_sym) = elem_symbol.get();
+                borrow_mut!(keyed_rows).rows.clear();
 
                 let template_children = cloned_self.base().get_instance_children();
                 let children_with_envs = iter::repeat(template_children)
@@ -195,38 +215,17 @@ impl RepeatInstance {
                         let property_i = Property::new(i);
                         let cp_source_expression = source_expression.clone();
                         let property_elem = Property::computed_with_name(
-                            move || {
-                                cp_source_expression.read(|source| {
-                                    if let PaxValue::Range(start, _) = source {
-                                        let start = isize::try_coerce(*start.clone()).unwrap();
-                                        let elem = (start + i as isize).to_pax_value();
-                                        elem
-                                    } else if let PaxValue::Vec(v) = source {
-                                        v[i].clone()
-                                    } else {
-                                        log::warn!("source is not a vec");
-                                        Default::default()
-                                    }
-                                })
-                            },
+                            move || cp_source_expression.read(|source| source_elem(source, i)),
                             &[source_expression.untyped()],
                             "repeat elem",
                         );
 
-                        let mut scope: HashMap<String, Variable> = HashMap::new();
-                        if let Some(i_symbol) = i_symbol.get() {
-                            scope.insert(
-                                i_symbol.clone(),
-                                Variable::new_from_typed_property(property_i),
-                            );
-                        }
-                        if let Some(elem_symbol) = elem_symbol.get() {
-                            scope.insert(
-                                elem_symbol,
-                                Variable::new_from_typed_property(property_elem),
-                            );
-                        }
-
+                        let scope = repeat_scope(
+                            &i_symbol.get(),
+                            &elem_symbol.get(),
+                            property_i,
+                            property_elem,
+                        );
                         let new_env = cloned_expanded_node.stack.push(scope);
                         borrow!(children)
                             .clone()
@@ -246,4 +245,265 @@ impl RepeatInstance {
         );
         expanded_node.children.replace_with(children);
     }
+
+    /// Expands the template children once per source element, reusing the expanded nodes
+    /// of rows whose key was already present in the previous expansion. The index of a
+    /// row is computed from `key_order`, so `elem` and `i` follow the element to its new
+    /// position without the row being rebuilt
+    fn keyed_children(
+        self: &Rc<Self>,
+        expanded_node: &Rc<ExpandedNode>,
+        context: &Rc<RuntimeContext>,
+        key_order: &Property<Rc<KeyOrder>>,
+        keyed_rows: &RefCell<KeyedRows>,
+        is_mount: bool,
+    ) -> Vec<Rc<ExpandedNode>> {
+        let (source_expression, i_symbol, elem_symbol) =
+            expanded_node.with_properties_unwrapped(|properties: &mut RepeatProperties| {
+                (
+                    properties.source_expression.clone(),
+                    properties.iterator_i_symbol.get(),
+                    properties.iterator_elem_symbol.get(),
+                )
+            });
+        let mut keyed_rows = borrow_mut!(keyed_rows);
+        if keyed_rows.i_symbol != i_symbol || keyed_rows.elem_symbol != elem_symbol {
+            // existing rows bind the old symbols in their stack frames
+            keyed_rows.rows.clear();
+            keyed_rows.i_symbol = i_symbol.clone();
+            keyed_rows.elem_symbol = elem_symbol.clone();
+        }
+
+        let order = key_order.get();
+        let template_children = self.base().get_instance_children();
+        let mut old_rows: HashMap<RowKey, KeyedRow> = std::mem::take(&mut keyed_rows.rows)
+            .into_iter()
+            .map(|row| (row.key.clone(), row))
+            .collect();
+        let mut created = Vec::new();
+        for key in order.keys.iter() {
+            let row = if let Some(row) = old_rows.remove(key) {
+                row
+            } else {
+                let cp_key_order = key_order.clone();
+                let cp_key = key.clone();
+                let index = Property::computed_with_name(
+                    move || {
+                        cp_key_order.read(|order| {
+                            // rows whose key was removed are dropped on the same update
+                            order.positions.get(&cp_key).copied().unwrap_or_default()
+                        })
+                    },
+                    &[key_order.untyped()],
+                    "repeat index (keyed)",
+                );
+                let cp_source_expression = source_expression.clone();
+                let cp_index = index.clone();
+                let property_elem = Property::computed_with_name(
+                    move || {
+                        let i = cp_index.get();
+                        cp_source_expression.read(|source| source_elem(source, i))
+                    },
+                    &[source_expression.untyped(), index.untyped()],
+                    "repeat elem (keyed)",
+                );
+                let scope = repeat_scope(&i_symbol, &elem_symbol, index, property_elem);
+                let new_env = expanded_node.stack.push(scope);
+                let nodes = expanded_node.create_children_detached(
+                    borrow!(template_children)
+                        .clone()
+                        .into_iter()
+                        .zip(iter::repeat(new_env)),
+                    context,
+                    &Rc::downgrade(expanded_node),
+                );
+                created.extend(nodes.iter().cloned());
+                KeyedRow {
+                    key: key.clone(),
+                    nodes,
+                }
+            };
+            keyed_rows.rows.push(row);
+        }
+
+        let children: Vec<_> = keyed_rows
+            .rows
+            .iter()
+            .flat_map(|row| row.nodes.iter().cloned())
+            .collect();
+        drop(keyed_rows);
+        if is_mount {
+            expanded_node.reattach_children(children, context, &expanded_node.parent_frame)
+        } else {
+            for child in created.iter() {
+                child.recurse_control_flow_expansion(context);
+            }
+            children
+        }
+    }
+}
+
+/// Computes the key of every source element of a keyed `for`, along with the position of
+/// each key. If two elements share a key, rows fall back to being keyed by index
+fn key_order(
+    expanded_node: &Rc<ExpandedNode>,
+    source_expression: &Property<PaxValue>,
+    key_expression: &Property<Option<Rc<ExpressionInfo>>>,
+) -> Property<Rc<KeyOrder>> {
+    let (i_symbol, elem_symbol) =
+        expanded_node.with_properties_unwrapped(|properties: &mut RepeatProperties| {
+            (
+                properties.iterator_i_symbol.clone(),
+                properties.iterator_elem_symbol.clone(),
+            )
+        });
+    let deps = [
+        source_expression.untyped(),
+        key_expression.untyped(),
+        i_symbol.untyped(),
+        elem_symbol.untyped(),
+    ];
+    let weak_expanded_node = Rc::downgrade(expanded_node);
+    let source_expression = source_expression.clone();
+    let key_expression = key_expression.clone();
+    Property::computed_with_name(
+        move || {
+            let (Some(key_expression), Some(expanded_node)) =
+                (key_expression.get(), weak_expanded_node.upgrade())
+            else {
+                return Default::default();
+            };
+            let (i_symbol, elem_symbol) = (i_symbol.get(), elem_symbol.get());
+            let source = source_expression.get();
+            let len = source_len(&source);
+            let mut keys = Vec::with_capacity(len);
+            let mut positions = HashMap::with_capacity(len);
+            for i in 0..len {
+                let key_scope = repeat_scope(
+                    &i_symbol,
+                    &elem_symbol,
+                    Property::new(i),
+                    Property::new(source_elem(&source, i)),
+                );
+                let key =
+                    BoundExpression::new(&key_expression, &expanded_node.stack.push(key_scope))
+                        .compute()
+                        .unwrap_or_else(|err| {
+                            log::warn!("Failed to compute repeat key: {:?}", err);
+                            Default::default()
+                        });
+                let key = RowKey::from(&key);
+                if positions.insert(key.clone(), i).is_some() {
+                    log::warn!(
+                        "duplicate repeat key {:?}, falling back to keying rows by index",
+                        key
+                    );
+                    return Rc::new(KeyOrder::by_index(len));
+                }
+                keys.push(key);
+            }
+            Rc::new(KeyOrder { keys, positions })
+        },
+        &deps,
+        "repeat key order",
+    )
+}
+
+/// The keys of a keyed `for` in source order, and the position of each
+#[derive(Default)]
+struct KeyOrder {
+    keys: Vec<RowKey>,
+    positions: HashMap<RowKey, usize>,
+}
+
+impl KeyOrder {
+    fn by_index(len: usize) -> Self {
+        let keys: Vec<_> = (0..len).map(RowKey::Index).collect();
+        let positions = keys.iter().cloned().zip(0..len).collect();
+        Self { keys, positions }
+    }
+}
+
+/// A hashable form of a key computed for a row. Numbers compare by value regardless of
+/// whether they are integers or floats, and values without a natural hash compare by
+/// their debug representation
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum RowKey {
+    Index(usize),
+    Bool(bool),
+    Int(i64),
+    Float(u64),
+    String(String),
+    Other(String),
+}
+
+impl From<&PaxValue> for RowKey {
+    fn from(value: &PaxValue) -> Self {
+        match value {
+            PaxValue::Bool(b) => RowKey::Bool(*b),
+            PaxValue::Numeric(n) if !n.is_float() || n.to_float().fract() == 0.0 => {
+                RowKey::Int(n.to_int())
+            }
+            PaxValue::Numeric(n) => RowKey::Float(n.to_float().to_bits()),
+            PaxValue::String(s) => RowKey::String(s.clone()),
+            other => RowKey::Other(format!("{:?}", other)),
+        }
+    }
+}
+
+/// The expanded nodes of one source element of a keyed `for`
+struct KeyedRow {
+    key: RowKey,
+    nodes: Vec<Rc<ExpandedNode>>,
+}
+
+#[derive(Default)]
+struct KeyedRows {
+    rows: Vec<KeyedRow>,
+    i_symbol: Option<String>,
+    elem_symbol: Option<String>,
+}
+
+fn repeat_scope(
+    i_symbol: &Option<String>,
+    elem_symbol: &Option<String>,
+    property_i: Property<usize>,
+    property_elem: Property<PaxValue>,
+) -> HashMap<String, Variable> {
+    let mut scope: HashMap<String, Variable> = HashMap::new();
+    if let Some(i_symbol) = i_symbol {
+        scope.insert(
+            i_symbol.clone(),
+            Variable::new_from_typed_property(property_i),
+        );
+    }
+    if let Some(elem_symbol) = elem_symbol {
+        scope.insert(
+            elem_symbol.clone(),
+            Variable::new_from_typed_property(property_elem),
+        );
+    }
+    scope
+}
+
+fn source_len(source: &PaxValue) -> usize {
+    if let PaxValue::Range(range) = source {
+        range.len()
+    } else if let PaxValue::Vec(v) = source {
+        v.len()
+    } else {
+        log::warn!("source is not a vec");
+        0
+    }
+}
+
+fn source_elem(source: &PaxValue, i: usize) -> PaxValue {
+    if let PaxValue::Range(range) = source {
+        range.get(i).unwrap_or_default().to_pax_value()
+    } else if let PaxValue::Vec(v) = source {
+        v.get(i).cloned().unwrap_or_default()
+    } else {
+        log::warn!("source is not a vec");
+        Default::default()
+    }
 }
@@ -0,0 +1,265 @@
+//! Overrides of node properties by the `@settings` blocks with pseudo-class selectors matching
+//! them, e.g. `.card:hover { fill: ... }`, applied while the node is in the interaction states
+//! of the selector.
+//!
+//! Each variant gets a weight, eased from 0 to 1 when its node enters all of its states and
+//! back when it leaves one. Overridden properties interpolate between their resting and
+//! overridden values by that weight, so variants without a `transition` switch instantly, and
+//! values that can't be interpolated only take the overridden value while fully eased in.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use pax_manifest::cartridge_generation::InlineStateVariant;
+use pax_manifest::selectors::PseudoClass;
+use pax_manifest::ValueDefinition;
+use pax_runtime_api::pax_value::{CoercionRules, PaxAny};
+use pax_runtime_api::properties::PropertyValue;
+use pax_runtime_api::{
+    borrow, use_RefCell, CommonProperties, EasingCurve, Property, TransitionDuration,
+};
+
+use crate::{ComponentFactory, ExpandedNode, RuntimePropertiesStackFrame};
+use_RefCell!();
+
+/// Pointer interaction state of an [`ExpandedNode`], updated by the [`crate::RuntimeContext`]
+/// as pointer events are dispatched. `:disabled` is read from the `disabled` common property.
+#[derive(Clone, Default)]
+pub struct InteractionState {
+    pub hovered: Property<bool>,
+    pub pressed: Property<bool>,
+    pub focused: Property<bool>,
+}
+
+impl InteractionState {
+    /// Makes this state follow `other`, for the properties of a node recreated in place of
+    /// the one `other` belongs to
+    pub fn mirror(&self, other: &InteractionState) {
+        for (state, other) in [
+            (&self.hovered, &other.hovered),
+            (&self.pressed, &other.pressed),
+            (&self.focused, &other.focused),
+        ] {
+            let other = other.clone();
+            let deps = [other.untyped()];
+            state.replace_with(Property::computed(move || other.get(), &deps));
+        }
+    }
+
+    /// Whether the node is in all of `states`
+    fn matches(
+        &self,
+        states: &[PseudoClass],
+        common_properties: &CommonProperties,
+    ) -> Property<bool> {
+        let states: Vec<Property<bool>> = states
+            .iter()
+            .map(|state| match state {
+                PseudoClass::Hover => self.hovered.clone(),
+                PseudoClass::Pressed => self.pressed.clone(),
+                PseudoClass::Focused => self.focused.clone(),
+                PseudoClass::Disabled => {
+                    let disabled = common_properties.disabled.clone();
+                    let deps = [disabled.untyped()];
+                    Property::computed(move || disabled.get().unwrap_or(false), &deps)
+                }
+            })
+            .collect();
+        let deps: Vec<_> = states.iter().map(|s| s.untyped()).collect();
+        Property::computed(move || states.iter().all(|s| s.get()), &deps)
+    }
+}
+
+type PropertiesFactory<T> =
+    Box<dyn Fn(Rc<RuntimePropertiesStackFrame>, Option<Rc<ExpandedNode>>) -> Option<T>>;
+
+/// The properties an instance node overrides while in all of `states`
+pub struct StateVariant {
+    pub states: Vec<PseudoClass>,
+    /// How long to ease in and out of the variant for, if at all
+    pub transition: Option<TransitionDuration>,
+    /// The curve to ease in and out of the variant along
+    pub easing: EasingCurve,
+    /// Names of the properties this variant overrides
+    pub overridden: Vec<String>,
+    pub properties_factory: PropertiesFactory<Rc<RefCell<PaxAny>>>,
+    pub common_properties_factory: PropertiesFactory<Rc<RefCell<CommonProperties>>>,
+    pub override_properties: Box<
+        dyn Fn(
+            &Rc<RefCell<PaxAny>>,
+            &Rc<RefCell<PaxAny>>,
+            &[String],
+            &Property<f64>,
+        ) -> Result<(), String>,
+    >,
+}
+
+impl StateVariant {
+    pub fn new(variant: InlineStateVariant, factory: &dyn ComponentFactory) -> Self {
+        StateVariant {
+            states: variant.states,
+            transition: literal_setting(variant.transition, "transition"),
+            easing: literal_setting(variant.easing, "easing").unwrap_or(EasingCurve::OutQuad),
+            overridden: variant.properties.keys().cloned().collect(),
+            properties_factory: factory.build_inline_properties(variant.properties.clone()),
+            common_properties_factory: factory.build_inline_common_properties(variant.properties),
+            override_properties: factory.build_state_variant_override(),
+        }
+    }
+}
+
+fn literal_setting<T: CoercionRules>(value: Option<ValueDefinition>, key: &str) -> Option<T> {
+    match value? {
+        ValueDefinition::LiteralValue(lv) => match T::try_coerce(lv) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log::warn!("Failed to coerce state {}. Error: {:?}", key, err);
+                None
+            }
+        },
+        _ => {
+            log::warn!("State {}s must be literal values", key);
+            None
+        }
+    }
+}
+
+/// Eases the weight of a state variant in and out as its node enters and leaves the states
+/// of the variant
+#[derive(Clone)]
+pub struct StateVariantTransition {
+    active: Property<bool>,
+    weight: Property<f64>,
+    transition: Option<TransitionDuration>,
+    easing: EasingCurve,
+    was_active: Cell<bool>,
+}
+
+impl StateVariantTransition {
+    /// Starts easing the weight towards whether the node is in the states of the variant,
+    /// if that changed since the last update. Called once a tick, outside of property
+    /// evaluation, since it sets the weight.
+    pub fn update(&self) {
+        let active = self.active.get();
+        if self.was_active.replace(active) == active {
+            return;
+        }
+        let target = if active { 1.0 } else { 0.0 };
+        match self.transition {
+            Some(duration) => self.weight.ease_to(target, duration, self.easing.clone()),
+            None => self.weight.set(target),
+        }
+    }
+}
+
+/// Overrides the properties of a node by its state variants, returning the transitions of
+/// the weights of the variants, which need to be updated every tick.
+pub fn apply_state_variants(
+    variants: &[StateVariant],
+    properties: &Rc<RefCell<PaxAny>>,
+    common_properties: &Rc<RefCell<CommonProperties>>,
+    state: &InteractionState,
+    stack: &Rc<RuntimePropertiesStackFrame>,
+) -> Vec<StateVariantTransition> {
+    variants
+        .iter()
+        .map(|variant| {
+            let active = state.matches(&variant.states, &borrow!(common_properties));
+            let was_active = active.get();
+            let weight = Property::new(if was_active { 1.0 } else { 0.0 });
+            if let Some(variant_properties) = (variant.properties_factory)(stack.clone(), None) {
+                if let Err(err) = (variant.override_properties)(
+                    properties,
+                    &variant_properties,
+                    &variant.overridden,
+                    &weight,
+                ) {
+                    log::warn!("Failed to apply state variant. Error: {}", err);
+                }
+            }
+            if let Some(variant_common_properties) =
+                (variant.common_properties_factory)(stack.clone(), None)
+            {
+                override_common_properties(
+                    &borrow!(common_properties),
+                    &borrow!(variant_common_properties),
+                    &variant.overridden,
+                    &weight,
+                );
+            }
+            StateVariantTransition {
+                active,
+                weight,
+                transition: variant.transition,
+                easing: variant.easing.clone(),
+                was_active: Cell::new(was_active),
+            }
+        })
+        .collect()
+}
+
+/// Makes `property` interpolate between its current definition and `variant` by `weight`,
+/// keeping its dependents
+pub fn override_with_state_variant<T: PropertyValue>(
+    property: &Property<T>,
+    variant: &Property<T>,
+    weight: &Property<f64>,
+) {
+    let resting = Property::default();
+    resting.replace_with(property.clone());
+    let variant = variant.clone();
+    let weight = weight.clone();
+    let deps = [resting.untyped(), variant.untyped(), weight.untyped()];
+    property.replace_with(Property::computed(
+        move || {
+            let t = weight.get();
+            if t <= 0.0 {
+                resting.get()
+            } else if t >= 1.0 {
+                variant.get()
+            } else {
+                resting.get().interpolate(&variant.get(), t)
+            }
+        },
+        &deps,
+    ));
+}
+
+fn override_common_properties(
+    properties: &CommonProperties,
+    variant: &CommonProperties,
+    overridden: &[String],
+    weight: &Property<f64>,
+) {
+    // `id` identifies the node, and `disabled` is matched by `:disabled`
+    macro_rules! override_fields {
+        ($($field:ident),*) => {
+            $(
+                if overridden.iter().any(|name| name == stringify!($field)) {
+                    override_with_state_variant(&properties.$field, &variant.$field, weight);
+                }
+            )*
+        };
+    }
+    override_fields!(
+        x,
+        y,
+        width,
+        height,
+        anchor_x,
+        anchor_y,
+        scale_x,
+        scale_y,
+        skew_x,
+        skew_y,
+        rotate,
+        transform,
+        unclippable,
+        _raycastable,
+        _suspended,
+        focusable,
+        tab_index,
+        opacity,
+        blend_mode
+    );
+}
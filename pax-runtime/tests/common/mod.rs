@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use pax_runtime::api::{CommonProperties, Layer, Platform, Property, OS};
+use pax_runtime::{
+    BaseInstance, ComponentInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs,
+    PaxEngine,
+};
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, PaxAny, ToFromPaxAny};
+use pax_runtime_api::PaxValue;
+
+/// A node with a bit of state, standing in for the nodes of a template
+#[derive(Default)]
+pub struct Row {
+    pub label: Property<String>,
+}
+
+impl ImplToFromPaxAny for Row {}
+
+pub struct RowInstance {
+    base: BaseInstance,
+}
+
+impl InstanceNode for RowInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                    is_slot: false,
+                },
+            ),
+        })
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Row").finish()
+    }
+}
+
+pub fn args(
+    properties: impl Fn() -> PaxAny + 'static,
+    children: Option<Vec<Rc<dyn InstanceNode>>>,
+    component_template: Option<Vec<Rc<dyn InstanceNode>>>,
+) -> InstantiationArgs {
+    InstantiationArgs {
+        prototypical_common_properties_factory: Box::new(|_, _| {
+            Some(Rc::new(RefCell::new(CommonProperties::default())))
+        }),
+        prototypical_properties_factory: Box::new(move |_, _| {
+            Some(Rc::new(RefCell::new(properties())))
+        }),
+        handler_registry: None,
+        children: children.map(RefCell::new),
+        component_template: component_template.map(RefCell::new),
+        template_node_identifier: None,
+        properties_scope_factory: None,
+        state_variants: Vec::new(),
+    }
+}
+
+/// Runs a [`PaxEngine`] on a 10x10 viewport without rendering it. Time advances by a 60th
+/// of a second per tick.
+pub struct TestEngine {
+    pub engine: PaxEngine,
+    ticks: Rc<Cell<u64>>,
+}
+
+impl TestEngine {
+    pub fn new(main_component_instance: Rc<ComponentInstance>) -> Self {
+        let ticks = Rc::new(Cell::new(0));
+        let elapsed_ticks = Rc::clone(&ticks);
+        let engine = PaxEngine::new(
+            main_component_instance,
+            (10.0, 10.0),
+            Platform::Unknown,
+            OS::Unknown,
+            Box::new(move || (elapsed_ticks.get() * 1000 / 60) as u128),
+        );
+        Self { engine, ticks }
+    }
+
+    pub fn tick(&mut self) {
+        self.engine.tick();
+        self.ticks.set(self.ticks.get() + 1);
+    }
+}
+
+/// Mounts `template` as the template of the main component, and ticks once
+pub fn engine_with_template(template: Vec<Rc<dyn InstanceNode>>) -> TestEngine {
+    let root =
+        ComponentInstance::instantiate(args(|| Row::default().to_pax_any(), None, Some(template)));
+    let mut engine = TestEngine::new(root);
+    engine.tick();
+    engine
+}
+
+/// A `Row` whose label is `label`
+pub fn row(label: &'static str) -> Rc<dyn InstanceNode> {
+    RowInstance::instantiate(args(
+        move || {
+            Row {
+                label: Property::new(label.to_owned()),
+            }
+            .to_pax_any()
+        },
+        None,
+        None,
+    ))
+}
+
+pub fn label(row: &Rc<ExpandedNode>) -> String {
+    row.with_properties_unwrapped(|row: &mut Row| row.label.get())
+}
+
+pub fn symbol(node: &ExpandedNode, symbol: &str) -> PaxValue {
+    node.stack
+        .resolve_symbol_as_variable(symbol)
+        .unwrap()
+        .get_as_pax_value()
+}
@@ -0,0 +1,294 @@
+// @generated by compiled_expression_tests.rs
+
+fn expression_0(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok(((vars[0].get_as::<Numeric>()?) + (Numeric::I64(1i64))))
+}
+
+fn expression_0_typed(vars: &[Variable]) -> Result<f64, String> {
+    let value = expression_0(vars)?;
+    Ok(value.into())
+}
+
+fn expression_1(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok(((((vars[0].get_as::<Numeric>()?) * (vars[1].get_as::<Numeric>()?))) - (Numeric::I64(2i64))))
+}
+
+fn expression_2(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok(((vars[0].get_as::<Numeric>()?) / (Numeric::I64(2i64))))
+}
+
+fn expression_3(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok(((vars[0].get_as::<Numeric>()?) % (Numeric::I64(2i64))))
+}
+
+fn expression_4(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok((vars[0].get_as::<Numeric>()?).pow(Numeric::I64(2i64)))
+}
+
+fn expression_5(vars: &[Variable]) -> Result<Numeric, String> {
+    Ok((-(vars[0].get_as::<Numeric>()?)))
+}
+
+fn expression_6(vars: &[Variable]) -> Result<bool, String> {
+    Ok((!(vars[0].get_as::<bool>()?)))
+}
+
+fn expression_7(vars: &[Variable]) -> Result<bool, String> {
+    Ok(((((vars[0].get_as::<bool>()?) & (((vars[1].get_as::<Numeric>()?) > (Numeric::I64(2i64)))))) | (((vars[2].get_as::<Numeric>()?) >= (Numeric::F64(1.0f64))))))
+}
+
+fn expression_8(vars: &[Variable]) -> Result<bool, String> {
+    Ok(((((vars[0].get_as::<Numeric>()?) == (Numeric::I64(3i64)))) & (((vars[1].get_as::<String>()?) != ("x".to_string())))))
+}
+
+fn expression_9(vars: &[Variable]) -> Result<bool, String> {
+    Ok(((vars[0].get_as::<Numeric>()?) > (vars[1].get_as::<Numeric>()?)))
+}
+
+fn expression_10(vars: &[Variable]) -> Result<bool, String> {
+    Ok(((vars[0].get_as::<String>()?) < ("z".to_string())))
+}
+
+fn expression_11(vars: &[Variable]) -> Result<String, String> {
+    Ok(((vars[0].get_as::<String>()?) + &("!".to_string())))
+}
+
+fn expression_12(vars: &[Variable]) -> Result<Percent, String> {
+    Ok(Percent(((vars[0].get_as::<Numeric>()?) * (Numeric::I64(10i64)))))
+}
+
+fn expression_12_typed(vars: &[Variable]) -> Result<pax_runtime_api::Size, String> {
+    let value = expression_12(vars)?;
+    Ok(<pax_runtime_api::Size>::try_coerce(value.to_pax_value())?)
+}
+
+fn expression_13(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok((((Size::Pixels(vars[0].get_as::<Numeric>()?)).to_pax_value()) + ((vars[1].get_as::<Size>()?).to_pax_value())))
+}
+
+fn expression_13_typed(vars: &[Variable]) -> Result<pax_runtime_api::Size, String> {
+    let value = expression_13(vars)?;
+    Ok(<pax_runtime_api::Size>::try_coerce(value)?)
+}
+
+fn expression_14(vars: &[Variable]) -> Result<bool, String> {
+    Ok(((vars[0].get_as::<Size>()?) == (Size::Pixels(Numeric::I64(4i64)))))
+}
+
+fn expression_15(vars: &[Variable]) -> Result<Rotation, String> {
+    Ok(Rotation::Degrees(vars[0].get_as::<Numeric>()?))
+}
+
+fn expression_16(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(PaxValue::Duration(TransitionDuration::Millis((Numeric::I64(2i64)).to_float() * 1000.0)))
+}
+
+fn expression_17(vars: &[Variable]) -> Result<String, String> {
+    Ok({ let mut string = String::new(); string.push_str(&vars[0].get_as::<String>()?); string.push_str(": "); evaluation::interpolate(&mut string, (vars[1].get_as::<Numeric>()?).to_pax_value()); string.push_str(" "); evaluation::interpolate(&mut string, (vars[2].get_as::<Numeric>()?).to_pax_value()); string.push_str(" "); evaluation::interpolate(&mut string, (vars[3].get_as::<bool>()?).to_pax_value()); string })
+}
+
+fn expression_18(vars: &[Variable]) -> Result<String, String> {
+    Ok(vars[0].read_pax_value_ref(|value| -> Result<String, String> { let value = evaluation::access_list(value, (Numeric::I64(1i64)).to_pax_value())?; String::try_coerce(value.clone()) })?)
+}
+
+fn expression_19(vars: &[Variable]) -> Result<String, String> {
+    Ok(vars[1].read_pax_value_ref(|value| -> Result<String, String> { let value = evaluation::access_list(value, (vars[0].get_as::<Numeric>()?).to_pax_value())?; String::try_coerce(value.clone()) })?)
+}
+
+fn expression_20(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::call_function_or_enum("List", "first", vec![vars[0].get_as_pax_value()])?)
+}
+
+fn expression_21(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::call_function_or_enum("List", "first", vec![(vars[0].get_as::<Numeric>()?).to_pax_value()])?)
+}
+
+fn expression_22(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::call_function_or_enum("List", "map", vec![vars[0].get_as_pax_value(), { let local_1 = vars[1].get_as_pax_value(); PaxValue::Function(PaxFunction::new("|s| String::len(s) * count".to_string(), move |args: Vec<PaxValue>| -> Result<PaxValue, String> { if args.len() != 1 { return Err(format!("Expected {} arguments for lambda, found {}", 1, args.len())); } let mut args = args.into_iter(); let local_2 = args.next().unwrap(); Ok(((evaluation::call_function_or_enum("String", "len", vec![local_2.clone()])?) * (local_1.clone()))) })) }])?)
+}
+
+fn expression_23(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::call_function_or_enum("List", "filter", vec![evaluation::build_range((Numeric::I64(0i64)).to_pax_value(), (vars[0].get_as::<Numeric>()?).to_pax_value(), false, None)?, { PaxValue::Function(PaxFunction::new("|n| (n %% 2) == 0".to_string(), move |args: Vec<PaxValue>| -> Result<PaxValue, String> { if args.len() != 1 { return Err(format!("Expected {} arguments for lambda, found {}", 1, args.len())); } let mut args = args.into_iter(); let local_1 = args.next().unwrap(); Ok(PaxValue::Bool(((((local_1.clone()) % ((Numeric::I64(2i64)).to_pax_value())))) == ((Numeric::I64(0i64)).to_pax_value()))) })) }])?)
+}
+
+fn expression_24(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::build_range((Numeric::I64(0i64)).to_pax_value(), (vars[0].get_as::<Numeric>()?).to_pax_value(), true, Some((Numeric::I64(2i64)).to_pax_value()))?)
+}
+
+fn expression_25(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(evaluation::call_function_or_enum("Color", "rgb", vec![(Numeric::I64(255i64)).to_pax_value(), (Numeric::I64(0i64)).to_pax_value(), (Numeric::I64(0i64)).to_pax_value()])?)
+}
+
+fn expression_26(vars: &[Variable]) -> Result<Color, String> {
+    Ok(Color::RED)
+}
+
+fn expression_27(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(PaxValue::Object(vec![("x".to_string(), (vars[0].get_as::<Numeric>()?).to_pax_value()), ("y".to_string(), (vars[1].get_as::<String>()?).to_pax_value())]))
+}
+
+fn expression_28(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(PaxValue::Vec(vec![(vars[0].get_as::<Numeric>()?).to_pax_value(), (vars[1].get_as::<Numeric>()?).to_pax_value(), (vars[2].get_as::<String>()?).to_pax_value()]))
+}
+
+fn expression_29(vars: &[Variable]) -> Result<PaxValue, String> {
+    Ok(((PaxValue::Duration(TransitionDuration::Millis((vars[0].get_as::<Numeric>()?).to_float() * 1000.0))) + (PaxValue::Duration(TransitionDuration::Millis(250.0)))))
+}
+
+fn expression_29_typed(vars: &[Variable]) -> Result<pax_runtime_api::TransitionDuration, String> {
+    let value = expression_29(vars)?;
+    Ok(<pax_runtime_api::TransitionDuration>::try_coerce(value)?)
+}
+
+pub static EXPRESSIONS: &[Option<CompiledExpression>] = &[
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_0(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_0_typed as TypedExpressionFn<f64>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "ratio", ],
+        evaluate: |vars| expression_1(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_1 as TypedExpressionFn<Numeric>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_2(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_2 as TypedExpressionFn<Numeric>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_3(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_3 as TypedExpressionFn<Numeric>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_4(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_4 as TypedExpressionFn<Numeric>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["ratio", ],
+        evaluate: |vars| expression_5(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_5 as TypedExpressionFn<Numeric>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["flag", ],
+        evaluate: |vars| expression_6(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_6 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["flag", "count", "ratio", ],
+        evaluate: |vars| expression_7(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_7 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "name", ],
+        evaluate: |vars| expression_8(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_8 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "ratio", ],
+        evaluate: |vars| expression_9(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_9 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["name", ],
+        evaluate: |vars| expression_10(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_10 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["name", ],
+        evaluate: |vars| expression_11(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_11 as TypedExpressionFn<String>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_12(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_12_typed as TypedExpressionFn<pax_runtime_api::Size>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["ratio", "offset", ],
+        evaluate: |vars| expression_13(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_13_typed as TypedExpressionFn<pax_runtime_api::Size>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["offset", ],
+        evaluate: |vars| expression_14(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_14 as TypedExpressionFn<bool>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_15(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_15 as TypedExpressionFn<Rotation>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &[],
+        evaluate: |vars| expression_16(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_16 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["name", "count", "ratio", "flag", ],
+        evaluate: |vars| expression_17(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_17 as TypedExpressionFn<String>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["items", ],
+        evaluate: |vars| expression_18(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_18 as TypedExpressionFn<String>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "items", ],
+        evaluate: |vars| expression_19(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_19 as TypedExpressionFn<String>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["items", ],
+        evaluate: |vars| expression_20(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_20 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_21(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_21 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["items", "count", ],
+        evaluate: |vars| expression_22(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_22 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_23(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_23 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", ],
+        evaluate: |vars| expression_24(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_24 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &[],
+        evaluate: |vars| expression_25(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_25 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &[],
+        evaluate: |vars| expression_26(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_26 as TypedExpressionFn<Color>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "name", ],
+        evaluate: |vars| expression_27(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_27 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["count", "ratio", "name", ],
+        evaluate: |vars| expression_28(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_28 as TypedExpressionFn<PaxValue>),
+    }),
+    Some(CompiledExpression {
+        dependencies: &["ratio", ],
+        evaluate: |vars| expression_29(vars).map(ToPaxValue::to_pax_value),
+        typed: &(expression_29_typed as TypedExpressionFn<pax_runtime_api::TransitionDuration>),
+    }),
+];
@@ -0,0 +1,296 @@
+#![cfg(not(feature = "designtime"))]
+
+//! Runs the Rust code `pax-compiler` emits for expressions in release builds against the
+//! interpreter. The code emitted for [`SOURCES`] is checked in at `compiled/expressions.rs`
+//! so that it's compiled into this test; run with `PAX_UPDATE_GOLDENS=1` to regenerate it
+//! after changing the emitter.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use pax_lang::interpreter::parse_pax_expression;
+use pax_lang::Computable;
+use pax_manifest::cartridge_generation::expressions::{
+    compile_expression, CompiledExpressionDefinition,
+};
+use pax_manifest::type_checking::{ExpressionType, Scope};
+use pax_manifest::{ExpressionInfo, TypeId, TypeTable};
+use pax_runtime::{
+    register_compiled_expressions, BoundExpression, RuntimePropertiesStackFrame, TypedExpressionFn,
+};
+use pax_runtime_api::{
+    CoercionRules, Functions, Property, Size, ToPaxValue, TransitionDuration, Variable,
+};
+
+#[allow(
+    unused_imports,
+    unused_parens,
+    unused_variables,
+    unused_mut,
+    clippy::all
+)]
+mod compiled {
+    use pax_lang::interpreter::{evaluation, PaxUnit};
+    use pax_runtime::{CompiledExpression, TypedExpressionFn};
+    use pax_runtime_api::{
+        functions, CoercionRules, Color, Numeric, PaxFunction, PaxValue, Percent, Rotation, Size,
+        ToPaxValue, TransitionDuration, Variable,
+    };
+
+    include!("compiled/expressions.rs");
+}
+
+const GENERATED_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/compiled/expressions.rs");
+
+/// Set to write the generated code over the checked in file instead of comparing against it
+const UPDATE_GOLDENS_ENV_VAR: &str = "PAX_UPDATE_GOLDENS";
+
+/// Expressions compiled to `compiled/expressions.rs`, with the index of each as its id
+const SOURCES: &[&str] = &[
+    "count + 1",
+    "count * ratio - 2",
+    "count / 2",
+    "count %% 2",
+    "count ^ 2",
+    "-ratio",
+    "!flag",
+    "flag && count > 2 || ratio >= 1.0",
+    "count == 3 && name != \"x\"",
+    "count > ratio",
+    "name < \"z\"",
+    "name + \"!\"",
+    "(count * 10)%",
+    "(ratio)px + offset",
+    "offset == (4)px",
+    "(count)deg",
+    "(2)s",
+    "`${name}: ${count} ${ratio} ${flag}`",
+    "items[1]",
+    "items[count]",
+    "List::first(items)",
+    "List::first(count)",
+    "List::map(items, |s| String::len(s) * count)",
+    "List::filter(0..count, |n| (n %% 2) == 0)",
+    "0..=count step 2",
+    "Color::rgb(255, 0, 0)",
+    "RED",
+    "{x: count, y: name}",
+    "[count, ratio, name]",
+    "(ratio)s + 250ms",
+];
+
+/// The types of the properties some of [`SOURCES`] are assigned to
+fn property_type(source: &str) -> Option<TypeId> {
+    match source {
+        "count + 1" => Some(TypeId::build_primitive("f64")),
+        "!flag" => Some(TypeId::build_primitive("bool")),
+        "(count * 10)%" | "(ratio)px + offset" => {
+            Some(TypeId::build_singleton("pax_runtime_api::Size", None))
+        }
+        "(ratio)s + 250ms" => Some(TypeId::build_singleton(
+            "pax_runtime_api::TransitionDuration",
+            None,
+        )),
+        _ => None,
+    }
+}
+
+/// Types of the symbols of [`stack`]
+fn scope() -> Scope {
+    [
+        ("count", ExpressionType::Numeric),
+        ("ratio", ExpressionType::Numeric),
+        ("flag", ExpressionType::Bool),
+        ("name", ExpressionType::String),
+        ("offset", ExpressionType::Size),
+        (
+            "items",
+            ExpressionType::List(Box::new(ExpressionType::String)),
+        ),
+    ]
+    .into_iter()
+    .map(|(name, ty)| (name.to_string(), ty))
+    .collect()
+}
+
+fn stack() -> Rc<RuntimePropertiesStackFrame> {
+    let items: Vec<String> = vec!["a".into(), "bb".into(), "ccc".into()];
+    RuntimePropertiesStackFrame::new(HashMap::from([
+        ("count".to_string(), variable(3usize)),
+        ("ratio".to_string(), variable(0.5f64)),
+        ("flag".to_string(), variable(true)),
+        ("name".to_string(), variable("pax".to_string())),
+        ("offset".to_string(), variable(Size::Pixels(4.into()))),
+        ("items".to_string(), variable(items)),
+    ]))
+}
+
+fn variable<T: pax_runtime_api::properties::PropertyValue + ToPaxValue>(value: T) -> Variable {
+    Variable::new_from_typed_property(Property::new(value))
+}
+
+fn expression_infos() -> Vec<ExpressionInfo> {
+    SOURCES
+        .iter()
+        .enumerate()
+        .map(|(id, source)| ExpressionInfo {
+            id: Some(id),
+            ..ExpressionInfo::new(parse_pax_expression(source).unwrap())
+        })
+        .collect()
+}
+
+/// The module `pax-compiler` generates for cartridges, minus registration
+fn render(compiled: &[Option<CompiledExpressionDefinition>]) -> String {
+    let mut functions = String::new();
+    let mut table = String::new();
+    for expression in compiled {
+        let Some(expression) = expression else {
+            table += "    None,\n";
+            continue;
+        };
+        functions += &format!(
+            "fn expression_{}(vars: &[Variable]) -> Result<{}, String> {{\n    {}\n}}\n\n",
+            expression.id, expression.return_type, expression.body
+        );
+        let mut typed = format!("expression_{}", expression.id);
+        if let Some(conversion) = &expression.conversion {
+            typed += "_typed";
+            functions += &format!(
+                "fn {}(vars: &[Variable]) -> Result<{}, String> {{\n    let value = expression_{}(vars)?;\n    Ok({})\n}}\n\n",
+                typed, expression.property_type, expression.id, conversion
+            );
+        }
+        let dependencies: String = expression
+            .dependencies
+            .iter()
+            .map(|d| format!("{:?}, ", d))
+            .collect();
+        table += &format!(
+            "    Some(CompiledExpression {{\n        dependencies: &[{}],\n        evaluate: |vars| expression_{}(vars).map(ToPaxValue::to_pax_value),\n        typed: &({} as TypedExpressionFn<{}>),\n    }}),\n",
+            dependencies, expression.id, typed, expression.property_type
+        );
+    }
+    format!(
+        "// @generated by compiled_expression_tests.rs\n\n{}pub static EXPRESSIONS: &[Option<CompiledExpression>] = &[\n{}];\n",
+        functions, table
+    )
+}
+
+#[test]
+fn generated_code_is_up_to_date() {
+    Functions::register_all_functions();
+    let scope = scope();
+    let compiled: Vec<_> = expression_infos()
+        .iter()
+        .zip(SOURCES)
+        .map(|(info, source)| {
+            compile_expression(
+                info,
+                &scope,
+                property_type(source).as_ref(),
+                &TypeTable::new(),
+            )
+        })
+        .collect();
+    for (source, expression) in SOURCES.iter().zip(&compiled) {
+        assert!(expression.is_some(), "`{}` wasn't compiled", source);
+    }
+    let generated = render(&compiled);
+    if std::env::var_os(UPDATE_GOLDENS_ENV_VAR).is_some() {
+        std::fs::write(GENERATED_PATH, generated).expect("couldn't write generated code");
+        return;
+    }
+    let checked_in = std::fs::read_to_string(GENERATED_PATH).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "{} is out of date, run with {}=1 to regenerate it",
+        GENERATED_PATH,
+        UPDATE_GOLDENS_ENV_VAR
+    );
+}
+
+#[test]
+fn compiled_expressions_match_interpreter() {
+    Functions::register_all_functions();
+    register_compiled_expressions(compiled::EXPRESSIONS);
+    let stack = stack();
+    let mut errors = 0;
+    for info in expression_infos() {
+        let expression = BoundExpression::new(&info, &stack);
+        assert!(expression.is_compiled(), "`{}` isn't compiled", info);
+        let compiled = expression.compute();
+        let interpreted = info.expression.compute(stack.clone());
+        assert_eq!(
+            format!("{:?}", compiled),
+            format!("{:?}", interpreted),
+            "`{}` evaluates differently when compiled",
+            info
+        );
+        errors += compiled.is_err() as usize;
+
+        let mut dependencies: Vec<_> = expression
+            .dependencies()
+            .iter()
+            .map(|p| p.get_id())
+            .collect();
+        let mut expected: Vec<_> = info
+            .dependencies
+            .iter()
+            .filter_map(|d| stack.resolve_symbol_as_erased_property(d))
+            .map(|p| p.get_id())
+            .collect();
+        dependencies.sort();
+        expected.sort();
+        assert_eq!(dependencies, expected, "dependencies of `{}`", info);
+    }
+    // out of bounds indices and wrongly typed arguments fail the same way
+    assert_eq!(errors, 2);
+}
+
+#[test]
+fn compiled_expressions_evaluate_to_property_types() {
+    Functions::register_all_functions();
+    register_compiled_expressions(compiled::EXPRESSIONS);
+    let stack = stack();
+    let infos = expression_infos();
+    let id = |source: &str| SOURCES.iter().position(|s| *s == source).unwrap();
+    let typed = |source: &str| compiled::EXPRESSIONS[id(source)].unwrap().typed;
+
+    assert!(typed("count + 1").is::<TypedExpressionFn<f64>>());
+    assert!(typed("!flag").is::<TypedExpressionFn<bool>>());
+    assert!(typed("(count * 10)%").is::<TypedExpressionFn<Size>>());
+    assert!(typed("(ratio)px + offset").is::<TypedExpressionFn<Size>>());
+    assert!(typed("(ratio)s + 250ms").is::<TypedExpressionFn<TransitionDuration>>());
+    // expressions not assigned to a property are typed as they evaluate
+    assert!(typed("offset == (4)px").is::<TypedExpressionFn<bool>>());
+
+    // either way, values are those of the interpreter coerced to the property's type
+    let expression = |source: &str| BoundExpression::new(&infos[id(source)], &stack);
+    let interpreted = |source: &str| infos[id(source)].expression.compute(stack.clone());
+    assert_eq!(
+        expression("count + 1").compute_as::<f64>(),
+        f64::try_coerce(interpreted("count + 1").unwrap())
+    );
+    for source in ["(count * 10)%", "(ratio)px + offset", "count * ratio - 2"] {
+        assert_eq!(
+            expression(source).compute_as::<Size>(),
+            Size::try_coerce(interpreted(source).unwrap()),
+            "`{}` as a Size",
+            source
+        );
+    }
+    assert_eq!(
+        expression("(ratio)s + 250ms").compute_as::<TransitionDuration>(),
+        TransitionDuration::try_coerce(interpreted("(ratio)s + 250ms").unwrap())
+    );
+}
+
+#[test]
+fn expressions_without_compiled_code_are_interpreted() {
+    let stack = stack();
+    let info = ExpressionInfo::new(parse_pax_expression("count + 1").unwrap());
+    let expression = BoundExpression::new(&info, &stack);
+    assert!(!expression.is_compiled());
+    assert_eq!(expression.compute(), info.expression.compute(stack.clone()));
+}
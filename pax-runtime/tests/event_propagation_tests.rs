@@ -0,0 +1,148 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use common::{args, Row, RowInstance, TestEngine};
+use pax_runtime::api::{Click, Event, MouseButton, MouseEventArgs, NodeContext, Property};
+use pax_runtime::{ComponentInstance, ExpandedNode, Handler, HandlerRegistry, InstanceNode};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+
+thread_local! {
+    /// The handlers run so far, as `(label of the node, phase)`
+    static LOG: RefCell<Vec<(String, &'static str)>> = const { RefCell::new(Vec::new()) };
+    /// The handler to stop propagation in
+    static STOP_AT: Cell<Option<(&'static str, &'static str)>> = const { Cell::new(None) };
+}
+
+fn record(properties: Rc<RefCell<PaxAny>>, event: Option<PaxAny>, phase: &'static str) {
+    let label = Row::ref_from_pax_any(&properties.borrow())
+        .unwrap()
+        .label
+        .get();
+    let event = event.unwrap();
+    let event = Event::<Click>::ref_from_pax_any(&event).unwrap();
+    if STOP_AT.get() == Some((label.as_str(), phase)) {
+        event.stop_propagation();
+    }
+    LOG.with_borrow_mut(|log| log.push((label, phase)));
+}
+
+fn capture(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, "capture");
+}
+
+fn bubble(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, "bubble");
+}
+
+/// Handlers of both phases of `click`, run with the properties of the node they're bound to
+fn click_handlers() -> Rc<RefCell<HandlerRegistry>> {
+    let mut registry = HandlerRegistry::default();
+    registry.handlers.insert(
+        "click.capture".to_owned(),
+        vec![Handler::new_component_handler(capture)],
+    );
+    registry.handlers.insert(
+        "click".to_owned(),
+        vec![Handler::new_component_handler(bubble)],
+    );
+    Rc::new(RefCell::new(registry))
+}
+
+fn labelled(label: &'static str) -> impl Fn() -> PaxAny {
+    move || {
+        Row {
+            label: Property::new(label.to_owned()),
+        }
+        .to_pax_any()
+    }
+}
+
+/// `root`, containing `outer`, containing `inner`, all listening to clicks in both phases
+fn nested_rows() -> TestEngine {
+    let mut inner_args = args(labelled("inner"), None, None);
+    inner_args.handler_registry = Some(click_handlers());
+    let inner: Rc<dyn InstanceNode> = RowInstance::instantiate(inner_args);
+
+    let mut outer_args = args(labelled("outer"), Some(vec![inner]), None);
+    outer_args.handler_registry = Some(click_handlers());
+    let outer: Rc<dyn InstanceNode> = RowInstance::instantiate(outer_args);
+
+    let mut root_args = args(labelled("root"), None, Some(vec![outer]));
+    root_args.handler_registry = Some(click_handlers());
+    let mut engine = TestEngine::new(ComponentInstance::instantiate(root_args));
+    engine.tick();
+    engine
+}
+
+fn inner(engine: &TestEngine) -> Rc<ExpandedNode> {
+    let outer = engine.engine.root_expanded_node.children.get()[0].clone();
+    outer.children.get()[0].clone()
+}
+
+/// Clicks `inner`, returning the handlers run
+fn click(stop_at: Option<(&'static str, &'static str)>) -> Vec<(String, &'static str)> {
+    let engine = nested_rows();
+    LOG.with_borrow_mut(Vec::clear);
+    STOP_AT.set(stop_at);
+    let context = engine.engine.runtime_context.clone();
+    inner(&engine).dispatch_click(
+        Event::new(Click {
+            mouse: MouseEventArgs {
+                x: 5.0,
+                y: 5.0,
+                button: MouseButton::Left,
+                modifiers: vec![],
+            },
+        }),
+        &context.globals(),
+        &context,
+    );
+    LOG.with_borrow_mut(std::mem::take)
+}
+
+fn handlers(expected: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    expected
+        .iter()
+        .map(|(label, phase)| (label.to_string(), *phase))
+        .collect()
+}
+
+#[test]
+fn capture_runs_from_root_to_target_before_bubbling() {
+    assert_eq!(
+        click(None),
+        handlers(&[
+            ("root", "capture"),
+            ("outer", "capture"),
+            ("inner", "capture"),
+            ("inner", "bubble"),
+            ("outer", "bubble"),
+            ("root", "bubble"),
+        ])
+    );
+}
+
+#[test]
+fn stop_propagation_in_capture_phase_skips_the_rest() {
+    assert_eq!(
+        click(Some(("outer", "capture"))),
+        handlers(&[("root", "capture"), ("outer", "capture")])
+    );
+}
+
+#[test]
+fn stop_propagation_in_bubble_phase_skips_ancestors() {
+    assert_eq!(
+        click(Some(("inner", "bubble"))),
+        handlers(&[
+            ("root", "capture"),
+            ("outer", "capture"),
+            ("inner", "capture"),
+            ("inner", "bubble"),
+        ])
+    );
+}
@@ -0,0 +1,252 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{args, engine_with_template, label, Row, RowInstance, TestEngine};
+use pax_runtime::api::{CommonProperties, NodeContext, Property};
+use pax_runtime::{
+    ConditionalInstance, ConditionalProperties, ExpandedNode, Handler, HandlerRegistry,
+    InstanceNode,
+};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+
+thread_local! {
+    /// The focus handlers run so far, as `(label of the node, event)`
+    static LOG: RefCell<Vec<(String, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn record(properties: Rc<RefCell<PaxAny>>, event: &'static str) {
+    let label = Row::ref_from_pax_any(&properties.borrow())
+        .unwrap()
+        .label
+        .get();
+    LOG.with_borrow_mut(|log| log.push((label, event)));
+}
+
+fn focus(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, _: Option<PaxAny>) {
+    record(properties, "focus");
+}
+
+fn blur(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, _: Option<PaxAny>) {
+    record(properties, "blur");
+}
+
+fn take_log() -> Vec<(String, &'static str)> {
+    LOG.with_borrow_mut(std::mem::take)
+}
+
+fn log(expected: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    expected
+        .iter()
+        .map(|(label, event)| (label.to_string(), *event))
+        .collect()
+}
+
+struct Focusable {
+    tab_index: Option<isize>,
+    disabled: bool,
+}
+
+/// A `Row` labelled `label`, listening to `@focus` and `@blur`, focusable unless `focusable`
+/// is `None`
+fn row(
+    label: &'static str,
+    focusable: Option<Focusable>,
+    children: Option<Vec<Rc<dyn InstanceNode>>>,
+) -> Rc<dyn InstanceNode> {
+    let mut row_args = args(
+        move || {
+            Row {
+                label: Property::new(label.to_owned()),
+            }
+            .to_pax_any()
+        },
+        children,
+        None,
+    );
+    let (tab_index, disabled) = match &focusable {
+        Some(f) => (f.tab_index, f.disabled),
+        None => (None, false),
+    };
+    let focusable = focusable.is_some();
+    row_args.prototypical_common_properties_factory = Box::new(move |_, _| {
+        Some(Rc::new(RefCell::new(CommonProperties {
+            focusable: Property::new(Some(focusable)),
+            tab_index: Property::new(tab_index),
+            disabled: Property::new(Some(disabled)),
+            ..Default::default()
+        })))
+    });
+    let mut registry = HandlerRegistry::default();
+    registry.handlers.insert(
+        "focus".to_owned(),
+        vec![Handler::new_component_handler(focus)],
+    );
+    registry.handlers.insert(
+        "blur".to_owned(),
+        vec![Handler::new_component_handler(blur)],
+    );
+    row_args.handler_registry = Some(Rc::new(RefCell::new(registry)));
+    RowInstance::instantiate(row_args)
+}
+
+fn tab(tab_index: Option<isize>) -> Option<Focusable> {
+    Some(Focusable {
+        tab_index,
+        disabled: false,
+    })
+}
+
+fn find(engine: &TestEngine, label: &str) -> Rc<ExpandedNode> {
+    let mut found = None;
+    engine
+        .engine
+        .root_expanded_node
+        .recurse_visit_preorder(&mut |node| {
+            let properties = Rc::clone(&*node.properties.borrow());
+            let properties = properties.borrow();
+            if Row::ref_from_pax_any(&properties).is_ok_and(|row| row.label.get() == label) {
+                found = Some(Rc::clone(node));
+            }
+        });
+    found.unwrap_or_else(|| panic!("no row labelled {}", label))
+}
+
+fn focused_label(engine: &TestEngine) -> Option<String> {
+    engine
+        .engine
+        .runtime_context
+        .get_focused_element()
+        .map(|node| label(&node))
+}
+
+#[test]
+fn set_focused_element_dispatches_focus_and_blur() {
+    let mut engine = engine_with_template(vec![
+        row("a", tab(None), Some(vec![row("a child", None, None)])),
+        row("b", tab(None), None),
+    ]);
+    engine.tick();
+    take_log();
+    let context = engine.engine.runtime_context.clone();
+    let a = find(&engine, "a");
+    let a_child = find(&engine, "a child");
+    let b = find(&engine, "b");
+
+    context.set_focused_element(Some(&a_child));
+    assert_eq!(focused_label(&engine).as_deref(), Some("a child"));
+    // the node and its template ancestors match `:focused`
+    assert!(a_child.interaction_state.focused.get());
+    assert!(a.interaction_state.focused.get());
+    assert!(!b.interaction_state.focused.get());
+    // focus events don't bubble
+    assert_eq!(take_log(), log(&[("a child", "focus")]));
+
+    context.set_focused_element(Some(&b));
+    assert_eq!(take_log(), log(&[("a child", "blur"), ("b", "focus")]));
+    assert!(!a_child.interaction_state.focused.get());
+    assert!(!a.interaction_state.focused.get());
+    assert!(b.interaction_state.focused.get());
+
+    context.set_focused_element(Some(&a));
+    assert_eq!(take_log(), log(&[("b", "blur"), ("a", "focus")]));
+
+    // focusing the focused node again doesn't dispatch anything
+    context.set_focused_element(Some(&a));
+    assert_eq!(take_log(), log(&[]));
+
+    context.set_focused_element(None);
+    assert_eq!(take_log(), log(&[("a", "blur")]));
+    assert_eq!(focused_label(&engine), None);
+    assert!(!a.interaction_state.focused.get());
+}
+
+#[test]
+fn focus_next_visits_positive_tab_indices_first_and_wraps_around() {
+    let mut engine = engine_with_template(vec![
+        row("a", tab(None), None),
+        row("b", tab(Some(2)), None),
+        row("not focusable", None, None),
+        row("d", tab(Some(1)), None),
+        row("skipped", tab(Some(-1)), None),
+        row(
+            "disabled",
+            Some(Focusable {
+                tab_index: Some(1),
+                disabled: true,
+            }),
+            None,
+        ),
+        row("g", tab(Some(0)), None),
+    ]);
+    engine.tick();
+    let context = engine.engine.runtime_context.clone();
+
+    let mut forwards = Vec::new();
+    for _ in 0..5 {
+        assert!(context.focus_next(false));
+        forwards.push(focused_label(&engine).unwrap());
+    }
+    assert_eq!(forwards, ["d", "b", "a", "g", "d"]);
+
+    context.set_focused_element(None);
+    let mut backwards = Vec::new();
+    for _ in 0..5 {
+        assert!(context.focus_next(true));
+        backwards.push(focused_label(&engine).unwrap());
+    }
+    assert_eq!(backwards, ["g", "a", "b", "d", "g"]);
+}
+
+#[test]
+fn focus_next_without_focusable_nodes() {
+    let mut engine = engine_with_template(vec![row("a", None, None)]);
+    engine.tick();
+    assert!(!engine.engine.runtime_context.focus_next(false));
+    assert_eq!(focused_label(&engine), None);
+}
+
+#[test]
+fn unmounting_focused_node_clears_focus() {
+    let shown = Property::new(true);
+    let condition = shown.clone();
+    let conditional = ConditionalInstance::instantiate(args(
+        move || {
+            let shown = condition.clone();
+            let deps = [condition.untyped()];
+            ConditionalProperties {
+                boolean_expression: Property::computed(move || shown.get(), &deps),
+                else_expressions: vec![],
+            }
+            .to_pax_any()
+        },
+        Some(vec![row(
+            "outer",
+            tab(None),
+            Some(vec![row("inner", tab(None), None)]),
+        )]),
+        None,
+    ));
+    let mut engine = engine_with_template(vec![conditional, row("sibling", tab(None), None)]);
+    engine.tick();
+    let context = engine.engine.runtime_context.clone();
+    let outer = find(&engine, "outer");
+    let inner = find(&engine, "inner");
+
+    // a focused descendant of the unmounted node
+    context.set_focused_element(Some(&inner));
+    take_log();
+    shown.set(false);
+    engine.tick();
+    assert_eq!(focused_label(&engine), None);
+    assert!(!inner.interaction_state.focused.get());
+    assert!(!outer.interaction_state.focused.get());
+    assert_eq!(take_log(), log(&[]));
+
+    // focus moves on from where it was cleared
+    assert!(context.focus_next(false));
+    assert_eq!(focused_label(&engine).as_deref(), Some("sibling"));
+}
@@ -0,0 +1,285 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{args, engine_with_template, Row, RowInstance, TestEngine};
+use pax_runtime::api::{
+    Drag, DragEnd, DragStart, Event, LongPress, MouseButton, NodeContext, Pinch, PointerEventArgs,
+    PointerType, Property, Swipe,
+};
+use pax_runtime::{
+    recognize_gestures, ExpandedNode, Handler, HandlerRegistry, InstanceNode, PointerPhase,
+};
+use pax_runtime_api::pax_value::{PaxAny, ToFromPaxAny};
+
+thread_local! {
+    /// The gestures dispatched so far, as "<label of the node> <gesture>"
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Logs `gesture` for the node with `properties`, without letting it bubble further
+fn record<T: Clone + 'static>(
+    properties: Rc<RefCell<PaxAny>>,
+    event: Option<PaxAny>,
+    gesture: impl FnOnce(&T) -> String,
+) {
+    let label = Row::ref_from_pax_any(&properties.borrow())
+        .unwrap()
+        .label
+        .get();
+    let event = event.unwrap();
+    let event = Event::<T>::ref_from_pax_any(&event).unwrap();
+    event.stop_propagation();
+    LOG.with_borrow_mut(|log| log.push(format!("{} {}", label, gesture(event))));
+}
+
+fn drag_start(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |e: &DragStart| {
+        format!("drag_start {},{}", e.drag.delta_x, e.drag.delta_y)
+    });
+}
+
+fn drag(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |e: &Drag| {
+        format!("drag {},{}", e.drag.delta_x, e.drag.delta_y)
+    });
+}
+
+fn drag_end(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |_: &DragEnd| "drag_end".to_owned());
+}
+
+fn long_press(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |_: &LongPress| "long_press".to_owned());
+}
+
+fn swipe(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |e: &Swipe| {
+        format!("swipe {:?}", e.direction)
+    });
+}
+
+fn pinch(properties: Rc<RefCell<PaxAny>>, _: &NodeContext, event: Option<PaxAny>) {
+    record(properties, event, |e: &Pinch| {
+        format!("pinch {:.2}", e.scale)
+    });
+}
+
+type GestureHandler = fn(Rc<RefCell<PaxAny>>, &NodeContext, Option<PaxAny>);
+
+/// A `Row` labelled `label` listening to all gestures
+fn row(label: &'static str, children: Option<Vec<Rc<dyn InstanceNode>>>) -> Rc<dyn InstanceNode> {
+    let mut row_args = args(
+        move || {
+            Row {
+                label: Property::new(label.to_owned()),
+            }
+            .to_pax_any()
+        },
+        children,
+        None,
+    );
+    let mut registry = HandlerRegistry::default();
+    let handlers: [(&str, GestureHandler); 6] = [
+        ("drag_start", drag_start),
+        ("drag", drag),
+        ("drag_end", drag_end),
+        ("long_press", long_press),
+        ("swipe", swipe),
+        ("pinch", pinch),
+    ];
+    for (event, handler) in handlers {
+        registry.handlers.insert(
+            event.to_owned(),
+            vec![Handler::new_component_handler(handler)],
+        );
+    }
+    row_args.handler_registry = Some(Rc::new(RefCell::new(registry)));
+    RowInstance::instantiate(row_args)
+}
+
+/// `outer`, containing `a` and `b`
+struct Rows {
+    engine: TestEngine,
+    a: Rc<ExpandedNode>,
+    b: Rc<ExpandedNode>,
+}
+
+impl Rows {
+    fn new() -> Self {
+        let engine = engine_with_template(vec![row(
+            "outer",
+            Some(vec![row("a", None), row("b", None)]),
+        )]);
+        let outer = engine.engine.root_expanded_node.children.get()[0].clone();
+        let (a, b) = {
+            let children = outer.children.get();
+            (children[0].clone(), children[1].clone())
+        };
+        LOG.with_borrow_mut(Vec::clear);
+        Self { engine, a, b }
+    }
+
+    /// Feeds an event of pointer `pointer_id` at `(x, y)`, dispatched to `target`, to the
+    /// gesture recognizer
+    fn pointer(
+        &self,
+        target: &Rc<ExpandedNode>,
+        phase: PointerPhase,
+        pointer_id: i64,
+        (x, y): (f64, f64),
+    ) {
+        let args = PointerEventArgs {
+            pointer_id,
+            pointer_type: PointerType::Touch,
+            x,
+            y,
+            button: MouseButton::Left,
+            modifiers: vec![],
+        };
+        recognize_gestures(&self.engine.engine.runtime_context, target, phase, &args);
+    }
+
+    fn ticks(&mut self, n: usize) {
+        for _ in 0..n {
+            self.engine.tick();
+        }
+    }
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with_borrow_mut(std::mem::take)
+}
+
+#[test]
+fn drags_start_once_past_the_threshold() {
+    let mut rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (3.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (2.0, 2.0));
+    assert_eq!(take_log(), Vec::<String>::new());
+
+    // dispatched to where the pointer was pressed, with the distance from there
+    rows.pointer(&rows.b.clone(), PointerPhase::Move, 1, (5.0, 0.0));
+    assert_eq!(take_log(), ["a drag_start 5,0"]);
+    rows.pointer(&a, PointerPhase::Move, 1, (6.0, 1.0));
+    assert_eq!(take_log(), ["a drag 1,1"]);
+
+    rows.ticks(10);
+    rows.pointer(&a, PointerPhase::Up, 1, (6.0, 1.0));
+    assert_eq!(take_log(), ["a drag_end"]);
+}
+
+#[test]
+fn taps_dont_drag() {
+    let rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (2.0, 3.0));
+    rows.pointer(&a, PointerPhase::Up, 1, (2.0, 3.0));
+    assert_eq!(take_log(), Vec::<String>::new());
+}
+
+#[test]
+fn holding_still_long_presses_once() {
+    let mut rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    // a tick is a 60th of a second, and long presses take half a second
+    rows.ticks(25);
+    assert_eq!(take_log(), Vec::<String>::new());
+    rows.ticks(10);
+    assert_eq!(take_log(), ["a long_press"]);
+    rows.ticks(60);
+    assert_eq!(take_log(), Vec::<String>::new());
+}
+
+#[test]
+fn dragging_doesnt_long_press() {
+    let mut rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (10.0, 0.0));
+    rows.ticks(60);
+    assert_eq!(take_log(), ["a drag_start 10,0"]);
+}
+
+/// The swipes of a pointer released right after moving by `(dx, dy)` each tick for a few ticks
+fn swipes(dx: f64, dy: f64) -> Vec<String> {
+    let mut rows = Rows::new();
+    let a = rows.a.clone();
+    let mut position = (0.0, 0.0);
+    rows.pointer(&a, PointerPhase::Down, 1, position);
+    for _ in 0..3 {
+        rows.ticks(1);
+        position = (position.0 + dx, position.1 + dy);
+        rows.pointer(&a, PointerPhase::Move, 1, position);
+    }
+    rows.pointer(&a, PointerPhase::Up, 1, position);
+    take_log()
+        .into_iter()
+        .filter(|gesture| gesture.contains("swipe"))
+        .collect()
+}
+
+#[test]
+fn fast_releases_swipe_in_the_main_direction() {
+    // 20px a tick is about 1.2px per millisecond
+    assert_eq!(swipes(20.0, 0.0), ["a swipe Right"]);
+    assert_eq!(swipes(-20.0, 5.0), ["a swipe Left"]);
+    assert_eq!(swipes(5.0, 20.0), ["a swipe Down"]);
+    assert_eq!(swipes(0.0, -20.0), ["a swipe Up"]);
+}
+
+#[test]
+fn slow_releases_dont_swipe() {
+    // about 0.3px per millisecond
+    assert_eq!(swipes(5.0, 0.0), Vec::<String>::new());
+
+    // fast, but held still before releasing
+    let mut rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    rows.ticks(1);
+    rows.pointer(&a, PointerPhase::Move, 1, (40.0, 0.0));
+    rows.ticks(10);
+    rows.pointer(&a, PointerPhase::Up, 1, (40.0, 0.0));
+    assert_eq!(take_log(), ["a drag_start 40,0", "a drag_end"]);
+}
+
+#[test]
+fn pinch_scales_with_the_distance_between_pointers() {
+    let rows = Rows::new();
+    let a = rows.a.clone();
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    rows.pointer(&a, PointerPhase::Down, 2, (10.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 2, (20.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (15.0, 0.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (5.0, 0.0));
+    assert_eq!(take_log(), ["a pinch 2.00", "a pinch 0.50", "a pinch 1.50"]);
+}
+
+#[test]
+fn pinch_goes_to_the_closest_common_ancestor() {
+    let rows = Rows::new();
+    let (a, b) = (rows.a.clone(), rows.b.clone());
+    rows.pointer(&a, PointerPhase::Down, 1, (0.0, 0.0));
+    // a drag in progress ends once a second pointer turns it into a pinch
+    rows.pointer(&a, PointerPhase::Move, 1, (0.0, 10.0));
+    rows.pointer(&b, PointerPhase::Down, 2, (0.0, 20.0));
+    rows.pointer(&b, PointerPhase::Move, 2, (0.0, 30.0));
+    assert_eq!(
+        take_log(),
+        ["a drag_start 0,10", "a drag_end", "outer pinch 2.00"]
+    );
+
+    // releasing a pinching pointer ends the pinch
+    rows.pointer(&b, PointerPhase::Up, 2, (0.0, 30.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (0.0, 12.0));
+    rows.pointer(&a, PointerPhase::Move, 1, (0.0, 20.0));
+    assert_eq!(take_log(), ["a drag_start 0,10"]);
+}
@@ -0,0 +1,152 @@
+#![cfg(not(feature = "designtime"))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use kurbo::Shape;
+use pax_runtime::api::{Color, CommonProperties, Fill, Layer, Property, RenderContext, Size};
+use pax_runtime::headless::HeadlessEngine;
+use pax_runtime::{
+    BaseInstance, ComponentInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs,
+    RuntimeContext,
+};
+use pax_runtime_api::pax_value::{ImplToFromPaxAny, ToFromPaxAny};
+
+#[derive(Default)]
+struct Swatch {
+    color: Color,
+}
+
+impl ImplToFromPaxAny for Swatch {}
+
+/// Fills its bounds with a color
+struct SwatchInstance {
+    base: BaseInstance,
+}
+
+impl InstanceNode for SwatchInstance {
+    fn instantiate(args: InstantiationArgs) -> Rc<Self> {
+        Rc::new(Self {
+            base: BaseInstance::new(
+                args,
+                InstanceFlags {
+                    invisible_to_slot: false,
+                    invisible_to_raycasting: false,
+                    layer: Layer::Canvas,
+                    is_component: false,
+                    is_slot: false,
+                },
+            ),
+        })
+    }
+
+    fn render(
+        &self,
+        expanded_node: &ExpandedNode,
+        context: &Rc<RuntimeContext>,
+        rc: &mut dyn RenderContext,
+    ) {
+        let layer_id = expanded_node.occlusion.get().occlusion_layer_id;
+        if !context.is_canvas_dirty(&layer_id) {
+            return;
+        }
+        let tab = expanded_node.transform_and_bounds.get();
+        let (width, height) = tab.bounds;
+        let path = Into::<kurbo::Affine>::into(tab.transform)
+            * kurbo::Rect::new(0.0, 0.0, width, height).to_path(0.1);
+        expanded_node.with_properties_unwrapped(|swatch: &mut Swatch| {
+            rc.fill(layer_id, path, &Fill::Solid(swatch.color.clone()));
+        });
+    }
+
+    fn base(&self) -> &BaseInstance {
+        &self.base
+    }
+
+    fn resolve_debug(
+        &self,
+        f: &mut std::fmt::Formatter,
+        _expanded_node: Option<&ExpandedNode>,
+    ) -> std::fmt::Result {
+        f.debug_struct("Swatch").finish()
+    }
+}
+
+fn args(
+    properties: impl Fn() -> pax_runtime_api::pax_value::PaxAny + 'static,
+    common_properties: impl Fn() -> CommonProperties + 'static,
+    component_template: Option<Vec<Rc<dyn InstanceNode>>>,
+) -> InstantiationArgs {
+    InstantiationArgs {
+        prototypical_common_properties_factory: Box::new(move |_, _| {
+            Some(Rc::new(RefCell::new(common_properties())))
+        }),
+        prototypical_properties_factory: Box::new(move |_, _| {
+            Some(Rc::new(RefCell::new(properties())))
+        }),
+        handler_registry: None,
+        children: None,
+        component_template: component_template.map(RefCell::new),
+        template_node_identifier: None,
+        properties_scope_factory: None,
+        state_variants: Vec::new(),
+    }
+}
+
+fn swatch(color: Color, common_properties: fn() -> CommonProperties) -> Rc<dyn InstanceNode> {
+    SwatchInstance::instantiate(args(
+        move || {
+            Swatch {
+                color: color.clone(),
+            }
+            .to_pax_any()
+        },
+        common_properties,
+        None,
+    ))
+}
+
+fn rgba8(color: &Color) -> [u8; 4] {
+    color
+        .to_rgba_0_1()
+        .map(|channel| (channel * 255.0).round() as u8)
+}
+
+/// A red background, with a blue swatch over its left half
+fn two_swatches() -> Rc<ComponentInstance> {
+    ComponentInstance::instantiate(args(
+        || Swatch::default().to_pax_any(),
+        CommonProperties::default,
+        Some(vec![
+            swatch(Color::BLUE, || CommonProperties {
+                width: Property::new(Some(Size::Percent(50.into()))),
+                ..Default::default()
+            }),
+            swatch(Color::RED, CommonProperties::default),
+        ]),
+    ))
+}
+
+#[test]
+fn renders_headlessly() {
+    let mut headless = HeadlessEngine::new(two_swatches(), 40, 20);
+    // layers are only created once the first frame has been laid out
+    headless.run_frames(2);
+    let snapshot = headless.snapshot();
+    assert_eq!((snapshot.width(), snapshot.height()), (40, 20));
+    assert_eq!(snapshot.pixel(5, 10), rgba8(&Color::BLUE));
+    assert_eq!(snapshot.pixel(35, 10), rgba8(&Color::RED));
+}
+
+#[test]
+fn renders_like_golden_image() {
+    let mut headless = HeadlessEngine::new(two_swatches(), 40, 20);
+    headless.run_frames(2);
+    headless.snapshot().assert_matches_golden(
+        concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/goldens/two_swatches.png"
+        ),
+        2,
+    );
+}
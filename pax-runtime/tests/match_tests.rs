@@ -0,0 +1,174 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{args, engine_with_template, label, row, symbol, TestEngine};
+use pax_lang::interpreter::parse_pax_expression;
+use pax_manifest::ExpressionInfo;
+use pax_runtime::api::Property;
+use pax_runtime::{ExpandedNode, MatchArm, MatchInstance, MatchProperties};
+use pax_runtime_api::pax_value::ToFromPaxAny;
+use pax_runtime_api::{PaxValue, ToPaxValue};
+
+fn arm(
+    variant_path: Option<&str>,
+    bindings: &[&str],
+    guard: Option<&str>,
+    label: &'static str,
+) -> MatchArm {
+    let mut path = variant_path.map(|path| path.rsplit("::"));
+    MatchArm {
+        variant: path.as_mut().and_then(|p| p.next()).map(str::to_owned),
+        enum_name: path.as_mut().and_then(|p| p.next()).map(str::to_owned),
+        bindings: bindings.iter().map(|b| b.to_string()).collect(),
+        guard: guard.map(|guard| ExpressionInfo::new(parse_pax_expression(guard).unwrap())),
+        children: RefCell::new(vec![row(label)]),
+    }
+}
+
+/// ```pax
+/// match subject {
+///     State::Error(message, code) if code >= 500 => { <Row label="server error"/> }
+///     State::Error(message) => { <Row label="error"/> }
+///     Other::Loading => { <Row label="other loading"/> }
+///     Loading => { <Row label="loading"/> }
+///     _ => { <Row label="fallback"/> }
+/// }
+/// ```
+fn state_match(subject: &Property<PaxValue>) -> TestEngine {
+    let subject = subject.clone();
+    let arms = vec![
+        arm(
+            Some("State::Error"),
+            &["message", "code"],
+            Some("code >= 500"),
+            "server error",
+        ),
+        arm(Some("State::Error"), &["message"], None, "error"),
+        arm(Some("Other::Loading"), &[], None, "other loading"),
+        arm(Some("Loading"), &[], None, "loading"),
+        arm(None, &[], None, "fallback"),
+    ];
+    let match_instance = MatchInstance::instantiate_with_arms(
+        args(
+            move || {
+                let cp_subject = subject.clone();
+                MatchProperties {
+                    subject: Property::computed(move || cp_subject.get(), &[subject.untyped()]),
+                }
+                .to_pax_any()
+            },
+            None,
+            None,
+        ),
+        arms,
+    );
+    engine_with_template(vec![match_instance])
+}
+
+fn state(enum_name: &str, variant: &str, payload: Vec<PaxValue>) -> PaxValue {
+    PaxValue::Enum(Box::new((
+        enum_name.to_owned(),
+        variant.to_owned(),
+        payload,
+    )))
+}
+
+fn mounted(engine: &TestEngine) -> Vec<Rc<ExpandedNode>> {
+    let match_node = engine.engine.root_expanded_node.children.get()[0].clone();
+    match_node.children.get()
+}
+
+fn mounted_label(engine: &TestEngine) -> String {
+    let mounted = mounted(engine);
+    assert_eq!(mounted.len(), 1, "exactly one arm should be mounted");
+    label(&mounted[0])
+}
+
+#[test]
+fn match_compares_enum_names_of_qualified_variants() {
+    let subject = Property::new(state("State", "Loading", vec![]));
+    let mut engine = state_match(&subject);
+    assert_eq!(mounted_label(&engine), "loading");
+
+    subject.set(state("Other", "Loading", vec![]));
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "other loading");
+
+    // unqualified variants match the variant of any enum
+    subject.set(state("Unrelated", "Loading", vec![]));
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "loading");
+}
+
+#[test]
+fn match_binds_payload_of_mounted_arm() {
+    let subject = Property::new(state(
+        "State",
+        "Error",
+        vec!["oops".to_owned().to_pax_value(), 404.to_pax_value()],
+    ));
+    let mut engine = state_match(&subject);
+    assert_eq!(mounted_label(&engine), "error");
+    let before = mounted(&engine);
+    assert_eq!(
+        symbol(&before[0], "message"),
+        "oops".to_owned().to_pax_value()
+    );
+
+    // bindings follow the payload without remounting the arm
+    subject.set(state(
+        "State",
+        "Error",
+        vec!["worse".to_owned().to_pax_value(), 418.to_pax_value()],
+    ));
+    engine.tick();
+    let after = mounted(&engine);
+    assert!(Rc::ptr_eq(&before[0], &after[0]));
+    assert_eq!(
+        symbol(&after[0], "message"),
+        "worse".to_owned().to_pax_value()
+    );
+}
+
+#[test]
+fn match_skips_arms_whose_guard_fails() {
+    let error = |code: i64| {
+        state(
+            "State",
+            "Error",
+            vec!["oops".to_owned().to_pax_value(), code.to_pax_value()],
+        )
+    };
+    let subject = Property::new(error(503));
+    let mut engine = state_match(&subject);
+    assert_eq!(mounted_label(&engine), "server error");
+    assert_eq!(symbol(&mounted(&engine)[0], "code"), 503.to_pax_value());
+
+    subject.set(error(404));
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "error");
+
+    subject.set(error(500));
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "server error");
+}
+
+#[test]
+fn match_falls_back_to_wildcard_arm() {
+    let subject = Property::new(state("State", "Ready", vec![]));
+    let mut engine = state_match(&subject);
+    assert_eq!(mounted_label(&engine), "fallback");
+
+    // values that aren't enums only match the wildcard
+    subject.set("Loading".to_owned().to_pax_value());
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "fallback");
+
+    subject.set(state("State", "Loading", vec![]));
+    engine.tick();
+    assert_eq!(mounted_label(&engine), "loading");
+}
@@ -0,0 +1,127 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{args, engine_with_template, label, Row, RowInstance, TestEngine};
+use pax_runtime::api::math::Point2;
+use pax_runtime::api::Window;
+use pax_runtime::api::{CommonProperties, Property, Size};
+use pax_runtime::{ConditionalInstance, ConditionalProperties, ExpandedNode, InstanceNode};
+use pax_runtime_api::pax_value::ToFromPaxAny;
+
+/// A `Row` labelled `label` covering the left `width` percent of the viewport
+fn row(label: &'static str, width: f64) -> Rc<dyn InstanceNode> {
+    let mut row_args = args(
+        move || {
+            Row {
+                label: Property::new(label.to_owned()),
+            }
+            .to_pax_any()
+        },
+        None,
+        None,
+    );
+    row_args.prototypical_common_properties_factory = Box::new(move |_, _| {
+        Some(Rc::new(RefCell::new(CommonProperties {
+            width: Property::new(Some(Size::Percent(width.into()))),
+            ..Default::default()
+        })))
+    });
+    RowInstance::instantiate(row_args)
+}
+
+/// `left` covering the left half of the viewport inside an `if` shown while `shown` is true,
+/// on top of `right` covering all of it
+fn halves(shown: &Property<bool>) -> TestEngine {
+    let condition = shown.clone();
+    let conditional = ConditionalInstance::instantiate(args(
+        move || {
+            let shown = condition.clone();
+            let deps = [condition.untyped()];
+            ConditionalProperties {
+                boolean_expression: Property::computed(move || shown.get(), &deps),
+                else_expressions: vec![],
+            }
+            .to_pax_any()
+        },
+        Some(vec![row("left", 50.0)]),
+        None,
+    ));
+    let mut engine = engine_with_template(vec![conditional, row("right", 100.0)]);
+    engine.tick();
+    engine
+}
+
+fn find(engine: &TestEngine, label: &str) -> Rc<ExpandedNode> {
+    let mut found = None;
+    engine
+        .engine
+        .root_expanded_node
+        .recurse_visit_preorder(&mut |node| {
+            let properties = Rc::clone(&*node.properties.borrow());
+            let properties = properties.borrow();
+            if Row::ref_from_pax_any(&properties).is_ok_and(|row| row.label.get() == label) {
+                found = Some(Rc::clone(node));
+            }
+        });
+    found.unwrap_or_else(|| panic!("no row labelled {}", label))
+}
+
+/// A point over `left`
+fn over_left() -> Point2<Window> {
+    Point2::new(2.0, 5.0)
+}
+
+/// A point over `right` but not `left`
+fn over_right() -> Point2<Window> {
+    Point2::new(8.0, 5.0)
+}
+
+#[test]
+fn pointer_target_is_the_node_hit_unless_captured() {
+    let engine = halves(&Property::new(true));
+    let context = engine.engine.runtime_context.clone();
+    assert_eq!(label(&context.get_pointer_target(1, over_left())), "left");
+    assert_eq!(label(&context.get_pointer_target(1, over_right())), "right");
+
+    let left = find(&engine, "left");
+    context.capture_pointer(1, &left);
+    assert_eq!(label(&context.get_pointer_target(1, over_right())), "left");
+    // other pointers aren't captured
+    assert_eq!(label(&context.get_pointer_target(2, over_right())), "right");
+
+    context.release_pointer_capture(1);
+    assert_eq!(label(&context.get_pointer_target(1, over_right())), "right");
+}
+
+#[test]
+fn pointer_target_leaves_hover_alone() {
+    let engine = halves(&Property::new(true));
+    let context = engine.engine.runtime_context.clone();
+    let left = find(&engine, "left");
+
+    context.get_pointer_target(1, over_left());
+    assert!(!left.interaction_state.hovered.get());
+
+    // unlike hit testing for mouse events
+    context.get_topmost_element_beneath_ray(over_left());
+    assert!(left.interaction_state.hovered.get());
+}
+
+#[test]
+fn unmounting_captor_releases_pointer() {
+    let shown = Property::new(true);
+    let mut engine = halves(&shown);
+    let context = engine.engine.runtime_context.clone();
+    let left = find(&engine, "left");
+    context.capture_pointer(1, &left);
+    assert!(context.get_pointer_capture(1).is_some());
+
+    shown.set(false);
+    engine.tick();
+    assert!(context.get_pointer_capture(1).is_none());
+    assert_eq!(label(&context.get_pointer_target(1, over_left())), "right");
+}
@@ -0,0 +1,154 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::rc::Rc;
+
+use common::{args, engine_with_template, label, symbol, Row, RowInstance, TestEngine};
+use pax_lang::interpreter::parse_pax_expression;
+use pax_manifest::ExpressionInfo;
+use pax_runtime::api::Property;
+use pax_runtime::{ExpandedNode, InstanceNode, RepeatInstance, RepeatProperties};
+use pax_runtime_api::pax_value::ToFromPaxAny;
+use pax_runtime_api::{PaxRange, PaxValue, ToPaxValue};
+
+/// `for (item, i) in source` with a `Row` per item, keyed by `key`
+fn keyed_repeat(source: &Property<PaxValue>, key: &str) -> TestEngine {
+    repeat(source, Some(key))
+}
+
+fn repeat(source: &Property<PaxValue>, key: Option<&str>) -> TestEngine {
+    let source = source.clone();
+    let key_expression =
+        key.map(|key| Rc::new(ExpressionInfo::new(parse_pax_expression(key).unwrap())));
+    let repeat = RepeatInstance::instantiate(args(
+        move || {
+            let mut properties = RepeatProperties::default();
+            let cp_source = source.clone();
+            properties.source_expression =
+                Property::computed(move || cp_source.get(), &[source.untyped()]);
+            properties.iterator_elem_symbol.set(Some("item".to_owned()));
+            properties.iterator_i_symbol.set(Some("i".to_owned()));
+            properties.key_expression.set(key_expression.clone());
+            properties.to_pax_any()
+        },
+        Some(vec![RowInstance::instantiate(args(
+            || Row::default().to_pax_any(),
+            None,
+            None,
+        ))]),
+        None,
+    ));
+    engine_with_template(vec![repeat])
+}
+
+fn strings(items: &[&str]) -> Vec<PaxValue> {
+    items.iter().map(|s| s.to_string().to_pax_value()).collect()
+}
+
+fn rows(engine: &TestEngine) -> Vec<Rc<ExpandedNode>> {
+    let repeat = engine.engine.root_expanded_node.children.get()[0].clone();
+    repeat.children.get()
+}
+
+fn items(rows: &[Rc<ExpandedNode>]) -> Vec<PaxValue> {
+    rows.iter().map(|row| symbol(row, "item")).collect()
+}
+
+fn set_labels_to_items(rows: &[Rc<ExpandedNode>]) {
+    for row in rows {
+        let PaxValue::String(item) = symbol(row, "item") else {
+            panic!("expected string items");
+        };
+        row.with_properties_unwrapped(|row: &mut Row| row.label.set(item));
+    }
+}
+
+fn assert_rows_kept_state(rows: &[Rc<ExpandedNode>]) {
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(symbol(row, "item"), label(row).to_pax_value());
+        assert_eq!(symbol(row, "i"), i.to_pax_value());
+    }
+}
+
+#[test]
+fn keyed_rows_follow_reordered_elements() {
+    let source = Property::new(PaxValue::Vec(strings(&["a", "b", "c"])));
+    let mut engine = keyed_repeat(&source, "item");
+    let before = rows(&engine);
+    assert_eq!(items(&before), strings(&["a", "b", "c"]));
+    set_labels_to_items(&before);
+
+    source.set(PaxValue::Vec(strings(&["c", "a", "b"])));
+    engine.tick();
+    let after = rows(&engine);
+    assert_eq!(items(&after), strings(&["c", "a", "b"]));
+    assert!(Rc::ptr_eq(&after[0], &before[2]));
+    assert!(Rc::ptr_eq(&after[1], &before[0]));
+    assert!(Rc::ptr_eq(&after[2], &before[1]));
+    assert_rows_kept_state(&after);
+}
+
+#[test]
+fn keyed_rows_survive_insertions_and_deletions() {
+    let source = Property::new(PaxValue::Vec(strings(&["a", "b", "c"])));
+    let mut engine = keyed_repeat(&source, "item");
+    let before = rows(&engine);
+    set_labels_to_items(&before);
+
+    source.set(PaxValue::Vec(strings(&["a", "x", "c"])));
+    engine.tick();
+    let after = rows(&engine);
+    assert_eq!(items(&after), strings(&["a", "x", "c"]));
+    assert!(Rc::ptr_eq(&after[0], &before[0]));
+    assert!(!before.iter().any(|row| Rc::ptr_eq(row, &after[1])));
+    assert!(Rc::ptr_eq(&after[2], &before[2]));
+    assert_eq!(label(&after[1]), "");
+    set_labels_to_items(&after[1..2]);
+
+    source.set(PaxValue::Vec(strings(&["c", "x"])));
+    engine.tick();
+    let last = rows(&engine);
+    assert_eq!(items(&last), strings(&["c", "x"]));
+    assert!(Rc::ptr_eq(&last[0], &after[2]));
+    assert!(Rc::ptr_eq(&last[1], &after[1]));
+    assert_rows_kept_state(&last);
+}
+
+#[test]
+fn duplicate_keys_fall_back_to_index_keys() {
+    let source = Property::new(PaxValue::Vec(strings(&["b", "b", "a"])));
+    let mut engine = keyed_repeat(&source, "item");
+    let before = rows(&engine);
+    assert_eq!(items(&before), strings(&["b", "b", "a"]));
+
+    source.set(PaxValue::Vec(strings(&["a", "a"])));
+    engine.tick();
+    let after = rows(&engine);
+    assert_eq!(items(&after), strings(&["a", "a"]));
+    // every row is shown, and rows are matched up by position
+    assert!(Rc::ptr_eq(&after[0], &before[0]));
+    assert!(Rc::ptr_eq(&after[1], &before[1]));
+    for (i, row) in after.iter().enumerate() {
+        assert_eq!(symbol(row, "i"), i.to_pax_value());
+    }
+}
+
+#[test]
+fn ranges_are_iterated_without_being_expanded() {
+    let range = |end: isize, step: isize| {
+        PaxValue::Range(Box::new(PaxRange::new(0, end, true, step).unwrap()))
+    };
+    let source = Property::new(range(10, 5));
+    let mut engine = repeat(&source, None);
+    let expected: Vec<_> = [0isize, 5, 10].iter().map(|i| i.to_pax_value()).collect();
+    assert_eq!(items(&rows(&engine)), expected);
+
+    source.set(range(isize::MAX, isize::MAX / 2));
+    engine.tick();
+    let expected: Vec<_> = [0, isize::MAX / 2, isize::MAX - 1]
+        .iter()
+        .map(|i| i.to_pax_value())
+        .collect();
+    assert_eq!(items(&rows(&engine)), expected);
+}
@@ -0,0 +1,171 @@
+#![cfg(not(feature = "designtime"))]
+
+mod common;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use common::{args, engine_with_template, label, Row, RowInstance, TestEngine};
+use pax_manifest::selectors::PseudoClass;
+use pax_runtime::api::math::Point2;
+use pax_runtime::api::{CommonProperties, EasingCurve, Property, TransitionDuration};
+use pax_runtime::{ExpandedNode, InstanceNode, StateVariant};
+use pax_runtime_api::pax_value::ToFromPaxAny;
+
+/// A variant of a `Row` labelled "row", with `label` and `opacity` overridden
+fn variant(
+    states: Vec<PseudoClass>,
+    transition: Option<TransitionDuration>,
+    label: &'static str,
+    opacity: f64,
+) -> StateVariant {
+    StateVariant {
+        states,
+        transition,
+        easing: EasingCurve::Linear,
+        overridden: vec!["label".to_owned(), "opacity".to_owned()],
+        properties_factory: Box::new(move |_, _| {
+            Some(Rc::new(RefCell::new(
+                Row {
+                    label: Property::new(label.to_owned()),
+                }
+                .to_pax_any(),
+            )))
+        }),
+        common_properties_factory: Box::new(move |_, _| {
+            Some(Rc::new(RefCell::new(CommonProperties {
+                opacity: Property::new(Some(opacity)),
+                ..Default::default()
+            })))
+        }),
+        override_properties: Box::new(|properties, variant, overridden, weight| {
+            let properties = &*properties.borrow();
+            let variant = &*variant.borrow();
+            let (Ok(properties), Ok(variant)) = (
+                Row::ref_from_pax_any(properties),
+                Row::ref_from_pax_any(variant),
+            ) else {
+                return Err("Failed to downcast properties to Row".to_owned());
+            };
+            if overridden.iter().any(|name| name == "label") {
+                pax_runtime::override_with_state_variant(&properties.label, &variant.label, weight);
+            }
+            Ok(())
+        }),
+    }
+}
+
+/// A fully opaque `Row` labelled "row", filling the viewport, with `variants`
+fn engine_with_variants(variants: Vec<StateVariant>) -> TestEngine {
+    let mut row_args = args(
+        || {
+            Row {
+                label: Property::new("row".to_owned()),
+            }
+            .to_pax_any()
+        },
+        None,
+        None,
+    );
+    row_args.prototypical_common_properties_factory = Box::new(|_, _| {
+        Some(Rc::new(RefCell::new(CommonProperties {
+            opacity: Property::new(Some(1.0)),
+            ..Default::default()
+        })))
+    });
+    row_args.state_variants = variants;
+    let row: Rc<dyn InstanceNode> = RowInstance::instantiate(row_args);
+    engine_with_template(vec![row])
+}
+
+fn row(engine: &TestEngine) -> Rc<ExpandedNode> {
+    engine.engine.root_expanded_node.children.get()[0].clone()
+}
+
+fn opacity(row: &ExpandedNode) -> f64 {
+    row.get_common_properties().borrow().opacity.get().unwrap()
+}
+
+#[test]
+fn pressed_variant_applies_while_pressed() {
+    let mut engine = engine_with_variants(vec![variant(
+        vec![PseudoClass::Pressed],
+        None,
+        "pressed",
+        0.5,
+    )]);
+    let row = row(&engine);
+    assert_eq!((label(&row), opacity(&row)), ("row".to_owned(), 1.0));
+
+    let context = engine.engine.runtime_context.clone();
+    context.set_pressed_element(Some(&row));
+    engine.tick();
+    assert_eq!((label(&row), opacity(&row)), ("pressed".to_owned(), 0.5));
+
+    context.set_pressed_element(None);
+    engine.tick();
+    assert_eq!((label(&row), opacity(&row)), ("row".to_owned(), 1.0));
+}
+
+#[test]
+fn variant_requires_all_of_its_states() {
+    let mut engine = engine_with_variants(vec![variant(
+        vec![PseudoClass::Hover, PseudoClass::Pressed],
+        None,
+        "hover and pressed",
+        0.5,
+    )]);
+    let row = row(&engine);
+    let context = engine.engine.runtime_context.clone();
+    context.set_pressed_element(Some(&row));
+    engine.tick();
+    assert_eq!(label(&row), "row");
+
+    context.get_topmost_element_beneath_ray(Point2::new(5.0, 5.0));
+    engine.tick();
+    assert_eq!(label(&row), "hover and pressed");
+}
+
+#[test]
+fn hover_variant_eases_in_and_out() {
+    let mut engine = engine_with_variants(vec![variant(
+        vec![PseudoClass::Hover],
+        Some(TransitionDuration::Frames(4)),
+        "hovered",
+        0.0,
+    )]);
+    let row = row(&engine);
+    let context = engine.engine.runtime_context.clone();
+
+    context.get_topmost_element_beneath_ray(Point2::new(5.0, 5.0));
+    let mut eased_in = Vec::new();
+    for _ in 0..6 {
+        engine.tick();
+        eased_in.push(opacity(&row));
+    }
+    assert!(
+        eased_in.windows(2).all(|w| w[1] <= w[0]) && eased_in.iter().any(|o| *o > 0.0 && *o < 1.0),
+        "opacity should ease from 1 to 0, got {:?}",
+        eased_in
+    );
+    assert_eq!(eased_in.last(), Some(&0.0));
+    // values that can't be interpolated switch once fully eased in, and back right away
+    assert_eq!(label(&row), "hovered");
+
+    // out of the viewport
+    context.get_topmost_element_beneath_ray(Point2::new(50.0, 50.0));
+    engine.tick();
+    assert_eq!(label(&row), "row");
+    let mut eased_out = vec![opacity(&row)];
+    for _ in 0..5 {
+        engine.tick();
+        eased_out.push(opacity(&row));
+    }
+    assert!(
+        eased_out.windows(2).all(|w| w[1] >= w[0])
+            && eased_out.iter().any(|o| *o > 0.0 && *o < 1.0),
+        "opacity should ease from 0 back to 1, got {:?}",
+        eased_out
+    );
+    assert_eq!(eased_out.last(), Some(&1.0));
+}
@@ -67,7 +67,10 @@ impl InstanceNode for YoutubeVideoInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.opacity.untyped(),
+            ])
             .collect();
         expanded_node
             .changed_listener
@@ -93,6 +96,11 @@ impl InstanceNode for YoutubeVideoInstance {
                             ),
                             patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
                             patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
+                            patch_if_needed(
+                                &mut old_state.opacity,
+                                &mut patch.opacity,
+                                expanded_node.opacity.get(),
+                            ),
                             patch_if_needed(
                                 &mut old_state.transform,
                                 &mut patch.transform,
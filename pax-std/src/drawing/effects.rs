@@ -0,0 +1,138 @@
+use kurbo::{Affine, BezPath, Rect, Shape};
+use pax_runtime::api::{Color, Compositing, Effect, Fill, RenderContext, Shadow};
+
+/// Draws a shape along with its effects, in window coordinates. Backdrop blurs come
+/// first, then everything else is blurred by the layer blurs: drop shadows, the fill,
+/// inner shadows over the fill, and finally the stroke.
+pub(crate) fn draw_with_effects(
+    rc: &mut dyn RenderContext,
+    layer: usize,
+    path: &BezPath,
+    effects: &[Effect],
+    draw_fill: impl FnOnce(&mut dyn RenderContext),
+    draw_stroke: impl FnOnce(&mut dyn RenderContext),
+) {
+    let mut layer_blur_variance = 0.0;
+    let mut drop_shadows = Vec::new();
+    let mut inner_shadows = Vec::new();
+    for effect in effects {
+        match effect {
+            Effect::BackdropBlur(radius) => rc.blur_backdrop(layer, path.clone(), *radius),
+            // successive gaussian blurs add up their variances
+            Effect::LayerBlur(radius) => layer_blur_variance += radius * radius,
+            Effect::DropShadow(shadow) => drop_shadows.push(shadow),
+            Effect::InnerShadow(shadow) => inner_shadows.push(shadow),
+        }
+    }
+
+    let layer_blur = layer_blur_variance.sqrt();
+    if layer_blur > 0.0 {
+        rc.push_group(
+            layer,
+            Compositing {
+                blur: layer_blur,
+                ..Default::default()
+            },
+        );
+    }
+    // like in CSS, the first shadow listed is drawn on top
+    for shadow in drop_shadows.into_iter().rev() {
+        draw_drop_shadow(rc, layer, path, shadow);
+    }
+    draw_fill(rc);
+    for shadow in inner_shadows.into_iter().rev() {
+        draw_inner_shadow(rc, layer, path, shadow);
+    }
+    draw_stroke(rc);
+    if layer_blur > 0.0 {
+        rc.pop_group(layer);
+    }
+}
+
+/// Drop shadows are only visible outside of the shape, even if its fill is transparent
+fn draw_drop_shadow(rc: &mut dyn RenderContext, layer: usize, path: &BezPath, shadow: &Shadow) {
+    let shadow_path = offset(&spread(path, shadow.spread), shadow);
+    let around = shadow_path
+        .bounding_box()
+        .union(path.bounding_box())
+        .inflate(extent(shadow), extent(shadow));
+    rc.save(layer);
+    rc.clip(layer, cut_out(around, path));
+    fill_blurred(rc, layer, shadow_path, &shadow.color, shadow.blur);
+    rc.restore(layer);
+}
+
+/// Inner shadows are cast by the outside of the shape onto its inside
+fn draw_inner_shadow(rc: &mut dyn RenderContext, layer: usize, path: &BezPath, shadow: &Shadow) {
+    let hole = offset(&spread(path, -shadow.spread), shadow);
+    let around = path.bounding_box().inflate(extent(shadow), extent(shadow));
+    rc.save(layer);
+    rc.clip(layer, path.clone());
+    fill_blurred(
+        rc,
+        layer,
+        cut_out(around, &hole),
+        &shadow.color,
+        shadow.blur,
+    );
+    rc.restore(layer);
+}
+
+fn fill_blurred(rc: &mut dyn RenderContext, layer: usize, path: BezPath, color: &Color, blur: f64) {
+    // CSS shadow blurs are twice the standard deviation of the gaussian
+    let sigma = blur / 2.0;
+    if sigma > 0.0 {
+        rc.push_group(
+            layer,
+            Compositing {
+                blur: sigma,
+                ..Default::default()
+            },
+        );
+    }
+    rc.fill(layer, path, &Fill::Solid(color.clone()));
+    if sigma > 0.0 {
+        rc.pop_group(layer);
+    }
+}
+
+/// How far past the shape a shadow can reach
+fn extent(shadow: &Shadow) -> f64 {
+    1.5 * shadow.blur + shadow.offset_x.abs() + shadow.offset_y.abs() + shadow.spread.abs() + 1.0
+}
+
+fn offset(path: &BezPath, shadow: &Shadow) -> BezPath {
+    Affine::translate((shadow.offset_x, shadow.offset_y)) * path.clone()
+}
+
+/// Grows the path by `amount` on every side, approximated by scaling it about its
+/// center. Exact for rectangles, and close enough for shadows of most other shapes.
+fn spread(path: &BezPath, amount: f64) -> BezPath {
+    let bounds = path.bounding_box();
+    let scale = |length: f64| {
+        if length > 0.0 {
+            ((length + 2.0 * amount) / length).max(0.0)
+        } else {
+            1.0
+        }
+    };
+    let center = bounds.center().to_vec2();
+    Affine::translate(center)
+        * Affine::scale_non_uniform(scale(bounds.width()), scale(bounds.height()))
+        * Affine::translate(-center)
+        * path.clone()
+}
+
+/// `around` with `hole` cut out of it
+pub(super) fn cut_out(around: Rect, hole: &BezPath) -> BezPath {
+    let mut path = around.to_path(0.1);
+    // winding the hole the other way cuts it out with both the nonzero and the
+    // even-odd fill rules
+    let hole = if hole.area().signum() == path.area().signum() {
+        hole.reverse_subpaths()
+    } else {
+        hole.clone()
+    };
+    path.extend(hole);
+    path
+}
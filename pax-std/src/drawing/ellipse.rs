@@ -1,12 +1,15 @@
 use kurbo::{Rect, Shape};
 use pax_engine::*;
 use pax_runtime::api::{use_RefCell, Stroke};
-use pax_runtime::api::{Fill, Layer, RenderContext};
+use pax_runtime::api::{Effect, Fill, Layer, RenderContext};
 use pax_runtime::BaseInstance;
 use pax_runtime::{ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext};
 use_RefCell!();
 use std::rc::Rc;
 
+use super::effects::draw_with_effects;
+use super::stroke::draw_stroke;
+
 /// A basic 2D vector ellipse
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -14,6 +17,7 @@ use std::rc::Rc;
 pub struct Ellipse {
     pub stroke: Property<Stroke>,
     pub fill: Property<Fill>,
+    pub effects: Property<Vec<Effect>>,
 }
 
 pub struct EllipseInstance {
@@ -45,11 +49,23 @@ impl InstanceNode for EllipseInstance {
         context: &Rc<RuntimeContext>,
     ) {
         let tab = expanded_node.transform_and_bounds.clone();
-        let (stroke, fill) = expanded_node.with_properties_unwrapped(|properties: &mut Ellipse| {
-            (properties.stroke.clone(), properties.fill.clone())
-        });
+        let (stroke, fill, effects) =
+            expanded_node.with_properties_unwrapped(|properties: &mut Ellipse| {
+                (
+                    properties.stroke.clone(),
+                    properties.fill.clone(),
+                    properties.effects.clone(),
+                )
+            });
 
-        let deps = &[tab.untyped(), stroke.untyped(), fill.untyped()];
+        let deps = &[
+            tab.untyped(),
+            stroke.untyped(),
+            fill.untyped(),
+            effects.untyped(),
+            expanded_node.opacity.untyped(),
+            expanded_node.blend_modes.untyped(),
+        ];
         let cloned_expanded_node = expanded_node.clone();
         let cloned_context = context.clone();
 
@@ -87,26 +103,28 @@ impl InstanceNode for EllipseInstance {
             let bez_path = ellipse.to_path(accuracy);
 
             let transformed_bez_path = Into::<kurbo::Affine>::into(tab.transform) * bez_path;
-            let duplicate_transformed_bez_path = transformed_bez_path.clone();
-            rc.fill(layer_id, transformed_bez_path, &properties.fill.get());
-
-            //hack to address "phantom stroke" bug on Web
-            let width: f64 = properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float();
 
-            if width > f64::EPSILON {
-                rc.stroke(
-                    layer_id,
-                    duplicate_transformed_bez_path,
-                    &Fill::Solid(properties.stroke.get().color.get()),
-                    width,
-                );
-            }
+            draw_with_effects(
+                rc,
+                layer_id,
+                &transformed_bez_path,
+                &properties.effects.get(),
+                |rc| {
+                    rc.fill(
+                        layer_id,
+                        transformed_bez_path.clone(),
+                        &properties.fill.get(),
+                    )
+                },
+                |rc| {
+                    draw_stroke(
+                        rc,
+                        layer_id,
+                        &transformed_bez_path,
+                        &properties.stroke.get(),
+                    )
+                },
+            );
         });
     }
 
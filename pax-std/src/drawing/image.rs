@@ -80,7 +80,11 @@ impl InstanceNode for ImageInstance {
         });
 
         let tab = expanded_node.transform_and_bounds.clone();
-        let deps = [tab.untyped()];
+        let deps = [
+            tab.untyped(),
+            expanded_node.opacity.untyped(),
+            expanded_node.blend_modes.untyped(),
+        ];
         let cloned_context = context.clone();
         let occlusion = expanded_node.occlusion.clone();
 
@@ -1,7 +1,9 @@
+mod effects;
 pub mod ellipse;
 pub mod image;
 pub mod path;
 pub mod rectangle;
+mod stroke;
 
 pub use ellipse::*;
 pub use image::*;
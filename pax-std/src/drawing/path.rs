@@ -1,12 +1,14 @@
 use kurbo::BezPath;
 
-use pax_engine::api::{Fill, PathElement};
+use pax_engine::api::{Effect, Fill, PathElement};
 use pax_runtime::api::{borrow, borrow_mut, use_RefCell};
 use pax_runtime::api::{Layer, RenderContext, Stroke};
 use pax_runtime::{
     BaseInstance, ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext,
 };
 
+use super::effects::draw_with_effects;
+use super::stroke::draw_stroke;
 use crate::common::Point;
 use pax_engine::*;
 
@@ -23,6 +25,7 @@ pub struct Path {
     pub elements: Property<Vec<PathElement>>,
     pub stroke: Property<Stroke>,
     pub fill: Property<Fill>,
+    pub effects: Property<Vec<Effect>>,
 }
 
 impl Path {
@@ -100,12 +103,13 @@ impl InstanceNode for PathInstance {
         });
 
         let tab = expanded_node.transform_and_bounds.clone();
-        let (elements, stroke, fill) =
+        let (elements, stroke, fill, effects) =
             expanded_node.with_properties_unwrapped(|properties: &mut Path| {
                 (
                     properties.elements.clone(),
                     properties.stroke.clone(),
                     properties.fill.clone(),
+                    properties.effects.clone(),
                 )
             });
 
@@ -114,6 +118,9 @@ impl InstanceNode for PathInstance {
             elements.untyped(),
             stroke.untyped(),
             fill.untyped(),
+            effects.untyped(),
+            expanded_node.opacity.untyped(),
+            expanded_node.blend_modes.untyped(),
         ];
         let cloned_expanded_node = expanded_node.clone();
         let cloned_context = context.clone();
@@ -218,35 +225,35 @@ impl InstanceNode for PathInstance {
             clip_path.close_path();
             let transformed_clip_path = transform * clip_path;
             let transformed_bez_path = transform * bez_path;
-            let duplicate_transformed_bez_path = transformed_bez_path.clone();
-            //our "save point" before clipping — restored to in the post_render
-
-            rc.save(layer_id);
-            rc.clip(layer_id, transformed_clip_path.clone());
-            rc.fill(layer_id, transformed_bez_path, &properties.fill.get());
-            if properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float()
-                > f64::EPSILON
-            {
-                rc.stroke(
-                    layer_id,
-                    duplicate_transformed_bez_path,
-                    &Fill::Solid(properties.stroke.get().color.get()),
-                    properties
-                        .stroke
-                        .get()
-                        .width
-                        .get()
-                        .expect_pixels()
-                        .to_float(),
-                );
-            }
-            rc.restore(layer_id);
+
+            // the path is clipped to its bounds, but its effects aren't
+            draw_with_effects(
+                rc,
+                layer_id,
+                &transformed_bez_path,
+                &properties.effects.get(),
+                |rc| {
+                    rc.save(layer_id);
+                    rc.clip(layer_id, transformed_clip_path.clone());
+                    rc.fill(
+                        layer_id,
+                        transformed_bez_path.clone(),
+                        &properties.fill.get(),
+                    );
+                    rc.restore(layer_id);
+                },
+                |rc| {
+                    rc.save(layer_id);
+                    rc.clip(layer_id, transformed_clip_path.clone());
+                    draw_stroke(
+                        rc,
+                        layer_id,
+                        &transformed_bez_path,
+                        &properties.stroke.get(),
+                    );
+                    rc.restore(layer_id);
+                },
+            );
         });
     }
 
@@ -5,12 +5,15 @@ use pax_runtime_api::use_RefCell;
 use pax_runtime::{ExpandedNode, InstanceFlags, InstanceNode, InstantiationArgs, RuntimeContext};
 
 use pax_runtime::api as pax_runtime_api;
-use pax_runtime::api::{Layer, RenderContext, Stroke};
+use pax_runtime::api::{Effect, Layer, RenderContext, Stroke};
 use_RefCell!();
 use pax_engine::{helpers, pax, Property};
 use pax_manifest::pax_runtime_api::Numeric;
 use std::rc::Rc;
 
+use super::effects::draw_with_effects;
+use super::stroke::draw_stroke;
+
 /// A basic 2D vector rectangle
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -19,6 +22,7 @@ pub struct Rectangle {
     pub stroke: Property<Stroke>,
     pub fill: Property<Fill>,
     pub corner_radii: Property<RectangleCornerRadii>,
+    pub effects: Property<Vec<Effect>>,
 }
 
 pub struct RectangleInstance {
@@ -47,12 +51,13 @@ impl InstanceNode for RectangleInstance {
         context: &Rc<RuntimeContext>,
     ) {
         let tab = expanded_node.transform_and_bounds.clone();
-        let (corner_radii, stroke, fill) =
+        let (corner_radii, stroke, fill, effects) =
             expanded_node.with_properties_unwrapped(|properties: &mut Rectangle| {
                 (
                     properties.corner_radii.clone(),
                     properties.stroke.clone(),
                     properties.fill.clone(),
+                    properties.effects.clone(),
                 )
             });
 
@@ -61,6 +66,9 @@ impl InstanceNode for RectangleInstance {
             corner_radii.untyped(),
             stroke.untyped(),
             fill.untyped(),
+            effects.untyped(),
+            expanded_node.opacity.untyped(),
+            expanded_node.blend_modes.untyped(),
         ];
         let cloned_expanded_node = expanded_node.clone();
         let cloned_context = context.clone();
@@ -97,25 +105,28 @@ impl InstanceNode for RectangleInstance {
             let bez_path = rect.to_path(0.1);
 
             let transformed_bez_path = Into::<kurbo::Affine>::into(tab.transform) * bez_path;
-            let duplicate_transformed_bez_path = transformed_bez_path.clone();
-
-            rc.fill(layer_id, transformed_bez_path, &properties.fill.get());
-            //hack to address "phantom stroke" bug on Web
-            let width: f64 = properties
-                .stroke
-                .get()
-                .width
-                .get()
-                .expect_pixels()
-                .to_float();
-            if width > f64::EPSILON {
-                rc.stroke(
-                    layer_id,
-                    duplicate_transformed_bez_path,
-                    &Fill::Solid(properties.stroke.get().color.get()),
-                    width,
-                );
-            }
+
+            draw_with_effects(
+                rc,
+                layer_id,
+                &transformed_bez_path,
+                &properties.effects.get(),
+                |rc| {
+                    rc.fill(
+                        layer_id,
+                        transformed_bez_path.clone(),
+                        &properties.fill.get(),
+                    )
+                },
+                |rc| {
+                    draw_stroke(
+                        rc,
+                        layer_id,
+                        &transformed_bez_path,
+                        &properties.stroke.get(),
+                    )
+                },
+            );
         });
     }
 
@@ -0,0 +1,81 @@
+use kurbo::{BezPath, Cap, Join, PathEl, Shape};
+use pax_runtime::api::{RenderContext, Stroke, StrokeAlignment, StrokeCap, StrokeJoin};
+
+use super::effects::cut_out;
+
+/// Strokes a path in window coordinates. Strokes aligned inside or outside of the shape
+/// are drawn twice as wide, clipped to the shape or to what's around it.
+pub(crate) fn draw_stroke(
+    rc: &mut dyn RenderContext,
+    layer: usize,
+    path: &BezPath,
+    stroke: &Stroke,
+) {
+    let width = stroke.width.get().expect_pixels().to_float();
+    //hack to address "phantom stroke" bug on Web
+    if width <= f64::EPSILON {
+        return;
+    }
+    let cap = match stroke.cap.get() {
+        StrokeCap::Butt => Cap::Butt,
+        StrokeCap::Round => Cap::Round,
+        StrokeCap::Square => Cap::Square,
+    };
+    let join = match stroke.join.get() {
+        StrokeJoin::Miter => Join::Miter,
+        StrokeJoin::Round => Join::Round,
+        StrokeJoin::Bevel => Join::Bevel,
+    };
+    let mut style = kurbo::Stroke::new(width)
+        .with_caps(cap)
+        .with_join(join)
+        .with_miter_limit(stroke.miter_limit.get());
+    let dash = stroke.dash.get();
+    // invalid patterns, such as ones with nothing but gaps, are drawn solid
+    if dash
+        .iter()
+        .all(|length| length.is_finite() && *length >= 0.0)
+        && dash.iter().sum::<f64>() > 0.0
+    {
+        style = style.with_dashes(stroke.dash_offset.get(), dash);
+    }
+    let paint = stroke.paint();
+
+    let alignment = if is_closed(path) {
+        stroke.alignment.get()
+    } else {
+        StrokeAlignment::Center
+    };
+    let clip = match alignment {
+        StrokeAlignment::Center => None,
+        StrokeAlignment::Inside => Some(path.clone()),
+        StrokeAlignment::Outside => {
+            let reach = width * style.miter_limit.max(std::f64::consts::SQRT_2) + 1.0;
+            Some(cut_out(path.bounding_box().inflate(reach, reach), path))
+        }
+    };
+    match clip {
+        None => rc.stroke(layer, path.clone(), &paint, &style),
+        Some(clip) => {
+            style.width *= 2.0;
+            rc.save(layer);
+            rc.clip(layer, clip);
+            rc.stroke(layer, path.clone(), &paint, &style);
+            rc.restore(layer);
+        }
+    }
+}
+
+/// Whether every subpath of `path` is closed, so that it has an inside and an outside
+fn is_closed(path: &BezPath) -> bool {
+    let mut open = false;
+    for el in path.elements() {
+        match el {
+            PathEl::MoveTo(_) if open => return false,
+            PathEl::MoveTo(_) => {}
+            PathEl::ClosePath => open = false,
+            _ => open = true,
+        }
+    }
+    !open
+}
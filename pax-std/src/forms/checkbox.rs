@@ -33,6 +33,7 @@ impl Default for Checkbox {
             outline: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             border_radius: Property::new(5.0),
             checked: Property::new(false),
@@ -87,7 +88,10 @@ impl InstanceNode for CheckboxInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.opacity.untyped(),
+            ])
             .collect();
         expanded_node
             .changed_listener
@@ -144,6 +148,11 @@ impl InstanceNode for CheckboxInstance {
                             ),
                             patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
                             patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
+                            patch_if_needed(
+                                &mut old_state.opacity,
+                                &mut patch.opacity,
+                                expanded_node.opacity.get(),
+                            ),
                             patch_if_needed(
                                 &mut old_state.transform,
                                 &mut patch.transform,
@@ -35,6 +35,7 @@ impl Default for RadioSet {
             outline: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             options: Property::new(vec!["option 1".to_string(), "option 2".to_string()]),
             selected_id: Property::new(0),
@@ -100,7 +101,10 @@ impl InstanceNode for RadioSetInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.opacity.untyped(),
+            ])
             .collect();
         expanded_node
             .changed_listener
@@ -122,6 +126,11 @@ impl InstanceNode for RadioSetInstance {
                         let updates = [
                             patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
                             patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
+                            patch_if_needed(
+                                &mut old_state.opacity,
+                                &mut patch.opacity,
+                                expanded_node.opacity.get(),
+                            ),
                             patch_if_needed(
                                 &mut old_state.transform,
                                 &mut patch.transform,
@@ -36,10 +36,12 @@ impl Default for Textbox {
             stroke: Property::new(Stroke {
                 color: Property::new(Color::rgb(209.into(), 213.into(), 219.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             outline: Property::new(Stroke {
                 color: Property::new(Color::rgb(0.into(), 0.into(), 0.into())),
                 width: Property::new(Size::Pixels(1.into())),
+                ..Default::default()
             }),
             placeholder: Property::new("".into()),
             border_radius: Property::new(8.0.into()),
@@ -104,7 +106,10 @@ impl InstanceNode for TextboxInstance {
             .values()
             .cloned()
             .map(|v| v.get_untyped_property().clone())
-            .chain([expanded_node.transform_and_bounds.untyped()])
+            .chain([
+                expanded_node.transform_and_bounds.untyped(),
+                expanded_node.opacity.untyped(),
+            ])
             .collect();
         expanded_node
             .changed_listener
@@ -131,6 +136,11 @@ impl InstanceNode for TextboxInstance {
                             ),
                             patch_if_needed(&mut old_state.size_x, &mut patch.size_x, width),
                             patch_if_needed(&mut old_state.size_y, &mut patch.size_y, height),
+                            patch_if_needed(
+                                &mut old_state.opacity,
+                                &mut patch.opacity,
+                                expanded_node.opacity.get(),
+                            ),
                             patch_if_needed(
                                 &mut old_state.transform,
                                 &mut patch.transform,
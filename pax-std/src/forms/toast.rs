@@ -5,7 +5,7 @@ use crate::*;
 use pax_engine::api::*;
 use pax_engine::*;
 
-const IN_OUT_TIME: u64 = 10;
+const IN_OUT_TIME: TransitionDuration = TransitionDuration::Frames(10);
 
 #[pax]
 #[engine_import_path("pax_engine")]
@@ -53,7 +53,7 @@ impl Toast {
                     // in
                     set_px_offset(&y_pos, Size::ZERO(), IN_OUT_TIME);
                     // stay
-                    set_px_offset_later(&y_pos, Size::ZERO(), 300);
+                    set_px_offset_later(&y_pos, Size::ZERO(), TransitionDuration::Frames(300));
                     // out
                     set_px_offset_later(&y_pos, height.get(), IN_OUT_TIME);
                 }
@@ -74,10 +74,10 @@ impl Toast {
     }
 }
 
-fn set_px_offset(y_pos: &Property<Size>, offset: Size, time: u64) {
+fn set_px_offset(y_pos: &Property<Size>, offset: Size, time: TransitionDuration) {
     y_pos.ease_to(Size::default() + offset, time, EasingCurve::InQuad);
 }
 
-fn set_px_offset_later(y_pos: &Property<Size>, offset: Size, time: u64) {
+fn set_px_offset_later(y_pos: &Property<Size>, offset: Size, time: TransitionDuration) {
     y_pos.ease_to_later(Size::default() + offset, time, EasingCurve::OutQuad);
 }
@@ -1,6 +1,6 @@
 #![allow(unused_imports)]
 use crate::*;
-use pax_engine::api::{Click, EasingCurve, Event};
+use pax_engine::api::{Click, EasingCurve, Event, TransitionDuration};
 use pax_engine::*;
 use pax_runtime::api::NodeContext;
 
@@ -65,7 +65,11 @@ impl Carousel {
         self.current_cell_on_change.replace_with(Property::computed(
             move || {
                 let current_cell = current_cell.get();
-                transition.ease_to(current_cell as f64 * 100.0, 60, EasingCurve::OutQuad);
+                transition.ease_to(
+                    current_cell as f64 * 100.0,
+                    TransitionDuration::Frames(60),
+                    EasingCurve::OutQuad,
+                );
                 false
             },
             &deps,